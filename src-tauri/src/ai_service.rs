@@ -1,10 +1,26 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
 use crate::error::{AppError, AppResult};
-use crate::models::{AiAnswer, AiAskInput, AiConfig};
+use crate::models::{
+    now_rfc3339, AiAnswer, AiAskHistoryEntry, AiAskInput, AiConfig, AiModelList, AiProfileInput,
+    AiProfileTestFailure, AiProfileTestResult, CommandHistoryEntry,
+};
+use crate::ops_agent::core::llm::validate_ai_config;
+use crate::ops_agent::core::prompting::{load_session_context, render_system_prompt};
 use crate::ops_agent::providers::{
-    request_message, ProviderChatMessage, ProviderChatMessageContent, ProviderChatRequestOptions,
+    list_models, request_message, ProviderChatMessage, ProviderChatMessageContent,
+    ProviderChatRequestOptions,
 };
 use crate::state::AppState;
 
+const AI_PROFILE_TEST_TIMEOUT: Duration = Duration::from_secs(20);
+const AI_MODEL_LIST_TIMEOUT: Duration = Duration::from_secs(20);
+const AI_ASK_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const RECENT_COMMANDS_MAX_ENTRIES: usize = 20;
+const RECENT_COMMANDS_MAX_CHARS: usize = 2_000;
+const RECENT_COMMAND_LINE_MAX_CHARS: usize = 200;
+
 /// Executes a configured provider request and extracts answer + command hint.
 pub async fn ask_ai(state: &AppState, input: AiAskInput) -> AppResult<AiAnswer> {
     if input.question.trim().is_empty() {
@@ -25,11 +41,24 @@ pub async fn ask_ai(state: &AppState, input: AiAskInput) -> AppResult<AiAnswer>
             }
         }
     }
+    if let (Some(limit), Some(session_id)) =
+        (input.include_recent_commands, input.session_id.as_deref())
+    {
+        let history = state.get_command_history(session_id, limit.min(RECENT_COMMANDS_MAX_ENTRIES));
+        if let Some(block) = format_recent_commands(&history) {
+            user_content.push_str("\n\nRecent commands (oldest first):\n");
+            user_content.push_str(&block);
+        }
+    }
 
+    let session_context = load_session_context(state, input.session_id.as_deref());
     let messages = vec![
         ProviderChatMessage {
             role: "system".to_string(),
-            content: ProviderChatMessageContent::text(config.system_prompt.clone()),
+            content: ProviderChatMessageContent::text(render_system_prompt(
+                &config.system_prompt,
+                &session_context,
+            )),
         },
         ProviderChatMessage {
             role: "user".to_string(),
@@ -37,13 +66,138 @@ pub async fn ask_ai(state: &AppState, input: AiAskInput) -> AppResult<AiAnswer>
         },
     ];
 
-    let response_text = request_completion(&config, &messages).await?;
-    Ok(AiAnswer {
-        suggested_command: extract_suggested_command(&response_text),
+    let response_text = match input.request_id.as_deref() {
+        Some(request_id) => {
+            state.begin_ai_ask(request_id);
+            let result =
+                run_cancellable(state, request_id, request_completion(&config, &messages)).await;
+            state.clear_ai_ask(request_id);
+            result?
+        }
+        None => request_completion(&config, &messages).await?,
+    };
+    let suggested_commands = extract_suggested_commands(&response_text);
+    let answer = AiAnswer {
+        suggested_command: suggested_commands.first().cloned(),
+        suggested_commands,
         answer: response_text,
+    };
+    // History is a convenience for the Q&A panel; a failed write must not lose the answer.
+    let _ = state.storage.append_ai_ask_history(AiAskHistoryEntry {
+        question: input.question.trim().to_string(),
+        answer: answer.answer.clone(),
+        suggested_commands: answer.suggested_commands.clone(),
+        session_id: input.session_id.clone(),
+        created_at: now_rfc3339(),
+    });
+    Ok(answer)
+}
+
+/// Sends a minimal "reply with OK" request through one stored AI profile.
+///
+/// Failures are reported in the result, categorized so the settings UI can
+/// point at the key, the network, the model or the URL.
+pub async fn test_ai_profile(state: &AppState, profile_id: &str) -> AppResult<AiProfileTestResult> {
+    let config = state.storage.get_ai_config_for_profile(profile_id)?;
+    let started = Instant::now();
+    let outcome = match validate_ai_config(&config) {
+        Ok(()) => {
+            let messages = vec![ProviderChatMessage {
+                role: "user".to_string(),
+                content: ProviderChatMessageContent::text("Reply with OK."),
+            }];
+            request_message(
+                &config,
+                messages,
+                ProviderChatRequestOptions::default(),
+                AI_PROFILE_TEST_TIMEOUT,
+                None,
+                "ai_profile_test",
+            )
+            .await
+        }
+        Err(err) => Err(err),
+    };
+    let latency_ms = started.elapsed().as_millis();
+
+    Ok(match outcome {
+        Ok(response) => AiProfileTestResult {
+            profile_id: profile_id.to_string(),
+            success: true,
+            latency_ms,
+            reply: Some(response.content.trim().to_string()),
+            failure: None,
+            message: None,
+        },
+        Err(err) => AiProfileTestResult {
+            profile_id: profile_id.to_string(),
+            success: false,
+            latency_ms,
+            reply: None,
+            failure: Some(classify_ai_test_failure(&err)),
+            message: Some(err.to_string()),
+        },
     })
 }
 
+/// Lists the models offered by the provider described in an unsaved profile
+/// form, so the settings UI can offer a dropdown instead of free text.
+pub async fn list_ai_models(input: AiProfileInput) -> AppResult<AiModelList> {
+    let config = AiConfig {
+        api_type: input.api_type,
+        base_url: input.base_url.trim().to_string(),
+        api_key: input.api_key.trim().to_string(),
+        ..AiConfig::default()
+    };
+    if config.base_url.is_empty() {
+        return Err(AppError::Validation("baseUrl cannot be empty".to_string()));
+    }
+    if config.api_key.is_empty() {
+        return Err(AppError::Validation("apiKey cannot be empty".to_string()));
+    }
+    // The key is not registered with `remember_secret`: the form may never be
+    // saved, and only stored profiles are tracked for redaction.
+    Ok(match list_models(&config, AI_MODEL_LIST_TIMEOUT).await? {
+        Some(models) => AiModelList {
+            models,
+            supported: true,
+        },
+        None => AiModelList {
+            models: Vec::new(),
+            supported: false,
+        },
+    })
+}
+
+fn classify_ai_test_failure(err: &AppError) -> AiProfileTestFailure {
+    match err {
+        AppError::Validation(_) => AiProfileTestFailure::InvalidConfig,
+        AppError::Reqwest(err) if err.is_builder() => AiProfileTestFailure::BadUrl,
+        AppError::Reqwest(_) => AiProfileTestFailure::Network,
+        // A success status with a non-JSON body is usually a web page, not an API.
+        AppError::SerdeJson(_) => AiProfileTestFailure::BadUrl,
+        AppError::Runtime(message) => {
+            let message = message.to_ascii_lowercase();
+            let mentions_model = message.contains("model");
+            if message.contains("status=401") || message.contains("status=403") {
+                AiProfileTestFailure::Auth
+            } else if mentions_model
+                && (message.contains("status=400") || message.contains("status=404"))
+            {
+                AiProfileTestFailure::BadModel
+            } else if message.contains("status=404")
+                || message.contains("status=405")
+                || message.contains("expected json from")
+            {
+                AiProfileTestFailure::BadUrl
+            } else {
+                AiProfileTestFailure::Other
+            }
+        }
+        _ => AiProfileTestFailure::Other,
+    }
+}
+
 async fn request_completion(
     config: &AiConfig,
     messages: &[ProviderChatMessage],
@@ -67,6 +221,26 @@ async fn request_completion(
     Ok(answer)
 }
 
+/// Drives `future` until it finishes or `cancel_ai_ask` flags `request_id`.
+///
+/// On cancellation the future is dropped, which aborts the in-flight HTTP request.
+async fn run_cancellable<T>(
+    state: &AppState,
+    request_id: &str,
+    future: impl Future<Output = AppResult<T>>,
+) -> AppResult<T> {
+    let mut future = std::pin::pin!(future);
+    loop {
+        if state.is_ai_ask_cancelled(request_id) {
+            return Err(AppError::Cancelled(format!("ai ask {request_id}")));
+        }
+        if let Ok(result) = tokio::time::timeout(AI_ASK_CANCEL_POLL_INTERVAL, future.as_mut()).await
+        {
+            return result;
+        }
+    }
+}
+
 fn ensure_ai_config_is_usable(config: &AiConfig) -> AppResult<()> {
     if config.base_url.trim().is_empty() {
         return Err(AppError::Validation("baseUrl cannot be empty".to_string()));
@@ -80,44 +254,94 @@ fn ensure_ai_config_is_usable(config: &AiConfig) -> AppResult<()> {
     Ok(())
 }
 
-fn extract_suggested_command(text: &str) -> Option<String> {
-    let mut in_block = false;
-    let mut command_lines = Vec::new();
+/// Renders history entries as `[exit N] cwd $ command` lines, keeping the
+/// newest ones that fit in [`RECENT_COMMANDS_MAX_CHARS`].
+fn format_recent_commands(history: &[CommandHistoryEntry]) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut total_chars = 0;
+    for entry in history.iter().rev() {
+        let command = entry.command.replace('\n', " ");
+        let mut line = format!(
+            "[exit {}] {} $ {}",
+            entry.exit_code,
+            entry.current_dir,
+            command.trim()
+        );
+        if line.chars().count() > RECENT_COMMAND_LINE_MAX_CHARS {
+            line = line
+                .chars()
+                .take(RECENT_COMMAND_LINE_MAX_CHARS)
+                .collect::<String>();
+            line.push_str("...");
+        }
+        total_chars += line.chars().count() + 1;
+        if total_chars > RECENT_COMMANDS_MAX_CHARS {
+            break;
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n"))
+}
+
+/// A runnable command found in an answer, in document order.
+/// Lists every shell block and prompt-line command in document order; the
+/// first one doubles as the single command hint.
+///
+/// Collects shell code blocks (tagged `bash`/`sh`/`shell` or untagged) and
+/// `$ ` prompt lines outside any code block. Blocks in other languages are skipped.
+fn extract_suggested_commands(text: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    // `Some(is_shell)` while inside a fenced block.
+    let mut block: Option<bool> = None;
+    let mut block_lines = Vec::new();
 
     for line in text.lines() {
         let trimmed = line.trim();
 
-        if trimmed.starts_with("```") {
-            if !in_block {
-                in_block = true;
-                continue;
+        if let Some(info) = trimmed.strip_prefix("```") {
+            match block.take() {
+                None => block = Some(is_shell_fence(info)),
+                Some(is_shell) => {
+                    push_fenced_candidate(&mut candidates, is_shell, &block_lines);
+                    block_lines.clear();
+                }
             }
-            break;
-        }
-
-        if in_block {
-            command_lines.push(line);
+            continue;
         }
-    }
 
-    if !command_lines.is_empty() {
-        let candidate = command_lines.join("\n").trim().to_string();
-        if !candidate.is_empty() {
-            return Some(candidate);
-        }
-    }
-
-    for line in text.lines() {
-        let trimmed = line.trim_start();
-        if let Some(rest) = trimmed.strip_prefix("$ ") {
+        if block.is_some() {
+            block_lines.push(line);
+        } else if let Some(rest) = line.trim_start().strip_prefix("$ ") {
             let command = rest.trim();
             if !command.is_empty() {
-                return Some(command.to_string());
+                candidates.push(command.to_string());
             }
         }
     }
 
-    None
+    if let Some(is_shell) = block {
+        push_fenced_candidate(&mut candidates, is_shell, &block_lines);
+    }
+    candidates
+}
+
+fn is_shell_fence(info: &str) -> bool {
+    let language = info.split_whitespace().next().unwrap_or("");
+    language.is_empty() || ["bash", "sh", "shell"].contains(&language.to_ascii_lowercase().as_str())
+}
+
+fn push_fenced_candidate(candidates: &mut Vec<String>, is_shell: bool, lines: &[&str]) {
+    if !is_shell {
+        return;
+    }
+    let command = lines.join("\n").trim().to_string();
+    if !command.is_empty() {
+        candidates.push(command);
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +349,7 @@ mod tests {
     use super::*;
     use crate::models::{
         now_rfc3339, AiApiType, AiAskInput, AiProfile, AiProfileInput, ShellSession,
+        SshHandshakeInfo,
     };
     use crate::state::AppState;
     use serde_json::Value;
@@ -133,7 +358,7 @@ mod tests {
     use std::io::{Read, Write};
     use std::net::TcpListener;
     use std::path::PathBuf;
-    use std::sync::mpsc;
+    use std::sync::{mpsc, Arc};
     use std::thread;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -258,17 +483,44 @@ mod tests {
     }
 
     #[test]
-    fn extract_suggested_command_from_fenced_block() {
+    fn extract_suggested_commands_from_fenced_block() {
         let text = "Try this:\n```bash\nls -la\npwd\n```";
-        let command = extract_suggested_command(text).expect("command");
-        assert_eq!(command, "ls -la\npwd");
+        assert_eq!(extract_suggested_commands(text), vec!["ls -la\npwd"]);
+    }
+
+    #[test]
+    fn extract_suggested_commands_skips_other_languages() {
+        let text = "Script:\n```python\nimport os\nprint(os.getcwd())\n```\nThen:\n```bash\nsystemctl restart nginx\n```\n```diff\n-a\n+b\n```";
+        assert_eq!(
+            extract_suggested_commands(text),
+            vec!["systemctl restart nginx"]
+        );
     }
 
     #[test]
-    fn extract_suggested_command_from_prompt_line() {
+    fn extract_suggested_commands_ignores_prompt_lines_inside_code() {
+        let text = "Output:\n```text\n$ rm -rf /tmp/cache\n```\nThen run:\n$ df -h";
+        assert_eq!(extract_suggested_commands(text), vec!["df -h"]);
+        assert!(extract_suggested_commands("```python\nprint(1)\n```").is_empty());
+    }
+
+    #[test]
+    fn extract_suggested_commands_keeps_every_block_in_order() {
+        let text = "Check first:\n```bash\nsystemctl status nginx\n```\nIf it failed:\n$ journalctl -u nginx -n 50\n```python\nprint('skip')\n```\nThen:\n```bash\nsystemctl restart nginx\n```";
+        assert_eq!(
+            extract_suggested_commands(text),
+            vec![
+                "systemctl status nginx".to_string(),
+                "journalctl -u nginx -n 50".to_string(),
+                "systemctl restart nginx".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_suggested_commands_from_prompt_line() {
         let text = "Run this first:\n$ df -h";
-        let command = extract_suggested_command(text).expect("command");
-        assert_eq!(command, "df -h");
+        assert_eq!(extract_suggested_commands(text), vec!["df -h"]);
     }
 
     #[test]
@@ -291,6 +543,9 @@ mod tests {
                 temperature: 0.2,
                 max_tokens: 100000,
                 max_context_tokens: 100000,
+                tool_output_max_bytes: 16_384,
+                max_tool_steps: 8,
+                supports_json_mode: false,
             })
             .expect("save profile");
         let profile_id = saved
@@ -311,10 +566,14 @@ mod tests {
             config_id: "config-1".to_string(),
             config_name: "prod".to_string(),
             current_dir: "/opt/service".to_string(),
+            previous_dir: None,
             last_output: "nginx.service: Failed with result 'exit-code'.".to_string(),
+            remote_os: Some("Linux".to_string()),
             created_at: now.clone(),
             updated_at: now,
+            handshake: SshHandshakeInfo::default(),
         });
+        state.record_command_history("session-1", history_entry("systemctl start nginx", 1));
 
         let answer = tauri::async_runtime::block_on(ask_ai(
             &state,
@@ -322,6 +581,8 @@ mod tests {
                 session_id: Some("session-1".to_string()),
                 question: "How should I debug nginx startup failure?".to_string(),
                 include_last_output: true,
+                include_recent_commands: Some(5),
+                request_id: None,
             },
         ))
         .expect("ask ai");
@@ -330,6 +591,10 @@ mod tests {
             answer.suggested_command.as_deref(),
             Some("systemctl status nginx")
         );
+        assert_eq!(
+            answer.suggested_commands,
+            vec!["systemctl status nginx".to_string()]
+        );
         assert!(answer.answer.contains("systemctl status nginx"));
 
         let captured = captured_request_rx
@@ -358,6 +623,221 @@ mod tests {
         assert!(user_content.contains("How should I debug nginx startup failure?"));
         assert!(user_content.contains("Terminal output context:"));
         assert!(user_content.contains("Failed with result 'exit-code'."));
+        assert!(user_content.contains("[exit 1] /opt/service $ systemctl start nginx"));
+
+        let history = state
+            .storage
+            .list_ai_ask_history(None)
+            .expect("ask history");
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].question,
+            "How should I debug nginx startup failure?"
+        );
+        assert_eq!(history[0].session_id.as_deref(), Some("session-1"));
+        assert_eq!(history[0].suggested_commands, answer.suggested_commands);
+    }
+
+    fn history_entry(command: &str, exit_code: i32) -> CommandHistoryEntry {
+        let now = now_rfc3339();
+        CommandHistoryEntry {
+            command: command.to_string(),
+            exit_code,
+            current_dir: "/opt/service".to_string(),
+            started_at: now.clone(),
+            finished_at: now,
+            duration_ms: 12,
+        }
+    }
+
+    #[test]
+    fn recent_commands_keep_newest_entries_within_cap() {
+        assert_eq!(format_recent_commands(&[]), None);
+
+        let history = (0..100)
+            .map(|index| history_entry(&format!("echo {index} {}", "x".repeat(60)), 0))
+            .collect::<Vec<_>>();
+        let block = format_recent_commands(&history).expect("block");
+        assert!(block.chars().count() <= RECENT_COMMANDS_MAX_CHARS);
+        assert!(block.ends_with(&format!("echo 99 {}", "x".repeat(60))));
+        assert!(!block.contains("echo 0 "));
+    }
+
+    #[test]
+    fn test_ai_profile_reports_latency_and_reply() {
+        let (base_url, captured_request_rx) =
+            start_mock_chat_server(r#"{"choices":[{"message":{"content":"OK"}}]}"#);
+        let state = AppState::new(temp_dir("test-ai-profile")).expect("create app state");
+        let saved = state
+            .storage
+            .save_ai_profile(AiProfileInput {
+                id: None,
+                name: "Probe".to_string(),
+                api_type: AiApiType::OpenAiChatCompletions,
+                base_url,
+                api_key: "test-api-key".to_string(),
+                model: "gpt-4o-mini".to_string(),
+                system_prompt: String::new(),
+                temperature: 0.2,
+                max_tokens: 256,
+                max_context_tokens: 32000,
+                tool_output_max_bytes: 16_384,
+                max_tool_steps: 8,
+                supports_json_mode: false,
+            })
+            .expect("save profile");
+        let profile_id = saved
+            .profiles
+            .iter()
+            .find(|item| item.name == "Probe")
+            .expect("new profile")
+            .id
+            .clone();
+
+        let result = tauri::async_runtime::block_on(test_ai_profile(&state, &profile_id))
+            .expect("test profile");
+        assert!(result.success);
+        assert_eq!(result.reply.as_deref(), Some("OK"));
+        assert!(result.failure.is_none());
+        let captured = captured_request_rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("captured request");
+        assert_eq!(captured.body_json["model"], "gpt-4o-mini");
+
+        let missing = tauri::async_runtime::block_on(test_ai_profile(&state, "missing"));
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+    }
+
+    #[test]
+    fn list_ai_models_uses_the_unsaved_profile_input() {
+        let (base_url, captured_request_rx) = start_mock_chat_server(
+            r#"{"object":"list","data":[{"id":"gpt-4o-mini"},{"id":"gpt-4o"}]}"#,
+        );
+        let listing = tauri::async_runtime::block_on(list_ai_models(AiProfileInput {
+            id: None,
+            name: "Draft".to_string(),
+            api_type: AiApiType::OpenAiResponses,
+            base_url: format!("{base_url}/"),
+            api_key: "test-api-key".to_string(),
+            model: String::new(),
+            system_prompt: String::new(),
+            temperature: 0.2,
+            max_tokens: 256,
+            max_context_tokens: 32000,
+            tool_output_max_bytes: 16_384,
+            max_tool_steps: 8,
+            supports_json_mode: false,
+        }))
+        .expect("list models");
+
+        assert!(listing.supported);
+        assert_eq!(
+            listing.models,
+            vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()]
+        );
+        let captured = captured_request_rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("captured request");
+        assert_eq!(captured.path, "/models");
+        assert_eq!(
+            captured.authorization.as_deref(),
+            Some("Bearer test-api-key")
+        );
+    }
+
+    #[test]
+    fn ask_ai_returns_cancelled_and_clears_the_request() {
+        // Accepts connections but never answers, so only cancellation can end the ask.
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind silent server");
+        let base_url = format!("http://{}", listener.local_addr().expect("addr"));
+        let state = Arc::new(AppState::new(temp_dir("ask-cancel")).expect("create app state"));
+        let saved = state
+            .storage
+            .save_ai_profile(AiProfileInput {
+                id: None,
+                name: "Silent".to_string(),
+                api_type: AiApiType::OpenAiChatCompletions,
+                base_url,
+                api_key: "test-api-key".to_string(),
+                model: "gpt-4o-mini".to_string(),
+                system_prompt: String::new(),
+                temperature: 0.2,
+                max_tokens: 256,
+                max_context_tokens: 32000,
+                tool_output_max_bytes: 16_384,
+                max_tool_steps: 8,
+                supports_json_mode: false,
+            })
+            .expect("save profile");
+        let profile_id = saved
+            .profiles
+            .iter()
+            .find(|item| item.name == "Silent")
+            .expect("new profile")
+            .id
+            .clone();
+        state
+            .storage
+            .set_active_ai_profile(&profile_id)
+            .expect("activate profile");
+
+        let canceller = {
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(250));
+                state.cancel_ai_ask("ask-1")
+            })
+        };
+        let result = tauri::async_runtime::block_on(ask_ai(
+            &state,
+            AiAskInput {
+                session_id: None,
+                question: "Is nginx running?".to_string(),
+                include_last_output: false,
+                include_recent_commands: None,
+                request_id: Some("ask-1".to_string()),
+            },
+        ));
+
+        assert!(canceller.join().expect("cancel thread"));
+        assert!(matches!(result, Err(AppError::Cancelled(_))));
+        assert!(!state.is_ai_ask_cancelled("ask-1"));
+        assert!(!state.cancel_ai_ask("ask-1"));
+        assert!(!state.is_ai_ask_cancelled("ask-1"));
+        drop(listener);
+    }
+
+    #[test]
+    fn classify_ai_test_failure_maps_provider_errors() {
+        let runtime = |message: &str| AppError::Runtime(message.to_string());
+        assert_eq!(
+            classify_ai_test_failure(&runtime(
+                "ops agent AI request failed: status=401 Unauthorized, body={}"
+            )),
+            AiProfileTestFailure::Auth
+        );
+        assert_eq!(
+            classify_ai_test_failure(&runtime(
+                "ops agent AI request failed: status=404 Not Found, body=The model `gpt-9` does not exist"
+            )),
+            AiProfileTestFailure::BadModel
+        );
+        assert_eq!(
+            classify_ai_test_failure(&runtime(
+                "ops agent AI request failed: status=404 Not Found, body="
+            )),
+            AiProfileTestFailure::BadUrl
+        );
+        assert_eq!(
+            classify_ai_test_failure(&runtime(
+                "ops agent AI request failed: expected JSON from http://10.0.0.8/chat/completions but got text/html; check baseUrl"
+            )),
+            AiProfileTestFailure::BadUrl
+        );
+        assert_eq!(
+            classify_ai_test_failure(&AppError::Validation("apiKey cannot be empty".to_string())),
+            AiProfileTestFailure::InvalidConfig
+        );
     }
 
     #[test]
@@ -415,6 +895,8 @@ mod tests {
                 session_id: None,
                 question: "Reply with one safe command to list current directory.".to_string(),
                 include_last_output: false,
+                include_recent_commands: None,
+                request_id: None,
             },
         ))
         .expect("live ask_ai");
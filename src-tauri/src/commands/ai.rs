@@ -3,8 +3,11 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::ai_service;
-use crate::error::to_command_error;
-use crate::models::{AiAnswer, AiAskInput};
+use crate::error::{to_command_error, CommandError};
+use crate::models::{
+    AiAnswer, AiAskHistoryEntry, AiAskInput, AiModelList, AiProfileInput, AiProfileTestResult,
+    CancelAiAskInput,
+};
 use crate::state::AppState;
 
 /// Sends question to the configured AI provider.
@@ -12,8 +15,57 @@ use crate::state::AppState;
 pub async fn ai_ask(
     state: State<'_, Arc<AppState>>,
     input: AiAskInput,
-) -> Result<AiAnswer, String> {
+) -> Result<AiAnswer, CommandError> {
     ai_service::ask_ai(&state, input)
         .await
         .map_err(to_command_error)
 }
+
+/// Aborts an in-flight `ai_ask` started with the same `requestId`.
+#[tauri::command]
+pub fn cancel_ai_ask(
+    state: State<'_, Arc<AppState>>,
+    input: CancelAiAskInput,
+) -> Result<bool, CommandError> {
+    Ok(state.cancel_ai_ask(&input.request_id))
+}
+
+/// Returns up to `limit` most recent `ai_ask` answers, oldest first.
+#[tauri::command]
+pub fn list_ai_ask_history(
+    state: State<'_, Arc<AppState>>,
+    limit: Option<usize>,
+) -> Result<Vec<AiAskHistoryEntry>, CommandError> {
+    state
+        .storage
+        .list_ai_ask_history(limit)
+        .map_err(to_command_error)
+}
+
+/// Drops all stored `ai_ask` answers and returns how many were removed.
+#[tauri::command]
+pub fn clear_ai_ask_history(state: State<'_, Arc<AppState>>) -> Result<usize, CommandError> {
+    state
+        .storage
+        .clear_ai_ask_history()
+        .map_err(to_command_error)
+}
+
+/// Sends a minimal request through one AI profile and reports latency or the failure kind.
+#[tauri::command]
+pub async fn test_ai_profile(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<AiProfileTestResult, CommandError> {
+    ai_service::test_ai_profile(&state, &id)
+        .await
+        .map_err(to_command_error)
+}
+
+/// Lists provider models for the profile form, or flags that listing is unsupported.
+#[tauri::command]
+pub async fn list_ai_models(input: AiProfileInput) -> Result<AiModelList, CommandError> {
+    ai_service::list_ai_models(input)
+        .await
+        .map_err(to_command_error)
+}
@@ -2,18 +2,20 @@ use std::sync::Arc;
 
 use tauri::State;
 
-use crate::error::to_command_error;
+use crate::error::{to_command_error, CommandError};
 use crate::models::{
     AgentContextContent, AgentContextInput, AiConfig, AiConfigInput, AiProfileInput,
-    AiProfilesState, SaveAgentContextInput, ScriptDefinition, ScriptInput, SetActiveAiProfileInput,
-    SetAiAgentModeInput, SetAiApprovalModeInput, SshConfig, SshConfigInput, SshKnownHost,
-    TrustSshHostKeyInput,
+    AiProfilesState, ImportSshConfigsInput, PurgeTrashResult, RedactionSettings,
+    RedactionSettingsInput, SaveAgentContextInput, ScriptDefinition, ScriptInput,
+    SetActiveAiProfileInput, SetAiAgentModeInput, SetAiApprovalModeInput, SshConfig,
+    SshConfigInput, SshKnownHost, SshPreset, StatusCommandProfile, StatusCommandProfileInput,
+    TrashedScript, TrashedSshConfig, TrustSshHostKeyInput,
 };
 use crate::state::AppState;
 
 /// Returns all stored SSH connection profiles.
 #[tauri::command]
-pub fn list_ssh_configs(state: State<'_, Arc<AppState>>) -> Result<Vec<SshConfig>, String> {
+pub fn list_ssh_configs(state: State<'_, Arc<AppState>>) -> Result<Vec<SshConfig>, CommandError> {
     Ok(state.storage.list_ssh_configs())
 }
 
@@ -22,52 +24,248 @@ pub fn list_ssh_configs(state: State<'_, Arc<AppState>>) -> Result<Vec<SshConfig
 pub fn save_ssh_config(
     state: State<'_, Arc<AppState>>,
     input: SshConfigInput,
-) -> Result<SshConfig, String> {
+) -> Result<SshConfig, CommandError> {
     state
         .storage
         .upsert_ssh_config(input)
         .map_err(to_command_error)
 }
 
-/// Deletes one SSH connection profile.
+/// Copies one SSH connection profile under a new id.
 #[tauri::command]
-pub fn delete_ssh_config(state: State<'_, Arc<AppState>>, id: String) -> Result<(), String> {
+pub fn duplicate_ssh_config(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<SshConfig, CommandError> {
+    state
+        .storage
+        .duplicate_ssh_config(&id)
+        .map_err(to_command_error)
+}
+
+/// Moves one SSH connection profile to the trash, or deletes it for good with `permanent`.
+#[tauri::command]
+pub fn delete_ssh_config(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    permanent: Option<bool>,
+) -> Result<(), CommandError> {
+    state
+        .storage
+        .delete_ssh_config(&id, permanent.unwrap_or(false))
+        .map_err(to_command_error)
+}
+
+/// Creates or updates several SSH connection profiles in one write.
+#[tauri::command]
+pub fn save_ssh_configs_batch(
+    state: State<'_, Arc<AppState>>,
+    inputs: Vec<SshConfigInput>,
+) -> Result<Vec<SshConfig>, CommandError> {
+    state
+        .storage
+        .save_ssh_configs_batch(inputs)
+        .map_err(to_command_error)
+}
+
+/// Deletes several SSH connection profiles in one write.
+#[tauri::command]
+pub fn delete_ssh_configs_batch(
+    state: State<'_, Arc<AppState>>,
+    ids: Vec<String>,
+    permanent: Option<bool>,
+) -> Result<Vec<SshConfig>, CommandError> {
+    state
+        .storage
+        .delete_ssh_configs_batch(&ids, permanent.unwrap_or(false))
+        .map_err(to_command_error)
+}
+
+/// Lists SSH connection profiles in the trash.
+#[tauri::command]
+pub fn list_trashed_ssh_configs(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<TrashedSshConfig>, CommandError> {
+    state
+        .storage
+        .list_trashed_ssh_configs()
+        .map_err(to_command_error)
+}
+
+/// Moves one SSH connection profile out of the trash.
+#[tauri::command]
+pub fn restore_ssh_config(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<SshConfig, CommandError> {
+    state
+        .storage
+        .restore_ssh_config(&id)
+        .map_err(to_command_error)
+}
+
+/// Exports all SSH connection profiles as JSON, optionally without secrets.
+#[tauri::command]
+pub fn export_ssh_configs(
+    state: State<'_, Arc<AppState>>,
+    redact_secrets: Option<bool>,
+) -> Result<String, CommandError> {
+    state
+        .storage
+        .export_ssh_configs(redact_secrets.unwrap_or(false))
+        .map_err(to_command_error)
+}
+
+/// Imports SSH connection profiles from an `export_ssh_configs` payload.
+#[tauri::command]
+pub fn import_ssh_configs(
+    state: State<'_, Arc<AppState>>,
+    input: ImportSshConfigsInput,
+) -> Result<Vec<SshConfig>, CommandError> {
     state
         .storage
-        .delete_ssh_config(&id)
+        .import_ssh_configs(&input.payload, input.merge_strategy)
+        .map_err(to_command_error)
+}
+
+/// Parses an OpenSSH client config into unsaved profile inputs for review.
+#[tauri::command]
+pub fn import_openssh_config(
+    state: State<'_, Arc<AppState>>,
+    text: String,
+) -> Result<Vec<SshConfigInput>, CommandError> {
+    Ok(state.storage.parse_openssh_config(&text))
+}
+
+/// Lists built-in connection presets for common cloud providers.
+#[tauri::command]
+pub fn list_ssh_presets(state: State<'_, Arc<AppState>>) -> Result<Vec<SshPreset>, CommandError> {
+    Ok(state.storage.list_ssh_presets())
+}
+
+/// Returns a pre-filled SSH config input from one preset for the editor.
+#[tauri::command]
+pub fn apply_ssh_preset(
+    state: State<'_, Arc<AppState>>,
+    preset_id: String,
+) -> Result<SshConfigInput, CommandError> {
+    state
+        .storage
+        .apply_ssh_preset(&preset_id)
         .map_err(to_command_error)
 }
 
 /// Lists all script definitions managed by user.
 #[tauri::command]
-pub fn list_scripts(state: State<'_, Arc<AppState>>) -> Result<Vec<ScriptDefinition>, String> {
+pub fn list_scripts(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ScriptDefinition>, CommandError> {
     Ok(state.storage.list_scripts())
 }
 
+/// Lists script definitions carrying one tag.
+#[tauri::command]
+pub fn list_scripts_by_tag(
+    state: State<'_, Arc<AppState>>,
+    tag: String,
+) -> Result<Vec<ScriptDefinition>, CommandError> {
+    Ok(state.storage.list_scripts_by_tag(&tag))
+}
+
+/// Lists the distinct tags used across scripts.
+#[tauri::command]
+pub fn list_script_tags(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, CommandError> {
+    Ok(state.storage.list_script_tags())
+}
+
 /// Creates or updates one script definition.
 #[tauri::command]
 pub fn save_script(
     state: State<'_, Arc<AppState>>,
     input: ScriptInput,
-) -> Result<ScriptDefinition, String> {
+) -> Result<ScriptDefinition, CommandError> {
     state.storage.upsert_script(input).map_err(to_command_error)
 }
 
-/// Deletes one script definition by id.
+/// Moves one script definition to the trash, or deletes it for good with `permanent`.
+#[tauri::command]
+pub fn delete_script(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+    permanent: Option<bool>,
+) -> Result<(), CommandError> {
+    state
+        .storage
+        .delete_script(&id, permanent.unwrap_or(false))
+        .map_err(to_command_error)
+}
+
+/// Creates or updates several script definitions in one write.
+#[tauri::command]
+pub fn save_scripts_batch(
+    state: State<'_, Arc<AppState>>,
+    inputs: Vec<ScriptInput>,
+) -> Result<Vec<ScriptDefinition>, CommandError> {
+    state
+        .storage
+        .save_scripts_batch(inputs)
+        .map_err(to_command_error)
+}
+
+/// Deletes several script definitions in one write.
 #[tauri::command]
-pub fn delete_script(state: State<'_, Arc<AppState>>, id: String) -> Result<(), String> {
-    state.storage.delete_script(&id).map_err(to_command_error)
+pub fn delete_scripts_batch(
+    state: State<'_, Arc<AppState>>,
+    ids: Vec<String>,
+    permanent: Option<bool>,
+) -> Result<Vec<ScriptDefinition>, CommandError> {
+    state
+        .storage
+        .delete_scripts_batch(&ids, permanent.unwrap_or(false))
+        .map_err(to_command_error)
+}
+
+/// Lists script definitions in the trash.
+#[tauri::command]
+pub fn list_trashed_scripts(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<TrashedScript>, CommandError> {
+    state
+        .storage
+        .list_trashed_scripts()
+        .map_err(to_command_error)
+}
+
+/// Moves one script definition out of the trash.
+#[tauri::command]
+pub fn restore_script(
+    state: State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<ScriptDefinition, CommandError> {
+    state.storage.restore_script(&id).map_err(to_command_error)
+}
+
+/// Permanently removes SSH profiles and scripts trashed at least `older_than_days` ago.
+#[tauri::command]
+pub fn purge_trash(
+    state: State<'_, Arc<AppState>>,
+    older_than_days: u32,
+) -> Result<PurgeTrashResult, CommandError> {
+    state
+        .storage
+        .purge_trash(older_than_days)
+        .map_err(to_command_error)
 }
 
 /// Returns AI provider configuration from persistent store.
 #[tauri::command]
-pub fn get_ai_config(state: State<'_, Arc<AppState>>) -> Result<AiConfig, String> {
+pub fn get_ai_config(state: State<'_, Arc<AppState>>) -> Result<AiConfig, CommandError> {
     Ok(state.storage.get_ai_config())
 }
 
 /// Returns all persisted AI profiles and active profile id.
 #[tauri::command]
-pub fn list_ai_profiles(state: State<'_, Arc<AppState>>) -> Result<AiProfilesState, String> {
+pub fn list_ai_profiles(state: State<'_, Arc<AppState>>) -> Result<AiProfilesState, CommandError> {
     Ok(state.storage.list_ai_profiles())
 }
 
@@ -76,7 +274,7 @@ pub fn list_ai_profiles(state: State<'_, Arc<AppState>>) -> Result<AiProfilesSta
 pub fn save_ai_profile(
     state: State<'_, Arc<AppState>>,
     input: AiProfileInput,
-) -> Result<AiProfilesState, String> {
+) -> Result<AiProfilesState, CommandError> {
     state
         .storage
         .save_ai_profile(input)
@@ -88,7 +286,7 @@ pub fn save_ai_profile(
 pub fn delete_ai_profile(
     state: State<'_, Arc<AppState>>,
     id: String,
-) -> Result<AiProfilesState, String> {
+) -> Result<AiProfilesState, CommandError> {
     state
         .storage
         .delete_ai_profile(&id)
@@ -100,7 +298,7 @@ pub fn delete_ai_profile(
 pub fn trust_ssh_host_key(
     state: State<'_, Arc<AppState>>,
     input: TrustSshHostKeyInput,
-) -> Result<SshKnownHost, String> {
+) -> Result<SshKnownHost, CommandError> {
     state
         .storage
         .trust_ssh_host_key(input)
@@ -112,7 +310,7 @@ pub fn trust_ssh_host_key(
 pub fn save_ai_approval_mode(
     state: State<'_, Arc<AppState>>,
     input: SetAiApprovalModeInput,
-) -> Result<AiProfilesState, String> {
+) -> Result<AiProfilesState, CommandError> {
     state
         .storage
         .save_ai_approval_mode(input.approval_mode)
@@ -124,7 +322,7 @@ pub fn save_ai_approval_mode(
 pub fn save_ai_agent_mode(
     state: State<'_, Arc<AppState>>,
     input: SetAiAgentModeInput,
-) -> Result<AiProfilesState, String> {
+) -> Result<AiProfilesState, CommandError> {
     state
         .storage
         .save_ai_agent_mode(input.agent_mode)
@@ -136,7 +334,7 @@ pub fn save_ai_agent_mode(
 pub fn get_agent_context(
     state: State<'_, Arc<AppState>>,
     input: AgentContextInput,
-) -> Result<AgentContextContent, String> {
+) -> Result<AgentContextContent, CommandError> {
     state
         .storage
         .get_agent_context(input.server_id.as_deref())
@@ -148,7 +346,7 @@ pub fn get_agent_context(
 pub fn save_agent_context(
     state: State<'_, Arc<AppState>>,
     input: SaveAgentContextInput,
-) -> Result<AgentContextContent, String> {
+) -> Result<AgentContextContent, CommandError> {
     state
         .storage
         .save_agent_context(input.server_id.as_deref(), &input.content)
@@ -160,7 +358,7 @@ pub fn save_agent_context(
 pub fn set_active_ai_profile(
     state: State<'_, Arc<AppState>>,
     input: SetActiveAiProfileInput,
-) -> Result<AiProfilesState, String> {
+) -> Result<AiProfilesState, CommandError> {
     state
         .storage
         .set_active_ai_profile(&input.id)
@@ -172,9 +370,49 @@ pub fn set_active_ai_profile(
 pub fn save_ai_config(
     state: State<'_, Arc<AppState>>,
     input: AiConfigInput,
-) -> Result<AiConfig, String> {
+) -> Result<AiConfig, CommandError> {
     state
         .storage
         .save_ai_config(input)
         .map_err(to_command_error)
 }
+
+/// Returns the regex patterns used to mask secrets in persisted terminal output.
+#[tauri::command]
+pub fn get_redaction_settings(
+    state: State<'_, Arc<AppState>>,
+) -> Result<RedactionSettings, CommandError> {
+    Ok(state.storage.get_redaction_settings())
+}
+
+/// Saves redaction patterns; omitting `patterns` restores the built-in set.
+#[tauri::command]
+pub fn save_redaction_settings(
+    state: State<'_, Arc<AppState>>,
+    input: RedactionSettingsInput,
+) -> Result<RedactionSettings, CommandError> {
+    state
+        .storage
+        .save_redaction_settings(input)
+        .map_err(to_command_error)
+}
+
+/// Returns the per-metric command overrides used by server status polling.
+#[tauri::command]
+pub fn get_status_command_profile(
+    state: State<'_, Arc<AppState>>,
+) -> Result<StatusCommandProfile, CommandError> {
+    Ok(state.storage.get_status_command_profile())
+}
+
+/// Saves per-metric command overrides used by server status polling.
+#[tauri::command]
+pub fn save_status_command_profile(
+    state: State<'_, Arc<AppState>>,
+    input: StatusCommandProfileInput,
+) -> Result<StatusCommandProfile, CommandError> {
+    state
+        .storage
+        .save_status_command_profile(input)
+        .map_err(to_command_error)
+}
@@ -2,16 +2,19 @@ use std::sync::Arc;
 
 use tauri::State;
 
-use crate::error::to_command_error;
+use crate::error::{to_command_error, CommandError};
 use crate::ops_agent::application as ops_agent_service;
 use crate::ops_agent::domain::types::{
     OpsAgentAttachmentContent, OpsAgentCancelRunInput, OpsAgentCancelRunResult,
-    OpsAgentChatAccepted, OpsAgentChatInput, OpsAgentCompactConversationInput,
+    OpsAgentChatAccepted, OpsAgentChatInput, OpsAgentChatResult, OpsAgentClearResolvedActionsInput,
+    OpsAgentClearResolvedActionsResult, OpsAgentCompactConversationInput,
     OpsAgentCompactConversationResult, OpsAgentConversation, OpsAgentConversationSummary,
-    OpsAgentCreateConversationInput, OpsAgentDeleteConversationInput,
-    OpsAgentGetAttachmentContentInput, OpsAgentGetConversationInput,
-    OpsAgentListPendingActionsInput, OpsAgentPendingAction, OpsAgentResolveActionInput,
-    OpsAgentResolveActionResult, OpsAgentSetActiveConversationInput,
+    OpsAgentCreateConversationInput, OpsAgentDeleteConversationInput, OpsAgentExportBundle,
+    OpsAgentGetAttachmentContentInput, OpsAgentGetConversationInput, OpsAgentImportAllInput,
+    OpsAgentImportAllResult, OpsAgentListConversationsInput, OpsAgentListPendingActionsInput,
+    OpsAgentPendingAction, OpsAgentRenameConversationInput, OpsAgentResolveActionInput,
+    OpsAgentResolveActionResult, OpsAgentRetryActionInput, OpsAgentSetActiveConversationInput,
+    OpsAgentSetArchivedInput, OpsAgentSetPinnedInput, OpsAgentSettings, OpsAgentSettingsInput,
 };
 use crate::state::AppState;
 
@@ -19,8 +22,13 @@ use crate::state::AppState;
 #[tauri::command]
 pub fn ops_agent_list_conversations(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<OpsAgentConversationSummary>, String> {
-    Ok(ops_agent_service::list_conversations(&state))
+    input: Option<OpsAgentListConversationsInput>,
+) -> Result<Vec<OpsAgentConversationSummary>, CommandError> {
+    let input = input.unwrap_or_default();
+    Ok(ops_agent_service::list_conversations(
+        &state,
+        input.include_archived,
+    ))
 }
 
 /// Creates one OpsAgent conversation.
@@ -28,11 +36,12 @@ pub fn ops_agent_list_conversations(
 pub fn ops_agent_create_conversation(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentCreateConversationInput,
-) -> Result<OpsAgentConversation, String> {
+) -> Result<OpsAgentConversation, CommandError> {
     ops_agent_service::create_conversation(
         &state,
         input.title.as_deref(),
         input.session_id.as_deref(),
+        input.ai_profile_id.as_deref(),
     )
     .map_err(to_command_error)
 }
@@ -42,7 +51,7 @@ pub fn ops_agent_create_conversation(
 pub fn ops_agent_get_conversation(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentGetConversationInput,
-) -> Result<OpsAgentConversation, String> {
+) -> Result<OpsAgentConversation, CommandError> {
     ops_agent_service::get_conversation(&state, &input.conversation_id).map_err(to_command_error)
 }
 
@@ -51,7 +60,7 @@ pub fn ops_agent_get_conversation(
 pub fn ops_agent_get_attachment_content(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentGetAttachmentContentInput,
-) -> Result<OpsAgentAttachmentContent, String> {
+) -> Result<OpsAgentAttachmentContent, CommandError> {
     ops_agent_service::get_attachment_content(&state, &input.attachment_id)
         .map_err(to_command_error)
 }
@@ -61,7 +70,7 @@ pub fn ops_agent_get_attachment_content(
 pub fn ops_agent_delete_conversation(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentDeleteConversationInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     ops_agent_service::delete_conversation(&state, &input.conversation_id).map_err(to_command_error)
 }
 
@@ -70,17 +79,64 @@ pub fn ops_agent_delete_conversation(
 pub fn ops_agent_set_active_conversation(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentSetActiveConversationInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     ops_agent_service::set_active_conversation(&state, &input.conversation_id)
         .map_err(to_command_error)
 }
 
+/// Returns OpsAgent settings such as the auto-title length and strategy.
+#[tauri::command]
+pub fn ops_agent_get_settings(
+    state: State<'_, Arc<AppState>>,
+) -> Result<OpsAgentSettings, CommandError> {
+    Ok(ops_agent_service::get_settings(&state))
+}
+
+/// Validates and persists OpsAgent settings.
+#[tauri::command]
+pub fn ops_agent_save_settings(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentSettingsInput,
+) -> Result<OpsAgentSettings, CommandError> {
+    ops_agent_service::save_settings(&state, input).map_err(to_command_error)
+}
+
+/// Renames one OpsAgent conversation; later messages no longer auto-title it.
+#[tauri::command]
+pub fn ops_agent_rename_conversation(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentRenameConversationInput,
+) -> Result<OpsAgentConversationSummary, CommandError> {
+    ops_agent_service::rename_conversation(&state, &input.conversation_id, &input.title)
+        .map_err(to_command_error)
+}
+
+/// Pins or unpins one OpsAgent conversation at the top of the list.
+#[tauri::command]
+pub fn ops_agent_set_pinned(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentSetPinnedInput,
+) -> Result<OpsAgentConversationSummary, CommandError> {
+    ops_agent_service::set_conversation_pinned(&state, &input.conversation_id, input.pinned)
+        .map_err(to_command_error)
+}
+
+/// Archives or restores one OpsAgent conversation.
+#[tauri::command]
+pub fn ops_agent_set_archived(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentSetArchivedInput,
+) -> Result<OpsAgentConversationSummary, CommandError> {
+    ops_agent_service::set_conversation_archived(&state, &input.conversation_id, input.archived)
+        .map_err(to_command_error)
+}
+
 /// Compacts one OpsAgent conversation history to reclaim context window.
 #[tauri::command]
 pub async fn ops_agent_compact_conversation(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentCompactConversationInput,
-) -> Result<OpsAgentCompactConversationResult, String> {
+) -> Result<OpsAgentCompactConversationResult, CommandError> {
     let app_state = Arc::clone(state.inner());
     ops_agent_service::compact_conversation(app_state, input)
         .await
@@ -93,42 +149,96 @@ pub fn ops_agent_chat_stream_start(
     state: State<'_, Arc<AppState>>,
     app: tauri::AppHandle,
     input: OpsAgentChatInput,
-) -> Result<OpsAgentChatAccepted, String> {
+) -> Result<OpsAgentChatAccepted, CommandError> {
     let app_state = Arc::clone(state.inner());
     ops_agent_service::start_chat_stream(app_state, app, input).map_err(to_command_error)
 }
 
+/// Runs one OpsAgent chat turn to completion and returns the final answer without streaming.
+#[tauri::command]
+pub async fn ops_agent_chat(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentChatInput,
+) -> Result<OpsAgentChatResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    ops_agent_service::chat(app_state, input)
+        .await
+        .map_err(to_command_error)
+}
+
 /// Lists pending/finished write-shell actions for approval UI.
 #[tauri::command]
 pub fn ops_agent_list_pending_actions(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentListPendingActionsInput,
-) -> Result<Vec<OpsAgentPendingAction>, String> {
+) -> Result<Vec<OpsAgentPendingAction>, CommandError> {
     Ok(ops_agent_service::list_pending_actions(
         &state,
         input.session_id.as_deref(),
         input.only_pending.unwrap_or(true),
+        input.offset,
+        input.limit,
     ))
 }
 
+/// Removes executed, rejected and failed actions, keeping pending ones.
+#[tauri::command]
+pub fn ops_agent_clear_resolved_actions(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentClearResolvedActionsInput,
+) -> Result<OpsAgentClearResolvedActionsResult, CommandError> {
+    ops_agent_service::clear_resolved_actions(
+        &state,
+        input.session_id.as_deref(),
+        input.older_than_days,
+    )
+    .map_err(to_command_error)
+}
+
 /// Approves or rejects one pending write-shell action.
 #[tauri::command]
 pub async fn ops_agent_resolve_action(
     state: State<'_, Arc<AppState>>,
     app: tauri::AppHandle,
     input: OpsAgentResolveActionInput,
-) -> Result<OpsAgentResolveActionResult, String> {
+) -> Result<OpsAgentResolveActionResult, CommandError> {
     let app_state = Arc::clone(state.inner());
     ops_agent_service::resolve_pending_action(app_state, Some(app), input)
         .await
         .map_err(to_command_error)
 }
 
+/// Sends one failed write-shell action back to the approval queue.
+#[tauri::command]
+pub fn ops_agent_retry_action(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentRetryActionInput,
+) -> Result<OpsAgentResolveActionResult, CommandError> {
+    ops_agent_service::retry_pending_action(&state, &input.action_id).map_err(to_command_error)
+}
+
 /// Cancels one running OpsAgent chat stream by run id.
 #[tauri::command]
 pub fn ops_agent_cancel_run(
     state: State<'_, Arc<AppState>>,
     input: OpsAgentCancelRunInput,
-) -> Result<OpsAgentCancelRunResult, String> {
+) -> Result<OpsAgentCancelRunResult, CommandError> {
     ops_agent_service::cancel_chat_run(&state, &input.run_id).map_err(to_command_error)
 }
+
+/// Exports all OpsAgent conversations, actions and the active pointer as one bundle.
+#[tauri::command]
+pub fn ops_agent_export_all(
+    state: State<'_, Arc<AppState>>,
+) -> Result<OpsAgentExportBundle, CommandError> {
+    ops_agent_service::export_all(&state).map_err(to_command_error)
+}
+
+/// Imports a bundle from `ops_agent_export_all`, replacing or merging with stored data.
+#[tauri::command]
+pub fn ops_agent_import_all(
+    state: State<'_, Arc<AppState>>,
+    input: OpsAgentImportAllInput,
+) -> Result<OpsAgentImportAllResult, CommandError> {
+    ops_agent_service::import_all(&state, input).map_err(to_command_error)
+}
@@ -7,20 +7,26 @@ mod server_ops;
 mod state;
 mod storage;
 
-#[cfg(not(test))]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(not(test))]
 use std::sync::Arc;
 
+#[cfg(not(test))]
+use tauri::Manager;
+
+#[cfg(not(test))]
+use ops_agent::infrastructure::logging::{append_debug_log_at_path, resolve_ops_agent_log_path};
+
 #[cfg(not(test))]
 use state::AppState;
 
 /// Application bootstrap entry.
 ///
 /// Runtime behavior:
-/// - Creates persistent storage under `.eshell-data` in current working directory.
+/// - Creates persistent storage under `ESHELL_DATA_DIR`, the platform config dir
+///   (`<config>/com.eee.eshell`) or, as a fallback, `.eshell-data` in the working directory.
 /// - Registers all Tauri commands used by frontend.
-/// - Starts Tauri event loop.
+/// - Starts Tauri event loop and closes all shell sessions when the app exits.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[cfg(not(test))]
 pub fn run() {
@@ -34,33 +40,92 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::config::list_ssh_configs,
             commands::config::save_ssh_config,
+            commands::config::duplicate_ssh_config,
             commands::config::delete_ssh_config,
+            commands::config::save_ssh_configs_batch,
+            commands::config::delete_ssh_configs_batch,
+            commands::config::list_trashed_ssh_configs,
+            commands::config::restore_ssh_config,
+            commands::config::export_ssh_configs,
+            commands::config::import_ssh_configs,
+            commands::config::import_openssh_config,
             commands::config::trust_ssh_host_key,
+            commands::config::list_ssh_presets,
+            commands::config::apply_ssh_preset,
             server_ops::commands::list_shell_sessions,
             server_ops::commands::open_shell_session,
+            server_ops::commands::restore_sessions,
             server_ops::commands::cancel_open_shell_session,
             server_ops::commands::close_shell_session,
+            server_ops::commands::shutdown_all_sessions,
             server_ops::commands::pty_write_input,
             server_ops::commands::pty_resize,
+            server_ops::commands::pty_send_signal,
+            server_ops::commands::get_pty_scrollback,
+            server_ops::commands::session_is_alive,
+            server_ops::commands::ping_session,
+            server_ops::commands::pty_start_recording,
+            server_ops::commands::pty_stop_recording,
             server_ops::commands::execute_shell_command,
+            server_ops::commands::execute_command_multi,
+            server_ops::commands::get_command_history,
+            server_ops::commands::diagnose_connection,
+            server_ops::commands::test_ssh_connection,
+            server_ops::commands::start_local_forward,
+            server_ops::commands::stop_forward,
             server_ops::commands::sftp_list_dir,
             server_ops::commands::sftp_read_file,
+            server_ops::commands::sftp_realpath,
+            server_ops::commands::remote_find,
+            server_ops::commands::sftp_dir_usage,
             server_ops::commands::sftp_write_file,
             server_ops::commands::sftp_create_file,
             server_ops::commands::sftp_create_directory,
             server_ops::commands::sftp_upload_file,
             server_ops::commands::sftp_delete_entry,
             server_ops::commands::sftp_upload_file_with_progress,
+            server_ops::commands::sftp_upload_verified,
+            server_ops::commands::sftp_checksum,
             server_ops::commands::sftp_download_file,
             server_ops::commands::sftp_download_file_to_local,
+            server_ops::commands::sftp_download_dir,
+            server_ops::commands::sftp_upload_dir,
+            server_ops::commands::sftp_copy,
             server_ops::commands::sftp_default_download_dir,
             server_ops::commands::sftp_cancel_transfer,
             server_ops::commands::fetch_server_status,
             server_ops::commands::get_cached_server_status,
+            server_ops::commands::start_status_polling,
+            server_ops::commands::stop_status_polling,
+            server_ops::commands::get_zombie_processes,
+            server_ops::commands::get_vmstat,
+            server_ops::commands::kill_process,
+            server_ops::commands::list_services,
+            server_ops::commands::start_service,
+            server_ops::commands::stop_service,
+            server_ops::commands::restart_service,
+            server_ops::commands::docker_list_containers,
+            server_ops::commands::docker_container_action,
+            server_ops::commands::watch_process,
+            server_ops::commands::stop_watch_process,
+            server_ops::commands::watch_file_start,
+            server_ops::commands::watch_file_stop,
             commands::config::list_scripts,
+            commands::config::list_scripts_by_tag,
+            commands::config::list_script_tags,
             commands::config::save_script,
             commands::config::delete_script,
+            commands::config::save_scripts_batch,
+            commands::config::delete_scripts_batch,
+            commands::config::list_trashed_scripts,
+            commands::config::restore_script,
+            commands::config::purge_trash,
             server_ops::commands::run_script,
+            server_ops::commands::preview_script,
+            server_ops::commands::schedule_script,
+            server_ops::commands::unschedule_script,
+            server_ops::commands::list_script_schedules,
+            server_ops::commands::upload_and_register_script,
             commands::config::get_ai_config,
             commands::config::list_ai_profiles,
             commands::config::save_ai_profile,
@@ -71,6 +136,10 @@ pub fn run() {
             commands::config::save_agent_context,
             commands::config::set_active_ai_profile,
             commands::config::save_ai_config,
+            commands::config::get_redaction_settings,
+            commands::config::save_redaction_settings,
+            commands::config::get_status_command_profile,
+            commands::config::save_status_command_profile,
             commands::ops_agent::ops_agent_list_conversations,
             commands::ops_agent::ops_agent_create_conversation,
             commands::ops_agent::ops_agent_get_conversation,
@@ -78,14 +147,35 @@ pub fn run() {
             commands::ops_agent::ops_agent_delete_conversation,
             commands::ops_agent::ops_agent_set_active_conversation,
             commands::ops_agent::ops_agent_compact_conversation,
+            commands::ops_agent::ops_agent_get_settings,
+            commands::ops_agent::ops_agent_save_settings,
+            commands::ops_agent::ops_agent_rename_conversation,
+            commands::ops_agent::ops_agent_set_pinned,
+            commands::ops_agent::ops_agent_set_archived,
             commands::ops_agent::ops_agent_chat_stream_start,
+            commands::ops_agent::ops_agent_chat,
             commands::ops_agent::ops_agent_list_pending_actions,
+            commands::ops_agent::ops_agent_clear_resolved_actions,
             commands::ops_agent::ops_agent_resolve_action,
+            commands::ops_agent::ops_agent_retry_action,
             commands::ops_agent::ops_agent_cancel_run,
-            commands::ai::ai_ask
+            commands::ops_agent::ops_agent_export_all,
+            commands::ops_agent::ops_agent_import_all,
+            commands::ai::ai_ask,
+            commands::ai::cancel_ai_ask,
+            commands::ai::list_ai_ask_history,
+            commands::ai::clear_ai_ask_history,
+            commands::ai::test_ai_profile,
+            commands::ai::list_ai_models
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { .. } | tauri::RunEvent::Exit = event {
+                let app_state = app.state::<Arc<AppState>>();
+                server_ops::shutdown_all_sessions(&app_state);
+            }
+        });
 }
 
 #[cfg(test)]
@@ -93,7 +183,71 @@ pub fn run() {}
 
 #[cfg(not(test))]
 fn resolve_storage_root() -> PathBuf {
-    std::env::current_dir()
-        .unwrap_or_else(|_| PathBuf::from("."))
-        .join(".eshell-data")
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let (root, source) = choose_storage_root(
+        std::env::var_os(DATA_DIR_ENV).map(PathBuf::from),
+        dirs::config_dir(),
+        &cwd,
+    );
+    append_debug_log_at_path(
+        &resolve_ops_agent_log_path(&root),
+        "info",
+        None,
+        None,
+        format!("eshell data directory: {} ({source})", root.display()),
+    );
+    root
+}
+
+const DATA_DIR_ENV: &str = "ESHELL_DATA_DIR";
+const APP_IDENTIFIER: &str = "com.eee.eshell";
+const LEGACY_DATA_DIR: &str = ".eshell-data";
+
+/// Picks the storage root: `ESHELL_DATA_DIR`, then the platform config dir, then cwd.
+///
+/// A `.eshell-data` left in cwd by older versions keeps being used until the
+/// platform directory exists, so upgrading does not hide existing profiles.
+fn choose_storage_root(
+    env_dir: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+    cwd: &Path,
+) -> (PathBuf, &'static str) {
+    if let Some(dir) = env_dir.filter(|dir| !dir.as_os_str().is_empty()) {
+        return (dir, DATA_DIR_ENV);
+    }
+    let legacy = cwd.join(LEGACY_DATA_DIR);
+    match config_dir.map(|dir| dir.join(APP_IDENTIFIER)) {
+        Some(platform) if platform.exists() || !legacy.exists() => {
+            (platform, "platform config dir")
+        }
+        Some(_) => (legacy, "legacy working directory data"),
+        None => (legacy, "working directory"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage_root_prefers_env_then_platform_then_cwd() {
+        let base = std::env::temp_dir().join(format!("eshell-root-{}", uuid::Uuid::new_v4()));
+        let cwd = base.join("cwd");
+        let config = base.join("config");
+
+        let (root, _) = choose_storage_root(Some(base.join("env")), Some(config.clone()), &cwd);
+        assert_eq!(root, base.join("env"));
+        let (root, _) = choose_storage_root(Some(PathBuf::new()), Some(config.clone()), &cwd);
+        assert_eq!(root, config.join(APP_IDENTIFIER));
+        let (root, _) = choose_storage_root(None, None, &cwd);
+        assert_eq!(root, cwd.join(LEGACY_DATA_DIR));
+
+        std::fs::create_dir_all(cwd.join(LEGACY_DATA_DIR)).expect("legacy dir");
+        let (root, _) = choose_storage_root(None, Some(config.clone()), &cwd);
+        assert_eq!(root, cwd.join(LEGACY_DATA_DIR));
+        std::fs::create_dir_all(config.join(APP_IDENTIFIER)).expect("platform dir");
+        let (root, _) = choose_storage_root(None, Some(config.clone()), &cwd);
+        assert_eq!(root, config.join(APP_IDENTIFIER));
+        let _ = std::fs::remove_dir_all(base);
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +12,16 @@ pub fn default_ai_max_context_tokens() -> u32 {
     100_000
 }
 
+/// Bytes of one tool output sent to the model before it is sampled down to head and tail.
+pub fn default_ai_tool_output_max_bytes() -> u32 {
+    16_384
+}
+
+/// Tool calls the Lite agent may chain in one run before it must answer.
+pub fn default_ai_max_tool_steps() -> u32 {
+    8
+}
+
 pub fn default_ai_approval_mode() -> AiApprovalMode {
     AiApprovalMode::RequireApproval
 }
@@ -51,6 +63,14 @@ pub struct SshConfig {
     pub private_key_passphrase: String,
     #[serde(default)]
     pub use_password_fallback: bool,
+    #[serde(default)]
+    pub sudo_tty_fallback: bool,
+    /// Password for `sudo` prompts: fed to approved agent `sudo` commands and to the
+    /// tty fallback. The login password is never used for sudo.
+    #[serde(default)]
+    pub sudo_password: String,
+    #[serde(default)]
+    pub jump_host: Option<SshJumpHost>,
     pub description: String,
     pub created_at: String,
     pub updated_at: String,
@@ -74,9 +94,66 @@ pub struct SshConfigInput {
     pub private_key_passphrase: String,
     #[serde(default)]
     pub use_password_fallback: bool,
+    #[serde(default)]
+    pub sudo_tty_fallback: bool,
+    /// Password fed to `sudo -S` when an approved agent command starts with `sudo`.
+    #[serde(default)]
+    pub sudo_password: String,
+    #[serde(default)]
+    pub jump_host: Option<SshJumpHost>,
     pub description: Option<String>,
 }
 
+/// SSH configuration moved to the trash by `delete_ssh_config`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedSshConfig {
+    pub config: SshConfig,
+    pub deleted_at: String,
+}
+
+/// How `import_ssh_configs` combines imported profiles with the stored ones.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SshImportMergeStrategy {
+    Merge,
+    Replace,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSshConfigsInput {
+    pub payload: String,
+    pub merge_strategy: SshImportMergeStrategy,
+}
+
+/// Bastion the target is reached through (a single hop).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshJumpHost {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    #[serde(default = "default_ssh_auth_type")]
+    pub auth_type: SshAuthType,
+    #[serde(default)]
+    pub password: String,
+    #[serde(default)]
+    pub private_key_path: String,
+    #[serde(default)]
+    pub private_key_passphrase: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshPreset {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub description: String,
+    pub defaults: SshConfigInput,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SshKnownHost {
@@ -122,9 +199,58 @@ pub struct ShellSession {
     pub config_id: String,
     pub config_name: String,
     pub current_dir: String,
+    /// Directory before the last `cd` that changed it; `cd -` switches back to it.
+    #[serde(default)]
+    pub previous_dir: Option<String>,
     pub last_output: String,
+    /// Kernel name reported by `uname -s` when the session was opened.
+    #[serde(default)]
+    pub remote_os: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    #[serde(flatten)]
+    pub handshake: SshHandshakeInfo,
+}
+
+/// What the server announced and negotiated during the SSH handshake.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHandshakeInfo {
+    #[serde(default)]
+    pub server_banner: Option<String>,
+    #[serde(default)]
+    pub host_key_type: Option<String>,
+    #[serde(default)]
+    pub kex_algorithm: Option<String>,
+    #[serde(default)]
+    pub cipher: Option<String>,
+}
+
+/// What `sessions.json` keeps of one open shell session so it can be reopened.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionManifestEntry {
+    pub id: String,
+    pub config_id: String,
+    pub current_dir: String,
+    #[serde(default)]
+    pub previous_dir: Option<String>,
+}
+
+/// Whether a shell session's SSH connection still accepts traffic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PingSessionResult {
+    pub session_id: String,
+    pub alive: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSessionsResult {
+    pub sessions: Vec<ShellSession>,
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +258,61 @@ pub struct ShellSession {
 pub struct ExecuteCommandInput {
     pub session_id: String,
     pub command: String,
+    /// Extra environment exported before the command runs.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Per-stream output cap; defaults to 10 MiB.
+    #[serde(default)]
+    pub max_output_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDiagnosticStep {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDiagnosticsReport {
+    pub config_id: String,
+    pub host: String,
+    pub port: u16,
+    pub success: bool,
+    pub steps: Vec<ConnectionDiagnosticStep>,
+    pub finished_at: String,
+}
+
+/// Outcome of `test_ssh_connection`; failures are returned as errors instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConnectionTestResult {
+    pub host: String,
+    pub port: u16,
+    pub handshake_ms: u128,
+    pub command_ms: u128,
+    #[serde(flatten)]
+    pub handshake: SshHandshakeInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteCommandMultiInput {
+    pub config_ids: Vec<String>,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostCommandResult {
+    pub config_id: String,
+    pub config_name: String,
+    pub host: String,
+    pub result: Option<CommandExecutionResult>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -146,6 +327,36 @@ pub struct CommandExecutionResult {
     pub started_at: String,
     pub finished_at: String,
     pub duration_ms: u128,
+    #[serde(default)]
+    pub tty_fallback_used: bool,
+    /// True when stdout or stderr hit the output cap and the command was cut off.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// One `execute_command` run as kept in session history, without its output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandHistoryEntry {
+    pub command: String,
+    pub exit_code: i32,
+    pub current_dir: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u128,
+}
+
+impl From<&CommandExecutionResult> for CommandHistoryEntry {
+    fn from(result: &CommandExecutionResult) -> Self {
+        Self {
+            command: result.command.clone(),
+            exit_code: result.exit_code,
+            current_dir: result.current_dir.clone(),
+            started_at: result.started_at.clone(),
+            finished_at: result.finished_at.clone(),
+            duration_ms: result.duration_ms,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -174,6 +385,32 @@ pub struct SftpListResponse {
     pub entries: Vec<SftpEntry>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFindInput {
+    pub session_id: String,
+    pub root: String,
+    /// `find -name` pattern, e.g. `*.conf`.
+    #[serde(default)]
+    pub name_glob: Option<String>,
+    /// When set, only files whose content matches this `grep` pattern are returned.
+    #[serde(default)]
+    pub content_grep: Option<String>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFindResult {
+    pub root: String,
+    pub entries: Vec<SftpEntry>,
+    /// More matches existed than `max_results`.
+    pub truncated: bool,
+    /// The search hit its time limit, so matches may be missing.
+    pub timed_out: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SftpListInput {
@@ -181,6 +418,13 @@ pub struct SftpListInput {
     pub path: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpRealpathInput {
+    pub session_id: String,
+    pub path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SftpReadInput {
@@ -221,6 +465,59 @@ pub struct SftpUploadWithProgressInput {
     pub local_name: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Sha256,
+    Sha1,
+    Md5,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpChecksumInput {
+    pub session_id: String,
+    pub path: String,
+    #[serde(default)]
+    pub algorithm: ChecksumAlgorithm,
+    /// Digest to compare against; fills `matches` in the result.
+    #[serde(default)]
+    pub expected: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpChecksumResult {
+    pub path: String,
+    pub algorithm: ChecksumAlgorithm,
+    pub hash: String,
+    /// `None` when no `expected` digest was given.
+    pub matches: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpUploadVerifiedInput {
+    pub session_id: String,
+    pub local_path: String,
+    pub remote_path: String,
+    #[serde(default)]
+    pub algorithm: ChecksumAlgorithm,
+    #[serde(default)]
+    pub delete_on_mismatch: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpUploadVerifiedResult {
+    pub remote_path: String,
+    pub size: u64,
+    pub algorithm: ChecksumAlgorithm,
+    pub local_hash: String,
+    pub remote_hash: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SftpDownloadInput {
@@ -230,123 +527,619 @@ pub struct SftpDownloadInput {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpDownloadToLocalInput {
+pub struct SftpDownloadToLocalInput {
+    pub session_id: String,
+    pub remote_path: String,
+    pub local_dir: String,
+    pub transfer_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpDownloadDirInput {
+    pub session_id: String,
+    pub remote_dir: String,
+    /// Archive file written in tar mode; the SFTP fallback mirrors the tree
+    /// into a directory of the same name without the `.tar.gz`/`.tgz` suffix.
+    pub local_path: String,
+    pub transfer_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpUploadDirInput {
+    pub session_id: String,
+    pub local_dir: String,
+    /// Receives the contents of `local_dir`; created when missing.
+    pub remote_dir: String,
+    pub transfer_id: String,
+    /// Adds execute bits remotely for files that are executable locally (Unix only).
+    #[serde(default)]
+    pub preserve_executable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpUploadDirResult {
+    pub transfer_id: String,
+    pub remote_dir: String,
+    pub local_dir: String,
+    pub files_transferred: usize,
+    /// Directories that did not exist remotely before the upload.
+    pub directories_created: usize,
+    pub bytes: u64,
+}
+
+/// How a directory download was carried out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SftpDirDownloadMode {
+    /// `tar -czf -` streamed over one exec channel.
+    Tar,
+    /// Per-file SFTP walk, used when the remote host has no `tar`.
+    Sftp,
+}
+
+impl SftpDirDownloadMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tar => "tar",
+            Self::Sftp => "sftp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpDirDownloadResult {
+    pub transfer_id: String,
+    pub mode: SftpDirDownloadMode,
+    pub remote_dir: String,
+    pub local_path: String,
+    /// Compressed archive size in tar mode, total file bytes in SFTP mode.
+    pub size: u64,
+    /// Only known in SFTP mode.
+    pub file_count: Option<usize>,
+    /// Set when tar finished with warnings (exit code 1), e.g. files changed while read.
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpCopyInput {
+    pub session_id: String,
+    pub src: String,
+    /// Full path of the new file; must not exist yet.
+    pub dest: String,
+}
+
+/// Which path a remote copy took.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SftpCopyMethod {
+    /// `cp -p` on the remote host.
+    Cp,
+    /// SFTP read and write, used when `cp` fails or produces a short file.
+    Sftp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpCopyResult {
+    pub src: String,
+    pub dest: String,
+    pub size: u64,
+    pub method: SftpCopyMethod,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpDeleteInput {
+    pub session_id: String,
+    pub path: String,
+    pub entry_type: SftpEntryType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpCancelTransferInput {
+    pub transfer_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpFileContent {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpDownloadPayload {
+    pub path: String,
+    pub file_name: String,
+    pub content_base64: String,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpTransferResult {
+    pub transfer_id: String,
+    pub direction: String,
+    pub remote_path: String,
+    pub local_path: String,
+    pub file_name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpTransferEvent {
+    pub transfer_id: String,
+    pub session_id: String,
+    pub direction: String,
+    pub stage: String,
+    pub remote_path: String,
+    pub local_path: Option<String>,
+    pub file_name: String,
+    pub transferred_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub percent: f64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStatus {
+    pub used_mb: f64,
+    pub total_mb: f64,
+    /// Derived from `available_mb` when the source reports it.
+    pub used_percent: f64,
+    #[serde(default)]
+    pub available_mb: Option<f64>,
+    #[serde(default)]
+    pub swap_used_mb: Option<f64>,
+    #[serde(default)]
+    pub swap_total_mb: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterfaceStatus {
+    pub interface: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// Receive throughput in bytes/sec, derived from the previous cached sample.
+    #[serde(default)]
+    pub rx_rate_bps: f64,
+    /// Transmit throughput in bytes/sec, derived from the previous cached sample.
+    #[serde(default)]
+    pub tx_rate_bps: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessStatus {
+    pub pid: i32,
+    /// Owning user; absent when a custom process command omits the column.
+    #[serde(default)]
+    pub user: Option<String>,
+    pub cpu_percent: f64,
+    /// `%MEM` as reported by `ps`.
+    pub memory_percent: f64,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZombieProcess {
+    pub pid: i32,
+    pub ppid: i32,
+    pub stat: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskStatus {
+    pub filesystem: String,
+    pub mount_point: String,
+    pub used: String,
+    pub total: String,
+    pub used_percent: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsageInput {
+    pub session_id: String,
+    pub path: String,
+    /// How many levels below `path` to report; defaults to 1.
+    #[serde(default)]
+    pub depth: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsageEntry {
+    pub path: String,
+    /// Size as printed by `du -h`, e.g. `1.5G`.
+    pub size: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirUsageResult {
+    pub path: String,
+    /// Largest first.
+    pub entries: Vec<DirUsageEntry>,
+    /// `du` complaints such as unreadable directories; their sizes are left out.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuStatus {
+    pub index: u32,
+    pub name: String,
+    /// `None` when the driver reports `[N/A]`.
+    pub utilization_percent: Option<f64>,
+    pub memory_used_mb: Option<f64>,
+    pub memory_total_mb: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmStat {
+    pub runnable: u64,
+    pub blocked: u64,
+    pub swap_used_kb: u64,
+    pub free_kb: u64,
+    pub buffer_kb: u64,
+    pub cache_kb: u64,
+    pub swap_in: u64,
+    pub swap_out: u64,
+    pub blocks_in: u64,
+    pub blocks_out: u64,
+    pub interrupts: u64,
+    pub context_switches: u64,
+    pub cpu_user: u64,
+    pub cpu_system: u64,
+    pub cpu_idle: u64,
+    pub cpu_wait: u64,
+    pub cpu_steal: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum KillSignal {
+    #[default]
+    Term,
+    Kill,
+}
+
+impl KillSignal {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Term => "TERM",
+            Self::Kill => "KILL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillProcessInput {
+    pub session_id: String,
+    pub pid: i64,
+    #[serde(default)]
+    pub signal: KillSignal,
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerContainer {
+    pub id: String,
+    pub image: String,
+    pub name: String,
+    pub state: String,
+    pub status: String,
+    pub ports: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DockerContainerAction {
+    Start,
+    Stop,
+    Restart,
+    Logs,
+}
+
+impl DockerContainerAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+            Self::Logs => "logs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerContainerActionInput {
+    pub session_id: String,
+    pub container_id: String,
+    pub action: DockerContainerAction,
+    pub tail: Option<u32>,
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatus {
+    pub name: String,
+    pub load_state: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceActionInput {
+    pub session_id: String,
+    pub service: String,
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub cpu_percent: f64,
+    #[serde(default)]
+    pub per_core: Vec<f64>,
+    pub memory: MemoryStatus,
+    pub network_interfaces: Vec<NetworkInterfaceStatus>,
+    pub selected_interface: Option<String>,
+    pub selected_interface_traffic: Option<NetworkInterfaceStatus>,
+    pub top_processes: Vec<ProcessStatus>,
+    pub disks: Vec<DiskStatus>,
+    /// NVIDIA GPUs reported by `nvidia-smi`; empty when the tool is absent.
+    #[serde(default)]
+    pub gpus: Vec<GpuStatus>,
+    pub fetched_at: String,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCommandProfile {
+    #[serde(default)]
+    pub top_command: Option<String>,
+    #[serde(default)]
+    pub process_command: Option<String>,
+    #[serde(default)]
+    pub disk_command: Option<String>,
+    #[serde(default)]
+    pub network_command: Option<String>,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCommandProfileInput {
+    pub top_command: Option<String>,
+    pub process_command: Option<String>,
+    pub disk_command: Option<String>,
+    pub network_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartStatusPollingInput {
+    pub session_id: String,
+    pub interval_secs: u64,
+    pub selected_interface: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusEvent {
+    pub session_id: String,
+    pub status: Option<ServerStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartLocalForwardInput {
+    pub session_id: String,
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    pub forward_id: String,
+    pub session_id: String,
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PortForwardStatus {
+    Bound,
+    Failed,
+    Closed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForwardEvent {
+    pub forward_id: String,
+    pub session_id: String,
+    pub status: PortForwardStatus,
+    pub local_port: u16,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchServerStatusInput {
+    pub session_id: String,
+    pub selected_interface: Option<String>,
+    /// Keep tmpfs, overlay, snap loops and other virtual mounts in `disks`.
+    #[serde(default)]
+    pub include_pseudo_fs: bool,
+    /// Number of `top_processes` rows; defaults to 4.
+    #[serde(default)]
+    pub process_limit: Option<u32>,
+    #[serde(default)]
+    pub process_sort: ProcessSort,
+}
+
+/// Column `top_processes` is ordered by.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSort {
+    #[default]
+    Cpu,
+    Mem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchProcessInput {
     pub session_id: String,
-    pub remote_path: String,
-    pub local_dir: String,
-    pub transfer_id: String,
+    pub pid_or_name: String,
+    /// Seconds between two samples.
+    pub interval: u64,
+    pub samples: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpDeleteInput {
+pub struct WatchProcessStarted {
+    pub watch_id: String,
     pub session_id: String,
-    pub path: String,
-    pub entry_type: SftpEntryType,
+    pub pid: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpCancelTransferInput {
-    pub transfer_id: String,
+pub struct StopWatchProcessInput {
+    pub watch_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpFileContent {
+pub struct WatchFileInput {
+    pub session_id: String,
     pub path: String,
-    pub content: String,
+    /// Seconds between two `stat` calls.
+    pub interval_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpDownloadPayload {
+pub struct FileWatch {
+    pub watch_id: String,
+    pub session_id: String,
     pub path: String,
-    pub file_name: String,
-    pub content_base64: String,
-    pub size: usize,
+    pub interval_secs: u64,
+    pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The parts of a remote `stat` that a file watch compares.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpTransferResult {
-    pub transfer_id: String,
-    pub direction: String,
-    pub remote_path: String,
-    pub local_path: String,
-    pub file_name: String,
+pub struct FileSnapshot {
     pub size: u64,
+    pub modified_at: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SftpTransferEvent {
-    pub transfer_id: String,
+pub struct FileChangedEvent {
+    pub watch_id: String,
     pub session_id: String,
-    pub direction: String,
-    pub stage: String,
-    pub remote_path: String,
-    pub local_path: Option<String>,
-    pub file_name: String,
-    pub transferred_bytes: u64,
-    pub total_bytes: Option<u64>,
-    pub percent: f64,
-    pub message: Option<String>,
+    pub path: String,
+    pub previous: FileSnapshot,
+    pub current: FileSnapshot,
 }
 
+/// Sent once when a watched file disappears; the watch stops afterwards.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MemoryStatus {
-    pub used_mb: f64,
-    pub total_mb: f64,
-    pub used_percent: f64,
+pub struct FileRemovedEvent {
+    pub watch_id: String,
+    pub session_id: String,
+    pub path: String,
+    pub last_seen: FileSnapshot,
 }
 
+/// Sent once when a watched file can no longer be stat'd for a reason other than
+/// removal (permissions, broken connection); the watch stops afterwards.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct NetworkInterfaceStatus {
-    pub interface: String,
-    pub rx_bytes: u64,
-    pub tx_bytes: u64,
+pub struct FileWatchErrorEvent {
+    pub watch_id: String,
+    pub session_id: String,
+    pub path: String,
+    pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct ProcessStatus {
+pub struct ProcessSample {
     pub pid: i32,
     pub cpu_percent: f64,
-    pub memory_mb: f64,
-    pub command: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct DiskStatus {
-    pub filesystem: String,
-    pub mount_point: String,
-    pub used: String,
-    pub total: String,
-    pub used_percent: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ServerStatus {
-    pub cpu_percent: f64,
-    pub memory: MemoryStatus,
-    pub network_interfaces: Vec<NetworkInterfaceStatus>,
-    pub selected_interface: Option<String>,
-    pub selected_interface_traffic: Option<NetworkInterfaceStatus>,
-    pub top_processes: Vec<ProcessStatus>,
-    pub disks: Vec<DiskStatus>,
-    pub fetched_at: String,
+    pub memory_percent: f64,
+    pub rss_kb: u64,
+    pub vsz_kb: u64,
+    pub sampled_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct FetchServerStatusInput {
+pub struct ProcessSampleEvent {
+    pub watch_id: String,
     pub session_id: String,
-    pub selected_interface: Option<String>,
+    pub pid: i32,
+    /// One of `sample`, `exited`, `completed`, `stopped` or `failed`.
+    pub stage: String,
+    pub samples: Vec<ProcessSample>,
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -359,10 +1152,32 @@ pub struct ScriptDefinition {
     pub description: String,
     #[serde(default)]
     pub parameters: Vec<ScriptParameter>,
+    #[serde(default)]
+    pub default_args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Script definition moved to the trash by `delete_script`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedScript {
+    pub script: ScriptDefinition,
+    pub deleted_at: String,
+}
+
+/// Number of trash entries removed by `purge_trash`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeTrashResult {
+    pub ssh_configs: usize,
+    pub scripts: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptParameter {
@@ -381,6 +1196,13 @@ pub fn default_script_parameter_quote() -> bool {
     true
 }
 
+/// Environment variable names accepted for scripts: `[A-Za-z_][A-Za-z0-9_]*`.
+pub fn is_valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(first) if first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ScriptInput {
@@ -391,6 +1213,23 @@ pub struct ScriptInput {
     pub description: Option<String>,
     #[serde(default)]
     pub parameters: Vec<ScriptParameter>,
+    #[serde(default)]
+    pub default_args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadAndRegisterScriptInput {
+    pub session_id: String,
+    pub name: String,
+    pub remote_path: String,
+    pub content_base64: String,
+    #[serde(default)]
+    pub make_executable: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -398,6 +1237,19 @@ pub struct ScriptInput {
 pub struct RunScriptInput {
     pub session_id: String,
     pub script_id: String,
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+    /// Extra environment for this run, on top of the script's own `env`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewScriptInput {
+    pub script_id: String,
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -408,6 +1260,40 @@ pub struct RunScriptResult {
     pub execution: CommandExecutionResult,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleScriptInput {
+    pub session_id: String,
+    pub script_id: String,
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSchedule {
+    pub schedule_id: String,
+    pub session_id: String,
+    pub script_id: String,
+    pub script_name: String,
+    pub interval_secs: u64,
+    pub running: bool,
+    pub skipped_ticks: u64,
+    pub last_run_at: Option<String>,
+    pub last_result: Option<RunScriptResult>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledScriptResultEvent {
+    pub schedule_id: String,
+    pub session_id: String,
+    pub script_id: String,
+    pub result: Option<RunScriptResult>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum AiApprovalMode {
@@ -460,6 +1346,37 @@ impl AiApiType {
     }
 }
 
+/// Why a `test_ai_profile` request failed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AiProfileTestFailure {
+    InvalidConfig,
+    Auth,
+    Network,
+    BadModel,
+    BadUrl,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiProfileTestResult {
+    pub profile_id: String,
+    pub success: bool,
+    pub latency_ms: u128,
+    pub reply: Option<String>,
+    pub failure: Option<AiProfileTestFailure>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AiModelList {
+    pub models: Vec<String>,
+    /// False when the provider has no models endpoint; the UI keeps free-text entry.
+    pub supported: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AiConfig {
@@ -473,6 +1390,13 @@ pub struct AiConfig {
     pub max_tokens: u32,
     #[serde(default = "default_ai_max_context_tokens")]
     pub max_context_tokens: u32,
+    #[serde(default = "default_ai_tool_output_max_bytes")]
+    pub tool_output_max_bytes: u32,
+    #[serde(default = "default_ai_max_tool_steps")]
+    pub max_tool_steps: u32,
+    /// Provider honours `response_format: json_object` for structured decisions.
+    #[serde(default)]
+    pub supports_json_mode: bool,
     #[serde(default = "default_ai_approval_mode")]
     pub approval_mode: AiApprovalMode,
     #[serde(default = "default_ai_agent_mode")]
@@ -492,6 +1416,9 @@ impl Default for AiConfig {
             temperature: 0.2,
             max_tokens: 800,
             max_context_tokens: default_ai_max_context_tokens(),
+            tool_output_max_bytes: default_ai_tool_output_max_bytes(),
+            max_tool_steps: default_ai_max_tool_steps(),
+            supports_json_mode: false,
             approval_mode: default_ai_approval_mode(),
             agent_mode: default_ai_agent_mode(),
             updated_at: now_rfc3339(),
@@ -512,6 +1439,13 @@ pub struct AiConfigInput {
     pub max_tokens: u32,
     #[serde(default = "default_ai_max_context_tokens")]
     pub max_context_tokens: u32,
+    #[serde(default = "default_ai_tool_output_max_bytes")]
+    pub tool_output_max_bytes: u32,
+    #[serde(default = "default_ai_max_tool_steps")]
+    pub max_tool_steps: u32,
+    /// Provider honours `response_format: json_object` for structured decisions.
+    #[serde(default)]
+    pub supports_json_mode: bool,
     #[serde(default = "default_ai_approval_mode")]
     pub approval_mode: AiApprovalMode,
     #[serde(default = "default_ai_agent_mode")]
@@ -533,6 +1467,13 @@ pub struct AiProfile {
     pub max_tokens: u32,
     #[serde(default = "default_ai_max_context_tokens")]
     pub max_context_tokens: u32,
+    #[serde(default = "default_ai_tool_output_max_bytes")]
+    pub tool_output_max_bytes: u32,
+    #[serde(default = "default_ai_max_tool_steps")]
+    pub max_tool_steps: u32,
+    /// Provider honours `response_format: json_object` for structured decisions.
+    #[serde(default)]
+    pub supports_json_mode: bool,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -552,6 +1493,13 @@ pub struct AiProfileInput {
     pub max_tokens: u32,
     #[serde(default = "default_ai_max_context_tokens")]
     pub max_context_tokens: u32,
+    #[serde(default = "default_ai_tool_output_max_bytes")]
+    pub tool_output_max_bytes: u32,
+    #[serde(default = "default_ai_max_tool_steps")]
+    pub max_tool_steps: u32,
+    /// Provider honours `response_format: json_object` for structured decisions.
+    #[serde(default)]
+    pub supports_json_mode: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -600,6 +1548,57 @@ pub struct SaveAgentContextInput {
     pub content: String,
 }
 
+/// Regex patterns used to mask secrets in terminal output before it is persisted.
+///
+/// A pattern with a named `secret` group only masks that group; otherwise the
+/// whole match is masked.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSettings {
+    #[serde(default = "default_redaction_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_redaction_patterns")]
+    pub patterns: Vec<String>,
+    #[serde(default = "now_rfc3339")]
+    pub updated_at: String,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_redaction_enabled(),
+            patterns: default_redaction_patterns(),
+            updated_at: now_rfc3339(),
+        }
+    }
+}
+
+pub fn default_redaction_enabled() -> bool {
+    true
+}
+
+pub fn default_redaction_patterns() -> Vec<String> {
+    [
+        r"(?i)(?:password|passwd|passphrase|secret|token|api[_-]?key)[ \t]*[:=][ \t]*(?P<secret>\S+)",
+        r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9._~+/-]+=*)",
+        r"\bAKIA[0-9A-Z]{16}\b",
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        r"\bsk-[A-Za-z0-9_-]{20,}\b",
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+    ]
+    .iter()
+    .map(|pattern| pattern.to_string())
+    .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSettingsInput {
+    pub enabled: bool,
+    /// `None` restores the built-in pattern set.
+    pub patterns: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentContextContent {
@@ -615,13 +1614,42 @@ pub struct AiAskInput {
     pub session_id: Option<String>,
     pub question: String,
     pub include_last_output: bool,
+    /// Appends up to this many of the session's latest commands and exit codes.
+    #[serde(default)]
+    pub include_recent_commands: Option<usize>,
+    /// Client-chosen id that `cancel_ai_ask` can use to abort this request.
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+/// One answered `ai_ask`, kept in `ai_ask_history.json` for the Q&A recall list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiAskHistoryEntry {
+    pub question: String,
+    pub answer: String,
+    #[serde(default)]
+    pub suggested_commands: Vec<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAiAskInput {
+    pub request_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AiAnswer {
     pub answer: String,
+    /// The first entry of `suggested_commands`.
     pub suggested_command: Option<String>,
+    /// Every runnable command in the answer, in the order it appears.
+    #[serde(default)]
+    pub suggested_commands: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -629,6 +1657,26 @@ pub struct AiAnswer {
 pub struct OpenShellInput {
     pub config_id: String,
     pub request_id: Option<String>,
+    #[serde(default)]
+    pub scrollback_bytes: Option<usize>,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Directory to `cd` into instead of the login directory; must exist.
+    #[serde(default)]
+    pub initial_dir: Option<String>,
+    /// Typed into the shell once it starts, e.g. `tmux attach`.
+    #[serde(default)]
+    pub initial_command: Option<String>,
+    /// `TERM` requested for the PTY; defaults to `xterm-256color`.
+    #[serde(default)]
+    pub term: Option<String>,
+    #[serde(default)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    pub rows: Option<u16>,
+    /// Reopen the PTY in the same directory when the connection drops.
+    #[serde(default)]
+    pub auto_reconnect: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -648,6 +1696,41 @@ pub struct CancelShellConnectionInput {
 pub struct PtyWriteInput {
     pub session_id: String,
     pub data: String,
+    /// Marks the data as a paste so multi-line text is not run line by line.
+    #[serde(default)]
+    pub bracketed: bool,
+}
+
+/// Terminal control keys the UI can send without knowing their byte values.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PtySignal {
+    /// Ctrl-C.
+    Interrupt,
+    /// Ctrl-\.
+    Quit,
+    /// Ctrl-Z.
+    Suspend,
+    /// Ctrl-D.
+    Eof,
+}
+
+impl PtySignal {
+    pub fn control_byte(self) -> u8 {
+        match self {
+            Self::Interrupt => 0x03,
+            Self::Quit => 0x1c,
+            Self::Suspend => 0x1a,
+            Self::Eof => 0x04,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtySignalInput {
+    pub session_id: String,
+    pub signal: PtySignal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -658,9 +1741,77 @@ pub struct PtyResizeInput {
     pub rows: u16,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyScrollbackInput {
+    pub session_id: String,
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyStartRecordingInput {
+    pub session_id: String,
+    pub local_path: String,
+    /// Also records keystrokes. Off by default: typed passwords cannot be redacted.
+    #[serde(default)]
+    pub record_input: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyStopRecordingInput {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyRecordingInfo {
+    pub session_id: String,
+    pub local_path: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub started_at: String,
+    pub records_input: bool,
+    /// Set when input is recorded, since typed passwords slip past redaction.
+    pub warning: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PtyOutputEvent {
     pub session_id: String,
     pub chunk: String,
 }
+
+/// Why a PTY worker stopped.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PtyCloseReason {
+    /// The session was closed or replaced from the app.
+    Closed,
+    /// The remote shell exited.
+    Eof,
+    /// Reading from or writing to the channel failed.
+    Error,
+}
+
+/// Progress of reopening a dropped PTY, emitted as `pty-reconnecting`,
+/// `pty-reconnected` and, once retries run out, `pty-reconnect-failed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyReconnectEvent {
+    pub session_id: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// Why the previous attempt, or the original connection, failed.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyClosedEvent {
+    pub session_id: String,
+    pub reason: PtyCloseReason,
+    pub detail: Option<String>,
+}
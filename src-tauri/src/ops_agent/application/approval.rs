@@ -7,7 +7,7 @@ use crate::error::{AppError, AppResult};
 use crate::ops_agent::core::runtime::{spawn_chat_run_task, OpsAgentChatRunTask};
 use crate::ops_agent::domain::types::{
     OpsAgentActionStatus, OpsAgentExecutorResume, OpsAgentPendingAction,
-    OpsAgentResolveActionInput, OpsAgentResolveActionResult, OpsAgentRunResume,
+    OpsAgentResolveActionInput, OpsAgentResolveActionResult, OpsAgentRole, OpsAgentRunResume,
 };
 use crate::ops_agent::infrastructure::logging::append_debug_log;
 use crate::ops_agent::tools::OpsAgentToolResolveRequest;
@@ -147,6 +147,36 @@ pub async fn resolve_pending_action(
     })
 }
 
+/// Re-queues a failed action so it goes through `resolve_pending_action` again.
+pub fn retry_pending_action(
+    state: &AppState,
+    action_id: &str,
+) -> AppResult<OpsAgentResolveActionResult> {
+    let action = state.ops_agent.reset_action_for_retry(action_id)?;
+    state.ops_agent.append_message(
+        &action.conversation_id,
+        OpsAgentRole::Tool,
+        &format!(
+            "Retry requested for failed action `{}`; it is waiting for approval again.",
+            action.command
+        ),
+        Some(action.tool_kind.clone()),
+        None,
+        Vec::new(),
+    )?;
+    append_debug_log(
+        state,
+        "application.approval.retry",
+        None,
+        Some(action.conversation_id.as_str()),
+        format!("action_id={} tool={}", action.id, action.tool_kind),
+    );
+    Ok(OpsAgentResolveActionResult {
+        action,
+        note: "Action queued for retry".to_string(),
+    })
+}
+
 fn maybe_resume_executor_after_action_resolution(
     state: Arc<AppState>,
     app: AppHandle,
@@ -229,19 +259,21 @@ fn maybe_resume_executor_after_action_resolution(
     );
     let resume_session_id =
         normalize_session_id(resume_session_id_override).or_else(|| action.session_id.clone());
-    spawn_chat_run_task(OpsAgentChatRunTask {
-        state: Arc::clone(&state),
+    spawn_chat_run_task(
         app,
-        run_id,
-        conversation_id: action.conversation_id.clone(),
-        session_id: resume_session_id,
-        current_user_message_id: source_user_message_id,
-        run_handle,
-        resume: Some(OpsAgentRunResume::Executor(OpsAgentExecutorResume {
-            context: resume_context,
-            resolved_action: action.clone(),
-        })),
-    });
+        OpsAgentChatRunTask {
+            state: Arc::clone(&state),
+            run_id,
+            conversation_id: action.conversation_id.clone(),
+            session_id: resume_session_id,
+            current_user_message_id: source_user_message_id,
+            run_handle,
+            resume: Some(OpsAgentRunResume::Executor(OpsAgentExecutorResume {
+                context: resume_context,
+                resolved_action: action.clone(),
+            })),
+        },
+    );
 }
 
 fn normalize_resolution_comment(value: Option<&str>) -> Option<String> {
@@ -261,4 +293,3 @@ fn normalize_session_id(value: Option<&str>) -> Option<String> {
         Some(trimmed.to_string())
     }
 }
-
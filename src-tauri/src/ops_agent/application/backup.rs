@@ -0,0 +1,44 @@
+use crate::error::AppResult;
+use crate::models::now_rfc3339;
+use crate::ops_agent::domain::types::{
+    OpsAgentExportBundle, OpsAgentImportAllInput, OpsAgentImportAllResult,
+};
+use crate::ops_agent::infrastructure::logging::append_debug_log;
+use crate::state::AppState;
+
+pub fn export_all(state: &AppState) -> AppResult<OpsAgentExportBundle> {
+    let data = state.ops_agent.export_all()?;
+    append_debug_log(
+        state,
+        "application.backup.export_all",
+        None,
+        None,
+        format!(
+            "conversations={} pending_actions={}",
+            data.conversations.len(),
+            data.pending_actions.len()
+        ),
+    );
+    Ok(OpsAgentExportBundle {
+        exported_at: now_rfc3339(),
+        data,
+    })
+}
+
+pub fn import_all(
+    state: &AppState,
+    input: OpsAgentImportAllInput,
+) -> AppResult<OpsAgentImportAllResult> {
+    let result = state.ops_agent.import_all(input.bundle.data, input.merge)?;
+    append_debug_log(
+        state,
+        "application.backup.import_all",
+        None,
+        None,
+        format!(
+            "merge={} conversations={} pending_actions={} reassigned_ids={}",
+            input.merge, result.conversations, result.pending_actions, result.reassigned_ids
+        ),
+    );
+    Ok(result)
+}
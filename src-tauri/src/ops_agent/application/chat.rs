@@ -6,22 +6,34 @@ use uuid::Uuid;
 use crate::error::{AppError, AppResult};
 use crate::models::now_rfc3339;
 use crate::ops_agent::core::helpers::truncate_for_log;
-use crate::ops_agent::core::runtime::{spawn_chat_run_task, OpsAgentChatRunTask};
+use crate::ops_agent::core::runtime::{
+    run_chat_to_completion, spawn_chat_run_task, OpsAgentChatRunTask,
+};
+use crate::ops_agent::core::ProcessChatOutcome;
 use crate::ops_agent::domain::types::{
-    OpsAgentCancelRunResult, OpsAgentChatAccepted, OpsAgentChatInput, OpsAgentConversation,
-    OpsAgentConversationSummary, OpsAgentPendingAction, OpsAgentRole,
+    OpsAgentCancelRunResult, OpsAgentChatAccepted, OpsAgentChatInput, OpsAgentChatResult,
+    OpsAgentClearResolvedActionsResult, OpsAgentConversation, OpsAgentConversationSummary,
+    OpsAgentPendingAction, OpsAgentRole, OpsAgentSettings, OpsAgentSettingsInput,
 };
 use crate::ops_agent::infrastructure::logging::append_debug_log;
 use crate::state::AppState;
 
-pub fn list_conversations(state: &AppState) -> Vec<OpsAgentConversationSummary> {
-    let conversations = state.ops_agent.list_conversation_summaries();
+pub fn list_conversations(
+    state: &AppState,
+    include_archived: bool,
+) -> Vec<OpsAgentConversationSummary> {
+    let conversations = state
+        .ops_agent
+        .list_conversation_summaries(include_archived);
     append_debug_log(
         state,
         "application.chat.list_conversations",
         None,
         None,
-        format!("count={}", conversations.len()),
+        format!(
+            "count={} include_archived={include_archived}",
+            conversations.len()
+        ),
     );
     conversations
 }
@@ -30,22 +42,45 @@ pub fn create_conversation(
     state: &AppState,
     title: Option<&str>,
     session_id: Option<&str>,
+    ai_profile_id: Option<&str>,
 ) -> AppResult<OpsAgentConversation> {
-    let conversation = state.ops_agent.create_conversation(title, session_id)?;
+    let ai_profile_id = normalize_ai_profile_id(state, ai_profile_id)?;
+    let mut conversation = state.ops_agent.create_conversation(title, session_id)?;
+    if ai_profile_id.is_some() {
+        conversation = state
+            .ops_agent
+            .set_conversation_ai_profile(&conversation.id, ai_profile_id.as_deref())?;
+    }
     append_debug_log(
         state,
         "application.chat.create_conversation",
         None,
         Some(conversation.id.as_str()),
         format!(
-            "title={} session_id={}",
+            "title={} session_id={} ai_profile_id={}",
             truncate_for_log(conversation.title.as_str(), 120),
-            conversation.session_id.as_deref().unwrap_or("-")
+            conversation.session_id.as_deref().unwrap_or("-"),
+            conversation.ai_profile_id.as_deref().unwrap_or("-")
         ),
     );
     Ok(conversation)
 }
 
+/// Trims an optional profile id and checks that the profile exists.
+fn normalize_ai_profile_id(
+    state: &AppState,
+    ai_profile_id: Option<&str>,
+) -> AppResult<Option<String>> {
+    let Some(id) = ai_profile_id
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    state.storage.get_ai_config_for_profile(id)?;
+    Ok(Some(id.to_string()))
+}
+
 pub fn get_conversation(
     state: &AppState,
     conversation_id: &str,
@@ -107,29 +142,121 @@ pub fn set_active_conversation(state: &AppState, conversation_id: &str) -> AppRe
     Ok(())
 }
 
+pub fn get_settings(state: &AppState) -> OpsAgentSettings {
+    state.ops_agent.get_settings()
+}
+
+pub fn save_settings(
+    state: &AppState,
+    input: OpsAgentSettingsInput,
+) -> AppResult<OpsAgentSettings> {
+    state.ops_agent.save_settings(input)
+}
+
+pub fn rename_conversation(
+    state: &AppState,
+    conversation_id: &str,
+    title: &str,
+) -> AppResult<OpsAgentConversationSummary> {
+    let conversation = state
+        .ops_agent
+        .rename_conversation(conversation_id, title)?;
+    append_debug_log(
+        state,
+        "application.chat.rename_conversation",
+        None,
+        Some(conversation_id),
+        format!(
+            "title={}",
+            truncate_for_log(conversation.title.as_str(), 120)
+        ),
+    );
+    Ok(OpsAgentConversationSummary::from_conversation(
+        &conversation,
+    ))
+}
+
+pub fn set_conversation_pinned(
+    state: &AppState,
+    conversation_id: &str,
+    pinned: bool,
+) -> AppResult<OpsAgentConversationSummary> {
+    let conversation = state
+        .ops_agent
+        .set_conversation_pinned(conversation_id, pinned)?;
+    Ok(OpsAgentConversationSummary::from_conversation(
+        &conversation,
+    ))
+}
+
+pub fn set_conversation_archived(
+    state: &AppState,
+    conversation_id: &str,
+    archived: bool,
+) -> AppResult<OpsAgentConversationSummary> {
+    let conversation = state
+        .ops_agent
+        .set_conversation_archived(conversation_id, archived)?;
+    Ok(OpsAgentConversationSummary::from_conversation(
+        &conversation,
+    ))
+}
+
 pub fn list_pending_actions(
     state: &AppState,
     session_id: Option<&str>,
     only_pending: bool,
+    offset: Option<usize>,
+    limit: Option<usize>,
 ) -> Vec<OpsAgentPendingAction> {
-    let actions = state
+    let mut actions = state
         .ops_agent
         .list_pending_actions(session_id, only_pending);
+    // The pending queue is always short; only the full history needs paging.
+    if !only_pending {
+        actions = actions
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+    }
     append_debug_log(
         state,
         "application.chat.list_pending_actions",
         None,
         None,
         format!(
-            "session_id={} only_pending={} count={}",
+            "session_id={} only_pending={} offset={} limit={} count={}",
             session_id.unwrap_or("-"),
             only_pending,
+            offset.unwrap_or(0),
+            limit
+                .map(|item| item.to_string())
+                .unwrap_or_else(|| "-".to_string()),
             actions.len()
         ),
     );
     actions
 }
 
+pub fn clear_resolved_actions(
+    state: &AppState,
+    session_id: Option<&str>,
+    older_than_days: Option<u32>,
+) -> AppResult<OpsAgentClearResolvedActionsResult> {
+    let removed = state
+        .ops_agent
+        .clear_resolved_actions(session_id, older_than_days)?;
+    append_debug_log(
+        state,
+        "application.chat.clear_resolved_actions",
+        None,
+        None,
+        format!("session_id={} removed={removed}", session_id.unwrap_or("-")),
+    );
+    Ok(OpsAgentClearResolvedActionsResult { removed })
+}
+
 pub fn cancel_chat_run(state: &AppState, run_id: &str) -> AppResult<OpsAgentCancelRunResult> {
     let cancelled = state.ops_agent_runs.cancel(run_id)?;
     let note = if cancelled {
@@ -157,6 +284,31 @@ pub fn start_chat_stream(
     app: AppHandle,
     input: OpsAgentChatInput,
 ) -> AppResult<OpsAgentChatAccepted> {
+    let (task, accepted) = prepare_chat_run(&state, input)?;
+    spawn_chat_run_task(app, task);
+    Ok(accepted)
+}
+
+/// Runs one chat turn headlessly and returns the final answer instead of streaming it.
+pub async fn chat(state: Arc<AppState>, input: OpsAgentChatInput) -> AppResult<OpsAgentChatResult> {
+    let (task, accepted) = prepare_chat_run(&state, input)?;
+    let ProcessChatOutcome::Completed {
+        answer,
+        pending_action,
+    } = run_chat_to_completion(task).await?;
+    Ok(OpsAgentChatResult {
+        run_id: accepted.run_id,
+        conversation_id: accepted.conversation_id,
+        answer,
+        pending_action,
+    })
+}
+
+/// Validates the request, stores the user message and registers the run.
+fn prepare_chat_run(
+    state: &Arc<AppState>,
+    input: OpsAgentChatInput,
+) -> AppResult<(OpsAgentChatRunTask, OpsAgentChatAccepted)> {
     let question = input.question.trim().to_string();
     let image_attachments = input.image_attachments;
     append_debug_log(
@@ -189,11 +341,17 @@ pub fn start_chat_stream(
     } else {
         question.as_str()
     };
-    let conversation = state.ops_agent.ensure_conversation(
+    let ai_profile_id = normalize_ai_profile_id(state, input.ai_profile_id.as_deref())?;
+    let mut conversation = state.ops_agent.ensure_conversation(
         input.conversation_id.as_deref(),
         title_hint,
         input.session_id.as_deref(),
     )?;
+    if ai_profile_id.is_some() {
+        conversation = state
+            .ops_agent
+            .set_conversation_ai_profile(&conversation.id, ai_profile_id.as_deref())?;
+    }
     let session_id = conversation.session_id.clone();
     if let Some(requested_session_id) = input
         .session_id
@@ -266,16 +424,15 @@ pub fn start_chat_stream(
         ),
     );
 
-    spawn_chat_run_task(OpsAgentChatRunTask {
-        state: Arc::clone(&state),
-        app,
-        run_id: run_id.clone(),
-        conversation_id: conversation.id.clone(),
+    let task = OpsAgentChatRunTask {
+        state: Arc::clone(state),
+        run_id,
+        conversation_id: conversation.id,
         session_id,
         current_user_message_id: user_message.id,
         run_handle,
         resume: None,
-    });
+    };
 
-    Ok(accepted)
+    Ok((task, accepted))
 }
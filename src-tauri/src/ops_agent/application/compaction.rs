@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use crate::error::AppResult;
 use crate::ops_agent::core::compaction::{compact_conversation_history, OpsAgentCompactMode};
+use crate::ops_agent::core::helpers::resolve_conversation_ai_config;
 use crate::ops_agent::domain::types::{
     OpsAgentCompactConversationInput, OpsAgentCompactConversationResult,
 };
@@ -20,7 +21,7 @@ pub async fn compact_conversation(
         "manual compaction requested",
     );
     let conversation = state.ops_agent.get_conversation(&input.conversation_id)?;
-    let config = state.storage.get_ai_config();
+    let config = resolve_conversation_ai_config(state.as_ref(), &conversation.id);
     let result = compact_conversation_history(
         state.as_ref(),
         conversation.clone(),
@@ -1,13 +1,17 @@
 mod approval;
 mod attachments;
+mod backup;
 mod chat;
 mod compaction;
 
-pub use approval::resolve_pending_action;
+pub use approval::{resolve_pending_action, retry_pending_action};
 pub use attachments::get_attachment_content;
+pub use backup::{export_all, import_all};
 pub use chat::{
-    cancel_chat_run, create_conversation, delete_conversation, get_conversation,
-    list_conversations, list_pending_actions, set_active_conversation, start_chat_stream,
+    cancel_chat_run, chat, clear_resolved_actions, create_conversation, delete_conversation,
+    get_conversation, get_settings, list_conversations, list_pending_actions, rename_conversation,
+    save_settings, set_active_conversation, set_conversation_archived, set_conversation_pinned,
+    start_chat_stream,
 };
 pub use compaction::compact_conversation;
 
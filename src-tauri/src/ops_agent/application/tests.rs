@@ -6,10 +6,11 @@ use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::error::{AppError, AppResult};
-use crate::ops_agent::core::helpers::normalized_reply;
+use crate::models::{AiApiType, AiProfileInput};
+use crate::ops_agent::core::helpers::{normalized_reply, resolve_conversation_ai_config};
 use crate::ops_agent::domain::types::{
-    OpsAgentActionStatus, OpsAgentMessage, OpsAgentPendingAction, OpsAgentResolveActionInput,
-    OpsAgentRiskLevel, OpsAgentRole, OpsAgentToolKind,
+    OpsAgentActionStatus, OpsAgentChatInput, OpsAgentMessage, OpsAgentPendingAction,
+    OpsAgentResolveActionInput, OpsAgentRiskLevel, OpsAgentRole, OpsAgentToolKind,
 };
 use crate::ops_agent::tools::{
     OpsAgentTool, OpsAgentToolDefinition, OpsAgentToolExecution, OpsAgentToolOutcome,
@@ -17,7 +18,7 @@ use crate::ops_agent::tools::{
 };
 use crate::state::AppState;
 
-use super::resolve_pending_action;
+use super::{chat, create_conversation, resolve_pending_action, retry_pending_action};
 
 type TestToolFuture<T> = Pin<Box<dyn Future<Output = AppResult<T>> + Send + 'static>>;
 const MOCK_AGENT_MAX_TOOL_STEPS: usize = 8;
@@ -664,3 +665,153 @@ fn resolve_pending_action_uses_requested_session_override_for_tool_resolution()
         Some(crate::ops_agent::domain::types::OpsAgentApprovalDecision::Approved)
     );
 }
+
+#[test]
+fn retrying_failed_action_requeues_it_and_rejects_resolved_ones() {
+    let state = test_state_with_registry(OpsAgentToolRegistry::new());
+    let conversation = state
+        .ops_agent
+        .create_conversation(Some("retry"), Some("session-1"))
+        .expect("create conversation");
+    let create = |command: &str| {
+        state
+            .ops_agent
+            .create_pending_action(
+                &conversation.id,
+                None,
+                Some("session-1"),
+                OpsAgentToolKind::write_shell(),
+                OpsAgentRiskLevel::High,
+                command,
+                "retry flow",
+            )
+            .expect("create action")
+    };
+    let failed = create("systemctl restart nginx");
+    let executed = create("touch /tmp/ok");
+    state
+        .ops_agent
+        .mark_action_failed(&failed.id, "connection reset".to_string(), None)
+        .expect("mark failed");
+    state
+        .ops_agent
+        .mark_action_executed(&executed.id, "ok".to_string(), 0, None)
+        .expect("mark executed");
+
+    let retried = retry_pending_action(&state, &failed.id).expect("retry failed action");
+    assert_eq!(retried.action.status, OpsAgentActionStatus::Pending);
+    assert!(retried.action.execution_output.is_none());
+    assert!(retried.action.execution_exit_code.is_none());
+    assert!(retried.action.resolved_at.is_none());
+    assert!(retried.action.approval_decision.is_none());
+
+    let messages = state
+        .ops_agent
+        .get_conversation(&conversation.id)
+        .expect("load conversation")
+        .messages;
+    let note = messages.last().expect("retry note");
+    assert_eq!(note.role, OpsAgentRole::Tool);
+    assert!(note.content.contains("systemctl restart nginx"));
+
+    let error = retry_pending_action(&state, &executed.id).expect_err("executed is final");
+    assert!(matches!(error, AppError::Validation(_)));
+    let error = retry_pending_action(&state, &failed.id).expect_err("already pending");
+    assert!(matches!(error, AppError::Validation(_)));
+}
+
+#[test]
+fn headless_chat_stores_the_question_and_releases_the_run_on_failure() {
+    let state = test_state_with_registry(OpsAgentToolRegistry::new());
+    let conversation = state
+        .ops_agent
+        .create_conversation(Some("headless"), None)
+        .expect("create conversation");
+    let input = |question: &str| OpsAgentChatInput {
+        conversation_id: Some(conversation.id.clone()),
+        session_id: None,
+        question: question.to_string(),
+        shell_context: None,
+        image_attachments: Vec::new(),
+        ai_profile_id: None,
+    };
+
+    let error = tauri::async_runtime::block_on(chat(Arc::clone(&state), input("   ")))
+        .expect_err("empty question");
+    assert!(matches!(error, AppError::Validation(_)));
+
+    // No AI profile is configured, so the run fails before contacting a provider.
+    let error = tauri::async_runtime::block_on(chat(Arc::clone(&state), input("check disk")))
+        .expect_err("missing ai config");
+    assert!(matches!(error, AppError::Validation(_)));
+
+    let messages = state
+        .ops_agent
+        .get_conversation(&conversation.id)
+        .expect("load conversation")
+        .messages;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].content, "check disk");
+    state
+        .ops_agent_runs
+        .register("next-run", conversation.id.clone())
+        .expect("run slot released");
+}
+
+#[test]
+fn conversation_ai_profile_is_validated_and_overrides_active_profile() {
+    let state = test_state_with_registry(OpsAgentToolRegistry::new());
+    let saved = state
+        .storage
+        .save_ai_profile(AiProfileInput {
+            id: None,
+            name: "Strong".to_string(),
+            api_type: AiApiType::OpenAiChatCompletions,
+            base_url: "https://example.invalid/v1".to_string(),
+            api_key: "test-api-key".to_string(),
+            model: "strong-model".to_string(),
+            system_prompt: String::new(),
+            temperature: 0.2,
+            max_tokens: 1024,
+            max_context_tokens: 32000,
+            tool_output_max_bytes: 16_384,
+            max_tool_steps: 8,
+            supports_json_mode: false,
+        })
+        .expect("save profile");
+    let profile_id = saved
+        .profiles
+        .iter()
+        .find(|item| item.name == "Strong")
+        .expect("saved profile")
+        .id
+        .clone();
+
+    let error = create_conversation(&state, Some("pinned"), None, Some("missing-profile"))
+        .expect_err("unknown profile");
+    assert!(matches!(error, AppError::NotFound(_)));
+
+    let pinned = create_conversation(&state, Some("pinned"), None, Some(profile_id.as_str()))
+        .expect("create pinned conversation");
+    assert_eq!(pinned.ai_profile_id.as_deref(), Some(profile_id.as_str()));
+    let unpinned =
+        create_conversation(&state, Some("unpinned"), None, None).expect("create conversation");
+
+    assert_eq!(
+        resolve_conversation_ai_config(&state, &pinned.id).model,
+        "strong-model"
+    );
+    assert_eq!(
+        resolve_conversation_ai_config(&state, &unpinned.id).model,
+        state.storage.get_ai_config().model
+    );
+
+    state
+        .ops_agent
+        .set_conversation_ai_profile(&unpinned.id, Some("deleted-profile"))
+        .expect("pin stale profile");
+    assert_eq!(
+        resolve_conversation_ai_config(&state, &unpinned.id).model,
+        state.storage.get_ai_config().model
+    );
+}
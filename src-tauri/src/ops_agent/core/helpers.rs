@@ -1,5 +1,8 @@
 use crate::error::{AppError, AppResult};
+use crate::models::AiConfig;
+use crate::ops_agent::infrastructure::logging::append_debug_log;
 use crate::ops_agent::infrastructure::run_registry::OpsAgentRunHandle;
+use crate::state::AppState;
 
 use super::OPS_AGENT_RUN_CANCELLED;
 
@@ -14,6 +17,34 @@ pub(crate) fn is_run_cancelled_error(error: &AppError) -> bool {
     matches!(error, AppError::Runtime(message) if message == OPS_AGENT_RUN_CANCELLED)
 }
 
+/// Resolves the AI config for a conversation, honouring its pinned profile.
+///
+/// A pinned profile that was deleted later falls back to the active profile.
+pub(crate) fn resolve_conversation_ai_config(state: &AppState, conversation_id: &str) -> AiConfig {
+    let pinned = state
+        .ops_agent
+        .get_conversation(conversation_id)
+        .ok()
+        .and_then(|conversation| conversation.ai_profile_id);
+    let Some(profile_id) = pinned else {
+        return state.storage.get_ai_config();
+    };
+
+    match state.storage.get_ai_config_for_profile(&profile_id) {
+        Ok(config) => config,
+        Err(error) => {
+            append_debug_log(
+                state,
+                "chat.ai_profile_fallback",
+                None,
+                Some(conversation_id),
+                format!("ai_profile_id={profile_id} error={error}"),
+            );
+            state.storage.get_ai_config()
+        }
+    }
+}
+
 #[cfg(test)]
 pub(crate) fn normalized_reply(reply: String, fallback: &str) -> String {
     if reply.trim().is_empty() {
@@ -10,21 +10,25 @@ use crate::state::AppState;
 
 use super::prompting::{
     build_answer_system_prompt, build_tool_summary_prompt, format_tool_result_user_message,
-    OpsAgentSessionContext, OpsAgentToolPromptHint,
+    render_system_prompt, sample_tool_output, OpsAgentSessionContext, OpsAgentToolPromptHint,
 };
 use crate::ops_agent::domain::types::{
-    OpsAgentExecutionReport, OpsAgentMessage, OpsAgentReviewReport, OpsAgentRole, OpsAgentToolKind,
-    OpsAgentValidationReport, OpsAgentWorkflowPlan, PlannedAgentReply, PlannedToolAction,
+    join_command_steps, OpsAgentExecutionReport, OpsAgentMessage, OpsAgentReviewReport,
+    OpsAgentRole, OpsAgentToolKind, OpsAgentValidationReport, OpsAgentWorkflowPlan,
+    PlannedAgentReply, PlannedToolAction,
 };
 use crate::ops_agent::infrastructure::logging::{truncate_for_log, OpsAgentLogContext};
 use crate::ops_agent::providers::{
     normalize_tool_kind_alias, request_message, stream_message, ProviderChatMessage,
     ProviderChatMessageContent, ProviderChatMessageResponse, ProviderChatRequestOptions,
-    ProviderImageUrlPart, ProviderMessageContentPart, ProviderToolChoice, ProviderToolDefinition,
+    ProviderImageUrlPart, ProviderMessageContentPart, ProviderResponseFormat, ProviderToolChoice,
+    ProviderToolDefinition,
 };
 
 const OPS_AGENT_AI_PLAN_TIMEOUT_SECS: u64 = 45;
 const OPS_AGENT_AI_STREAM_TIMEOUT_SECS: u64 = 240;
+const OPS_AGENT_AI_TITLE_TIMEOUT_SECS: u64 = 20;
+const OPS_AGENT_AI_TITLE_MAX_TOKENS: u32 = 32;
 const AI_LOG_MESSAGE_PREVIEW_CHARS: usize = 280;
 const AI_LOG_ARGUMENT_PREVIEW_CHARS: usize = 220;
 const AI_LOG_LAST_OUTPUT_PREVIEW_CHARS: usize = 180;
@@ -102,27 +106,21 @@ pub async fn route_chat(
     let mut messages = Vec::new();
     messages.push(ProviderChatMessage {
         role: "system".to_string(),
-        content: ProviderChatMessageContent::text(build_gateway_prompt(
-            &config.system_prompt,
-            session_context,
-            tool_hints,
+        content: ProviderChatMessageContent::text(structured_system_prompt(
+            config,
+            build_gateway_prompt(&config.system_prompt, session_context, tool_hints),
         )),
     });
     push_agent_context_message(state, &mut messages, session_context, log_context);
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
-    messages.push(convert_history_message(state, current_message)?);
+    messages.push(convert_history_message(state, config, current_message)?);
 
     let response = request_message(
         config,
         messages,
-        ProviderChatRequestOptions {
-            tools: vec![build_submit_route_tool_definition()],
-            tool_choice: Some(ProviderToolChoice::Named("submit_route".to_string())),
-            response_format: None,
-            stream: false,
-        },
+        structured_request_options(config, build_submit_route_tool_definition()),
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
         log_context,
         "gateway",
@@ -178,27 +176,21 @@ pub async fn route_agent_mode(
     let mut messages = Vec::new();
     messages.push(ProviderChatMessage {
         role: "system".to_string(),
-        content: ProviderChatMessageContent::text(build_agent_mode_gateway_prompt(
-            &config.system_prompt,
-            session_context,
-            tool_hints,
+        content: ProviderChatMessageContent::text(structured_system_prompt(
+            config,
+            build_agent_mode_gateway_prompt(&config.system_prompt, session_context, tool_hints),
         )),
     });
     push_agent_context_message(state, &mut messages, session_context, log_context);
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
-    messages.push(convert_history_message(state, current_message)?);
+    messages.push(convert_history_message(state, config, current_message)?);
 
     let response = request_message(
         config,
         messages,
-        ProviderChatRequestOptions {
-            tools: vec![build_submit_agent_mode_tool_definition()],
-            tool_choice: Some(ProviderToolChoice::Named("submit_agent_mode".to_string())),
-            response_format: None,
-            stream: false,
-        },
+        structured_request_options(config, build_submit_agent_mode_tool_definition()),
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
         log_context,
         "agent_mode_gateway",
@@ -262,21 +254,27 @@ pub async fn plan_reply(
             "Read-only shell commands can run immediately; commands outside the safe read-only allowlist require user approval."
         }
     };
+    let mut system_prompt = super::prompting::build_planner_system_prompt(
+        &config.system_prompt,
+        session_context,
+        tool_hints,
+        shell_execution_policy,
+    );
+    if config.supports_json_mode {
+        system_prompt.push_str(
+            "\n\nIf you answer without calling a tool, reply with a single JSON object: {\"reply\": \"<your answer>\"}.",
+        );
+    }
     let mut messages = Vec::new();
     messages.push(ProviderChatMessage {
         role: "system".to_string(),
-        content: ProviderChatMessageContent::text(super::prompting::build_planner_system_prompt(
-            &config.system_prompt,
-            session_context,
-            tool_hints,
-            shell_execution_policy,
-        )),
+        content: ProviderChatMessageContent::text(system_prompt),
     });
     push_agent_context_message(state, &mut messages, session_context, log_context);
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
-    messages.push(convert_history_message(state, current_message)?);
+    messages.push(convert_history_message(state, config, current_message)?);
 
     let response = request_message(
         config,
@@ -284,7 +282,9 @@ pub async fn plan_reply(
         ProviderChatRequestOptions {
             tools: build_react_tool_definitions(tool_hints),
             tool_choice: (!tool_hints.is_empty()).then_some(ProviderToolChoice::Auto),
-            response_format: None,
+            response_format: config
+                .supports_json_mode
+                .then_some(ProviderResponseFormat::JsonObject),
             stream: false,
         },
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
@@ -338,9 +338,9 @@ where
     });
     push_agent_context_message(state, &mut messages, session_context, log_context);
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
-    messages.push(convert_history_message(state, current_message)?);
+    messages.push(convert_history_message(state, config, current_message)?);
 
     stream_message(
         config,
@@ -401,6 +401,65 @@ pub async fn compact_history_summary(
     .await
 }
 
+/// Asks the model for a short conversation title from the first exchange.
+pub async fn generate_conversation_title(
+    config: &AiConfig,
+    question: &str,
+    answer: &str,
+    max_chars: usize,
+    log_context: Option<OpsAgentLogContext<'_>>,
+) -> AppResult<String> {
+    validate_ai_config(config)?;
+    let title_config = AiConfig {
+        max_tokens: OPS_AGENT_AI_TITLE_MAX_TOKENS,
+        temperature: 0.2,
+        ..config.clone()
+    };
+    let messages = vec![
+        ProviderChatMessage {
+            role: "system".to_string(),
+            content: ProviderChatMessageContent::text(format!(
+                "You name ops troubleshooting conversations. Reply with one concise title of at most {max_chars} characters in the user's language. No quotes, no trailing punctuation, no explanation."
+            )),
+        },
+        ProviderChatMessage {
+            role: "user".to_string(),
+            content: ProviderChatMessageContent::text(format!(
+                "User: {}\n\nAssistant: {}",
+                truncate_for_log(question, 600),
+                truncate_for_log(answer, 600)
+            )),
+        },
+    ];
+
+    let reply = request_text_completion(
+        &title_config,
+        messages,
+        Duration::from_secs(OPS_AGENT_AI_TITLE_TIMEOUT_SECS),
+        log_context,
+        "conversation_title",
+    )
+    .await?;
+    normalize_generated_title(&reply, max_chars)
+        .ok_or_else(|| AppError::Runtime("ops agent AI title response was empty".to_string()))
+}
+
+/// Keeps the first non-empty line of a title reply, unquoted and clipped.
+fn normalize_generated_title(reply: &str, max_chars: usize) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let title = line
+        .trim()
+        .trim_matches(|ch: char| matches!(ch, '"' | '\'' | '`' | '*' | '#'))
+        .trim_end_matches(['.', '。'])
+        .trim();
+    if title.is_empty() {
+        return None;
+    }
+    let clipped = title.chars().take(max_chars).collect::<String>();
+    Some(clipped.trim_end().to_string())
+}
+
 pub async fn plan_workflow(
     state: &AppState,
     config: &AiConfig,
@@ -425,27 +484,21 @@ pub async fn plan_workflow(
     let mut messages = Vec::new();
     messages.push(ProviderChatMessage {
         role: "system".to_string(),
-        content: ProviderChatMessageContent::text(build_workflow_planner_prompt(
-            &config.system_prompt,
-            session_context,
-            tool_hints,
+        content: ProviderChatMessageContent::text(structured_system_prompt(
+            config,
+            build_workflow_planner_prompt(&config.system_prompt, session_context, tool_hints),
         )),
     });
     push_agent_context_message(state, &mut messages, session_context, log_context);
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
-    messages.push(convert_history_message(state, current_message)?);
+    messages.push(convert_history_message(state, config, current_message)?);
 
     let response = request_message(
         config,
         messages,
-        ProviderChatRequestOptions {
-            tools: vec![build_submit_plan_tool_definition(tool_hints)],
-            tool_choice: Some(ProviderToolChoice::Named("submit_plan".to_string())),
-            response_format: None,
-            stream: false,
-        },
+        structured_request_options(config, build_submit_plan_tool_definition(tool_hints)),
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
         log_context,
         "workflow_plan",
@@ -496,7 +549,10 @@ pub async fn review_execution(
     let messages = vec![
         ProviderChatMessage {
             role: "system".to_string(),
-            content: ProviderChatMessageContent::text(build_reviewer_prompt(&config.system_prompt)),
+            content: ProviderChatMessageContent::text(structured_system_prompt(
+                config,
+                build_reviewer_prompt(&config.system_prompt),
+            )),
         },
         ProviderChatMessage {
             role: "user".to_string(),
@@ -511,12 +567,7 @@ pub async fn review_execution(
     let response = request_message(
         config,
         messages,
-        ProviderChatRequestOptions {
-            tools: vec![build_submit_review_tool_definition()],
-            tool_choice: Some(ProviderToolChoice::Named("submit_review".to_string())),
-            response_format: None,
-            stream: false,
-        },
+        structured_request_options(config, build_submit_review_tool_definition()),
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
         log_context,
         "review",
@@ -556,7 +607,10 @@ pub async fn validate_completion(
     let messages = vec![
         ProviderChatMessage {
             role: "system".to_string(),
-            content: ProviderChatMessageContent::text(build_validator_prompt(&config.system_prompt)),
+            content: ProviderChatMessageContent::text(structured_system_prompt(
+                config,
+                build_validator_prompt(&config.system_prompt),
+            )),
         },
         ProviderChatMessage {
             role: "user".to_string(),
@@ -572,12 +626,7 @@ pub async fn validate_completion(
     let response = request_message(
         config,
         messages,
-        ProviderChatRequestOptions {
-            tools: vec![build_submit_validation_tool_definition()],
-            tool_choice: Some(ProviderToolChoice::Named("submit_validation".to_string())),
-            response_format: None,
-            stream: false,
-        },
+        structured_request_options(config, build_submit_validation_tool_definition()),
         Duration::from_secs(OPS_AGENT_AI_PLAN_TIMEOUT_SECS),
         log_context,
         "validation",
@@ -620,12 +669,15 @@ where
         )),
     });
     for message in history {
-        messages.push(convert_history_message(state, message)?);
+        messages.push(convert_history_message(state, config, message)?);
     }
     messages.push(ProviderChatMessage {
         role: "user".to_string(),
         content: ProviderChatMessageContent::text(format_tool_result_user_message(
-            tool_kind, command, output, exit_code,
+            tool_kind,
+            command,
+            &sample_tool_output(output, config.tool_output_max_bytes as usize),
+            exit_code,
         )),
     });
 
@@ -641,6 +693,34 @@ where
     .await
 }
 
+/// Structured decisions are submitted through a named tool. Profiles flagged with
+/// `supports_json_mode` also request `response_format: json_object`, so the
+/// plain-content fallback stays parseable when a model skips the tool call.
+fn structured_request_options(
+    config: &AiConfig,
+    tool: ProviderToolDefinition,
+) -> ProviderChatRequestOptions {
+    let tool_name = tool.name.clone();
+    ProviderChatRequestOptions {
+        tools: vec![tool],
+        tool_choice: Some(ProviderToolChoice::Named(tool_name)),
+        response_format: config
+            .supports_json_mode
+            .then_some(ProviderResponseFormat::JsonObject),
+        stream: false,
+    }
+}
+
+/// OpenAI-compatible JSON mode rejects requests whose messages never mention JSON.
+fn structured_system_prompt(config: &AiConfig, prompt: String) -> String {
+    if !config.supports_json_mode {
+        return prompt;
+    }
+    format!(
+        "{prompt}\n\nIf you answer without calling the tool, reply with a single JSON object using the tool's fields."
+    )
+}
+
 fn build_workflow_planner_prompt(
     base_prompt: &str,
     session_context: &OpsAgentSessionContext,
@@ -657,7 +737,7 @@ Rules:\n\
 3) Use toolKind only when a registered tool is needed. Use null/no toolKind for reasoning-only steps.\n\
 4) Shell commands must be concrete, minimal, and read-only unless the user explicitly asked for a change.\n\
 5) Include success criteria the Validator can check later.",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, session_context),
         tool_block = format_tool_catalog_for_prompt(tool_hints),
         session_block = session_context.to_prompt_block(),
     )
@@ -681,7 +761,7 @@ If you choose workflow, leave answer empty and put a short reason in reason.\n\
 Available workflow tools:\n{tool_block}\n\n\
 Session context:\n{session_block}\n\n\
 Submit exactly one route via the submit_route tool.",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, session_context),
         tool_block = format_tool_catalog_for_prompt(tool_hints),
         session_block = session_context.to_prompt_block(),
     )
@@ -705,7 +785,7 @@ Prefer direct_reply when tools are unnecessary. Prefer lite unless pro's review
 Available tools:\n{tool_block}\n\n\
 Session context:\n{session_block}\n\n\
 Submit exactly one decision via the submit_agent_mode tool. If mode is direct_reply, put the final user-facing response in answer; otherwise leave answer empty.",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, session_context),
         tool_block = format_tool_catalog_for_prompt(tool_hints),
         session_block = session_context.to_prompt_block(),
     )
@@ -716,7 +796,7 @@ fn build_reviewer_prompt(base_prompt: &str) -> String {
         "{base}\n\nYou are the Reviewer sub-agent in a serial multi-agent operations workflow.\n\
 Review whether the Executor followed the plan, whether evidence supports the result, and whether there are obvious safety or completeness gaps.\n\
 Submit your review via the submit_review tool. Be concise and practical.",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, &OpsAgentSessionContext::default()),
     )
 }
 
@@ -725,7 +805,7 @@ fn build_validator_prompt(base_prompt: &str) -> String {
         "{base}\n\nYou are the Validator sub-agent in a serial multi-agent operations workflow.\n\
 Determine whether the user's task is complete based only on the plan, execution report, and reviewer report.\n\
 Submit validation via the submit_validation tool. Mark completed=false when approval is pending, execution failed, or evidence is insufficient.",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, &OpsAgentSessionContext::default()),
     )
 }
 
@@ -817,9 +897,10 @@ fn build_react_tool_definitions(
                     "additionalProperties": false,
                     "properties": {
                         "command": {
-                            "type": "string",
+                            "type": ["string", "array"],
+                            "items": { "type": "string" },
                             "description": if requires_command {
-                                "Concrete command or input for this tool."
+                                "Concrete command or input for this tool. Use an array for a short sequence of steps; they run in order and stop at the first failure."
                             } else {
                                 "Optional command or selector. This tool may ignore it."
                             }
@@ -1026,12 +1107,7 @@ fn parse_planned_reply_from_response(
         }
 
         let arguments: serde_json::Value = serde_json::from_str(&tool_call.arguments)?;
-        let command = arguments
-            .get("command")
-            .and_then(serde_json::Value::as_str)
-            .map(str::trim)
-            .filter(|value| !value.is_empty())
-            .map(ToString::to_string);
+        let command = parse_command_argument(arguments.get("command"));
         let reason = arguments
             .get("reason")
             .and_then(serde_json::Value::as_str)
@@ -1040,7 +1116,7 @@ fn parse_planned_reply_from_response(
             .map(ToString::to_string);
 
         return Ok(PlannedAgentReply {
-            reply: response.content.trim().to_string(),
+            reply: planned_reply_text(&response.content),
             tool: PlannedToolAction {
                 kind: normalized_kind,
                 command,
@@ -1050,7 +1126,7 @@ fn parse_planned_reply_from_response(
     }
 
     Ok(PlannedAgentReply {
-        reply: response.content.trim().to_string(),
+        reply: planned_reply_text(&response.content),
         tool: PlannedToolAction {
             kind: OpsAgentToolKind::none(),
             command: None,
@@ -1059,6 +1135,35 @@ fn parse_planned_reply_from_response(
     })
 }
 
+/// Unwraps `{"reply": ...}`, the shape JSON mode asks for; other content is the reply itself.
+fn planned_reply_text(content: &str) -> String {
+    let content = content.trim();
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|value| {
+            value
+                .get("reply")
+                .and_then(serde_json::Value::as_str)
+                .map(|reply| reply.trim().to_string())
+        })
+        .unwrap_or_else(|| content.to_string())
+}
+
+/// Accepts `command` as a single string or as an ordered list of steps.
+fn parse_command_argument(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(command) => join_command_steps(&[command.as_str()]),
+        serde_json::Value::Array(items) => {
+            let steps = items
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .collect::<Vec<_>>();
+            join_command_steps(&steps)
+        }
+        _ => None,
+    }
+}
+
 fn parse_workflow_plan_from_response(
     response: &ProviderChatMessageResponse,
     tool_hints: &[OpsAgentToolPromptHint],
@@ -1199,16 +1304,70 @@ fn normalize_workflow_plan(plan: &mut OpsAgentWorkflowPlan, tool_hints: &[OpsAge
         .collect();
 }
 
+/// Finds the JSON object payload in a free-form model reply.
+///
+/// Fenced blocks are tried before the raw text, and within each source balanced
+/// `{...}` spans are tried outermost first so nested fields never win over the
+/// payload that contains them. Spans that do not parse are skipped, which keeps
+/// braces in surrounding prose from hiding the real object.
 fn extract_json_object(value: &str) -> Option<&str> {
     let trimmed = value.trim();
-    if trimmed.starts_with('{') && trimmed.ends_with('}') {
-        return Some(trimmed);
+    fenced_blocks(trimmed)
+        .into_iter()
+        .chain(std::iter::once(trimmed))
+        .find_map(|source| {
+            json_object_spans(source).into_iter().find(|span| {
+                serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(span).is_ok()
+            })
+        })
+}
+
+fn fenced_blocks(value: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("```") {
+        // Skip an optional language tag such as `json` on the opening fence.
+        let body = rest[start + 3..].trim_start_matches(|ch: char| ch.is_ascii_alphanumeric());
+        let Some(end) = body.find("```") else {
+            break;
+        };
+        blocks.push(body[..end].trim());
+        rest = &body[end + 3..];
     }
+    blocks
+}
 
-    let fenced_start = trimmed.find("```json")?;
-    let after_fence = &trimmed[fenced_start + "```json".len()..];
-    let fenced_end = after_fence.find("```")?;
-    Some(after_fence[..fenced_end].trim())
+fn json_object_spans(value: &str) -> Vec<&str> {
+    let mut spans = Vec::new();
+    let mut starts = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, ch) in value.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            // Quotes only delimit strings inside an object; prose quotes are ignored.
+            '"' if !starts.is_empty() => in_string = true,
+            '{' => starts.push(index),
+            '}' => {
+                if let Some(start) = starts.pop() {
+                    spans.push((starts.len(), &value[start..=index]));
+                }
+            }
+            _ => {}
+        }
+    }
+    // Stable sort keeps source order among spans at the same depth.
+    spans.sort_by_key(|(depth, _)| *depth);
+    spans.into_iter().map(|(_, span)| span).collect()
 }
 
 fn normalize_single_line(value: &str) -> String {
@@ -1258,7 +1417,7 @@ fn format_tool_catalog_for_prompt(tool_hints: &[OpsAgentToolPromptHint]) -> Stri
         .join("\n")
 }
 
-fn validate_ai_config(config: &AiConfig) -> AppResult<()> {
+pub(crate) fn validate_ai_config(config: &AiConfig) -> AppResult<()> {
     if config.base_url.trim().is_empty() {
         return Err(AppError::Validation("baseUrl cannot be empty".to_string()));
     }
@@ -1355,6 +1514,7 @@ fn truncate_context_section(value: &str) -> String {
 
 fn convert_history_message(
     state: &AppState,
+    config: &AiConfig,
     item: &OpsAgentMessage,
 ) -> AppResult<ProviderChatMessage> {
     let role = match item.role {
@@ -1364,7 +1524,10 @@ fn convert_history_message(
         OpsAgentRole::Tool => "user",
     };
     let content = if item.role == OpsAgentRole::Tool {
-        ProviderChatMessageContent::text(format!("[tool-result]\n{}", item.content))
+        ProviderChatMessageContent::text(format!(
+            "[tool-result]\n{}",
+            sample_tool_output(&item.content, config.tool_output_max_bytes as usize)
+        ))
     } else if item.role == OpsAgentRole::User {
         build_user_history_content(state, item)?
     } else {
@@ -1639,6 +1802,32 @@ mod tests {
         assert_eq!(tool_kind_enum, &json!(["shell", "ui_context", null]));
     }
 
+    #[test]
+    fn structured_requests_only_use_json_mode_when_the_profile_supports_it() {
+        let mut config = AiConfig::default();
+        let options = structured_request_options(&config, build_submit_review_tool_definition());
+        assert_eq!(options.response_format, None);
+        assert_eq!(
+            options.tool_choice,
+            Some(ProviderToolChoice::Named("submit_review".to_string()))
+        );
+        assert_eq!(
+            structured_system_prompt(&config, "base".to_string()),
+            "base"
+        );
+
+        config.supports_json_mode = true;
+        let options = structured_request_options(&config, build_submit_review_tool_definition());
+        assert_eq!(
+            options.response_format,
+            Some(ProviderResponseFormat::JsonObject)
+        );
+        assert!(structured_system_prompt(&config, "base".to_string()).contains("JSON"));
+
+        // Free-text calls such as the tool summary keep the default options.
+        assert_eq!(ProviderChatRequestOptions::default().response_format, None);
+    }
+
     #[test]
     fn parses_gateway_direct_reply_tool_call() {
         let response = ProviderChatMessageResponse {
@@ -1760,4 +1949,92 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn extracts_fenced_json_surrounded_by_braced_prose() {
+        let content = "Check `{HOME}` first.\n```json\n{\"route\": \"workflow\", \"answer\": \"\", \"reason\": \"Needs shell.\"}\n```\nThen report {status}.";
+        assert_eq!(
+            extract_json_object(content),
+            Some("{\"route\": \"workflow\", \"answer\": \"\", \"reason\": \"Needs shell.\"}")
+        );
+
+        let response = ProviderChatMessageResponse {
+            content: content.to_string(),
+            ..ProviderChatMessageResponse::default()
+        };
+        assert_eq!(
+            parse_chat_route_from_response(&response).expect("parse route"),
+            OpsAgentChatRoute::Workflow {
+                reason: "Needs shell.".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn extracts_json_followed_by_trailing_prose() {
+        let content = "Here is my decision: {\"mode\": \"lite\", \"reason\": \"One command.\"} Let me know if {anything} changes.";
+        assert_eq!(
+            extract_json_object(content),
+            Some("{\"mode\": \"lite\", \"reason\": \"One command.\"}")
+        );
+    }
+
+    #[test]
+    fn extracts_outer_json_with_braces_inside_string_values() {
+        let content = "{\"summary\": \"Inspect ${PATH} and } stray\", \"steps\": [{\"id\": \"a\", \"command\": \"awk '{print $1}' /etc/hosts\"}]}";
+        assert_eq!(extract_json_object(content), Some(content));
+        assert_eq!(extract_json_object("plain text with {braces}"), None);
+    }
+
+    #[test]
+    fn generated_title_is_unquoted_and_clipped() {
+        assert_eq!(
+            normalize_generated_title("\n\"Nginx 502 on staging.\"\nextra", 40).as_deref(),
+            Some("Nginx 502 on staging")
+        );
+        assert_eq!(
+            normalize_generated_title("Title: Disk usage cleanup", 10).as_deref(),
+            Some("Disk usage")
+        );
+        assert_eq!(normalize_generated_title("  \n ``", 40), None);
+    }
+
+    #[test]
+    fn planned_reply_accepts_command_lists_and_strings() {
+        let tool_hints = [OpsAgentToolPromptHint {
+            kind: OpsAgentToolKind::shell(),
+            description: "Run shell".to_string(),
+            usage_notes: Vec::new(),
+            requires_approval: false,
+        }];
+        let response_with = |command: serde_json::Value| ProviderChatMessageResponse {
+            tool_calls: vec![crate::ops_agent::providers::types::ProviderToolCall {
+                id: Some("call-1".to_string()),
+                name: "shell".to_string(),
+                arguments: json!({ "command": command, "reason": "Fix config." }).to_string(),
+            }],
+            ..ProviderChatMessageResponse::default()
+        };
+
+        let plan = parse_planned_reply_from_response(
+            &response_with(json!(["cd /etc/nginx", "nginx -t", " "])),
+            &tool_hints,
+        )
+        .expect("parse list");
+        assert_eq!(
+            plan.tool.command.as_deref(),
+            Some("cd /etc/nginx && nginx -t")
+        );
+
+        let plan =
+            parse_planned_reply_from_response(&response_with(json!("uptime; df -h")), &tool_hints)
+                .expect("parse string");
+        assert_eq!(plan.tool.command.as_deref(), Some("uptime; df -h"));
+
+        assert_eq!(
+            planned_reply_text(r#" {"reply": " All good. "} "#),
+            "All good."
+        );
+        assert_eq!(planned_reply_text(" All good. "), "All good.");
+    }
 }
@@ -1,3 +1,5 @@
+use crate::ops_agent::domain::types::OpsAgentPendingAction;
+
 pub mod agents;
 pub mod compaction;
 pub(crate) mod helpers;
@@ -8,9 +10,12 @@ pub mod react_loop;
 pub mod runtime;
 
 pub(crate) const OPS_AGENT_RUN_CANCELLED: &str = "__ops_agent_run_cancelled__";
-pub(crate) const OPS_AGENT_MAX_REACT_STEPS: usize = 8;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Final result of a chat run, mirroring the payload of the `completed` stream event.
+#[derive(Debug, Clone)]
 pub(crate) enum ProcessChatOutcome {
-    Completed,
+    Completed {
+        answer: String,
+        pending_action: Option<OpsAgentPendingAction>,
+    },
 }
@@ -2,7 +2,6 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use serde_json::json;
-use tauri::AppHandle;
 use tokio::time::sleep;
 
 use crate::error::{AppError, AppResult};
@@ -11,7 +10,9 @@ use crate::ops_agent::core::agents::{
     ExecutorAgent, ExecutorAgentInput, PlannerAgent, PlannerAgentInput, ReviewerAgent,
     ReviewerAgentInput, ValidatorAgent, ValidatorAgentInput,
 };
-use crate::ops_agent::core::helpers::{ensure_run_not_cancelled, is_run_cancelled_error};
+use crate::ops_agent::core::helpers::{
+    ensure_run_not_cancelled, is_run_cancelled_error, resolve_conversation_ai_config,
+};
 use crate::ops_agent::core::prompting::OpsAgentSessionContext;
 use crate::ops_agent::domain::types::{
     OpsAgentExecutionReport, OpsAgentExecutorResume, OpsAgentKind, OpsAgentMessage,
@@ -19,9 +20,7 @@ use crate::ops_agent::domain::types::{
     OpsAgentValidationReport, OpsAgentWorkflowPlan,
 };
 use crate::ops_agent::infrastructure::agent_trace_store::OpsAgentRunManifest;
-use crate::ops_agent::infrastructure::logging::{
-    append_debug_log, resolve_ops_agent_log_path, OpsAgentLogContext,
-};
+use crate::ops_agent::infrastructure::logging::{append_debug_log, OpsAgentLogContext};
 use crate::ops_agent::infrastructure::run_registry::OpsAgentRunHandle;
 use crate::ops_agent::transport::events::OpsAgentEventEmitter;
 use crate::state::AppState;
@@ -35,7 +34,7 @@ const OPS_AGENT_AI_RETRY_SLEEP_SLICE_MS: u64 = 200;
 
 pub(crate) async fn process_chat_stream(
     state: Arc<AppState>,
-    app: AppHandle,
+    emitter: OpsAgentEventEmitter,
     run_id: String,
     conversation_id: String,
     session_id: Option<String>,
@@ -43,12 +42,6 @@ pub(crate) async fn process_chat_stream(
     run_handle: OpsAgentRunHandle,
     resume: Option<OpsAgentRunResume>,
 ) -> AppResult<ProcessChatOutcome> {
-    let emitter = OpsAgentEventEmitter::new(
-        app,
-        resolve_ops_agent_log_path(&state.storage.data_dir()),
-        run_id.clone(),
-        conversation_id.clone(),
-    );
     emitter.started();
     create_trace_run(
         &state,
@@ -81,7 +74,7 @@ pub(crate) async fn process_chat_stream(
     );
     ensure_run_not_cancelled(&run_handle)?;
 
-    let config = state.storage.get_ai_config();
+    let config = resolve_conversation_ai_config(&state, &conversation_id);
     let executor_resume = match resume.as_ref() {
         Some(OpsAgentRunResume::Executor(resume)) => Some(resume.clone()),
         None => None,
@@ -688,8 +681,11 @@ fn finalize_chat_completion(
         None,
         Vec::new(),
     )?;
-    emitter.completed(assistant_answer, pending_action);
-    Ok(ProcessChatOutcome::Completed)
+    emitter.completed(assistant_answer.clone(), pending_action.clone());
+    Ok(ProcessChatOutcome::Completed {
+        answer: assistant_answer,
+        pending_action,
+    })
 }
 
 fn finalize_approval_pause(
@@ -697,7 +693,10 @@ fn finalize_approval_pause(
     emitter: &OpsAgentEventEmitter,
 ) -> AppResult<ProcessChatOutcome> {
     emitter.completed(String::new(), Some(pending_action.clone()));
-    Ok(ProcessChatOutcome::Completed)
+    Ok(ProcessChatOutcome::Completed {
+        answer: String::new(),
+        pending_action: Some(pending_action.clone()),
+    })
 }
 
 fn describe_run_resume(resume: Option<&OpsAgentRunResume>) -> &'static str {
@@ -1,3 +1,5 @@
+use chrono::Utc;
+
 use crate::models::ShellSession;
 use crate::state::AppState;
 
@@ -9,6 +11,8 @@ const LAST_OUTPUT_PREVIEW_CHARS: usize = 240;
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct OpsAgentSessionContext {
     pub session_id: Option<String>,
+    pub host_name: Option<String>,
+    pub remote_os: Option<String>,
     pub current_dir: Option<String>,
     pub last_output_preview: Option<String>,
 }
@@ -30,10 +34,24 @@ impl OpsAgentSessionContext {
             "Current SSH session id: {}",
             self.session_id.as_deref().unwrap_or("unavailable")
         ));
+        rows.push(format!(
+            "Connected host: {}",
+            self.host_name.as_deref().unwrap_or("unavailable")
+        ));
+        rows.push(format!(
+            "Remote OS: {}",
+            self.remote_os.as_deref().unwrap_or("unknown")
+        ));
         rows.push(format!(
             "Current working directory: {}",
             self.current_dir.as_deref().unwrap_or("unknown")
         ));
+        if self.current_dir.is_some() {
+            rows.push(
+                "Commands run from the current working directory; prefer absolute paths over relative `cd` hops."
+                    .to_string(),
+            );
+        }
 
         if let Some(last_output_preview) = &self.last_output_preview {
             rows.push(format!(
@@ -61,6 +79,46 @@ pub fn load_session_context(state: &AppState, session_id: Option<&str>) -> OpsAg
     build_session_context(Some(session))
 }
 
+/// Renders a profile system prompt, filling `{{host}}`, `{{date}}`, `{{cwd}}` and `{{os}}`.
+///
+/// Values missing from the session render as `unknown`; other placeholders stay literal.
+pub fn render_system_prompt(template: &str, session_context: &OpsAgentSessionContext) -> String {
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    substitute_prompt_placeholders(template.trim(), session_context, &date)
+}
+
+fn substitute_prompt_placeholders(
+    template: &str,
+    session_context: &OpsAgentSessionContext,
+    date: &str,
+) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return rendered;
+        };
+        let value = match after_open[..end].trim() {
+            "host" => session_context.host_name.as_deref(),
+            "cwd" => session_context.current_dir.as_deref(),
+            "os" => session_context.remote_os.as_deref(),
+            "date" => Some(date),
+            _ => {
+                rendered.push_str("{{");
+                rest = after_open;
+                continue;
+            }
+        };
+        rendered.push_str(value.unwrap_or("unknown"));
+        rest = &after_open[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
 /// Builds a planner-style prompt using the runtime tool catalog instead of hard-coded text.
 pub fn build_planner_system_prompt(
     base_prompt: &str,
@@ -80,8 +138,9 @@ Rules:\n\
 3) You may chain read-only commands with && or ; for efficiency. Read-only chains auto-execute; write commands require approval.\n\
 4) Keep commands minimal and targeted. Use head, tail, grep, or wc to limit verbose output.\n\
 5) Choose registered tool names exactly as documented above.\n\
-6) Shell execution policy: {shell_execution_policy}",
-        base = base_prompt.trim(),
+6) Match command flags to the remote OS from the session context; BSD and macOS tools differ from GNU ones.\n\
+7) Shell execution policy: {shell_execution_policy}",
+        base = render_system_prompt(base_prompt, session_context),
         tool_block = format_tool_catalog(tool_hints),
         session_block = session_context.to_prompt_block(),
         shell_execution_policy = shell_execution_policy,
@@ -106,7 +165,7 @@ Provide a concise markdown answer that directly helps the user.\n\
 Prefer evidence-based statements, highlight uncertainty, and keep next steps safe.\n\
 {planner_hint}Session context:\n\
 {session_block}",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, session_context),
         session_block = session_context.to_prompt_block(),
     )
 }
@@ -122,7 +181,7 @@ pub fn build_tool_summary_prompt(
 Include: what happened, the most relevant evidence, and a safe next step when useful.\n\
 Session context:\n\
 {session_block}",
-        base = base_prompt.trim(),
+        base = render_system_prompt(base_prompt, session_context),
         session_block = session_context.to_prompt_block(),
     )
 }
@@ -143,6 +202,60 @@ pub fn format_tool_result_user_message(
     )
 }
 
+/// Shrinks tool output to about `max_bytes` for the model, keeping the first and
+/// last lines around a `[N lines omitted]` marker. The stored message keeps the
+/// full text for the UI.
+pub fn sample_tool_output(output: &str, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output.to_string();
+    }
+
+    let lines = output.lines().collect::<Vec<_>>();
+    let half_budget = max_bytes / 2;
+    let mut head_bytes = 0;
+    let head_count = lines
+        .iter()
+        .take_while(|line| {
+            head_bytes += line.len() + 1;
+            head_bytes <= half_budget
+        })
+        .count();
+    let mut tail_bytes = 0;
+    let tail_count = lines[head_count..]
+        .iter()
+        .rev()
+        .take_while(|line| {
+            tail_bytes += line.len() + 1;
+            tail_bytes <= half_budget
+        })
+        .count();
+
+    if head_count + tail_count == 0 {
+        // A few very long lines: cut by bytes instead.
+        let head = &output[..floor_char_boundary(output, half_budget)];
+        let tail_start = floor_char_boundary(output, output.len() - half_budget);
+        let tail = &output[tail_start..];
+        return format!(
+            "{head}\n[{} bytes omitted]\n{tail}",
+            tail_start - head.len()
+        );
+    }
+
+    let omitted = lines.len() - head_count - tail_count;
+    let mut sampled = lines[..head_count].join("\n");
+    sampled.push_str(&format!("\n[{omitted} lines omitted]\n"));
+    sampled.push_str(&lines[lines.len() - tail_count..].join("\n"));
+    sampled
+}
+
+fn floor_char_boundary(value: &str, index: usize) -> usize {
+    let mut index = index.min(value.len());
+    while !value.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 fn build_session_context(session: Option<ShellSession>) -> OpsAgentSessionContext {
     let Some(session) = session else {
         return OpsAgentSessionContext::default();
@@ -150,6 +263,8 @@ fn build_session_context(session: Option<ShellSession>) -> OpsAgentSessionContex
 
     OpsAgentSessionContext {
         session_id: Some(session.id),
+        host_name: Some(session.config_name).filter(|value| !value.trim().is_empty()),
+        remote_os: session.remote_os,
         current_dir: Some(session.current_dir),
         last_output_preview: build_last_output_preview(&session.last_output),
     }
@@ -233,6 +348,78 @@ mod tests {
         assert!(prompt.contains("no approval"));
     }
 
+    #[test]
+    fn session_block_describes_host_and_falls_back_without_session() {
+        let context = build_session_context(Some(ShellSession {
+            id: "s1".to_string(),
+            config_id: "c1".to_string(),
+            config_name: "prod-web".to_string(),
+            current_dir: "/var/log".to_string(),
+            previous_dir: None,
+            last_output: String::new(),
+            remote_os: Some("Darwin".to_string()),
+            created_at: String::new(),
+            updated_at: String::new(),
+            handshake: Default::default(),
+        }));
+        let block = context.to_prompt_block();
+        assert!(block.contains("Connected host: prod-web"));
+        assert!(block.contains("Remote OS: Darwin"));
+        assert!(block.contains("Current working directory: /var/log"));
+
+        let block = OpsAgentSessionContext::default().to_prompt_block();
+        assert!(block.contains("Connected host: unavailable"));
+        assert!(block.contains("Remote OS: unknown"));
+        assert!(!block.contains("prefer absolute paths"));
+    }
+
+    #[test]
+    fn system_prompt_placeholders_use_session_values() {
+        let context = OpsAgentSessionContext {
+            session_id: Some("s1".to_string()),
+            host_name: Some("prod-web".to_string()),
+            remote_os: Some("Linux".to_string()),
+            current_dir: Some("/srv/app".to_string()),
+            last_output_preview: None,
+        };
+        let rendered = substitute_prompt_placeholders(
+            "Host {{host}} ({{ os }}) in {{cwd}} on {{date}}. Keep {{ticket}} and {{{{host}}.",
+            &context,
+            "2026-01-02",
+        );
+        assert_eq!(
+            rendered,
+            "Host prod-web (Linux) in /srv/app on 2026-01-02. Keep {{ticket}} and {{prod-web."
+        );
+
+        let prompt = build_planner_system_prompt(
+            "  Operating on {{host}}.  ",
+            &context,
+            &[],
+            "Read-only runs immediately.",
+        );
+        assert!(prompt.starts_with("Operating on prod-web."));
+    }
+
+    #[test]
+    fn system_prompt_placeholders_fall_back_without_session() {
+        let rendered = substitute_prompt_placeholders(
+            "Host {{host}} {{os}} {{cwd}} {{date}} {{unclosed",
+            &OpsAgentSessionContext::default(),
+            "2026-01-02",
+        );
+        assert_eq!(
+            rendered,
+            "Host unknown unknown unknown 2026-01-02 {{unclosed"
+        );
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            render_system_prompt("{{date}}", &OpsAgentSessionContext::default()),
+            today
+        );
+    }
+
     #[test]
     fn tool_result_message_contains_exit_code() {
         let payload = format_tool_result_user_message(
@@ -245,4 +432,24 @@ mod tests {
         assert!(payload.contains("kind: shell"));
         assert!(payload.contains("exitCode: 0"));
     }
+
+    #[test]
+    fn sample_tool_output_keeps_head_and_tail_within_budget() {
+        let output = (1..=1000)
+            .map(|index| format!("line {index:04}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(sample_tool_output("short", 64), "short");
+
+        let sampled = sample_tool_output(&output, 200);
+        assert!(sampled.len() <= 240);
+        assert!(sampled.starts_with("line 0001\n"));
+        assert!(sampled.ends_with("line 1000"));
+        assert!(sampled.contains("lines omitted]"));
+
+        let wide = "é".repeat(500);
+        let sampled = sample_tool_output(&wide, 101);
+        assert!(sampled.contains("bytes omitted]"));
+        assert!(sampled.len() < wide.len());
+    }
 }
@@ -1,7 +1,6 @@
 use std::sync::Arc;
 use std::time::Instant;
 
-use tauri::AppHandle;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
@@ -9,32 +8,24 @@ use crate::ops_agent::domain::types::{
     OpsAgentMessage, OpsAgentPendingAction, OpsAgentRole, OpsAgentToolCall, OpsAgentToolCallStatus,
     OpsAgentToolKind,
 };
-use crate::ops_agent::infrastructure::logging::{
-    append_debug_log, resolve_ops_agent_log_path, OpsAgentLogContext,
-};
+use crate::ops_agent::infrastructure::logging::{append_debug_log, OpsAgentLogContext};
 use crate::ops_agent::infrastructure::run_registry::OpsAgentRunHandle;
 use crate::ops_agent::tools::{OpsAgentToolOutcome, OpsAgentToolRequest};
 use crate::ops_agent::transport::events::OpsAgentEventEmitter;
 use crate::state::AppState;
 
-use super::helpers::{ensure_run_not_cancelled, truncate_for_log};
+use super::helpers::{ensure_run_not_cancelled, resolve_conversation_ai_config, truncate_for_log};
 use super::ProcessChatOutcome;
 
 pub(crate) async fn process_chat_stream(
     state: Arc<AppState>,
-    app: AppHandle,
+    emitter: OpsAgentEventEmitter,
     run_id: String,
     conversation_id: String,
     session_id: Option<String>,
     current_user_message_id: String,
     run_handle: OpsAgentRunHandle,
 ) -> AppResult<ProcessChatOutcome> {
-    let emitter = OpsAgentEventEmitter::new(
-        app,
-        resolve_ops_agent_log_path(&state.storage.data_dir()),
-        run_id.clone(),
-        conversation_id.clone(),
-    );
     emitter.started();
     append_debug_log(
         state.as_ref(),
@@ -49,7 +40,7 @@ pub(crate) async fn process_chat_stream(
     );
     ensure_run_not_cancelled(&run_handle)?;
 
-    let config = state.storage.get_ai_config();
+    let config = resolve_conversation_ai_config(&state, &conversation_id);
     let conversation = state.ops_agent.get_conversation(&conversation_id)?;
     let conversation = super::compaction::model_conversation_for_current_message(
         state.as_ref(),
@@ -65,7 +56,8 @@ pub(crate) async fn process_chat_stream(
     let mut working_history = history;
     let mut last_planner_reply = String::new();
 
-    for step in 0..super::OPS_AGENT_MAX_REACT_STEPS {
+    let max_tool_steps = config.max_tool_steps.max(1) as usize;
+    for step in 0..max_tool_steps {
         ensure_run_not_cancelled(&run_handle)?;
         let step_number = step + 1;
         let plan = super::llm::plan_reply(
@@ -230,10 +222,7 @@ pub(crate) async fn process_chat_stream(
         }
     }
 
-    let step_limit_hint = format!(
-        "I reached the autonomous tool step limit ({}).",
-        super::OPS_AGENT_MAX_REACT_STEPS
-    );
+    let step_limit_hint = format!("I reached the autonomous tool step limit ({max_tool_steps}).");
     let planner_reply_on_limit = normalized_reply(last_planner_reply, &step_limit_hint);
     let answer = stream_answer(
         state.as_ref(),
@@ -299,8 +288,11 @@ fn finalize_chat_completion(
         None,
         Vec::new(),
     )?;
-    emitter.completed(assistant_answer, pending_action);
-    Ok(ProcessChatOutcome::Completed)
+    emitter.completed(assistant_answer.clone(), pending_action.clone());
+    Ok(ProcessChatOutcome::Completed {
+        answer: assistant_answer,
+        pending_action,
+    })
 }
 
 fn split_history_for_current_message(
@@ -336,7 +328,7 @@ fn normalized_reply(reply: String, fallback: &str) -> String {
 
 fn emit_static_reply(reply: String, emitter: &OpsAgentEventEmitter) -> String {
     if !reply.is_empty() {
-        emitter.delta(reply.clone());
+        emitter.delta_chunked(&reply);
     }
     reply
 }
@@ -4,6 +4,7 @@ use tauri::AppHandle;
 
 use super::helpers::ensure_run_not_cancelled;
 use super::helpers::is_run_cancelled_error;
+use super::helpers::resolve_conversation_ai_config;
 use super::ProcessChatOutcome;
 use crate::models::AiAgentMode;
 use crate::ops_agent::domain::types::{OpsAgentMessage, OpsAgentRole, OpsAgentRunResume};
@@ -20,7 +21,6 @@ enum EffectiveChatRoute {
 
 pub(crate) struct OpsAgentChatRunTask {
     pub state: Arc<AppState>,
-    pub app: AppHandle,
     pub run_id: String,
     pub conversation_id: String,
     pub session_id: Option<String>,
@@ -29,17 +29,22 @@ pub(crate) struct OpsAgentChatRunTask {
     pub resume: Option<OpsAgentRunResume>,
 }
 
-pub(crate) fn spawn_chat_run_task(task: OpsAgentChatRunTask) {
+pub(crate) fn spawn_chat_run_task(app: AppHandle, task: OpsAgentChatRunTask) {
     let state_for_task = Arc::clone(&task.state);
-    let app_for_task = task.app.clone();
     let run_id_for_task = task.run_id.clone();
     let conversation_id_for_task = task.conversation_id.clone();
+    let emitter = OpsAgentEventEmitter::new(
+        app,
+        resolve_ops_agent_log_path(&state_for_task.storage.data_dir()),
+        run_id_for_task.clone(),
+        conversation_id_for_task.clone(),
+    );
     tauri::async_runtime::spawn(async move {
-        let result = run_chat_task(Arc::clone(&state_for_task), app_for_task.clone(), task).await;
+        let result = run_chat_task(emitter.clone(), task).await;
         state_for_task.ops_agent_runs.finish(&run_id_for_task);
 
         match result {
-            Ok(ProcessChatOutcome::Completed) => {
+            Ok(ProcessChatOutcome::Completed { .. }) => {
                 append_debug_log(
                     state_for_task.as_ref(),
                     "chat.completed",
@@ -57,32 +62,53 @@ pub(crate) fn spawn_chat_run_task(task: OpsAgentChatRunTask) {
                     error.to_string(),
                 );
                 if is_run_cancelled_error(&error) {
-                    OpsAgentEventEmitter::new(
-                        app_for_task,
-                        resolve_ops_agent_log_path(&state_for_task.storage.data_dir()),
-                        run_id_for_task,
-                        conversation_id_for_task,
-                    )
-                    .completed(String::new(), None);
+                    emitter.completed(String::new(), None);
                     return;
                 }
-                OpsAgentEventEmitter::new(
-                    app_for_task,
-                    resolve_ops_agent_log_path(&state_for_task.storage.data_dir()),
-                    run_id_for_task,
-                    conversation_id_for_task,
-                )
-                .error(error.to_string());
+                emitter.error(error.to_string());
             }
         }
     });
 }
 
+/// Runs a chat turn to completion without emitting stream events.
+///
+/// Routing, tool execution and persistence are shared with [`spawn_chat_run_task`];
+/// only the transport differs.
+pub(crate) async fn run_chat_to_completion(
+    task: OpsAgentChatRunTask,
+) -> crate::error::AppResult<ProcessChatOutcome> {
+    let state = Arc::clone(&task.state);
+    let run_id = task.run_id.clone();
+    let conversation_id = task.conversation_id.clone();
+    let emitter = OpsAgentEventEmitter::detached(
+        resolve_ops_agent_log_path(&state.storage.data_dir()),
+        run_id.clone(),
+        conversation_id.clone(),
+    );
+    let result = run_chat_task(emitter, task).await;
+    state.ops_agent_runs.finish(&run_id);
+    let (event, message) = match &result {
+        Ok(_) => ("chat.completed", "headless run finished".to_string()),
+        Err(error) => ("chat.error", error.to_string()),
+    };
+    append_debug_log(
+        state.as_ref(),
+        event,
+        Some(run_id.as_str()),
+        Some(conversation_id.as_str()),
+        message,
+    );
+    result
+}
+
 async fn run_chat_task(
-    state: Arc<AppState>,
-    app: AppHandle,
+    emitter: OpsAgentEventEmitter,
     task: OpsAgentChatRunTask,
 ) -> crate::error::AppResult<ProcessChatOutcome> {
+    let state = task.state;
+    let run_id = task.run_id.clone();
+    let conversation_id = task.conversation_id.clone();
     let effective_route = resolve_effective_chat_route(
         state.as_ref(),
         &task.run_id,
@@ -102,10 +128,10 @@ async fn run_chat_task(
         describe_effective_route(&effective_route),
     );
 
-    match effective_route {
+    let outcome = match effective_route {
         EffectiveChatRoute::DirectReply { answer, reason } => complete_direct_reply(
-            state,
-            app,
+            Arc::clone(&state),
+            emitter,
             task.run_id,
             task.conversation_id,
             answer,
@@ -116,8 +142,8 @@ async fn run_chat_task(
             ..
         } => {
             super::react_loop::process_chat_stream(
-                state,
-                app,
+                Arc::clone(&state),
+                emitter,
                 task.run_id,
                 task.conversation_id,
                 task.session_id,
@@ -131,8 +157,8 @@ async fn run_chat_task(
             ..
         } => {
             super::orchestrator::process_chat_stream(
-                state,
-                app,
+                Arc::clone(&state),
+                emitter,
                 task.run_id,
                 task.conversation_id,
                 task.session_id,
@@ -142,7 +168,74 @@ async fn run_chat_task(
             )
             .await
         }
+    }?;
+
+    let ProcessChatOutcome::Completed { answer, .. } = &outcome;
+    spawn_conversation_title_task(state, run_id, conversation_id, answer.clone());
+    Ok(outcome)
+}
+
+/// Replaces the truncated auto-title with a model-generated one after the
+/// first exchange when `aiTitles` is enabled. Nothing is spawned when the
+/// conversation already has a real title. Runs detached so the chat stream
+/// never waits on it; any failure keeps the truncated title.
+fn spawn_conversation_title_task(
+    state: Arc<AppState>,
+    run_id: String,
+    conversation_id: String,
+    answer: String,
+) {
+    let settings = state.ops_agent.get_settings();
+    if !settings.ai_titles {
+        return;
+    }
+    let Ok(conversation) = state.ops_agent.get_conversation(&conversation_id) else {
+        return;
+    };
+    let mut user_messages = conversation
+        .messages
+        .iter()
+        .filter(|message| message.role == OpsAgentRole::User);
+    let (Some(question), None) = (user_messages.next(), user_messages.next()) else {
+        return;
+    };
+    if !state.ops_agent.wants_generated_title(&conversation) {
+        return;
     }
+    let question = question.content.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let config = resolve_conversation_ai_config(state.as_ref(), &conversation_id);
+        let log_context = OpsAgentLogContext::new(
+            state.as_ref(),
+            Some(run_id.as_str()),
+            Some(conversation_id.as_str()),
+        );
+        let result = super::llm::generate_conversation_title(
+            &config,
+            &question,
+            &answer,
+            settings.auto_title_max_chars as usize,
+            Some(log_context),
+        )
+        .await
+        .and_then(|title| {
+            state
+                .ops_agent
+                .apply_generated_title(&conversation_id, &title)
+        });
+        let (event, message) = match result {
+            Ok(conversation) => ("chat.title.generated", conversation.title),
+            Err(error) => ("chat.title.fallback", error.to_string()),
+        };
+        append_debug_log(
+            state.as_ref(),
+            event,
+            Some(run_id.as_str()),
+            Some(conversation_id.as_str()),
+            message,
+        );
+    });
 }
 
 async fn resolve_effective_chat_route(
@@ -154,7 +247,7 @@ async fn resolve_effective_chat_route(
     run_handle: &OpsAgentRunHandle,
     resume: Option<&OpsAgentRunResume>,
 ) -> crate::error::AppResult<EffectiveChatRoute> {
-    let config = state.storage.get_ai_config();
+    let config = resolve_conversation_ai_config(state, conversation_id);
     ensure_run_not_cancelled(run_handle)?;
     if let Some(compaction) = super::compaction::auto_compact_conversation_if_needed(
         state,
@@ -256,18 +349,12 @@ async fn resolve_effective_chat_route(
 
 fn complete_direct_reply(
     state: Arc<AppState>,
-    app: AppHandle,
+    emitter: OpsAgentEventEmitter,
     run_id: String,
     conversation_id: String,
     answer: String,
     reason: String,
 ) -> crate::error::AppResult<ProcessChatOutcome> {
-    let emitter = OpsAgentEventEmitter::new(
-        app,
-        resolve_ops_agent_log_path(&state.storage.data_dir()),
-        run_id.clone(),
-        conversation_id.clone(),
-    );
     emitter.started();
     append_debug_log(
         state.as_ref(),
@@ -276,7 +363,7 @@ fn complete_direct_reply(
         Some(conversation_id.as_str()),
         format!("reason={reason} answer_chars={}", answer.chars().count()),
     );
-    emitter.delta(answer.clone());
+    emitter.delta_chunked(&answer);
     state.ops_agent.append_message(
         &conversation_id,
         OpsAgentRole::Assistant,
@@ -285,8 +372,11 @@ fn complete_direct_reply(
         None,
         Vec::new(),
     )?;
-    emitter.completed(answer, None);
-    Ok(ProcessChatOutcome::Completed)
+    emitter.completed(answer.clone(), None);
+    Ok(ProcessChatOutcome::Completed {
+        answer,
+        pending_action: None,
+    })
 }
 
 fn describe_effective_route(route: &EffectiveChatRoute) -> String {
@@ -208,6 +208,16 @@ pub struct OpsAgentConversation {
     pub id: String,
     pub title: String,
     pub session_id: Option<String>,
+    /// Profile used for this conversation instead of the active one.
+    #[serde(default)]
+    pub ai_profile_id: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub archived: bool,
+    /// Set once the user renames the conversation so auto-titling leaves it alone.
+    #[serde(default)]
+    pub title_locked: bool,
     pub messages: Vec<OpsAgentMessage>,
     pub created_at: String,
     pub updated_at: String,
@@ -219,6 +229,12 @@ pub struct OpsAgentConversationSummary {
     pub id: String,
     pub title: String,
     pub session_id: Option<String>,
+    #[serde(default)]
+    pub ai_profile_id: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub archived: bool,
     pub message_count: usize,
     pub last_message_preview: Option<String>,
     pub created_at: String,
@@ -253,6 +269,9 @@ pub struct OpsAgentPendingAction {
     pub execution_exit_code: Option<i32>,
     #[serde(default)]
     pub resume_context: Option<OpsAgentExecutorResumeContext>,
+    /// Individual steps of an `&&` chain; empty for single commands.
+    #[serde(default)]
+    pub command_steps: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -276,11 +295,37 @@ pub struct OpsAgentData {
     pub pending_actions: Vec<OpsAgentPendingAction>,
 }
 
+/// Whole ops-agent store as one document, for backup and migration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentExportBundle {
+    pub exported_at: String,
+    pub data: OpsAgentData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentImportAllInput {
+    pub bundle: OpsAgentExportBundle,
+    #[serde(default)]
+    pub merge: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentImportAllResult {
+    pub conversations: usize,
+    pub pending_actions: usize,
+    pub reassigned_ids: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpsAgentCreateConversationInput {
     pub title: Option<String>,
     pub session_id: Option<String>,
+    #[serde(default)]
+    pub ai_profile_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -301,6 +346,73 @@ pub struct OpsAgentSetActiveConversationInput {
     pub conversation_id: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentListConversationsInput {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentSetPinnedInput {
+    pub conversation_id: String,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentSetArchivedInput {
+    pub conversation_id: String,
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentRenameConversationInput {
+    pub conversation_id: String,
+    pub title: String,
+}
+
+/// How OpsAgent names new conversations after the first user message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentSettings {
+    #[serde(default = "default_auto_title_max_chars")]
+    pub auto_title_max_chars: u32,
+    /// Ask the model for a concise title after the first exchange; the
+    /// truncated first message stays in place if that call fails.
+    #[serde(default)]
+    pub ai_titles: bool,
+    #[serde(default = "now_rfc3339")]
+    pub updated_at: String,
+}
+
+impl Default for OpsAgentSettings {
+    fn default() -> Self {
+        Self {
+            auto_title_max_chars: default_auto_title_max_chars(),
+            ai_titles: false,
+            updated_at: now_rfc3339(),
+        }
+    }
+}
+
+pub const AUTO_TITLE_MIN_CHARS: u32 = 4;
+pub const AUTO_TITLE_MAX_CHARS_LIMIT: u32 = 120;
+
+pub fn default_auto_title_max_chars() -> u32 {
+    32
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentSettingsInput {
+    pub auto_title_max_chars: u32,
+    #[serde(default)]
+    pub ai_titles: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpsAgentChatInput {
@@ -311,6 +423,9 @@ pub struct OpsAgentChatInput {
     pub shell_context: Option<OpsAgentShellContext>,
     #[serde(default)]
     pub image_attachments: Vec<OpsAgentImageAttachmentInput>,
+    /// Pins the conversation to this profile before the run starts.
+    #[serde(default)]
+    pub ai_profile_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -346,6 +461,16 @@ pub struct OpsAgentChatAccepted {
     pub started_at: String,
 }
 
+/// Final outcome of a headless chat run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentChatResult {
+    pub run_id: String,
+    pub conversation_id: String,
+    pub answer: String,
+    pub pending_action: Option<OpsAgentPendingAction>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum OpsAgentStreamStage {
@@ -391,6 +516,25 @@ pub struct OpsAgentStreamEvent {
 pub struct OpsAgentListPendingActionsInput {
     pub session_id: Option<String>,
     pub only_pending: Option<bool>,
+    /// Paging over the full history; ignored when only pending actions are listed.
+    #[serde(default)]
+    pub offset: Option<usize>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentClearResolvedActionsInput {
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub older_than_days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentClearResolvedActionsResult {
+    pub removed: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -435,6 +579,12 @@ pub struct OpsAgentCancelRunResult {
     pub note: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpsAgentRetryActionInput {
+    pub action_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpsAgentResolveActionResult {
@@ -445,6 +595,7 @@ pub struct OpsAgentResolveActionResult {
 #[derive(Debug, Clone)]
 pub struct PlannedToolAction {
     pub kind: OpsAgentToolKind,
+    /// Executable command; multi-step plans are already joined with `&&`.
     pub command: Option<String>,
     pub reason: Option<String>,
 }
@@ -588,6 +739,9 @@ impl OpsAgentConversationSummary {
             id: conversation.id.clone(),
             title: conversation.title.clone(),
             session_id: conversation.session_id.clone(),
+            ai_profile_id: conversation.ai_profile_id.clone(),
+            pinned: conversation.pinned,
+            archived: conversation.archived,
             message_count: conversation.messages.len(),
             last_message_preview,
             created_at: conversation.created_at.clone(),
@@ -639,6 +793,135 @@ impl OpsAgentShellContext {
     }
 }
 
+/// Joins planned command steps into one `&&` chain.
+///
+/// Steps that contain their own top-level `;`, `||`, `&`, line break or `#`
+/// comment are wrapped in a `{ ...` newline `}` group so the chain still stops
+/// at the first failing step; the newline keeps a trailing `&` or comment from
+/// swallowing the closing brace. A single step is returned unchanged.
+pub fn join_command_steps<S: AsRef<str>>(steps: &[S]) -> Option<String> {
+    let steps = steps
+        .iter()
+        .map(|step| step.as_ref().trim())
+        .filter(|step| !step.is_empty())
+        .collect::<Vec<_>>();
+    match steps.as_slice() {
+        [] => None,
+        [single] => Some(single.to_string()),
+        _ => Some(
+            steps
+                .iter()
+                .map(|step| {
+                    let step = step.trim_end_matches(';').trim_end();
+                    let needs_group = top_level_operators(step)
+                        .iter()
+                        .any(|(_, operator)| *operator != "&&");
+                    if needs_group {
+                        format!("{{ {step}\n}}")
+                    } else {
+                        step.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" && "),
+        ),
+    }
+}
+
+/// Splits a command at its top-level `&&` operators for display.
+///
+/// Returns an empty list for single commands so callers can fall back to the raw text.
+pub fn split_command_steps(command: &str) -> Vec<String> {
+    let mut steps = Vec::new();
+    let mut start = 0;
+    for (index, operator) in top_level_operators(command) {
+        if operator == "&&" {
+            steps.push(&command[start..index]);
+            start = index + operator.len();
+        }
+    }
+    if steps.is_empty() {
+        return Vec::new();
+    }
+    steps.push(&command[start..]);
+
+    steps
+        .into_iter()
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(|step| {
+            step.strip_prefix("{ ")
+                .and_then(|inner| {
+                    inner
+                        .strip_suffix("\n}")
+                        .or_else(|| inner.strip_suffix("; }"))
+                })
+                .unwrap_or(step)
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Finds shell control operators outside quotes and `{ ... }` groups.
+///
+/// The `&` of redirections such as `2>&1`, `>&2`, `&>` and `|&` is not an
+/// operator. A `#` comment is reported as `"#"` and skipped up to the line end.
+fn top_level_operators(command: &str) -> Vec<(usize, &'static str)> {
+    let bytes = command.as_bytes();
+    let mut operators = Vec::new();
+    let mut depth = 0_usize;
+    let mut quote: Option<u8> = None;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        match quote {
+            Some(b'\'') => {
+                if byte == b'\'' {
+                    quote = None;
+                }
+            }
+            Some(_) => {
+                if byte == b'\\' {
+                    index += 1;
+                } else if byte == b'"' {
+                    quote = None;
+                }
+            }
+            None => match byte {
+                b'\\' => index += 1,
+                b'\'' | b'"' => quote = Some(byte),
+                b'{' => depth += 1,
+                b'}' => depth = depth.saturating_sub(1),
+                _ if depth > 0 => {}
+                b'&' | b'|' if bytes.get(index + 1) == Some(&byte) => {
+                    operators.push((index, if byte == b'&' { "&&" } else { "||" }));
+                    index += 1;
+                }
+                b'&' if matches!(
+                    index.checked_sub(1).map(|prev| bytes[prev]),
+                    Some(b'>' | b'<' | b'|')
+                ) || bytes.get(index + 1) == Some(&b'>') => {}
+                b'&' => operators.push((index, "&")),
+                b'#' if index == 0
+                    || bytes[index - 1].is_ascii_whitespace()
+                    || b";&|".contains(&bytes[index - 1]) =>
+                {
+                    operators.push((index, "#"));
+                    while index + 1 < bytes.len() && bytes[index + 1] != b'\n' {
+                        index += 1;
+                    }
+                }
+                b';' => operators.push((index, ";")),
+                b'\n' => operators.push((index, "\n")),
+                _ => {}
+            },
+        }
+        index += 1;
+    }
+    operators
+}
+
 fn default_pending_action_tool_kind() -> OpsAgentToolKind {
     OpsAgentToolKind::shell()
 }
@@ -706,6 +989,58 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn command_steps_join_with_grouping_and_split_back() {
+        let joined = join_command_steps(&[
+            "cd /srv/app",
+            "  git pull; ",
+            "systemctl restart app || journalctl -u app -n 20",
+            "echo 'a && b'",
+            "",
+        ])
+        .expect("joined");
+        assert_eq!(
+            joined,
+            "cd /srv/app && git pull && { systemctl restart app || journalctl -u app -n 20\n} && echo 'a && b'"
+        );
+        assert_eq!(
+            split_command_steps(&joined),
+            vec![
+                "cd /srv/app",
+                "git pull",
+                "systemctl restart app || journalctl -u app -n 20",
+                "echo 'a && b'",
+            ]
+        );
+        assert!(split_command_steps("df -h").is_empty());
+        assert_eq!(
+            join_command_steps(&[" uptime; df -h "]).as_deref(),
+            Some("uptime; df -h")
+        );
+        assert_eq!(join_command_steps(&["  ", ""]), None);
+    }
+
+    #[test]
+    fn command_steps_group_trailing_background_and_comments() {
+        assert_eq!(
+            join_command_steps(&["sleep 5 &", "uptime"]).as_deref(),
+            Some("{ sleep 5 &\n} && uptime")
+        );
+        assert_eq!(
+            join_command_steps(&["df -h # it's fine", "uptime"]).as_deref(),
+            Some("{ df -h # it's fine\n} && uptime")
+        );
+        assert_eq!(
+            join_command_steps(&["make 2>&1", "ls &>/dev/null", "uptime"]).as_deref(),
+            Some("make 2>&1 && ls &>/dev/null && uptime")
+        );
+        assert_eq!(
+            split_command_steps("{ sleep 5 &\n} && uptime"),
+            vec!["sleep 5 &", "uptime"]
+        );
+        assert!(top_level_operators("echo a#b").is_empty());
+    }
+
     #[test]
     fn normalizes_shell_context_payload() {
         let payload = OpsAgentShellContext::normalize(Some(OpsAgentShellContext {
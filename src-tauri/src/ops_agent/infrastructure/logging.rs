@@ -3,6 +3,7 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+use crate::error::redact_secrets;
 use crate::models::now_rfc3339;
 use crate::state::AppState;
 
@@ -82,7 +83,7 @@ pub fn append_debug_log_at_path(
         level,
         run_id.unwrap_or("-"),
         conversation_id.unwrap_or("-"),
-        sanitize_for_single_line(message.as_ref())
+        sanitize_for_single_line(&redact_secrets(message.as_ref()))
     );
     let _ = file.write_all(line.as_bytes());
 }
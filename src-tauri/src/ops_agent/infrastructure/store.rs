@@ -3,6 +3,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,18 +12,21 @@ use crate::models::now_rfc3339;
 use crate::ops_agent::infrastructure::logging::{
     append_debug_log_at_path, resolve_ops_agent_log_path, truncate_for_log,
 };
+use crate::storage::io::{backup_path, read_json, read_json_or_default, write_json_pretty};
 
 use crate::ops_agent::domain::types::{
-    OpsAgentActionStatus, OpsAgentApprovalDecision, OpsAgentConversation,
-    OpsAgentConversationSummary, OpsAgentData, OpsAgentExecutorResumeContext, OpsAgentMessage,
-    OpsAgentPendingAction, OpsAgentRiskLevel, OpsAgentRole, OpsAgentShellContext, OpsAgentToolKind,
+    split_command_steps, OpsAgentActionStatus, OpsAgentApprovalDecision, OpsAgentConversation,
+    OpsAgentConversationSummary, OpsAgentData, OpsAgentExecutorResumeContext,
+    OpsAgentImportAllResult, OpsAgentMessage, OpsAgentPendingAction, OpsAgentRiskLevel,
+    OpsAgentRole, OpsAgentSettings, OpsAgentSettingsInput, OpsAgentShellContext, OpsAgentToolKind,
+    AUTO_TITLE_MAX_CHARS_LIMIT, AUTO_TITLE_MIN_CHARS,
 };
 
 const LEGACY_DATA_FILE: &str = "ops_agent.json";
 const CONVERSATION_LIST_FILE: &str = "ops_agent_conversation_list.json";
 const CONVERSATIONS_DIR: &str = "ops_agent_conversations";
 const DEFAULT_CONVERSATION_TITLE: &str = "New Conversation";
-const AUTO_TITLE_MAX_CHARS: usize = 10;
+const SETTINGS_FILE: &str = "ops_agent_settings.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -50,7 +54,9 @@ pub struct OpsAgentStore {
     log_path: PathBuf,
     list_path: PathBuf,
     conversations_dir: PathBuf,
+    settings_path: PathBuf,
     data: RwLock<OpsAgentData>,
+    settings: RwLock<OpsAgentSettings>,
 }
 
 impl OpsAgentStore {
@@ -66,11 +72,20 @@ impl OpsAgentStore {
         let mut data = load_ops_agent_data(&list_path, &conversations_dir, &legacy_path)?;
         normalize_data(&mut data);
 
+        let settings_path = root.join(SETTINGS_FILE);
+        let mut settings = read_json_or_default::<OpsAgentSettings>(&settings_path)?;
+        settings.auto_title_max_chars = settings
+            .auto_title_max_chars
+            .clamp(AUTO_TITLE_MIN_CHARS, AUTO_TITLE_MAX_CHARS_LIMIT);
+        write_json_pretty(&settings_path, &settings)?;
+
         let store = Self {
             log_path,
             list_path,
             conversations_dir,
+            settings_path,
             data: RwLock::new(data),
+            settings: RwLock::new(settings),
         };
 
         {
@@ -109,14 +124,64 @@ impl OpsAgentStore {
         Ok(store)
     }
 
-    pub fn list_conversation_summaries(&self) -> Vec<OpsAgentConversationSummary> {
+    pub fn get_settings(&self) -> OpsAgentSettings {
+        self.settings
+            .read()
+            .expect("ops agent settings lock poisoned")
+            .clone()
+    }
+
+    pub fn save_settings(&self, input: OpsAgentSettingsInput) -> AppResult<OpsAgentSettings> {
+        if !(AUTO_TITLE_MIN_CHARS..=AUTO_TITLE_MAX_CHARS_LIMIT)
+            .contains(&input.auto_title_max_chars)
+        {
+            return Err(AppError::Validation(format!(
+                "autoTitleMaxChars must be between {AUTO_TITLE_MIN_CHARS} and {AUTO_TITLE_MAX_CHARS_LIMIT}"
+            )));
+        }
+        let settings = OpsAgentSettings {
+            auto_title_max_chars: input.auto_title_max_chars,
+            ai_titles: input.ai_titles,
+            updated_at: now_rfc3339(),
+        };
+
+        let mut guard = self
+            .settings
+            .write()
+            .expect("ops agent settings lock poisoned");
+        write_json_pretty(&self.settings_path, &settings)?;
+        *guard = settings.clone();
+        self.log(
+            "infrastructure.store.settings_saved",
+            None,
+            None,
+            format!(
+                "auto_title_max_chars={} ai_titles={}",
+                settings.auto_title_max_chars, settings.ai_titles
+            ),
+        );
+        Ok(settings)
+    }
+
+    /// Lists conversations pinned first, then by recency. Archived ones are
+    /// skipped unless `include_archived` is set, in which case they sort last.
+    pub fn list_conversation_summaries(
+        &self,
+        include_archived: bool,
+    ) -> Vec<OpsAgentConversationSummary> {
         let guard = self.data.read().expect("ops agent lock poisoned");
         let mut rows = guard
             .conversations
             .iter()
+            .filter(|item| include_archived || !item.archived)
             .map(OpsAgentConversationSummary::from_conversation)
             .collect::<Vec<_>>();
-        rows.sort_by(|left, right| right.updated_at.cmp(&left.updated_at));
+        rows.sort_by(|left, right| {
+            left.archived
+                .cmp(&right.archived)
+                .then_with(|| right.pinned.cmp(&left.pinned))
+                .then_with(|| right.updated_at.cmp(&left.updated_at))
+        });
         rows
     }
 
@@ -190,6 +255,10 @@ impl OpsAgentStore {
             id: Uuid::new_v4().to_string(),
             title: derive_conversation_title(title),
             session_id: normalize_session_id(session_id),
+            ai_profile_id: None,
+            pinned: false,
+            archived: false,
+            title_locked: false,
             messages: Vec::new(),
             created_at: now.clone(),
             updated_at: now,
@@ -213,6 +282,120 @@ impl OpsAgentStore {
         Ok(conversation)
     }
 
+    /// Pins a conversation to an AI profile, or clears the pin with `None`.
+    pub fn set_conversation_ai_profile(
+        &self,
+        id: &str,
+        ai_profile_id: Option<&str>,
+    ) -> AppResult<OpsAgentConversation> {
+        let ai_profile_id = ai_profile_id
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string);
+        self.update_conversation(
+            id,
+            "infrastructure.store.conversation_ai_profile_set",
+            true,
+            |conversation| {
+                if conversation.ai_profile_id == ai_profile_id {
+                    return false;
+                }
+                conversation.ai_profile_id = ai_profile_id;
+                true
+            },
+        )
+    }
+
+    /// Sets a user-chosen title and stops auto-titling from replacing it.
+    pub fn rename_conversation(&self, id: &str, title: &str) -> AppResult<OpsAgentConversation> {
+        if title.trim().is_empty() {
+            return Err(AppError::Validation(
+                "conversation title cannot be empty".to_string(),
+            ));
+        }
+        let title = derive_conversation_title(Some(title));
+        self.update_conversation(
+            id,
+            "infrastructure.store.conversation_renamed",
+            true,
+            |conversation| {
+                let changed = conversation.title != title || !conversation.title_locked;
+                conversation.title = title;
+                conversation.title_locked = true;
+                changed
+            },
+        )
+    }
+
+    /// Replaces the title derived from the first user message with a
+    /// model-generated one. Titles the user chose or renamed are left alone.
+    pub fn apply_generated_title(&self, id: &str, title: &str) -> AppResult<OpsAgentConversation> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(AppError::Validation(
+                "conversation title cannot be empty".to_string(),
+            ));
+        }
+        let auto_title_max_chars = self.get_settings().auto_title_max_chars as usize;
+        self.update_conversation(
+            id,
+            "infrastructure.store.conversation_title_generated",
+            false,
+            |conversation| {
+                if !has_derived_title(conversation, auto_title_max_chars) {
+                    return false;
+                }
+                conversation.title = title.to_string();
+                true
+            },
+        )
+    }
+
+    /// Whether the conversation still carries the title derived from its first
+    /// user message, i.e. a generated title would be applied.
+    pub fn wants_generated_title(&self, conversation: &OpsAgentConversation) -> bool {
+        has_derived_title(
+            conversation,
+            self.get_settings().auto_title_max_chars as usize,
+        )
+    }
+
+    /// Pins a conversation to the top of the list. Does not change its recency.
+    pub fn set_conversation_pinned(
+        &self,
+        id: &str,
+        pinned: bool,
+    ) -> AppResult<OpsAgentConversation> {
+        self.update_conversation(
+            id,
+            "infrastructure.store.conversation_pinned_set",
+            false,
+            |conversation| std::mem::replace(&mut conversation.pinned, pinned) != pinned,
+        )
+    }
+
+    /// Hides a conversation from the default list without deleting it.
+    pub fn set_conversation_archived(
+        &self,
+        id: &str,
+        archived: bool,
+    ) -> AppResult<OpsAgentConversation> {
+        self.update_conversation(
+            id,
+            "infrastructure.store.conversation_archived_set",
+            false,
+            |conversation| std::mem::replace(&mut conversation.archived, archived) != archived,
+        )
+    }
+
+    pub fn active_conversation_id(&self) -> Option<String> {
+        self.data
+            .read()
+            .expect("ops agent lock poisoned")
+            .active_conversation_id
+            .clone()
+    }
+
     pub fn set_active_conversation(&self, id: &str) -> AppResult<()> {
         let mut guard = self.data.write().expect("ops agent lock poisoned");
         if !guard.conversations.iter().any(|item| item.id == id) {
@@ -249,7 +432,9 @@ impl OpsAgentStore {
             guard.active_conversation_id = guard.conversations.first().map(|item| item.id.clone());
         }
 
-        remove_file_if_exists(&self.conversation_path(id))?;
+        let path = self.conversation_path(id);
+        remove_file_if_exists(&path)?;
+        remove_file_if_exists(&backup_path(&path))?;
         self.persist_list_locked(&guard)?;
         self.log(
             "infrastructure.store.conversation_deleted",
@@ -287,6 +472,7 @@ impl OpsAgentStore {
             None
         };
 
+        let auto_title_max_chars = self.get_settings().auto_title_max_chars as usize;
         let mut guard = self.data.write().expect("ops agent lock poisoned");
         let (message, snapshot) = {
             let conversation = guard
@@ -298,7 +484,7 @@ impl OpsAgentStore {
                 })?;
 
             let should_auto_title = matches!(role, OpsAgentRole::User)
-                && should_auto_rename_title(&conversation.title)
+                && should_auto_rename_title(conversation)
                 && !conversation
                     .messages
                     .iter()
@@ -319,6 +505,7 @@ impl OpsAgentStore {
                 conversation.title = derive_title_from_first_user_prompt(
                     derive_title_seed(message.content.as_str(), message.attachment_ids.len())
                         .as_str(),
+                    auto_title_max_chars,
                 );
             }
             conversation.updated_at = now_rfc3339();
@@ -403,6 +590,52 @@ impl OpsAgentStore {
             .collect()
     }
 
+    /// Removes executed, rejected and failed actions, optionally limited to one
+    /// session and to actions resolved at least `older_than_days` ago. Pending
+    /// actions are never touched. Returns how many actions were removed.
+    pub fn clear_resolved_actions(
+        &self,
+        session_id: Option<&str>,
+        older_than_days: Option<u32>,
+    ) -> AppResult<usize> {
+        let cutoff = older_than_days.map(|days| Utc::now() - Duration::days(i64::from(days)));
+        let mut guard = self.data.write().expect("ops agent lock poisoned");
+        let before = guard.pending_actions.len();
+        guard.pending_actions.retain(|item| {
+            let session_match = session_id
+                .map(|session| item.session_id.as_deref() == Some(session))
+                .unwrap_or(true);
+            let age_match = cutoff
+                .map(|cutoff| {
+                    let resolved_at = item.resolved_at.as_deref().unwrap_or(&item.updated_at);
+                    DateTime::parse_from_rfc3339(resolved_at).is_ok_and(|at| at <= cutoff)
+                })
+                .unwrap_or(true);
+            item.status == OpsAgentActionStatus::Pending || !(session_match && age_match)
+        });
+        let removed = before - guard.pending_actions.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        self.persist_list_locked(&guard)?;
+        self.log(
+            "infrastructure.store.resolved_actions_cleared",
+            None,
+            None,
+            format!(
+                "session_id={} older_than_days={} removed={} remaining={}",
+                session_id.unwrap_or("-"),
+                older_than_days
+                    .map(|item| item.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                removed,
+                guard.pending_actions.len()
+            ),
+        );
+        Ok(removed)
+    }
+
     pub fn create_pending_action(
         &self,
         conversation_id: &str,
@@ -457,6 +690,7 @@ impl OpsAgentStore {
             execution_output: None,
             execution_exit_code: None,
             resume_context: None,
+            command_steps: split_command_steps(command),
         };
         guard.pending_actions.push(action.clone());
 
@@ -580,6 +814,46 @@ impl OpsAgentStore {
         )
     }
 
+    /// Puts a failed action back into the approval queue, dropping the result
+    /// and approval of the failed attempt. Only `Failed` actions can be retried.
+    pub fn reset_action_for_retry(&self, action_id: &str) -> AppResult<OpsAgentPendingAction> {
+        let mut guard = self.data.write().expect("ops agent lock poisoned");
+        let action = guard
+            .pending_actions
+            .iter_mut()
+            .find(|item| item.id == action_id)
+            .ok_or_else(|| AppError::NotFound(format!("ops agent action {action_id}")))?;
+        if action.status != OpsAgentActionStatus::Failed {
+            return Err(AppError::Validation(format!(
+                "only failed actions can be retried; action is {:?}",
+                action.status
+            )));
+        }
+
+        action.status = OpsAgentActionStatus::Pending;
+        action.updated_at = now_rfc3339();
+        action.resolved_at = None;
+        action.approval_decision = None;
+        action.approval_comment = None;
+        action.approval_at = None;
+        action.execution_output = None;
+        action.execution_exit_code = None;
+        let snapshot = action.clone();
+
+        self.persist_list_locked(&guard)?;
+        self.log(
+            "infrastructure.store.pending_action_retry",
+            None,
+            Some(snapshot.conversation_id.as_str()),
+            format!(
+                "action_id={} command={}",
+                snapshot.id,
+                truncate_for_log(snapshot.command.as_str(), 160)
+            ),
+        );
+        Ok(snapshot)
+    }
+
     fn update_action_status(
         &self,
         action_id: &str,
@@ -653,6 +927,160 @@ impl OpsAgentStore {
         Ok(snapshot)
     }
 
+    /// Rebuilds the whole store from the list file and the conversation files.
+    pub fn export_all(&self) -> AppResult<OpsAgentData> {
+        let _guard = self.data.read().expect("ops agent lock poisoned");
+        let list_data = read_json_or_default::<OpsAgentConversationListData>(&self.list_path)?;
+        let conversations = load_conversations_with_preferred_order(
+            &self.conversations_dir,
+            &list_data.conversations,
+        )?;
+        Ok(OpsAgentData {
+            conversations,
+            active_conversation_id: list_data.active_conversation_id,
+            pending_actions: list_data.pending_actions,
+        })
+    }
+
+    /// Writes an exported store back out through the split-file layout.
+    ///
+    /// Without `merge` the bundle replaces everything. With `merge` it is added
+    /// next to the existing data: colliding conversation and action ids get
+    /// fresh ones, actions follow their renamed conversation, and the current
+    /// active conversation is kept. Actions whose conversation is missing are
+    /// dropped. Ids become file names, so a bundle carrying any id outside
+    /// `[A-Za-z0-9_-]` is rejected as a whole.
+    pub fn import_all(
+        &self,
+        bundle: OpsAgentData,
+        merge: bool,
+    ) -> AppResult<OpsAgentImportAllResult> {
+        let ids = bundle
+            .conversations
+            .iter()
+            .map(|item| item.id.as_str())
+            .chain(bundle.pending_actions.iter().map(|item| item.id.as_str()));
+        for id in ids {
+            if !id.trim().is_empty() && !is_safe_record_id(id) {
+                return Err(AppError::Validation(format!(
+                    "invalid id in ops agent import: {id:?}"
+                )));
+            }
+        }
+
+        let mut guard = self.data.write().expect("ops agent lock poisoned");
+        let mut next = if merge {
+            guard.clone()
+        } else {
+            OpsAgentData::default()
+        };
+        let mut conversation_ids = next
+            .conversations
+            .iter()
+            .map(|item| item.id.clone())
+            .collect::<HashSet<_>>();
+        let mut action_ids = next
+            .pending_actions
+            .iter()
+            .map(|item| item.id.clone())
+            .collect::<HashSet<_>>();
+        let mut renamed = HashMap::<String, String>::new();
+        let mut result = OpsAgentImportAllResult::default();
+
+        for mut conversation in bundle.conversations {
+            if conversation.id.trim().is_empty() || conversation_ids.contains(&conversation.id) {
+                let fresh = Uuid::new_v4().to_string();
+                renamed.insert(conversation.id.clone(), fresh.clone());
+                conversation.id = fresh;
+                result.reassigned_ids += 1;
+            }
+            conversation_ids.insert(conversation.id.clone());
+            next.conversations.push(conversation);
+            result.conversations += 1;
+        }
+
+        for mut action in bundle.pending_actions {
+            if let Some(fresh) = renamed.get(&action.conversation_id) {
+                action.conversation_id = fresh.clone();
+            }
+            if !conversation_ids.contains(&action.conversation_id) {
+                continue;
+            }
+            if action.id.trim().is_empty() || action_ids.contains(&action.id) {
+                action.id = Uuid::new_v4().to_string();
+                result.reassigned_ids += 1;
+            }
+            action_ids.insert(action.id.clone());
+            next.pending_actions.push(action);
+            result.pending_actions += 1;
+        }
+
+        let imported_active = bundle
+            .active_conversation_id
+            .map(|id| renamed.get(&id).cloned().unwrap_or(id));
+        if !merge || next.active_conversation_id.is_none() {
+            next.active_conversation_id = imported_active;
+        }
+        normalize_data(&mut next);
+
+        self.persist_all_locked(&next)?;
+        *guard = next;
+        self.log(
+            "infrastructure.store.imported_all",
+            None,
+            guard.active_conversation_id.as_deref(),
+            format!(
+                "merge={} conversations={} pending_actions={} reassigned_ids={}",
+                merge, result.conversations, result.pending_actions, result.reassigned_ids
+            ),
+        );
+        Ok(result)
+    }
+
+    /// Applies a metadata change to one conversation and persists it when `mutate` reports a change.
+    ///
+    /// `touch` bumps `updated_at`, which also moves the conversation up the recency order.
+    fn update_conversation<F>(
+        &self,
+        id: &str,
+        event: &str,
+        touch: bool,
+        mutate: F,
+    ) -> AppResult<OpsAgentConversation>
+    where
+        F: FnOnce(&mut OpsAgentConversation) -> bool,
+    {
+        let mut guard = self.data.write().expect("ops agent lock poisoned");
+        let conversation = guard
+            .conversations
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("ops agent conversation {id}")))?;
+        if !mutate(conversation) {
+            return Ok(conversation.clone());
+        }
+        if touch {
+            conversation.updated_at = now_rfc3339();
+        }
+        let snapshot = conversation.clone();
+
+        self.persist_conversation_locked(&snapshot)?;
+        self.persist_list_locked(&guard)?;
+        self.log(
+            event,
+            None,
+            Some(id),
+            format!(
+                "title={} pinned={} archived={} ai_profile_id={}",
+                truncate_for_log(snapshot.title.as_str(), 80),
+                snapshot.pinned,
+                snapshot.archived,
+                snapshot.ai_profile_id.as_deref().unwrap_or("-")
+            ),
+        );
+        Ok(snapshot)
+    }
+
     fn persist_conversation_locked(&self, conversation: &OpsAgentConversation) -> AppResult<()> {
         write_json_pretty(&self.conversation_path(&conversation.id), conversation)
     }
@@ -697,6 +1125,7 @@ impl OpsAgentStore {
 
             if !valid_ids.contains(file_id) {
                 remove_file_if_exists(&path)?;
+                remove_file_if_exists(&backup_path(&path))?;
             }
         }
 
@@ -771,12 +1200,33 @@ fn risk_level_label(value: &OpsAgentRiskLevel) -> &'static str {
     }
 }
 
-fn should_auto_rename_title(current_title: &str) -> bool {
-    let normalized = current_title.trim();
+fn should_auto_rename_title(conversation: &OpsAgentConversation) -> bool {
+    if conversation.title_locked {
+        return false;
+    }
+    let normalized = conversation.title.trim();
     normalized.is_empty() || normalized == DEFAULT_CONVERSATION_TITLE
 }
 
-fn derive_title_from_first_user_prompt(prompt: &str) -> String {
+fn has_derived_title(conversation: &OpsAgentConversation, max_chars: usize) -> bool {
+    if conversation.title_locked {
+        return false;
+    }
+    let Some(first_user) = conversation
+        .messages
+        .iter()
+        .find(|item| item.role == OpsAgentRole::User)
+    else {
+        return false;
+    };
+    let derived = derive_title_from_first_user_prompt(
+        derive_title_seed(first_user.content.as_str(), first_user.attachment_ids.len()).as_str(),
+        max_chars,
+    );
+    conversation.title == derived
+}
+
+fn derive_title_from_first_user_prompt(prompt: &str, max_chars: usize) -> String {
     let compact = prompt
         .replace('\r', " ")
         .replace('\n', " ")
@@ -787,11 +1237,8 @@ fn derive_title_from_first_user_prompt(prompt: &str) -> String {
         return DEFAULT_CONVERSATION_TITLE.to_string();
     }
 
-    let mut out = compact
-        .chars()
-        .take(AUTO_TITLE_MAX_CHARS)
-        .collect::<String>();
-    if compact.chars().count() > AUTO_TITLE_MAX_CHARS {
+    let mut out = compact.chars().take(max_chars).collect::<String>();
+    if compact.chars().count() > max_chars {
         out.push_str("...");
     }
     out
@@ -903,6 +1350,14 @@ fn read_all_conversation_files(conversations_dir: &Path) -> AppResult<Vec<OpsAge
     Ok(rows)
 }
 
+/// Record ids double as file names: only `[A-Za-z0-9_-]` (which covers UUIDs) is allowed.
+fn is_safe_record_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+}
+
 fn is_json_file(path: &Path) -> bool {
     path.is_file()
         && path
@@ -920,37 +1375,6 @@ fn remove_file_if_exists(path: &Path) -> AppResult<()> {
     Ok(())
 }
 
-fn read_json_or_default<T>(path: &Path) -> AppResult<T>
-where
-    T: serde::de::DeserializeOwned + Default,
-{
-    if !path.exists() {
-        return Ok(T::default());
-    }
-    let content = fs::read_to_string(path)?;
-    if content.trim().is_empty() {
-        return Ok(T::default());
-    }
-    Ok(serde_json::from_str(&content)?)
-}
-
-fn read_json<T>(path: &Path) -> AppResult<T>
-where
-    T: serde::de::DeserializeOwned,
-{
-    let content = fs::read_to_string(path)?;
-    Ok(serde_json::from_str(&content)?)
-}
-
-fn write_json_pretty<T>(path: &Path, value: &T) -> AppResult<()>
-where
-    T: serde::Serialize,
-{
-    let text = serde_json::to_string_pretty(value)?;
-    fs::write(path, text)?;
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -971,7 +1395,7 @@ mod tests {
         let conversation = store
             .create_conversation(Some("CPU analysis"), Some("session-1"))
             .expect("create conversation");
-        assert_eq!(store.list_conversation_summaries().len(), 1);
+        assert_eq!(store.list_conversation_summaries(false).len(), 1);
 
         store
             .append_message(
@@ -1028,6 +1452,19 @@ mod tests {
     #[test]
     fn first_user_message_derives_short_title() {
         let store = OpsAgentStore::new(temp_dir("title")).expect("create store");
+        assert_eq!(store.get_settings().auto_title_max_chars, 32);
+        assert!(store
+            .save_settings(OpsAgentSettingsInput {
+                auto_title_max_chars: 2,
+                ai_titles: false,
+            })
+            .is_err());
+        store
+            .save_settings(OpsAgentSettingsInput {
+                auto_title_max_chars: 10,
+                ai_titles: false,
+            })
+            .expect("save settings");
         let conversation = store
             .create_conversation(None, Some("session-1"))
             .expect("create conversation");
@@ -1047,6 +1484,48 @@ mod tests {
             .get_conversation(&conversation.id)
             .expect("load conversation");
         assert_eq!(loaded.title, "abcdefghij...");
+
+        let generated = store
+            .apply_generated_title(&conversation.id, "Alphabet check")
+            .expect("apply generated title");
+        assert_eq!(generated.title, "Alphabet check");
+    }
+
+    #[test]
+    fn renamed_conversation_keeps_title_after_first_message() {
+        let store = OpsAgentStore::new(temp_dir("rename")).expect("create store");
+        let conversation = store
+            .create_conversation(None, Some("session-1"))
+            .expect("create conversation");
+
+        assert!(store.rename_conversation(&conversation.id, "   ").is_err());
+        let renamed = store
+            .rename_conversation(&conversation.id, "Disk cleanup")
+            .expect("rename conversation");
+        assert_eq!(renamed.title, "Disk cleanup");
+        assert!(renamed.title_locked);
+
+        store
+            .append_message(
+                &conversation.id,
+                OpsAgentRole::User,
+                "abcdefghijklmnopqrstuvwxyz",
+                None,
+                None,
+                Vec::new(),
+            )
+            .expect("append user");
+
+        let loaded = store
+            .get_conversation(&conversation.id)
+            .expect("load conversation");
+        assert_eq!(loaded.title, "Disk cleanup");
+        assert!(!store.wants_generated_title(&loaded));
+
+        let untouched = store
+            .apply_generated_title(&conversation.id, "Free up disk space")
+            .expect("apply generated title");
+        assert_eq!(untouched.title, "Disk cleanup");
     }
 
     #[test]
@@ -1112,6 +1591,10 @@ mod tests {
             id: "legacy-conv-1".to_string(),
             title: "Legacy Title".to_string(),
             session_id: Some("session-legacy".to_string()),
+            ai_profile_id: None,
+            pinned: false,
+            archived: false,
+            title_locked: false,
             messages: vec![OpsAgentMessage {
                 id: "legacy-msg-1".to_string(),
                 role: OpsAgentRole::User,
@@ -1134,7 +1617,7 @@ mod tests {
         write_json_pretty(&root.join(LEGACY_DATA_FILE), &legacy_data).expect("write legacy file");
 
         let store = OpsAgentStore::new(root.clone()).expect("create store from legacy");
-        assert_eq!(store.list_conversation_summaries().len(), 1);
+        assert_eq!(store.list_conversation_summaries(false).len(), 1);
         assert!(root.join(CONVERSATION_LIST_FILE).exists());
         assert!(root
             .join(CONVERSATIONS_DIR)
@@ -1159,4 +1642,210 @@ mod tests {
             .expect("reload conversation");
         assert_eq!(loaded.session_id.as_deref(), Some("session-2"));
     }
+
+    #[test]
+    fn export_all_round_trips_and_merge_reassigns_colliding_ids() {
+        let source = OpsAgentStore::new(temp_dir("export-source")).expect("create store");
+        let conversation = source
+            .create_conversation(Some("Disk usage"), Some("session-1"))
+            .expect("create conversation");
+        source
+            .append_message(
+                &conversation.id,
+                OpsAgentRole::User,
+                "check disk",
+                None,
+                None,
+                Vec::new(),
+            )
+            .expect("append user");
+        let action = source
+            .create_pending_action(
+                &conversation.id,
+                None,
+                Some("session-1"),
+                OpsAgentToolKind::write_shell(),
+                OpsAgentRiskLevel::Medium,
+                "rm -rf /tmp/cache",
+                "free space",
+            )
+            .expect("create action");
+        let bundle = source.export_all().expect("export");
+        assert_eq!(bundle.conversations.len(), 1);
+        assert_eq!(bundle.conversations[0].messages.len(), 1);
+        assert_eq!(bundle.pending_actions.len(), 1);
+
+        let root = temp_dir("export-target");
+        let target = OpsAgentStore::new(root.clone()).expect("create target");
+        target
+            .create_conversation(Some("Existing"), None)
+            .expect("create existing");
+        let replaced = target
+            .import_all(bundle.clone(), false)
+            .expect("replace import");
+        assert_eq!(replaced.reassigned_ids, 0);
+        assert_eq!(
+            target.active_conversation_id().as_deref(),
+            Some(conversation.id.as_str())
+        );
+
+        let merged = target.import_all(bundle, true).expect("merge import");
+        assert_eq!(merged.conversations, 1);
+        assert_eq!(merged.reassigned_ids, 2);
+        let actions = target.list_pending_actions(None, false);
+        assert_eq!(actions.len(), 2);
+        assert_ne!(actions[0].id, actions[1].id);
+        assert_eq!(actions[0].conversation_id, conversation.id);
+        assert_ne!(actions[1].conversation_id, conversation.id);
+        assert!(actions.iter().all(|item| item.command == action.command));
+
+        let reloaded = OpsAgentStore::new(root).expect("reload target");
+        assert_eq!(reloaded.list_conversation_summaries(false).len(), 2);
+        assert_eq!(
+            reloaded
+                .get_conversation(&actions[1].conversation_id)
+                .expect("merged conversation")
+                .messages
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn import_all_rejects_ids_that_are_not_file_safe() {
+        let source = OpsAgentStore::new(temp_dir("import-unsafe-source")).expect("create store");
+        source
+            .create_conversation(Some("Disk usage"), None)
+            .expect("create conversation");
+        let mut bundle = source.export_all().expect("export");
+        bundle.conversations[0].id = "../../escape".to_string();
+
+        let root = temp_dir("import-unsafe-target");
+        let target = OpsAgentStore::new(root.clone()).expect("create target");
+        let existing = target
+            .create_conversation(Some("Existing"), None)
+            .expect("create existing");
+        let error = target
+            .import_all(bundle, false)
+            .expect_err("unsafe id must be rejected");
+        assert!(matches!(error, AppError::Validation(_)));
+        assert!(!root.parent().expect("parent").join("escape.json").exists());
+        assert_eq!(target.list_conversation_summaries(false).len(), 1);
+        assert!(target.get_conversation(&existing.id).is_ok());
+    }
+
+    #[test]
+    fn clear_resolved_actions_keeps_pending_ones() {
+        let store = OpsAgentStore::new(temp_dir("clear-resolved")).expect("create store");
+        let conversation = store
+            .create_conversation(Some("Cleanup"), Some("session-1"))
+            .expect("create conversation");
+        let create = |session_id: &str, command: &str| {
+            store
+                .create_pending_action(
+                    &conversation.id,
+                    None,
+                    Some(session_id),
+                    OpsAgentToolKind::write_shell(),
+                    OpsAgentRiskLevel::Medium,
+                    command,
+                    "test",
+                )
+                .expect("create action")
+        };
+        let pending = create("session-1", "systemctl restart nginx");
+        let executed = create("session-1", "touch /tmp/a");
+        let rejected = create("session-1", "reboot");
+        let failed = create("session-1", "apt upgrade");
+        let other_session = create("session-2", "rm /tmp/b");
+        store
+            .mark_action_executed(&executed.id, "ok".to_string(), 0, None)
+            .expect("execute");
+        store
+            .mark_action_rejected(&rejected.id, None)
+            .expect("reject");
+        store
+            .mark_action_failed(&failed.id, "timeout".to_string(), None)
+            .expect("fail");
+        store
+            .mark_action_executed(&other_session.id, "ok".to_string(), 0, None)
+            .expect("execute other");
+
+        assert_eq!(
+            store
+                .clear_resolved_actions(Some("session-1"), Some(1))
+                .expect("clear old"),
+            0
+        );
+        assert_eq!(
+            store
+                .clear_resolved_actions(Some("session-1"), None)
+                .expect("clear session"),
+            3
+        );
+        let remaining = store.list_pending_actions(None, false);
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().any(|item| item.id == pending.id));
+        assert!(remaining.iter().any(|item| item.id == other_session.id));
+
+        assert_eq!(
+            store
+                .clear_resolved_actions(None, Some(0))
+                .expect("clear all"),
+            1
+        );
+        let remaining = store.list_pending_actions(None, false);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].status, OpsAgentActionStatus::Pending);
+    }
+
+    #[test]
+    fn pinned_conversations_sort_first_and_archived_ones_are_hidden() {
+        let root = temp_dir("pin-archive");
+        let store = OpsAgentStore::new(root.clone()).expect("create store");
+        let oldest = store
+            .create_conversation(Some("Reference"), None)
+            .expect("create oldest");
+        let archived = store
+            .create_conversation(Some("Done"), None)
+            .expect("create archived");
+        let newest = store
+            .create_conversation(Some("Current"), None)
+            .expect("create newest");
+
+        let pinned = store
+            .set_conversation_pinned(&oldest.id, true)
+            .expect("pin");
+        assert_eq!(pinned.updated_at, oldest.updated_at);
+        store
+            .set_conversation_archived(&archived.id, true)
+            .expect("archive");
+        assert!(matches!(
+            store.set_conversation_pinned("missing", true),
+            Err(AppError::NotFound(_))
+        ));
+
+        let ids = |rows: Vec<OpsAgentConversationSummary>| {
+            rows.into_iter().map(|item| item.id).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(store.list_conversation_summaries(false)),
+            vec![oldest.id.clone(), newest.id.clone()]
+        );
+        assert_eq!(
+            ids(store.list_conversation_summaries(true)),
+            vec![oldest.id.clone(), newest.id.clone(), archived.id.clone()]
+        );
+
+        let reloaded = OpsAgentStore::new(root).expect("reload store");
+        let rows = reloaded.list_conversation_summaries(true);
+        assert!(rows[0].pinned);
+        assert!(rows[2].archived);
+        assert!(
+            reloaded
+                .get_conversation(&archived.id)
+                .expect("archived conversation")
+                .archived
+        );
+    }
 }
@@ -3,7 +3,7 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::error::{AppError, AppResult};
+use crate::error::{redact_secrets, AppError, AppResult};
 use crate::models::AiConfig;
 use crate::ops_agent::infrastructure::logging::{truncate_for_log, OpsAgentLogContext};
 use crate::ops_agent::transport::stream::{SseEvent, SseEventDecoder};
@@ -12,6 +12,7 @@ use super::types::{
     ProviderChatMessage, ProviderChatMessageContent, ProviderChatMessageResponse,
     ProviderChatRequestOptions, ProviderMessageContentPart, ProviderToolCall, ProviderToolChoice,
 };
+use super::{describe_non_json_response, is_html_response, response_content_type};
 
 const PROVIDER_LOG_MESSAGE_PREVIEW_CHARS: usize = 320;
 const PROVIDER_LOG_TOOL_PREVIEW_CHARS: usize = 220;
@@ -130,6 +131,8 @@ pub async fn request_message(
         })?;
     let status = response.status();
     let content_length = response.content_length();
+    let endpoint = response.url().to_string();
+    let content_type = response_content_type(&response);
     if !status.is_success() {
         let body = response.text().await.unwrap_or_default();
         if let Some(log_context) = log_context {
@@ -143,8 +146,14 @@ pub async fn request_message(
                 ),
             );
         }
+        let detail = if is_html_response(content_type.as_deref(), &body) {
+            describe_non_json_response(&endpoint, content_type.as_deref(), &body)
+        } else {
+            None
+        }
+        .unwrap_or_else(|| format!("body={}", redact_secrets(&body)));
         return Err(AppError::Runtime(format!(
-            "ops agent AI request failed: status={status}, body={body}"
+            "ops agent AI request failed: status={status}, {detail}"
         )));
     }
 
@@ -171,6 +180,20 @@ pub async fn request_message(
         );
     }
 
+    if let Some(message) = describe_non_json_response(&endpoint, content_type.as_deref(), &raw_body)
+    {
+        if let Some(log_context) = log_context {
+            log_context.append(
+                "ai.provider.response_not_json",
+                format!(
+                    "kind={request_kind} provider=anthropic_messages status={status} {message}"
+                ),
+            );
+        }
+        return Err(AppError::Runtime(format!(
+            "ops agent AI request failed: {message}"
+        )));
+    }
     let body: MessagesResponse = serde_json::from_str(&raw_body).map_err(|error| {
         if let Some(log_context) = log_context {
             log_context.append(
@@ -255,7 +278,8 @@ where
             );
         }
         return Err(AppError::Runtime(format!(
-            "ops agent AI stream request failed: status={status}, body={body}"
+            "ops agent AI stream request failed: status={status}, body={}",
+            redact_secrets(&body)
         )));
     }
     if let Some(log_context) = log_context {
@@ -393,6 +417,21 @@ fn build_client_request(
         .json(&payload))
 }
 
+pub(super) fn build_models_request(
+    config: &AiConfig,
+    timeout: Duration,
+) -> reqwest::RequestBuilder {
+    let endpoint = format!(
+        "{}/v1/models?limit=1000",
+        config.base_url.trim_end_matches('/')
+    );
+    reqwest::Client::new()
+        .get(endpoint)
+        .timeout(timeout)
+        .header("x-api-key", &config.api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+}
+
 fn split_messages(
     messages: Vec<ProviderChatMessage>,
 ) -> AppResult<(Option<String>, Vec<WireMessage>)> {
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
 use std::time::Duration;
 
-use crate::error::AppResult;
+use serde_json::Value;
+
+use crate::error::{redact_secrets, AppError, AppResult};
 use crate::models::{AiApiType, AiConfig};
 use crate::ops_agent::infrastructure::logging::OpsAgentLogContext;
 
@@ -19,7 +21,7 @@ pub mod types;
 pub use types::{
     ProviderChatMessage, ProviderChatMessageContent, ProviderChatMessageResponse,
     ProviderChatRequestOptions, ProviderImageUrlPart, ProviderMessageContentPart,
-    ProviderToolChoice, ProviderToolDefinition,
+    ProviderResponseFormat, ProviderToolChoice, ProviderToolDefinition,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -136,6 +138,15 @@ impl ProviderInterface {
             }
         }
     }
+
+    fn build_models_request(self, config: &AiConfig, timeout: Duration) -> reqwest::RequestBuilder {
+        match self {
+            Self::OpenAiChatCompletions | Self::OpenAiResponses => {
+                openai_compat::build_models_request(config, timeout)
+            }
+            Self::AnthropicMessages => anthropic::build_models_request(config, timeout),
+        }
+    }
 }
 
 pub async fn request_message(
@@ -183,6 +194,71 @@ where
         .await
 }
 
+/// Lists the model ids offered by the provider's models endpoint.
+///
+/// `None` means the provider does not implement model listing; auth and
+/// network failures are still reported as errors.
+pub async fn list_models(config: &AiConfig, timeout: Duration) -> AppResult<Option<Vec<String>>> {
+    let response = ProviderInterface::from_config(config)
+        .build_models_request(config, timeout)
+        .send()
+        .await?;
+    let status = response.status();
+    let endpoint = response.url().to_string();
+    let content_type = response_content_type(&response);
+    let body = response.text().await?;
+    parse_model_listing(status, &endpoint, content_type.as_deref(), &body)
+}
+
+fn parse_model_listing(
+    status: reqwest::StatusCode,
+    endpoint: &str,
+    content_type: Option<&str>,
+    body: &str,
+) -> AppResult<Option<Vec<String>>> {
+    if matches!(status.as_u16(), 404 | 405 | 501) {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let detail = if is_html_response(content_type, body) {
+            describe_non_json_response(endpoint, content_type, body)
+        } else {
+            None
+        }
+        .unwrap_or_else(|| format!("body={}", redact_secrets(body)));
+        return Err(AppError::Runtime(format!(
+            "model listing failed: status={status}, {detail}"
+        )));
+    }
+    // Gateways without a models route often answer with their landing page.
+    if describe_non_json_response(endpoint, content_type, body).is_some() {
+        return Ok(None);
+    }
+
+    let value: Value = serde_json::from_str(body)?;
+    let Some(items) = value
+        .get("data")
+        .or_else(|| value.get("models"))
+        .and_then(Value::as_array)
+    else {
+        return Ok(None);
+    };
+    let mut models = items
+        .iter()
+        .filter_map(|item| {
+            item.as_str()
+                .or_else(|| item.get("id").and_then(Value::as_str))
+                .or_else(|| item.get("name").and_then(Value::as_str))
+        })
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    models.sort();
+    models.dedup();
+    Ok(Some(models))
+}
+
 pub fn normalize_tool_kind_alias(
     requested_kind: OpsAgentToolKind,
     registered_tools: &HashSet<String>,
@@ -203,3 +279,128 @@ pub fn normalize_tool_kind_alias(
 
     requested_kind
 }
+
+/// Reads the `Content-Type` header of a provider response, if any.
+pub(crate) fn response_content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Explains a provider body that is not JSON, which almost always means
+/// `baseUrl` points at a web server instead of the API. `None` when the body
+/// should be parsed as JSON.
+pub(crate) fn describe_non_json_response(
+    endpoint: &str,
+    content_type: Option<&str>,
+    body: &str,
+) -> Option<String> {
+    let content_type = content_type
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if content_type.is_some_and(|value| value.to_ascii_lowercase().contains("json")) {
+        return None;
+    }
+    let trimmed = body.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return None;
+    }
+
+    let mut message = format!(
+        "expected JSON from {endpoint} but got {}; check baseUrl",
+        content_type.unwrap_or("a response without Content-Type")
+    );
+    if looks_like_not_found_page(body) {
+        message.push_str(" (the server returned a 404 Not Found page)");
+    }
+    Some(message)
+}
+
+/// True for HTML bodies, which error responses from a real API never are.
+pub(crate) fn is_html_response(content_type: Option<&str>, body: &str) -> bool {
+    content_type.is_some_and(|value| value.to_ascii_lowercase().contains("text/html"))
+        || body.trim_start().starts_with('<')
+}
+
+fn looks_like_not_found_page(body: &str) -> bool {
+    let lower = body.to_ascii_lowercase();
+    lower.contains("404") && lower.contains("not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NGINX_404_PAGE: &str = "<html>\r\n<head><title>404 Not Found</title></head>\r\n<body>\r\n<center><h1>404 Not Found</h1></center>\r\n<hr><center>nginx/1.24.0</center>\r\n</body>\r\n</html>\r\n";
+
+    #[test]
+    fn html_bodies_are_reported_with_endpoint_and_content_type() {
+        let message = describe_non_json_response(
+            "https://example.com/v1/chat/completions",
+            Some("text/html; charset=utf-8"),
+            NGINX_404_PAGE,
+        )
+        .expect("html is not json");
+        assert_eq!(
+            message,
+            "expected JSON from https://example.com/v1/chat/completions but got text/html; charset=utf-8; check baseUrl (the server returned a 404 Not Found page)"
+        );
+        assert!(is_html_response(None, NGINX_404_PAGE));
+
+        let landing = describe_non_json_response(
+            "http://10.0.0.8/chat/completions",
+            None,
+            "<!doctype html><h1>Welcome</h1>",
+        )
+        .expect("landing page");
+        assert!(landing.contains("a response without Content-Type"));
+        assert!(!landing.contains("404"));
+    }
+
+    #[test]
+    fn json_bodies_pass_even_with_a_generic_content_type() {
+        assert_eq!(
+            describe_non_json_response("http://x", Some("application/json"), "oops"),
+            None
+        );
+        assert_eq!(
+            describe_non_json_response("http://x", Some("text/plain"), " {\"choices\":[]}"),
+            None
+        );
+        assert!(!is_html_response(Some("text/plain"), "Unauthorized"));
+    }
+
+    #[test]
+    fn model_listing_reads_ids_and_flags_missing_endpoints() {
+        let models = parse_model_listing(
+            reqwest::StatusCode::OK,
+            "http://x/models",
+            Some("application/json"),
+            r#"{"object":"list","data":[{"id":"gpt-4o-mini"},{"id":"gpt-4o"},{"id":"gpt-4o"}]}"#,
+        )
+        .expect("parse listing");
+        assert_eq!(
+            models,
+            Some(vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string()])
+        );
+
+        for status in [reqwest::StatusCode::NOT_FOUND, reqwest::StatusCode::OK] {
+            assert_eq!(
+                parse_model_listing(status, "http://x/models", Some("text/html"), NGINX_404_PAGE)
+                    .expect("unsupported listing"),
+                None
+            );
+        }
+
+        let err = parse_model_listing(
+            reqwest::StatusCode::UNAUTHORIZED,
+            "http://x/models",
+            Some("application/json"),
+            r#"{"error":{"message":"invalid api key"}}"#,
+        )
+        .expect_err("auth failure");
+        assert!(err.to_string().contains("status=401"));
+    }
+}
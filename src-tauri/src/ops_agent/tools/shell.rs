@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::error::{AppError, AppResult};
@@ -330,8 +331,12 @@ impl OpsAgentTool for ShellTool {
             };
 
             let command = action.command.clone();
-            let execution =
-                execute_remote_command(request.state.clone(), session_id, command.clone()).await;
+            let execution = if server_ops::is_sudo_command(&command) {
+                execute_remote_sudo_command(request.state.clone(), session_id, command.clone())
+                    .await
+            } else {
+                execute_remote_command(request.state.clone(), session_id, command.clone()).await
+            };
             match execution {
                 Ok(execution) => {
                     let output = format_execution_output(
@@ -463,7 +468,20 @@ async fn execute_remote_command(
     command: String,
 ) -> AppResult<CommandExecutionResult> {
     tauri::async_runtime::spawn_blocking(move || {
-        server_ops::execute_command(&state, &session_id, &command)
+        server_ops::execute_command(&state, &session_id, &command, &BTreeMap::new(), None)
+    })
+    .await
+    .map_err(|error| AppError::Runtime(error.to_string()))?
+}
+
+/// Runs an approved `sudo` command with the profile's stored sudo password.
+async fn execute_remote_sudo_command(
+    state: Arc<crate::state::AppState>,
+    session_id: String,
+    command: String,
+) -> AppResult<CommandExecutionResult> {
+    tauri::async_runtime::spawn_blocking(move || {
+        server_ops::execute_sudo_command(&state, &session_id, &command)
     })
     .await
     .map_err(|error| AppError::Runtime(error.to_string()))?
@@ -7,11 +7,13 @@ use crate::ops_agent::domain::types::{
     OpsAgentRunPhase, OpsAgentStreamEvent, OpsAgentStreamStage, OpsAgentToolCall,
 };
 use crate::ops_agent::infrastructure::logging::{append_debug_log_at_path, truncate_for_log};
+use crate::ops_agent::transport::stream::{split_stream_chunks, STREAM_CHUNK_TARGET_CHARS};
 
 /// Thin helper around Tauri event emission so service code stays protocol-focused.
 #[derive(Clone)]
 pub struct OpsAgentEventEmitter {
-    app: AppHandle,
+    /// `None` for headless runs: events are still logged but never emitted.
+    app: Option<AppHandle>,
     log_path: PathBuf,
     run_id: String,
     conversation_id: String,
@@ -25,7 +27,21 @@ impl OpsAgentEventEmitter {
         conversation_id: impl Into<String>,
     ) -> Self {
         Self {
-            app,
+            app: Some(app),
+            log_path,
+            run_id: run_id.into(),
+            conversation_id: conversation_id.into(),
+        }
+    }
+
+    /// Creates an emitter for runs whose caller waits for the final result instead of events.
+    pub fn detached(
+        log_path: PathBuf,
+        run_id: impl Into<String>,
+        conversation_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            app: None,
             log_path,
             run_id: run_id.into(),
             conversation_id: conversation_id.into(),
@@ -136,6 +152,13 @@ impl OpsAgentEventEmitter {
         });
     }
 
+    /// Replays a reply that did not come from a provider stream as word-sized deltas.
+    pub fn delta_chunked(&self, text: &str) {
+        for chunk in split_stream_chunks(text, STREAM_CHUNK_TARGET_CHARS) {
+            self.delta(chunk);
+        }
+    }
+
     pub fn tool_call(&self, tool_call: OpsAgentToolCall) {
         self.emit(OpsAgentStreamStage::ToolCall, |event| {
             let mut next = event;
@@ -193,7 +216,9 @@ impl OpsAgentEventEmitter {
             stage,
         ));
         self.log_event(&event);
-        let _ = self.app.emit("ops-agent-stream", event);
+        if let Some(app) = &self.app {
+            let _ = app.emit("ops-agent-stream", event);
+        }
     }
 
     fn log_event(&self, event: &OpsAgentStreamEvent) {
@@ -1,3 +1,8 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Preferred delta size when replaying a non-streamed reply as stream chunks.
+pub const STREAM_CHUNK_TARGET_CHARS: usize = 36;
+
 /// One decoded SSE event frame.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SseEvent {
@@ -71,6 +76,68 @@ impl SseEventDecoder {
     }
 }
 
+/// Splits text into stream deltas of roughly `target_chars` graphemes.
+///
+/// Chunks end after whitespace or a CJK character near the target so words
+/// stay whole; text without such a break is cut at a grapheme boundary once it
+/// reaches 1.5x the target, so flags and ZWJ emoji are never split.
+pub fn split_stream_chunks(text: &str, target_chars: usize) -> Vec<String> {
+    let target_chars = target_chars.max(1);
+    let hard_limit = target_chars + target_chars / 2;
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    // Byte offset and grapheme count of the last break opportunity in `current`.
+    let mut last_break: Option<(usize, usize)> = None;
+
+    for grapheme in text.graphemes(true) {
+        current.push_str(grapheme);
+        current_len += 1;
+        if allows_break_after(grapheme) {
+            last_break = Some((current.len(), current_len));
+        }
+        if current_len < target_chars {
+            continue;
+        }
+
+        match last_break.filter(|(_, count)| count * 2 >= target_chars) {
+            Some((offset, count)) => {
+                let rest = current.split_off(offset);
+                chunks.push(std::mem::replace(&mut current, rest));
+                current_len -= count;
+                last_break = None;
+            }
+            None if current_len >= hard_limit => {
+                chunks.push(std::mem::take(&mut current));
+                current_len = 0;
+                last_break = None;
+            }
+            None => {}
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn allows_break_after(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace) || grapheme.chars().any(is_cjk_char)
+}
+
+fn is_cjk_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3000}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +184,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_chunks_break_between_words() {
+        let text = "Checked nginx status: the service is active and listening on port 443.";
+        let chunks = split_stream_chunks(text, 20);
+
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.ends_with(' '), "chunk split mid-word: {chunk:?}");
+        }
+    }
+
+    #[test]
+    fn stream_chunks_split_cjk_text_near_target() {
+        let text = "磁盘空间不足，建议先清理日志目录，然后检查大文件并确认备份策略是否合理。";
+        let chunks = split_stream_chunks(text, 10);
+
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.len() >= 3);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.graphemes(true).count() <= 15));
+    }
+
+    #[test]
+    fn stream_chunks_keep_emoji_graphemes_whole() {
+        let flags = "🇯🇵🇨🇳🇺🇸🇩🇪🇫🇷🇬🇧";
+        let family = "👨‍👩‍👧‍👦";
+        let text = format!("{flags}{family}{family}{flags}");
+        let chunks = split_stream_chunks(&text, 4);
+
+        assert_eq!(chunks.concat(), text);
+        let graphemes = text.graphemes(true).collect::<Vec<_>>();
+        for chunk in &chunks {
+            assert!(chunk
+                .graphemes(true)
+                .all(|grapheme| graphemes.contains(&grapheme)));
+        }
+        assert!(split_stream_chunks("", 4).is_empty());
+    }
+
     #[test]
     fn finish_flushes_last_event_without_trailing_blank_line() {
         let mut decoder = SseEventDecoder::default();
@@ -0,0 +1,128 @@
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::models::ChecksumAlgorithm;
+
+/// Incremental hasher over the supported checksum algorithms.
+pub enum ChecksumHasher {
+    Sha256(Sha256),
+    Sha1(Sha1),
+    Md5(Md5),
+}
+
+impl ChecksumHasher {
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            ChecksumAlgorithm::Md5 => Self::Md5(Md5::new()),
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(bytes),
+            Self::Sha1(hasher) => hasher.update(bytes),
+            Self::Md5(hasher) => hasher.update(bytes),
+        }
+    }
+
+    /// Consumes the hasher and returns the lowercase hex digest.
+    pub fn finalize_hex(self) -> String {
+        let digest = match self {
+            Self::Sha256(hasher) => hasher.finalize().to_vec(),
+            Self::Sha1(hasher) => hasher.finalize().to_vec(),
+            Self::Md5(hasher) => hasher.finalize().to_vec(),
+        };
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
+
+/// Returns the coreutils tool that prints a digest for `algorithm`.
+pub fn checksum_tool(algorithm: ChecksumAlgorithm) -> &'static str {
+    match algorithm {
+        ChecksumAlgorithm::Sha256 => "sha256sum",
+        ChecksumAlgorithm::Sha1 => "sha1sum",
+        ChecksumAlgorithm::Md5 => "md5sum",
+    }
+}
+
+/// Extracts the digest from `sha256sum`-style output (`<hex>  <path>`).
+pub fn parse_checksum_output(output: &str, algorithm: ChecksumAlgorithm) -> Option<String> {
+    let expected_len = match algorithm {
+        ChecksumAlgorithm::Sha256 => 64,
+        ChecksumAlgorithm::Sha1 => 40,
+        ChecksumAlgorithm::Md5 => 32,
+    };
+    // GNU coreutils prefixes the digest with `\` when the path needs escaping.
+    let token = output.split_whitespace().next()?.trim_start_matches('\\');
+    if token.len() == expected_len && token.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        Some(token.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+/// Compares a user-supplied digest with a computed one, ignoring case and
+/// anything after the first token (so a pasted `<hex>  <path>` line works).
+pub fn checksum_matches(expected: &str, actual: &str) -> bool {
+    expected
+        .split_whitespace()
+        .next()
+        .is_some_and(|token| token.eq_ignore_ascii_case(actual))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(algorithm: ChecksumAlgorithm, bytes: &[u8]) -> String {
+        let mut hasher = ChecksumHasher::new(algorithm);
+        hasher.update(&bytes[..1]);
+        hasher.update(&bytes[1..]);
+        hasher.finalize_hex()
+    }
+
+    #[test]
+    fn checksum_hasher_matches_known_digests() {
+        assert_eq!(
+            digest(ChecksumAlgorithm::Sha256, b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            digest(ChecksumAlgorithm::Sha1, b"abc"),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            digest(ChecksumAlgorithm::Md5, b"abc"),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+    }
+
+    #[test]
+    fn parse_checksum_output_validates_digest_length() {
+        let output = "900150983cd24fb0d6963f7d28e17f72  /tmp/a b.txt\n";
+        assert_eq!(
+            parse_checksum_output(output, ChecksumAlgorithm::Md5).as_deref(),
+            Some("900150983cd24fb0d6963f7d28e17f72")
+        );
+        assert!(parse_checksum_output(output, ChecksumAlgorithm::Sha256).is_none());
+        assert!(parse_checksum_output("sha256sum: not found", ChecksumAlgorithm::Sha256).is_none());
+    }
+
+    #[test]
+    fn checksum_matches_accepts_pasted_tool_output() {
+        let actual = "900150983cd24fb0d6963f7d28e17f72";
+        assert!(checksum_matches(
+            " 900150983CD24FB0D6963F7D28E17F72 ",
+            actual
+        ));
+        assert!(checksum_matches(
+            "900150983cd24fb0d6963f7d28e17f72  release.tar.gz",
+            actual
+        ));
+        assert!(!checksum_matches("", actual));
+        assert!(!checksum_matches("900150983cd24fb0", actual));
+    }
+}
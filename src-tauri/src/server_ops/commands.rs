@@ -2,20 +2,37 @@ use std::sync::Arc;
 
 use tauri::State;
 
-use crate::error::{to_command_error, AppError, AppResult};
+use crate::error::{to_command_error, AppError, AppResult, CommandError};
 use crate::models::{
-    CancelShellConnectionInput, CloseShellInput, CommandExecutionResult, ExecuteCommandInput,
-    FetchServerStatusInput, OpenShellInput, PtyResizeInput, PtyWriteInput, RunScriptInput,
-    RunScriptResult, SftpCancelTransferInput, SftpCreateInput, SftpDeleteInput, SftpDownloadInput,
-    SftpDownloadPayload, SftpDownloadToLocalInput, SftpFileContent, SftpListInput,
-    SftpListResponse, SftpReadInput, SftpTransferResult, SftpUploadInput,
-    SftpUploadWithProgressInput, SftpWriteInput, ShellSession,
+    CancelShellConnectionInput, CloseShellInput, CommandExecutionResult, CommandHistoryEntry,
+    ConnectionDiagnosticsReport, DirUsageInput, DirUsageResult, DockerContainer,
+    DockerContainerActionInput, ExecuteCommandInput, ExecuteCommandMultiInput,
+    FetchServerStatusInput, FileWatch, HostCommandResult, KillProcessInput, OpenShellInput,
+    PingSessionResult, PortForward, PreviewScriptInput, PtyRecordingInfo, PtyResizeInput,
+    PtyScrollbackInput, PtySignalInput, PtyStartRecordingInput, PtyStopRecordingInput,
+    PtyWriteInput, RemoteFindInput, RemoteFindResult, RestoreSessionsResult, RunScriptInput,
+    RunScriptResult, ScheduleScriptInput, ScriptDefinition, ScriptSchedule, ServiceAction,
+    ServiceActionInput, ServiceStatus, SftpCancelTransferInput, SftpChecksumInput,
+    SftpChecksumResult, SftpCopyInput, SftpCopyResult, SftpCreateInput, SftpDeleteInput,
+    SftpDirDownloadResult, SftpDownloadDirInput, SftpDownloadInput, SftpDownloadPayload,
+    SftpDownloadToLocalInput, SftpFileContent, SftpListInput, SftpListResponse, SftpReadInput,
+    SftpRealpathInput, SftpTransferResult, SftpUploadDirInput, SftpUploadDirResult,
+    SftpUploadInput, SftpUploadVerifiedInput, SftpUploadVerifiedResult,
+    SftpUploadWithProgressInput, SftpWriteInput, ShellSession, SshConfigInput,
+    SshConnectionTestResult, StartLocalForwardInput, StartStatusPollingInput,
+    StopWatchProcessInput, UploadAndRegisterScriptInput, VmStat, WatchFileInput, WatchProcessInput,
+    WatchProcessStarted, ZombieProcess,
 };
+use crate::ops_agent::domain::types::OpsAgentPendingAction;
 use crate::state::AppState;
 
+use super::DockerContainerActionResult;
+
 /// Returns all in-memory shell sessions (multi-tab shell support).
 #[tauri::command]
-pub fn list_shell_sessions(state: State<'_, Arc<AppState>>) -> Result<Vec<ShellSession>, String> {
+pub fn list_shell_sessions(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ShellSession>, CommandError> {
     Ok(state.list_sessions())
 }
 
@@ -28,17 +45,26 @@ pub async fn open_shell_session(
     state: State<'_, Arc<AppState>>,
     app: tauri::AppHandle,
     input: OpenShellInput,
-) -> Result<ShellSession, String> {
+) -> Result<ShellSession, CommandError> {
     let app_state = Arc::clone(state.inner());
-    run_blocking(move || {
-        super::open_shell_session(
-            app_state,
-            app,
-            &input.config_id,
-            input.request_id.as_deref(),
-        )
-    })
-    .await
+    run_blocking(move || super::open_shell_session(app_state, app, &input)).await
+}
+
+/// Reopens the shell sessions left open by the previous run, restoring their cwd.
+#[tauri::command]
+pub async fn restore_sessions(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+) -> Result<RestoreSessionsResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || Ok(super::restore_sessions(app_state, app))).await
+}
+
+/// Closes every shell session ahead of app exit; returns how many were open.
+#[tauri::command]
+pub async fn shutdown_all_sessions(state: State<'_, Arc<AppState>>) -> Result<usize, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || Ok(super::close_all_sessions(&app_state))).await
 }
 
 /// Requests cancellation for a pending shell connection attempt.
@@ -46,7 +72,7 @@ pub async fn open_shell_session(
 pub fn cancel_open_shell_session(
     state: State<'_, Arc<AppState>>,
     input: CancelShellConnectionInput,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     Ok(state.cancel_shell_connection(&input.request_id))
 }
 
@@ -55,7 +81,7 @@ pub fn cancel_open_shell_session(
 pub fn close_shell_session(
     state: State<'_, Arc<AppState>>,
     input: CloseShellInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     super::close_shell_session(&state, &input.session_id).map_err(to_command_error)
 }
 
@@ -64,16 +90,117 @@ pub fn close_shell_session(
 pub fn pty_write_input(
     state: State<'_, Arc<AppState>>,
     input: PtyWriteInput,
-) -> Result<(), String> {
-    super::pty_write_input(&state, &input.session_id, &input.data).map_err(to_command_error)
+) -> Result<(), CommandError> {
+    super::pty_write_input(&state, &input.session_id, &input.data, input.bracketed)
+        .map_err(to_command_error)
+}
+
+/// Sends a terminal control key such as Ctrl-C to the PTY shell.
+#[tauri::command]
+pub fn pty_send_signal(
+    state: State<'_, Arc<AppState>>,
+    input: PtySignalInput,
+) -> Result<(), CommandError> {
+    super::pty_send_signal(&state, &input.session_id, input.signal).map_err(to_command_error)
 }
 
 /// Resizes PTY viewport to keep remote interactive applications aligned.
 #[tauri::command]
-pub fn pty_resize(state: State<'_, Arc<AppState>>, input: PtyResizeInput) -> Result<(), String> {
+pub fn pty_resize(
+    state: State<'_, Arc<AppState>>,
+    input: PtyResizeInput,
+) -> Result<(), CommandError> {
     super::pty_resize(&state, &input.session_id, input.cols, input.rows).map_err(to_command_error)
 }
 
+/// Returns retained PTY output for repainting a reopened terminal view.
+#[tauri::command]
+pub fn get_pty_scrollback(
+    state: State<'_, Arc<AppState>>,
+    input: PtyScrollbackInput,
+) -> Result<String, CommandError> {
+    super::get_pty_scrollback(&state, &input.session_id, input.max_bytes).map_err(to_command_error)
+}
+
+/// Reports whether the session's PTY worker is still running.
+#[tauri::command]
+pub fn session_is_alive(state: State<'_, Arc<AppState>>, session_id: String) -> bool {
+    super::session_is_alive(&state, &session_id)
+}
+
+/// Checks whether the session's SSH connection still works, for a per-tab health indicator.
+#[tauri::command]
+pub async fn ping_session(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<PingSessionResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::ping_session(&app_state, &session_id)).await
+}
+
+/// Starts recording PTY output (and, when asked, input) to a local asciicast v2 file.
+#[tauri::command]
+pub fn pty_start_recording(
+    state: State<'_, Arc<AppState>>,
+    input: PtyStartRecordingInput,
+) -> Result<PtyRecordingInfo, CommandError> {
+    super::pty_start_recording(
+        &state,
+        &input.session_id,
+        &input.local_path,
+        input.record_input,
+    )
+    .map_err(to_command_error)
+}
+
+/// Stops the active PTY recording; returns false when none was running.
+#[tauri::command]
+pub fn pty_stop_recording(
+    state: State<'_, Arc<AppState>>,
+    input: PtyStopRecordingInput,
+) -> Result<bool, CommandError> {
+    super::pty_stop_recording(&state, &input.session_id).map_err(to_command_error)
+}
+
+/// Forwards a local port through the session's server and reports binding via events.
+#[tauri::command]
+pub fn start_local_forward(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: StartLocalForwardInput,
+) -> Result<PortForward, CommandError> {
+    super::start_local_forward(Arc::clone(state.inner()), app, input).map_err(to_command_error)
+}
+
+/// Stops one port forward; returns false when it was not active.
+#[tauri::command]
+pub fn stop_forward(
+    state: State<'_, Arc<AppState>>,
+    forward_id: String,
+) -> Result<bool, CommandError> {
+    Ok(super::stop_forward(&state, &forward_id))
+}
+
+/// Tests DNS, TCP, SSH banner, key exchange, host key and auth for one SSH profile.
+#[tauri::command]
+pub async fn diagnose_connection(
+    state: State<'_, Arc<AppState>>,
+    config_id: String,
+) -> Result<ConnectionDiagnosticsReport, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::diagnose_connection(&app_state, &config_id)).await
+}
+
+/// Verifies an unsaved SSH profile by connecting and running `echo ok`.
+#[tauri::command]
+pub async fn test_ssh_connection(
+    state: State<'_, Arc<AppState>>,
+    input: SshConfigInput,
+) -> Result<SshConnectionTestResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::test_ssh_connection(&app_state, input)).await
+}
+
 /// Executes a terminal command in the selected shell tab.
 ///
 /// The execution is isolated per session so different tabs do not overwrite
@@ -82,10 +209,41 @@ pub fn pty_resize(state: State<'_, Arc<AppState>>, input: PtyResizeInput) -> Res
 pub async fn execute_shell_command(
     state: State<'_, Arc<AppState>>,
     input: ExecuteCommandInput,
-) -> Result<CommandExecutionResult, String> {
+) -> Result<CommandExecutionResult, CommandError> {
     let app_state = Arc::clone(state.inner());
-    run_blocking(move || super::execute_command(&app_state, &input.session_id, &input.command))
-        .await
+    run_blocking(move || {
+        super::execute_command(
+            &app_state,
+            &input.session_id,
+            &input.command,
+            &input.env,
+            input.max_output_bytes,
+        )
+    })
+    .await
+}
+
+/// Returns recent commands of one session for history recall, oldest first.
+#[tauri::command]
+pub fn get_command_history(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<CommandHistoryEntry>, CommandError> {
+    super::get_command_history(&state, &session_id, limit).map_err(to_command_error)
+}
+
+/// Runs one command on several saved hosts concurrently and reports each host separately.
+#[tauri::command]
+pub async fn execute_command_multi(
+    state: State<'_, Arc<AppState>>,
+    input: ExecuteCommandMultiInput,
+) -> Result<Vec<HostCommandResult>, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || {
+        super::execute_command_multi(&app_state, &input.config_ids, &input.command)
+    })
+    .await
 }
 
 /// Browses one remote directory via SFTP.
@@ -93,17 +251,47 @@ pub async fn execute_shell_command(
 pub async fn sftp_list_dir(
     state: State<'_, Arc<AppState>>,
     input: SftpListInput,
-) -> Result<SftpListResponse, String> {
+) -> Result<SftpListResponse, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_list_dir(&app_state, input)).await
 }
 
+/// Searches a remote tree by file name and optionally by content.
+#[tauri::command]
+pub async fn remote_find(
+    state: State<'_, Arc<AppState>>,
+    input: RemoteFindInput,
+) -> Result<RemoteFindResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::remote_find(&app_state, input)).await
+}
+
+/// Returns per-directory disk usage under a remote path, largest first.
+#[tauri::command]
+pub async fn sftp_dir_usage(
+    state: State<'_, Arc<AppState>>,
+    input: DirUsageInput,
+) -> Result<DirUsageResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_dir_usage(&app_state, input)).await
+}
+
+/// Resolves a remote path (following symlinks) to its canonical absolute path.
+#[tauri::command]
+pub async fn sftp_realpath(
+    state: State<'_, Arc<AppState>>,
+    input: SftpRealpathInput,
+) -> Result<String, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_realpath(&app_state, input)).await
+}
+
 /// Reads remote text file content for editor view.
 #[tauri::command]
 pub async fn sftp_read_file(
     state: State<'_, Arc<AppState>>,
     input: SftpReadInput,
-) -> Result<SftpFileContent, String> {
+) -> Result<SftpFileContent, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_read_file(&app_state, input)).await
 }
@@ -113,7 +301,7 @@ pub async fn sftp_read_file(
 pub async fn sftp_write_file(
     state: State<'_, Arc<AppState>>,
     input: SftpWriteInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_write_file(&app_state, input)).await
 }
@@ -123,7 +311,7 @@ pub async fn sftp_write_file(
 pub async fn sftp_create_file(
     state: State<'_, Arc<AppState>>,
     input: SftpCreateInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_create_file(&app_state, input)).await
 }
@@ -133,7 +321,7 @@ pub async fn sftp_create_file(
 pub async fn sftp_create_directory(
     state: State<'_, Arc<AppState>>,
     input: SftpCreateInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_create_directory(&app_state, input)).await
 }
@@ -143,17 +331,27 @@ pub async fn sftp_create_directory(
 pub async fn sftp_upload_file(
     state: State<'_, Arc<AppState>>,
     input: SftpUploadInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_upload_file(&app_state, input)).await
 }
 
+/// Uploads a script file over SFTP and registers it as a saved script.
+#[tauri::command]
+pub async fn upload_and_register_script(
+    state: State<'_, Arc<AppState>>,
+    input: UploadAndRegisterScriptInput,
+) -> Result<ScriptDefinition, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::upload_and_register_script(&app_state, input)).await
+}
+
 /// Deletes one remote file or symlink via SFTP.
 #[tauri::command]
 pub async fn sftp_delete_entry(
     state: State<'_, Arc<AppState>>,
     input: SftpDeleteInput,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_delete_entry(&app_state, input)).await
 }
@@ -164,17 +362,37 @@ pub async fn sftp_upload_file_with_progress(
     state: State<'_, Arc<AppState>>,
     app: tauri::AppHandle,
     input: SftpUploadWithProgressInput,
-) -> Result<SftpTransferResult, String> {
+) -> Result<SftpTransferResult, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_upload_file_with_progress(&app_state, &app, input)).await
 }
 
+/// Uploads a local file and fails unless the remote checksum matches the source.
+#[tauri::command]
+pub async fn sftp_upload_verified(
+    state: State<'_, Arc<AppState>>,
+    input: SftpUploadVerifiedInput,
+) -> Result<SftpUploadVerifiedResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_upload_verified(&app_state, input)).await
+}
+
+/// Returns a remote file's digest, optionally checked against an expected value.
+#[tauri::command]
+pub async fn sftp_checksum(
+    state: State<'_, Arc<AppState>>,
+    input: SftpChecksumInput,
+) -> Result<SftpChecksumResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_checksum(&app_state, input)).await
+}
+
 /// Downloads remote file content via SFTP and returns base64 payload.
 #[tauri::command]
 pub async fn sftp_download_file(
     state: State<'_, Arc<AppState>>,
     input: SftpDownloadInput,
-) -> Result<SftpDownloadPayload, String> {
+) -> Result<SftpDownloadPayload, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_download_file(&app_state, input)).await
 }
@@ -185,14 +403,46 @@ pub async fn sftp_download_file_to_local(
     state: State<'_, Arc<AppState>>,
     app: tauri::AppHandle,
     input: SftpDownloadToLocalInput,
-) -> Result<SftpTransferResult, String> {
+) -> Result<SftpTransferResult, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::sftp_download_file_to_local(&app_state, &app, input)).await
 }
 
+/// Downloads a remote directory as a tar stream, or file by file when `tar` is unavailable.
+#[tauri::command]
+pub async fn sftp_download_dir(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: SftpDownloadDirInput,
+) -> Result<SftpDirDownloadResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_download_dir(&app_state, &app, input)).await
+}
+
+/// Uploads a local directory tree into a remote directory with progress events.
+#[tauri::command]
+pub async fn sftp_upload_dir(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: SftpUploadDirInput,
+) -> Result<SftpUploadDirResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_upload_dir(&app_state, &app, input)).await
+}
+
+/// Copies a remote file to a new path on the same host.
+#[tauri::command]
+pub async fn sftp_copy(
+    state: State<'_, Arc<AppState>>,
+    input: SftpCopyInput,
+) -> Result<SftpCopyResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::sftp_copy(&app_state, input)).await
+}
+
 /// Returns default local download directory for current OS.
 #[tauri::command]
-pub fn sftp_default_download_dir() -> Result<String, String> {
+pub fn sftp_default_download_dir() -> Result<String, CommandError> {
     Ok(super::default_download_dir())
 }
 
@@ -201,7 +451,7 @@ pub fn sftp_default_download_dir() -> Result<String, String> {
 pub fn sftp_cancel_transfer(
     state: State<'_, Arc<AppState>>,
     input: SftpCancelTransferInput,
-) -> Result<bool, String> {
+) -> Result<bool, CommandError> {
     Ok(super::sftp_cancel_transfer(&state, &input.transfer_id))
 }
 
@@ -210,7 +460,7 @@ pub fn sftp_cancel_transfer(
 pub async fn fetch_server_status(
     state: State<'_, Arc<AppState>>,
     input: FetchServerStatusInput,
-) -> Result<crate::models::ServerStatus, String> {
+) -> Result<crate::models::ServerStatus, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || super::fetch_server_status(&app_state, input)).await
 }
@@ -220,10 +470,158 @@ pub async fn fetch_server_status(
 pub fn get_cached_server_status(
     state: State<'_, Arc<AppState>>,
     session_id: String,
-) -> Result<Option<crate::models::ServerStatus>, String> {
+) -> Result<Option<crate::models::ServerStatus>, CommandError> {
     Ok(super::get_cached_server_status(&state, &session_id))
 }
 
+/// Returns a point-in-time `vmstat` overview (run queue, context switches, io).
+#[tauri::command]
+pub async fn get_vmstat(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<VmStat, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::get_vmstat(&app_state, &session_id)).await
+}
+
+/// Lists services and their load/active/sub states, optionally filtered by name.
+#[tauri::command]
+pub async fn list_services(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+    filter: Option<String>,
+) -> Result<Vec<ServiceStatus>, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::list_services(&app_state, &session_id, filter.as_deref())).await
+}
+
+/// Requests a service start through the Ops Agent approval flow.
+#[tauri::command]
+pub fn start_service(
+    state: State<'_, Arc<AppState>>,
+    input: ServiceActionInput,
+) -> Result<OpsAgentPendingAction, CommandError> {
+    super::request_service_action(&state, input, ServiceAction::Start).map_err(to_command_error)
+}
+
+/// Requests a service stop through the Ops Agent approval flow.
+#[tauri::command]
+pub fn stop_service(
+    state: State<'_, Arc<AppState>>,
+    input: ServiceActionInput,
+) -> Result<OpsAgentPendingAction, CommandError> {
+    super::request_service_action(&state, input, ServiceAction::Stop).map_err(to_command_error)
+}
+
+/// Requests a service restart through the Ops Agent approval flow.
+#[tauri::command]
+pub fn restart_service(
+    state: State<'_, Arc<AppState>>,
+    input: ServiceActionInput,
+) -> Result<OpsAgentPendingAction, CommandError> {
+    super::request_service_action(&state, input, ServiceAction::Restart).map_err(to_command_error)
+}
+
+/// Lists Docker containers on the session host.
+#[tauri::command]
+pub async fn docker_list_containers(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<Vec<DockerContainer>, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::docker_list_containers(&app_state, &session_id)).await
+}
+
+/// Starts a container or fetches its logs; stop/restart are queued for approval.
+#[tauri::command]
+pub async fn docker_container_action(
+    state: State<'_, Arc<AppState>>,
+    input: DockerContainerActionInput,
+) -> Result<DockerContainerActionResult, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::docker_container_action(&app_state, input)).await
+}
+
+/// Queues a signal to one process in the approval queue (TERM by default, or KILL).
+#[tauri::command]
+pub fn kill_process(
+    state: State<'_, Arc<AppState>>,
+    input: KillProcessInput,
+) -> Result<OpsAgentPendingAction, CommandError> {
+    super::kill_process(&state, input).map_err(to_command_error)
+}
+
+/// Starts background status polling; updates arrive as `server-status` events.
+#[tauri::command]
+pub async fn start_status_polling(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: StartStatusPollingInput,
+) -> Result<(), CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::start_status_polling(app_state, app, input)).await
+}
+
+/// Stops background status polling for one session.
+#[tauri::command]
+pub fn stop_status_polling(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<bool, CommandError> {
+    Ok(super::stop_status_polling(&state, &session_id))
+}
+
+/// Lists zombie/defunct processes and their parents; empty when there are none.
+#[tauri::command]
+pub async fn get_zombie_processes(
+    state: State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<Vec<ZombieProcess>, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::get_zombie_processes(&app_state, &session_id)).await
+}
+
+/// Starts sampling one remote process and streams `process-sample` events.
+#[tauri::command]
+pub async fn watch_process(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: WatchProcessInput,
+) -> Result<WatchProcessStarted, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::watch_process(app_state, app, input)).await
+}
+
+/// Stops a running process watch.
+#[tauri::command]
+pub fn stop_watch_process(
+    state: State<'_, Arc<AppState>>,
+    input: StopWatchProcessInput,
+) -> Result<bool, CommandError> {
+    Ok(super::stop_watch_process(&state, &input.watch_id))
+}
+
+/// Starts polling a remote file; changes arrive as `file-changed` / `file-removed` /
+/// `file-watch-error` events.
+#[tauri::command]
+pub async fn watch_file_start(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: WatchFileInput,
+) -> Result<FileWatch, CommandError> {
+    let app_state = Arc::clone(state.inner());
+    run_blocking(move || super::watch_file_start(app_state, app, input)).await
+}
+
+/// Stops one file watch; returns false when it did not exist.
+#[tauri::command]
+pub fn watch_file_stop(
+    state: State<'_, Arc<AppState>>,
+    watch_id: String,
+) -> Result<bool, CommandError> {
+    Ok(super::watch_file_stop(&state, &watch_id))
+}
+
 /// Executes one saved script in selected shell tab.
 ///
 /// Priority:
@@ -233,30 +631,58 @@ pub fn get_cached_server_status(
 pub async fn run_script(
     state: State<'_, Arc<AppState>>,
     input: RunScriptInput,
-) -> Result<RunScriptResult, String> {
+) -> Result<RunScriptResult, CommandError> {
     let app_state = Arc::clone(state.inner());
     run_blocking(move || {
-        let script = app_state.storage.find_script(&input.script_id)?;
-        let command = if script.command.trim().is_empty() {
-            format!("bash {}", shell_quote(&script.path))
-        } else {
-            script.command.clone()
-        };
-        let execution = super::execute_command(&app_state, &input.session_id, &command)?;
-        Ok(RunScriptResult {
-            script_id: script.id,
-            script_name: script.name,
-            execution,
-        })
+        super::run_script(
+            &app_state,
+            &input.session_id,
+            &input.script_id,
+            input.args.as_deref(),
+            &input.env,
+        )
     })
     .await
 }
 
-fn shell_quote(value: &str) -> String {
-    format!("'{}'", value.replace('\'', "'\"'\"'"))
+/// Runs a saved script on a timer; results arrive as `scheduled-script-result` events.
+#[tauri::command]
+pub fn schedule_script(
+    state: State<'_, Arc<AppState>>,
+    app: tauri::AppHandle,
+    input: ScheduleScriptInput,
+) -> Result<ScriptSchedule, CommandError> {
+    super::schedule_script(Arc::clone(state.inner()), app, input).map_err(to_command_error)
+}
+
+/// Stops one script schedule; returns false when it did not exist.
+#[tauri::command]
+pub fn unschedule_script(
+    state: State<'_, Arc<AppState>>,
+    schedule_id: String,
+) -> Result<bool, CommandError> {
+    Ok(super::unschedule_script(&state, &schedule_id))
+}
+
+/// Lists script schedules with their latest result, optionally for one session.
+#[tauri::command]
+pub fn list_script_schedules(
+    state: State<'_, Arc<AppState>>,
+    session_id: Option<String>,
+) -> Result<Vec<ScriptSchedule>, CommandError> {
+    Ok(super::list_script_schedules(&state, session_id.as_deref()))
+}
+
+/// Shows the exact command a script run would execute, without executing it.
+#[tauri::command]
+pub fn preview_script(
+    state: State<'_, Arc<AppState>>,
+    input: PreviewScriptInput,
+) -> Result<String, CommandError> {
+    super::preview_script(&state, &input.script_id, input.args.as_deref()).map_err(to_command_error)
 }
 
-async fn run_blocking<T, F>(work: F) -> Result<T, String>
+async fn run_blocking<T, F>(work: F) -> Result<T, CommandError>
 where
     T: Send + 'static,
     F: FnOnce() -> AppResult<T> + Send + 'static,
@@ -1,11 +1,27 @@
+mod checksum;
 pub mod commands;
+mod pty_decoder;
+mod recording;
+mod scrollback;
 mod service;
 mod status_parser;
 
+pub use recording::PtyRecorder;
+pub use scrollback::ScrollbackBuffer;
+pub use service::DockerContainerActionResult;
 pub use service::{
-    close_shell_session, default_download_dir, execute_command, fetch_server_status,
-    get_cached_server_status, open_shell_session, pty_resize, pty_write_input,
-    sftp_cancel_transfer, sftp_create_directory, sftp_create_file, sftp_delete_entry,
-    sftp_download_file, sftp_download_file_to_local, sftp_list_dir, sftp_read_file,
-    sftp_upload_file, sftp_upload_file_with_progress, sftp_write_file,
+    close_all_sessions, close_shell_session, default_download_dir, diagnose_connection,
+    docker_container_action, docker_list_containers, execute_command, execute_command_multi,
+    execute_sudo_command, fetch_server_status, get_cached_server_status, get_command_history,
+    get_pty_scrollback, get_vmstat, get_zombie_processes, is_sudo_command, kill_process,
+    list_script_schedules, list_services, open_shell_session, ping_session, preview_script,
+    pty_resize, pty_send_signal, pty_start_recording, pty_stop_recording, pty_write_input,
+    remote_find, request_service_action, restore_sessions, run_script, schedule_script,
+    session_is_alive, sftp_cancel_transfer, sftp_checksum, sftp_copy, sftp_create_directory,
+    sftp_create_file, sftp_delete_entry, sftp_dir_usage, sftp_download_dir, sftp_download_file,
+    sftp_download_file_to_local, sftp_list_dir, sftp_read_file, sftp_realpath, sftp_upload_dir,
+    sftp_upload_file, sftp_upload_file_with_progress, sftp_upload_verified, sftp_write_file,
+    shutdown_all_sessions, start_local_forward, start_status_polling, stop_forward,
+    stop_status_polling, stop_watch_process, test_ssh_connection, unschedule_script,
+    upload_and_register_script, watch_file_start, watch_file_stop, watch_process,
 };
@@ -0,0 +1,106 @@
+use encoding_rs::{Decoder, Encoding};
+
+use crate::error::{AppError, AppResult};
+
+/// Turns raw PTY reads into text for one shell session.
+///
+/// Without an explicit encoding reads are decoded as lossy UTF-8, holding back
+/// a trailing incomplete sequence until the next read completes it. With one,
+/// decoding goes through `encoding_rs`, whose streaming decoder does the same.
+pub struct PtyOutputDecoder {
+    decoder: Option<Decoder>,
+    utf8_leftover: Vec<u8>,
+}
+
+impl PtyOutputDecoder {
+    /// Builds a decoder from a WHATWG encoding label such as `gbk` or `latin1`.
+    pub fn for_label(label: Option<&str>) -> AppResult<Self> {
+        let Some(label) = label.map(str::trim).filter(|label| !label.is_empty()) else {
+            return Ok(Self {
+                decoder: None,
+                utf8_leftover: Vec::new(),
+            });
+        };
+        let encoding = Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+            AppError::Validation(format!("unsupported terminal encoding: {label}"))
+        })?;
+        Ok(Self {
+            decoder: Some(encoding.new_decoder_without_bom_handling()),
+            utf8_leftover: Vec::new(),
+        })
+    }
+
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        let Some(decoder) = self.decoder.as_mut() else {
+            return self.decode_utf8(bytes);
+        };
+        let capacity = decoder
+            .max_utf8_buffer_length(bytes.len())
+            .unwrap_or(bytes.len() * 3 + 4);
+        let mut output = String::with_capacity(capacity);
+        let _ = decoder.decode_to_string(bytes, &mut output, false);
+        output
+    }
+
+    fn decode_utf8(&mut self, bytes: &[u8]) -> String {
+        self.utf8_leftover.extend_from_slice(bytes);
+        let complete = self.utf8_leftover.len() - incomplete_utf8_tail_len(&self.utf8_leftover);
+        let text = String::from_utf8_lossy(&self.utf8_leftover[..complete]).to_string();
+        self.utf8_leftover.drain(..complete);
+        text
+    }
+}
+
+/// Returns how many trailing bytes form a UTF-8 sequence that is still missing bytes.
+fn incomplete_utf8_tail_len(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0b1100_0000 == 0b1000_0000 {
+            continue;
+        }
+        let width = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if width > back { back } else { 0 };
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pty_output_decoder_handles_gbk_split_across_reads() {
+        // "中文" in GBK.
+        let bytes = [0xD6, 0xD0, 0xCE, 0xC4];
+        let mut decoder = PtyOutputDecoder::for_label(Some("GBK")).expect("gbk decoder");
+        let first = decoder.decode(&bytes[..3]);
+        let second = decoder.decode(&bytes[3..]);
+        assert_eq!(first, "中");
+        assert_eq!(format!("{first}{second}"), "中文");
+    }
+
+    #[test]
+    fn pty_output_decoder_keeps_utf8_split_across_reads() {
+        let bytes = "ok 中文 ✓".as_bytes();
+        let mut decoder = PtyOutputDecoder::for_label(None).expect("default decoder");
+        // Split inside the three-byte "中" and again inside "✓".
+        let first = decoder.decode(&bytes[..4]);
+        let second = decoder.decode(&bytes[4..bytes.len() - 1]);
+        let third = decoder.decode(&bytes[bytes.len() - 1..]);
+        assert_eq!(first, "ok ");
+        assert!(!second.contains('\u{FFFD}'));
+        assert_eq!(format!("{first}{second}{third}"), "ok 中文 ✓");
+    }
+
+    #[test]
+    fn pty_output_decoder_rejects_unknown_label() {
+        assert!(PtyOutputDecoder::for_label(Some("klingon")).is_err());
+        let mut decoder = PtyOutputDecoder::for_label(Some("  ")).expect("default decoder");
+        assert_eq!(decoder.decode("ok".as_bytes()), "ok");
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde_json::json;
+
+use crate::error::{AppError, AppResult};
+
+/// Writes one PTY session as an asciicast v2 file (`asciinema play` compatible).
+///
+/// The file is a JSON header line followed by one `[seconds, code, data]` line
+/// per event, where code is `o` (output), `i` (input) or `r` (resize).
+pub struct PtyRecorder {
+    path: PathBuf,
+    started_at: String,
+    started: Instant,
+    writer: BufWriter<File>,
+    records_input: bool,
+    /// Redacted output held back until more output shows it holds no split secret.
+    pending_output: String,
+}
+
+impl PtyRecorder {
+    /// Starts a new recording file; an existing file is never overwritten.
+    ///
+    /// `term` is reported as the recorded `TERM`. Input events are only written
+    /// when `records_input` is set.
+    pub fn create(
+        path: &Path,
+        cols: u16,
+        rows: u16,
+        term: &str,
+        records_input: bool,
+    ) -> AppResult<Self> {
+        let file = match OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                return Err(AppError::Validation(format!(
+                    "recording file already exists: {}",
+                    path.display()
+                )));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let mut writer = BufWriter::new(file);
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": chrono::Utc::now().timestamp(),
+            "env": { "TERM": term },
+        });
+        writeln!(writer, "{header}")?;
+        writer.flush()?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            started_at: crate::models::now_rfc3339(),
+            started: Instant::now(),
+            writer,
+            records_input,
+            pending_output: String::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn started_at(&self) -> &str {
+        &self.started_at
+    }
+
+    pub fn records_input(&self) -> bool {
+        self.records_input
+    }
+
+    /// Records output passed through `redact`.
+    ///
+    /// The last `overlap` bytes stay pending and are redacted again together
+    /// with the next chunk, so a secret split across two reads is still matched.
+    pub fn record_redacted_output<R>(
+        &mut self,
+        data: &str,
+        overlap: usize,
+        redact: R,
+    ) -> AppResult<()>
+    where
+        R: Fn(&str) -> String,
+    {
+        self.pending_output.push_str(data);
+        let redacted = redact(&self.pending_output);
+        let mut split = redacted.len().saturating_sub(overlap);
+        while !redacted.is_char_boundary(split) {
+            split -= 1;
+        }
+        self.pending_output = redacted[split..].to_string();
+        if split == 0 {
+            return Ok(());
+        }
+        self.write_event("o", &redacted[..split])
+    }
+
+    /// Writes the output still held back by `record_redacted_output`.
+    pub fn flush_output(&mut self) -> AppResult<()> {
+        if self.pending_output.is_empty() {
+            return Ok(());
+        }
+        let pending = std::mem::take(&mut self.pending_output);
+        self.write_event("o", &pending)
+    }
+
+    pub fn record_input(&mut self, data: &str) -> AppResult<()> {
+        self.write_event("i", data)
+    }
+
+    pub fn record_resize(&mut self, cols: u16, rows: u16) -> AppResult<()> {
+        self.flush_output()?;
+        self.write_event("r", &format!("{cols}x{rows}"))
+    }
+
+    pub fn finish(mut self) -> AppResult<()> {
+        self.flush_output()?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_event(&mut self, code: &str, data: &str) -> AppResult<()> {
+        let elapsed = (self.started.elapsed().as_secs_f64() * 1_000_000.0).round() / 1_000_000.0;
+        writeln!(self.writer, "{}", json!([elapsed, code, data]))?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorder_writes_asciicast_header_and_events() {
+        let path =
+            std::env::temp_dir().join(format!("eshell-recording-{}.cast", uuid::Uuid::new_v4()));
+        let mut recorder =
+            PtyRecorder::create(&path, 120, 36, "screen-256color", true).expect("create recorder");
+        assert!(recorder.records_input());
+        recorder
+            .record_redacted_output("hello\r\n", 0, |text| text.to_string())
+            .expect("output");
+        recorder.record_input("ls\r").expect("input");
+        recorder.record_resize(100, 30).expect("resize");
+        recorder.finish().expect("finish");
+
+        let content = std::fs::read_to_string(&path).expect("read cast");
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+
+        let header: serde_json::Value = serde_json::from_str(lines[0]).expect("header json");
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 120);
+        assert_eq!(header["height"], 36);
+        assert_eq!(header["env"]["TERM"], "screen-256color");
+
+        let output: serde_json::Value = serde_json::from_str(lines[1]).expect("event json");
+        assert!(output[0].as_f64().expect("timestamp") >= 0.0);
+        assert_eq!(output[1], "o");
+        assert_eq!(output[2], "hello\r\n");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(lines[3]).expect("resize")[2],
+            "100x30"
+        );
+
+        assert!(PtyRecorder::create(&path, 80, 24, "xterm", false).is_err());
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("reread cast"),
+            content
+        );
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn recorder_holds_back_an_overlap_until_flushed() {
+        let path =
+            std::env::temp_dir().join(format!("eshell-recording-{}.cast", uuid::Uuid::new_v4()));
+        let mut recorder =
+            PtyRecorder::create(&path, 80, 24, "xterm", false).expect("create recorder");
+        let mask = |text: &str| text.replace("hunter2", "***");
+        recorder
+            .record_redacted_output("login ok\npw hun", 4, mask)
+            .expect("first chunk");
+        recorder
+            .record_redacted_output("ter2\n", 4, mask)
+            .expect("second chunk");
+        recorder.finish().expect("finish");
+
+        let content = std::fs::read_to_string(&path).expect("read cast");
+        assert!(!content.contains("hunter2"));
+        let output = content
+            .lines()
+            .skip(1)
+            .map(|line| {
+                let event: serde_json::Value = serde_json::from_str(line).expect("event json");
+                event[2].as_str().expect("event data").to_string()
+            })
+            .collect::<String>();
+        assert_eq!(output, "login ok\npw ***\n");
+        let _ = std::fs::remove_file(path);
+    }
+}
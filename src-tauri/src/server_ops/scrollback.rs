@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+/// Byte-bounded ring buffer holding the most recent PTY output of one session.
+///
+/// Appends are amortized O(chunk); once the capacity is reached the oldest
+/// bytes are dropped. Reads skip a leading partial UTF-8 sequence left behind
+/// by eviction so callers always get valid text.
+pub struct ScrollbackBuffer {
+    bytes: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            bytes: VecDeque::with_capacity(capacity.min(64 * 1024)),
+            capacity,
+        }
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        let incoming = text.as_bytes();
+        if incoming.len() >= self.capacity {
+            let start = incoming.len() - self.capacity;
+            self.bytes.clear();
+            self.bytes.extend(&incoming[start..]);
+            return;
+        }
+        let overflow = (self.bytes.len() + incoming.len()).saturating_sub(self.capacity);
+        self.bytes.drain(..overflow);
+        self.bytes.extend(incoming);
+    }
+
+    /// Removes and returns up to the last `max_bytes` bytes, widened to a char boundary.
+    pub fn take_tail(&mut self, max_bytes: usize) -> String {
+        let mut start = self.bytes.len().saturating_sub(max_bytes);
+        while start > 0 && is_utf8_continuation(self.bytes[start]) {
+            start -= 1;
+        }
+        let tail = self.bytes.drain(start..).collect::<Vec<_>>();
+        String::from_utf8_lossy(&tail).into_owned()
+    }
+
+    /// Returns up to the last `max_bytes` bytes, starting on a char boundary.
+    pub fn tail(&self, max_bytes: usize) -> String {
+        let start = self.bytes.len().saturating_sub(max_bytes);
+        let tail = self
+            .bytes
+            .range(start..)
+            .skip_while(|byte| is_utf8_continuation(**byte))
+            .copied()
+            .collect::<Vec<_>>();
+        String::from_utf8_lossy(&tail).into_owned()
+    }
+}
+
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrollback_buffer_evicts_oldest_bytes() {
+        let mut buffer = ScrollbackBuffer::new(8);
+        buffer.push_str("hello ");
+        buffer.push_str("world");
+        assert_eq!(buffer.tail(usize::MAX), "lo world");
+        assert_eq!(buffer.tail(5), "world");
+
+        buffer.push_str("0123456789");
+        assert_eq!(buffer.tail(usize::MAX), "23456789");
+    }
+
+    #[test]
+    fn scrollback_buffer_tail_starts_on_char_boundary() {
+        let mut buffer = ScrollbackBuffer::new(5);
+        buffer.push_str("a中文");
+        // "中" is three bytes; eviction leaves two of them at the front.
+        assert_eq!(buffer.tail(usize::MAX), "文");
+        assert_eq!(buffer.tail(4), "文");
+    }
+
+    #[test]
+    fn scrollback_buffer_take_tail_keeps_whole_chars() {
+        let mut buffer = ScrollbackBuffer::new(64);
+        buffer.push_str("ab中文");
+        assert_eq!(buffer.take_tail(2), "文");
+        assert_eq!(buffer.tail(usize::MAX), "ab中");
+        assert_eq!(buffer.take_tail(usize::MAX), "ab中");
+        assert_eq!(buffer.tail(usize::MAX), "");
+    }
+}
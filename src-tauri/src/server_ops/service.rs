@@ -1,9 +1,12 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
-use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use std::time::Instant;
@@ -12,51 +15,139 @@ use base64::engine::general_purpose::{
     STANDARD as BASE64_STANDARD, STANDARD_NO_PAD as BASE64_STANDARD_NO_PAD,
 };
 use base64::Engine;
-use ssh2::{ErrorCode, FileStat, HashType, HostKeyType, Session};
+use serde::{Deserialize, Serialize};
+use ssh2::{ErrorCode, FileStat, HashType, HostKeyType, MethodType, Session};
 use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+use super::checksum::{checksum_matches, checksum_tool, parse_checksum_output, ChecksumHasher};
+use super::pty_decoder::PtyOutputDecoder;
+use super::recording::PtyRecorder;
+use super::scrollback::ScrollbackBuffer;
 use super::status_parser::{
-    parse_cpu_percent, parse_disks, parse_memory, parse_network_interfaces, parse_top_processes,
+    apply_network_rates, is_pseudo_filesystem, parse_bsd_sysctl_memory, parse_cpu_percent,
+    parse_disks, parse_docker_containers, parse_du_output, parse_first_pid, parse_memory,
+    parse_netstat_interfaces, parse_network_interfaces, parse_nvidia_smi_gpus, parse_os_family,
+    parse_per_core_cpu, parse_proc_stat_cpu, parse_process_sample, parse_service_statuses,
+    parse_top_processes, parse_vm_stat_memory, parse_vmstat, parse_zombie_processes,
+    RemoteOsFamily,
 };
-use crate::error::{AppError, AppResult};
+use crate::error::{redact_secrets, AppError, AppResult, SSH_HOST_KEY_TRUST_REQUIRED_PREFIX};
 use crate::models::{
-    now_rfc3339, CommandExecutionResult, FetchServerStatusInput, MemoryStatus,
-    NetworkInterfaceStatus, PtyOutputEvent, SftpCreateInput, SftpDeleteInput, SftpDownloadInput,
-    SftpDownloadPayload, SftpDownloadToLocalInput, SftpEntry, SftpEntryType, SftpFileContent,
-    SftpListInput, SftpListResponse, SftpReadInput, SftpTransferEvent, SftpTransferResult,
-    SftpUploadInput, SftpUploadWithProgressInput, SftpWriteInput, ShellSession, SshAuthType,
-    SshConfig, SshHostKeyTrustChallenge, SshHostKeyTrustReason,
+    is_valid_env_var_name, now_rfc3339, ChecksumAlgorithm, CommandExecutionResult,
+    CommandHistoryEntry, ConnectionDiagnosticStep, ConnectionDiagnosticsReport, DirUsageInput,
+    DirUsageResult, DockerContainer, DockerContainerAction, DockerContainerActionInput,
+    FetchServerStatusInput, FileChangedEvent, FileRemovedEvent, FileSnapshot, FileWatch,
+    FileWatchErrorEvent, GpuStatus, HostCommandResult, KillProcessInput, KillSignal, MemoryStatus,
+    NetworkInterfaceStatus, OpenShellInput, PingSessionResult, PortForward, PortForwardEvent,
+    PortForwardStatus, ProcessSample, ProcessSampleEvent, ProcessSort, PtyCloseReason,
+    PtyClosedEvent, PtyOutputEvent, PtyReconnectEvent, PtyRecordingInfo, PtySignal,
+    RemoteFindInput, RemoteFindResult, RestoreSessionsResult, RunScriptResult, ScheduleScriptInput,
+    ScheduledScriptResultEvent, ScriptDefinition, ScriptInput, ScriptSchedule, ServerStatusEvent,
+    ServiceAction, ServiceActionInput, ServiceStatus, SessionManifestEntry, SftpChecksumInput,
+    SftpChecksumResult, SftpCopyInput, SftpCopyMethod, SftpCopyResult, SftpCreateInput,
+    SftpDeleteInput, SftpDirDownloadMode, SftpDirDownloadResult, SftpDownloadDirInput,
+    SftpDownloadInput, SftpDownloadPayload, SftpDownloadToLocalInput, SftpEntry, SftpEntryType,
+    SftpFileContent, SftpListInput, SftpListResponse, SftpReadInput, SftpRealpathInput,
+    SftpTransferEvent, SftpTransferResult, SftpUploadDirInput, SftpUploadDirResult,
+    SftpUploadInput, SftpUploadVerifiedInput, SftpUploadVerifiedResult,
+    SftpUploadWithProgressInput, SftpWriteInput, ShellSession, SshAuthType, SshConfig,
+    SshConfigInput, SshConnectionTestResult, SshHandshakeInfo, SshHostKeyTrustChallenge,
+    SshHostKeyTrustReason, SshJumpHost, StartLocalForwardInput, StartStatusPollingInput,
+    StatusCommandProfile, UploadAndRegisterScriptInput, VmStat, WatchFileInput, WatchProcessInput,
+    WatchProcessStarted, ZombieProcess,
+};
+use crate::ops_agent::domain::types::{OpsAgentPendingAction, OpsAgentRiskLevel, OpsAgentToolKind};
+use crate::state::{
+    AppState, PtyCommand, SharedSshSession, FILE_WATCH_WORKER_PREFIX, MAX_COMMAND_HISTORY_ENTRIES,
+    PORT_FORWARD_WORKER_PREFIX, PROCESS_WATCH_WORKER_PREFIX, SCRIPT_SCHEDULE_WORKER_PREFIX,
+    STATUS_POLLING_WORKER_PREFIX,
 };
-use crate::state::{AppState, PtyCommand};
 
+const DEFAULT_PTY_TERM: &str = "xterm-256color";
 const DEFAULT_PTY_COLS: u16 = 120;
 const DEFAULT_PTY_ROWS: u16 = 36;
-const MAX_SESSION_LAST_OUTPUT_CHARS: usize = 16_000;
-const PTY_IDLE_SLEEP_MS: u64 = 8;
+const MIN_PTY_COLS: u16 = 20;
+const MIN_PTY_ROWS: u16 = 8;
+const REDACTION_OVERLAP_BYTES: usize = 1024;
+const DEFAULT_PTY_SCROLLBACK_BYTES: usize = 1024 * 1024;
+const MIN_PTY_SCROLLBACK_BYTES: usize = 64 * 1024;
+const MAX_PTY_SCROLLBACK_BYTES: usize = 16 * 1024 * 1024;
+// The SSH session is non-blocking and shared with writes, so the worker cannot block on
+// a socket read. While idle it waits on the command channel instead, backing off from
+// the minimum to the maximum: input wakes it immediately, but remote output that
+// arrives during a long idle stretch can be up to the maximum late.
+const PTY_IDLE_MIN_WAIT: Duration = Duration::from_millis(1);
+const PTY_IDLE_MAX_WAIT: Duration = Duration::from_millis(50);
 const PTY_MAX_COMMANDS_PER_TICK: usize = 64;
+// The jump host relay polls two non-blocking ends and backs off the same way while
+// neither has data, so an idle tunnel costs a wakeup every `RELAY_IDLE_MAX_WAIT`.
+const RELAY_IDLE_MIN_WAIT: Duration = Duration::from_millis(1);
+const RELAY_IDLE_MAX_WAIT: Duration = Duration::from_millis(50);
 const PTY_MAX_WRITE_OPS_PER_TICK: usize = 24;
 const PTY_MAX_READ_CHUNKS_PER_TICK: usize = 8;
 const SFTP_TRANSFER_EVENT: &str = "sftp-transfer";
 const SFTP_TRANSFER_CHUNK_BYTES: usize = 64 * 1024;
+const PROCESS_SAMPLE_EVENT: &str = "process-sample";
+const SERVER_STATUS_EVENT: &str = "server-status";
+const PORT_FORWARD_EVENT: &str = "port-forward";
+const PTY_CLOSED_EVENT: &str = "pty-closed";
+const PTY_RECONNECTING_EVENT: &str = "pty-reconnecting";
+const PTY_RECONNECTED_EVENT: &str = "pty-reconnected";
+const PTY_RECONNECT_FAILED_EVENT: &str = "pty-reconnect-failed";
+const PTY_RECONNECT_MAX_ATTEMPTS: u32 = 5;
+/// Wait before the first reconnect attempt; doubled after each failure.
+const PTY_RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const PTY_RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Longest a PTY rerun of a sudo command may take, prompts included.
+const SUDO_TTY_FALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+/// How long `shutdown_all_sessions` waits for PTY workers to close their channels.
+const SHUTDOWN_WORKER_GRACE: Duration = Duration::from_secs(2);
+/// How long opening a PTY channel waits on a shared session busy with other tabs.
+const PTY_CHANNEL_OPEN_TIMEOUT: Duration = Duration::from_secs(15);
+const FILE_CHANGED_EVENT: &str = "file-changed";
+const FILE_REMOVED_EVENT: &str = "file-removed";
+const FILE_WATCH_ERROR_EVENT: &str = "file-watch-error";
+const FILE_WATCH_MIN_INTERVAL_SECS: u64 = 1;
+const FILE_WATCH_MAX_INTERVAL_SECS: u64 = 3600;
+const MULTI_HOST_MAX_PARALLEL: usize = 8;
+const SCHEDULED_SCRIPT_RESULT_EVENT: &str = "scheduled-script-result";
+const SCRIPT_SCHEDULE_MIN_INTERVAL_SECS: u64 = 10;
+const SCRIPT_SCHEDULE_MAX_INTERVAL_SECS: u64 = 86_400;
+const DOCKER_LOGS_DEFAULT_TAIL: u32 = 200;
+const STATUS_POLLING_MIN_INTERVAL_SECS: u64 = 1;
+const STATUS_POLLING_MAX_INTERVAL_SECS: u64 = 3600;
+const PROCESS_WATCH_MAX_SAMPLES: u32 = 10_000;
+/// Matches the former `head -n 5`: one header line plus four processes.
+const STATUS_PROCESS_DEFAULT_LIMIT: u32 = 4;
+const STATUS_PROCESS_MAX_LIMIT: u32 = 50;
+const BACKGROUND_WORKER_POLL_INTERVAL: Duration = Duration::from_millis(200);
 const SSH_CONNECT_TOTAL_TIMEOUT: Duration = Duration::from_secs(45);
 const SSH_CONNECT_SLICE_TIMEOUT: Duration = Duration::from_millis(500);
 const SSH_CONNECT_POLL_INTERVAL: Duration = Duration::from_millis(25);
 const SSH_CONNECTION_CANCELLED_MESSAGE: &str = "SSH connection cancelled by user";
-const SSH_HOST_KEY_TRUST_REQUIRED_PREFIX: &str = "SSH_HOST_KEY_TRUST_REQUIRED:";
+const DEFAULT_COMMAND_OUTPUT_MAX_BYTES: usize = 10 * 1024 * 1024;
+const DIR_USAGE_DEFAULT_DEPTH: u32 = 1;
+const DIR_USAGE_MAX_DEPTH: u32 = 8;
+const DIR_USAGE_MAX_WARNINGS: usize = 100;
+const REMOTE_FIND_DEFAULT_RESULTS: usize = 200;
+const REMOTE_FIND_MAX_RESULTS: usize = 5000;
+const REMOTE_FIND_TIMEOUT_SECS: u64 = 30;
+/// Enough room for `REMOTE_FIND_MAX_RESULTS` paths of typical length plus one.
+const REMOTE_FIND_OUTPUT_BYTES_PER_RESULT: usize = 1024;
 
 /// Creates a shell session and starts a long-lived PTY worker for interactive terminal IO.
 pub fn open_shell_session(
     state: Arc<AppState>,
     app: AppHandle,
-    config_id: &str,
-    request_id: Option<&str>,
+    input: &OpenShellInput,
 ) -> AppResult<ShellSession> {
+    let request_id = input.request_id.as_deref();
     if let Some(request_id) = request_id {
         state.begin_shell_connection(request_id);
     }
 
-    let result = open_shell_session_inner(Arc::clone(&state), app, config_id, request_id);
+    let result = open_shell_session_inner(Arc::clone(&state), app, input, None);
 
     if let Some(request_id) = request_id {
         state.clear_shell_connection(request_id);
@@ -65,13 +156,104 @@ pub fn open_shell_session(
     result
 }
 
+/// Reopens the shell sessions that were open when the app last exited.
+///
+/// Each session keeps its id and working directory. Sessions whose SSH config
+/// was deleted, or that fail to reconnect, are skipped and reported as warnings.
+pub fn restore_sessions(state: Arc<AppState>, app: AppHandle) -> RestoreSessionsResult {
+    let mut sessions = Vec::new();
+    let mut warnings = Vec::new();
+    for entry in state.take_restorable_sessions() {
+        if state.storage.find_ssh_config(&entry.config_id).is_err() {
+            warnings.push(format!(
+                "skipped session {}: ssh config {} no longer exists",
+                entry.id, entry.config_id
+            ));
+            continue;
+        }
+        let input = OpenShellInput {
+            config_id: entry.config_id.clone(),
+            request_id: None,
+            scrollback_bytes: None,
+            encoding: None,
+            initial_dir: None,
+            initial_command: None,
+            term: None,
+            cols: None,
+            rows: None,
+            auto_reconnect: false,
+        };
+        match open_shell_session_inner(Arc::clone(&state), app.clone(), &input, Some(&entry)) {
+            Ok(session) => sessions.push(session),
+            Err(err) => warnings.push(format!("failed to restore session {}: {err}", entry.id)),
+        }
+    }
+    RestoreSessionsResult { sessions, warnings }
+}
+
 fn open_shell_session_inner(
     state: Arc<AppState>,
     app: AppHandle,
-    config_id: &str,
-    request_id: Option<&str>,
+    input: &OpenShellInput,
+    restore: Option<&SessionManifestEntry>,
 ) -> AppResult<ShellSession> {
-    let config = state.storage.find_ssh_config(config_id)?;
+    if state.is_shutting_down() {
+        return Err(AppError::Runtime(
+            "cannot open a shell session while the app is shutting down".to_string(),
+        ));
+    }
+    let request_id = input.request_id.as_deref();
+    let decoder = PtyOutputDecoder::for_label(input.encoding.as_deref())?;
+    let pty_spec = PtySpec::from_input(input)?;
+    let config = state.storage.find_ssh_config(&input.config_id)?;
+    let session_id = restore
+        .map(|entry| entry.id.clone())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let requested_dir = input
+        .initial_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty());
+
+    // Another tab of this config may already hold a connection; add a channel to it.
+    // Its session is non-blocking, so directories are not checked over SFTP here
+    // and a missing one is reported by the shell's own `cd`.
+    if let Some(shared) = state.acquire_shared_ssh(&config.id, &session_id) {
+        match open_pty_channel(&shared.session, &pty_spec) {
+            Ok(channel) => {
+                let host = ShellHostInfo {
+                    login_dir: shared.login_dir,
+                    initial_dir: requested_dir.map(normalize_remote_path),
+                    restored_dir: restore.map(|entry| sanitize_cwd(&entry.current_dir)),
+                    remote_os: shared.remote_os,
+                    handshake: shared.handshake,
+                };
+                return start_shell_session(
+                    state,
+                    app,
+                    input,
+                    restore,
+                    &config,
+                    session_id,
+                    shared.session,
+                    channel,
+                    decoder,
+                    &pty_spec,
+                    host,
+                );
+            }
+            Err(err) => {
+                append_server_ops_debug_log(
+                    state.as_ref(),
+                    "pty.shared_ssh.discarded",
+                    &session_id,
+                    err.to_string(),
+                );
+                state.discard_shared_ssh(&session_id);
+            }
+        }
+    }
+
     let ssh = connect_with_cancellation(&state, &config, request_id.map(|id| (&*state, id)))?;
     let (pwd_out, _, status) = run_channel_command(&ssh, "pwd")?;
     if status != 0 {
@@ -81,23 +263,246 @@ fn open_shell_session_inner(
         )));
     }
 
-    let cwd = sanitize_cwd(pwd_out.trim());
+    // Canonicalize so a symlinked home directory shows its real location in the browser.
+    let sftp = ssh.sftp().ok();
+    let login_dir = sftp
+        .as_ref()
+        .and_then(|sftp| resolve_remote_realpath(sftp, pwd_out.trim()).ok())
+        .unwrap_or_else(|| sanitize_cwd(pwd_out.trim()));
+    let initial_dir = match requested_dir {
+        Some(dir) => Some(match sftp.as_ref() {
+            Some(sftp) => resolve_remote_realpath(sftp, &normalize_remote_path(dir))?,
+            None => normalize_remote_path(dir),
+        }),
+        None => None,
+    };
+    // A restored directory that no longer exists falls back to the login directory.
+    let restored_dir = restore.and_then(|entry| {
+        sftp.as_ref()
+            .and_then(|sftp| resolve_remote_realpath(sftp, &entry.current_dir).ok())
+    });
+    drop(sftp);
+    // Best effort: the agent prompt only uses this as a hint.
+    let remote_os = run_channel_command(&ssh, "uname -s")
+        .ok()
+        .filter(|(_, _, status)| *status == 0)
+        .map(|(stdout, _, _)| stdout.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let host = ShellHostInfo {
+        login_dir,
+        initial_dir,
+        restored_dir,
+        remote_os,
+        handshake: read_handshake_info(&ssh),
+    };
+    let channel = open_pty_channel(&ssh, &pty_spec)?;
+    state.register_shared_ssh(
+        &config.id,
+        &session_id,
+        SharedSshSession {
+            pool_id: Uuid::new_v4().to_string(),
+            session: ssh.clone(),
+            login_dir: host.login_dir.clone(),
+            remote_os: host.remote_os.clone(),
+            handshake: host.handshake.clone(),
+        },
+    );
+    start_shell_session(
+        state, app, input, restore, &config, session_id, ssh, channel, decoder, &pty_spec, host,
+    )
+}
+
+/// What a new shell session needs to know about its host before the PTY starts.
+struct ShellHostInfo {
+    login_dir: String,
+    initial_dir: Option<String>,
+    restored_dir: Option<String>,
+    remote_os: Option<String>,
+    handshake: SshHandshakeInfo,
+}
+
+/// Registers the session and hands its open PTY channel to a worker.
+#[allow(clippy::too_many_arguments)]
+fn start_shell_session(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: &OpenShellInput,
+    restore: Option<&SessionManifestEntry>,
+    config: &SshConfig,
+    session_id: String,
+    ssh: Session,
+    channel: ssh2::Channel,
+    decoder: PtyOutputDecoder,
+    pty_spec: &PtySpec,
+    host: ShellHostInfo,
+) -> AppResult<ShellSession> {
     let now = now_rfc3339();
-    let session_id = Uuid::new_v4().to_string();
     let session = ShellSession {
         id: session_id.clone(),
         config_id: config.id.clone(),
         config_name: config.name.clone(),
-        current_dir: cwd,
+        current_dir: host
+            .initial_dir
+            .clone()
+            .or_else(|| host.restored_dir.clone())
+            .unwrap_or(host.login_dir),
+        previous_dir: restore
+            .filter(|_| host.initial_dir.is_none() && host.restored_dir.is_some())
+            .and_then(|entry| entry.previous_dir.clone()),
         last_output: String::new(),
+        remote_os: host.remote_os,
         created_at: now.clone(),
         updated_at: now,
+        handshake: host.handshake,
     };
     state.put_session(session.clone());
-    start_pty_worker(Arc::clone(&state), app, session_id, ssh)?;
+    state.init_pty_scrollback(
+        &session_id,
+        input
+            .scrollback_bytes
+            .unwrap_or(DEFAULT_PTY_SCROLLBACK_BYTES)
+            .clamp(MIN_PTY_SCROLLBACK_BYTES, MAX_PTY_SCROLLBACK_BYTES),
+    );
+    let startup_input = build_pty_startup_input(
+        host.initial_dir.or(host.restored_dir).as_deref(),
+        input.initial_command.as_deref(),
+    );
+    let reconnect = input.auto_reconnect.then(|| PtyReconnectPlan {
+        spec: pty_spec.clone(),
+        encoding: input.encoding.clone(),
+    });
+    if let Err(err) = start_pty_worker(
+        Arc::clone(&state),
+        app,
+        session_id.clone(),
+        ssh,
+        channel,
+        decoder,
+        pty_spec,
+        startup_input.as_deref(),
+        reconnect,
+    ) {
+        let _ = state.remove_session(&session_id);
+        return Err(err);
+    }
     Ok(session)
 }
 
+/// Terminal type and initial size requested for a new PTY.
+#[derive(Clone)]
+struct PtySpec {
+    term: String,
+    cols: u16,
+    rows: u16,
+}
+
+impl PtySpec {
+    /// Applies defaults and the same minimum size as `pty_resize`.
+    fn from_input(input: &OpenShellInput) -> AppResult<Self> {
+        let term = match input.term.as_deref().map(str::trim) {
+            None | Some("") => DEFAULT_PTY_TERM.to_string(),
+            Some(term) => {
+                let valid = term.len() <= 64
+                    && term
+                        .chars()
+                        .all(|ch| ch.is_ascii_alphanumeric() || "-_.+".contains(ch));
+                if !valid {
+                    return Err(AppError::Validation(format!(
+                        "invalid terminal type: {term}"
+                    )));
+                }
+                term.to_string()
+            }
+        };
+        Ok(Self {
+            term,
+            cols: input.cols.unwrap_or(DEFAULT_PTY_COLS).max(MIN_PTY_COLS),
+            rows: input.rows.unwrap_or(DEFAULT_PTY_ROWS).max(MIN_PTY_ROWS),
+        })
+    }
+}
+
+/// What a PTY worker opened with `auto_reconnect` needs to reopen its shell.
+#[derive(Clone)]
+struct PtyReconnectPlan {
+    spec: PtySpec,
+    encoding: Option<String>,
+}
+
+/// Lines typed into a new PTY before the user gets it: an optional `cd` to the
+/// quoted directory, then the startup command verbatim with its own newline.
+fn build_pty_startup_input(dir: Option<&str>, command: Option<&str>) -> Option<String> {
+    let mut input = String::new();
+    if let Some(dir) = dir {
+        input.push_str("cd -- ");
+        input.push_str(&shell_quote(dir));
+        input.push('\n');
+    }
+    if let Some(command) = command
+        .map(|command| command.trim_end_matches(['\r', '\n']))
+        .filter(|command| !command.trim().is_empty())
+    {
+        input.push_str(command);
+        input.push('\n');
+    }
+    (!input.is_empty()).then_some(input)
+}
+
+/// Closes every shell session as the app exits and returns how many were open.
+///
+/// Sets the shutdown latch for good, so no session can be opened afterwards. Only
+/// the first call does anything; later calls return 0.
+pub fn shutdown_all_sessions(state: &AppState) -> usize {
+    if !state.begin_shutdown() {
+        return 0;
+    }
+    close_all_sessions_locked(state)
+}
+
+/// Closes every shell session ahead of app exit without ending the app's lifetime.
+///
+/// The shutdown latch is held only while the sessions close and cleared again
+/// afterwards, so a cancelled exit leaves the app usable. Returns 0 when an app
+/// exit is already in progress.
+pub fn close_all_sessions(state: &AppState) -> usize {
+    if !state.begin_shutdown() {
+        return 0;
+    }
+    let closed = close_all_sessions_locked(state);
+    state.end_shutdown();
+    closed
+}
+
+/// PTY workers are asked to close their channels and given a short grace period
+/// to exit; sessions whose worker does not finish in time are removed directly.
+/// Shared SSH connections are then disconnected. `sessions.json` is left as it
+/// was (the latch freezes it), so the next launch can still restore these sessions.
+fn close_all_sessions_locked(state: &AppState) -> usize {
+    let sessions = state.list_sessions();
+    for session in &sessions {
+        state.remove_pty_channel(&session.id);
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_WORKER_GRACE;
+    while !state.list_sessions().is_empty() && Instant::now() < deadline {
+        thread::sleep(PTY_IDLE_MAX_WAIT);
+    }
+    for session in state.list_sessions() {
+        append_server_ops_debug_log(
+            state,
+            "pty.worker.shutdown_timeout",
+            &session.id,
+            "removed without waiting for the worker",
+        );
+        let _ = state.remove_session(&session.id);
+    }
+
+    for ssh in state.take_shared_ssh_sessions() {
+        let _ = ssh.disconnect(None, "eshell is shutting down", None);
+    }
+    sessions.len()
+}
+
 /// Closes and removes a shell session from runtime registry.
 pub fn close_shell_session(state: &AppState, session_id: &str) -> AppResult<()> {
     match state.remove_session(session_id) {
@@ -108,17 +513,71 @@ pub fn close_shell_session(state: &AppState, session_id: &str) -> AppResult<()>
 }
 
 /// Writes raw input bytes into PTY shell channel.
-pub fn pty_write_input(state: &AppState, session_id: &str, data: &str) -> AppResult<()> {
+///
+/// Multi-line pastes (`bracketed`) are wrapped in bracketed-paste markers when the remote
+/// shell has enabled that mode, so it inserts the text instead of running each line.
+/// Everything else is forwarded as-is.
+pub fn pty_write_input(
+    state: &AppState,
+    session_id: &str,
+    data: &str,
+    bracketed: bool,
+) -> AppResult<()> {
     if data.is_empty() {
         return Ok(());
     }
-    state.send_pty_command(session_id, PtyCommand::Input(data.to_string()))
+    let payload = if state.is_pty_bracketed_paste(session_id) {
+        wrap_bracketed_paste(data, bracketed)
+    } else {
+        data.to_string()
+    };
+    state.send_pty_command(session_id, PtyCommand::Input(payload))?;
+    // Keystrokes arrive one at a time, so redaction cannot catch a typed password.
+    state.with_pty_recording(session_id, |recorder| {
+        if recorder.records_input() {
+            recorder.record_input(&state.storage.redact_output(data))
+        } else {
+            Ok(())
+        }
+    });
+    Ok(())
+}
+
+const BRACKETED_PASTE_ON: &str = "\x1b[?2004h";
+const BRACKETED_PASTE_OFF: &str = "\x1b[?2004l";
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+
+/// Wraps multi-line pastes in bracketed-paste markers; single lines pass through unchanged.
+fn wrap_bracketed_paste(data: &str, bracketed: bool) -> String {
+    if !bracketed || data.starts_with(BRACKETED_PASTE_START) {
+        return data.to_string();
+    }
+    let multi_line = data.trim_end_matches(['\r', '\n']).contains(['\r', '\n']);
+    if !multi_line {
+        return data.to_string();
+    }
+    // An embedded end marker would let pasted text escape the paste and run.
+    let body = data.replace(BRACKETED_PASTE_END, "");
+    format!("{BRACKETED_PASTE_START}{body}{BRACKETED_PASTE_END}")
+}
+
+/// Returns the last bracketed paste mode switch found in PTY output, if any.
+fn bracketed_paste_mode_change(chunk: &str) -> Option<bool> {
+    let on = chunk.rfind(BRACKETED_PASTE_ON);
+    let off = chunk.rfind(BRACKETED_PASTE_OFF);
+    match (on, off) {
+        (Some(on), Some(off)) => Some(on > off),
+        (Some(_), None) => Some(true),
+        (None, Some(_)) => Some(false),
+        (None, None) => None,
+    }
 }
 
 /// Resizes PTY shell dimensions to match frontend terminal viewport.
 pub fn pty_resize(state: &AppState, session_id: &str, cols: u16, rows: u16) -> AppResult<()> {
-    let safe_cols = cols.max(20);
-    let safe_rows = rows.max(8);
+    let safe_cols = cols.max(MIN_PTY_COLS);
+    let safe_rows = rows.max(MIN_PTY_ROWS);
     state.send_pty_command(
         session_id,
         PtyCommand::Resize {
@@ -128,12 +587,466 @@ pub fn pty_resize(state: &AppState, session_id: &str, cols: u16, rows: u16) -> A
     )
 }
 
+/// Sends a terminal control key (Ctrl-C and friends) to the PTY shell.
+pub fn pty_send_signal(state: &AppState, session_id: &str, signal: PtySignal) -> AppResult<()> {
+    state.send_pty_command(session_id, PtyCommand::Signal(signal))
+}
+
+/// True while the session still has a running PTY worker.
+pub fn session_is_alive(state: &AppState, session_id: &str) -> bool {
+    state.has_pty_channel(session_id)
+}
+
+/// Checks that a session's own SSH connection still works, without writing to its terminal.
+///
+/// Sends an SSH keepalive on the connection the session's PTY worker runs on. A
+/// health check never opens connections or touches the shared pool; a dead
+/// connection is reported as not alive rather than as an error, and the worker
+/// picks the failure up on its own.
+pub fn ping_session(state: &AppState, session_id: &str) -> AppResult<PingSessionResult> {
+    let session = state.get_session(session_id)?;
+    let outcome = match state.get_pty_connection(&session.id) {
+        Some(ssh) => match ssh.keepalive_send() {
+            Ok(_) => Ok(()),
+            // The worker holds the socket in non-blocking mode; busy is not dead.
+            Err(err) if is_would_block_ssh_error(&err) => Ok(()),
+            Err(err) => Err(err.to_string()),
+        },
+        None => Err("session has no open SSH connection".to_string()),
+    };
+    Ok(PingSessionResult {
+        session_id: session.id,
+        alive: outcome.is_ok(),
+        error: outcome.err(),
+    })
+}
+
+/// Returns retained PTY output so a reopened terminal view can repaint history.
+pub fn get_pty_scrollback(
+    state: &AppState,
+    session_id: &str,
+    max_bytes: Option<usize>,
+) -> AppResult<String> {
+    state.get_pty_scrollback(session_id, max_bytes.unwrap_or(usize::MAX))
+}
+
+/// Builds `ENV=value bash <path> args...` (or the custom command) for one script run.
+///
+/// `args` overrides the script's default arguments when given. Every env value
+/// and argument is shell-quoted.
+fn build_script_command(script: &ScriptDefinition, args: Option<&[String]>) -> AppResult<String> {
+    let mut parts = quoted_env_assignments(&script.env, "script environment variable")?;
+    if script.command.trim().is_empty() {
+        parts.push(format!("bash {}", shell_quote(&script.path)));
+    } else {
+        parts.push(script.command.clone());
+    }
+    let args = args.unwrap_or(&script.default_args);
+    parts.extend(args.iter().map(|arg| shell_quote(arg)));
+    Ok(parts.join(" "))
+}
+
+/// Renders `NAME='value'` pairs after checking every name is a valid shell identifier.
+fn quoted_env_assignments(env: &BTreeMap<String, String>, label: &str) -> AppResult<Vec<String>> {
+    env.iter()
+        .map(|(name, value)| {
+            if is_valid_env_var_name(name) {
+                Ok(format!("{name}={}", shell_quote(value)))
+            } else {
+                Err(AppError::Validation(format!(
+                    "{label} {name} must match [A-Za-z_][A-Za-z0-9_]*"
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Builds the `export A='x' B='y' && ` prefix that scopes `env` to a whole command line.
+fn env_export_prefix(env: &BTreeMap<String, String>) -> AppResult<String> {
+    let assignments = quoted_env_assignments(env, "environment variable")?;
+    if assignments.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("export {} && ", assignments.join(" ")))
+}
+
+/// Runs a saved script in the context of one shell session.
+pub fn run_script(
+    state: &AppState,
+    session_id: &str,
+    script_id: &str,
+    args: Option<&[String]>,
+    env: &BTreeMap<String, String>,
+) -> AppResult<RunScriptResult> {
+    let script = state.storage.find_script(script_id)?;
+    let command = build_script_command(&script, args)?;
+    let execution = execute_command(state, session_id, &command, env, None)?;
+    Ok(RunScriptResult {
+        script_id: script.id,
+        script_name: script.name,
+        execution,
+    })
+}
+
+/// Runs a saved script against a session every `interval_secs`.
+///
+/// The first run starts immediately. A tick that arrives while the previous
+/// run is still going is skipped and counted in `skipped_ticks`. Each finished
+/// run is kept as the schedule's latest result and emitted as a
+/// `scheduled-script-result` event.
+pub fn schedule_script(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: ScheduleScriptInput,
+) -> AppResult<ScriptSchedule> {
+    state.get_session(&input.session_id)?;
+    let script = state.storage.find_script(&input.script_id)?;
+    let schedule = ScriptSchedule {
+        schedule_id: Uuid::new_v4().to_string(),
+        session_id: input.session_id,
+        script_id: script.id,
+        script_name: script.name,
+        interval_secs: input.interval_secs.clamp(
+            SCRIPT_SCHEDULE_MIN_INTERVAL_SECS,
+            SCRIPT_SCHEDULE_MAX_INTERVAL_SECS,
+        ),
+        running: false,
+        skipped_ticks: 0,
+        last_run_at: None,
+        last_result: None,
+        last_error: None,
+        created_at: now_rfc3339(),
+    };
+    let worker_id = format!(
+        "{SCRIPT_SCHEDULE_WORKER_PREFIX}{}:{}",
+        schedule.session_id, schedule.schedule_id
+    );
+    state.begin_background_worker(&worker_id);
+    state.put_script_schedule(schedule.clone());
+
+    let worker_schedule = schedule.clone();
+    thread::spawn(move || {
+        let _guard = BackgroundWorkerGuard::new(Arc::clone(&state), &worker_id);
+        run_script_schedule(&state, &app, &worker_id, &worker_schedule);
+    });
+    Ok(schedule)
+}
+
+/// Stops one script schedule. Returns whether it existed.
+pub fn unschedule_script(state: &AppState, schedule_id: &str) -> bool {
+    state.stop_script_schedule(schedule_id)
+}
+
+/// Lists script schedules, optionally for one session.
+pub fn list_script_schedules(state: &AppState, session_id: Option<&str>) -> Vec<ScriptSchedule> {
+    state.list_script_schedules(session_id)
+}
+
+fn run_script_schedule(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    worker_id: &str,
+    schedule: &ScriptSchedule,
+) {
+    let interval = Duration::from_secs(schedule.interval_secs);
+    loop {
+        if state.is_background_worker_cancelled(worker_id) {
+            return;
+        }
+        let mut start_run = false;
+        let exists = state
+            .mutate_script_schedule(&schedule.schedule_id, |entry| {
+                if entry.running {
+                    entry.skipped_ticks += 1;
+                } else {
+                    entry.running = true;
+                    start_run = true;
+                }
+            })
+            .is_some();
+        if !exists {
+            return;
+        }
+
+        if start_run {
+            let state = Arc::clone(state);
+            let app = app.clone();
+            let schedule = schedule.clone();
+            thread::spawn(move || {
+                let result = run_script(
+                    &state,
+                    &schedule.session_id,
+                    &schedule.script_id,
+                    None,
+                    &BTreeMap::new(),
+                );
+                let (result, error) = match result {
+                    Ok(result) => (Some(result), None),
+                    Err(err) => (None, Some(err.to_string())),
+                };
+                let updated = state.mutate_script_schedule(&schedule.schedule_id, |entry| {
+                    entry.running = false;
+                    entry.last_run_at = Some(now_rfc3339());
+                    entry.last_result = result.clone();
+                    entry.last_error = error.clone();
+                });
+                if updated.is_some() {
+                    let _ = app.emit(
+                        SCHEDULED_SCRIPT_RESULT_EVENT,
+                        ScheduledScriptResultEvent {
+                            schedule_id: schedule.schedule_id,
+                            session_id: schedule.session_id,
+                            script_id: schedule.script_id,
+                            result,
+                            error,
+                        },
+                    );
+                }
+            });
+        }
+
+        if !sleep_unless_worker_cancelled(state, worker_id, interval) {
+            return;
+        }
+    }
+}
+
+/// Resolves the command `run_script` would execute, without running it.
+pub fn preview_script(
+    state: &AppState,
+    script_id: &str,
+    args: Option<&[String]>,
+) -> AppResult<String> {
+    let script = state.storage.find_script(script_id)?;
+    build_script_command(&script, args)
+}
+
+/// Runs one command on several saved hosts at once.
+///
+/// Each host gets a transient connection and the command runs in the login
+/// directory. At most `MULTI_HOST_MAX_PARALLEL` hosts are contacted at a time,
+/// and a host that fails to connect is reported in its own entry instead of
+/// failing the batch. Results keep the order of `config_ids`.
+pub fn execute_command_multi(
+    state: &AppState,
+    config_ids: &[String],
+    command: &str,
+) -> AppResult<Vec<HostCommandResult>> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err(AppError::Validation("command cannot be empty".to_string()));
+    }
+    if config_ids.is_empty() {
+        return Err(AppError::Validation("select at least one host".to_string()));
+    }
+
+    let next_index = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; config_ids.len()]);
+    thread::scope(|scope| {
+        for _ in 0..config_ids.len().min(MULTI_HOST_MAX_PARALLEL) {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(config_id) = config_ids.get(index) else {
+                    return;
+                };
+                let result = execute_command_on_host(state, config_id, command);
+                results.lock().expect("multi-host results lock poisoned")[index] = Some(result);
+            });
+        }
+    });
+
+    Ok(results
+        .into_inner()
+        .expect("multi-host results lock poisoned")
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+fn execute_command_on_host(state: &AppState, config_id: &str, command: &str) -> HostCommandResult {
+    let config = match state.storage.find_ssh_config(config_id) {
+        Ok(config) => config,
+        Err(err) => {
+            return HostCommandResult {
+                config_id: config_id.to_string(),
+                config_name: String::new(),
+                host: String::new(),
+                result: None,
+                error: Some(err.to_string()),
+            }
+        }
+    };
+
+    let started_at = now_rfc3339();
+    let started_clock = Instant::now();
+    let outcome = connect(state, &config).and_then(|ssh| {
+        run_channel_command_capped(&ssh, command, DEFAULT_COMMAND_OUTPUT_MAX_BYTES, None)
+    });
+    let (result, error) = match outcome {
+        Ok(output) => (
+            Some(CommandExecutionResult {
+                session_id: String::new(),
+                command: command.to_string(),
+                stdout: output.stdout,
+                stderr: output.stderr,
+                exit_code: output.exit_code,
+                current_dir: "~".to_string(),
+                started_at,
+                finished_at: now_rfc3339(),
+                duration_ms: started_clock.elapsed().as_millis(),
+                tty_fallback_used: false,
+                truncated: output.truncated,
+            }),
+            None,
+        ),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    HostCommandResult {
+        config_id: config.id,
+        config_name: config.name,
+        host: config.host,
+        result,
+        error,
+    }
+}
+
+/// Starts writing one PTY session to a local asciicast v2 file.
+///
+/// Output is always redacted. Input is only recorded when `record_input` is set,
+/// and then includes anything typed at a password prompt, so the result carries
+/// a warning.
+pub fn pty_start_recording(
+    state: &AppState,
+    session_id: &str,
+    local_path: &str,
+    record_input: bool,
+) -> AppResult<PtyRecordingInfo> {
+    state.get_session(session_id)?;
+    let local_path = local_path.trim();
+    if local_path.is_empty() {
+        return Err(AppError::Validation(
+            "recording path cannot be empty".to_string(),
+        ));
+    }
+
+    // Checked before the file is touched; `put_pty_recording` re-checks under its lock.
+    if state.has_pty_recording(session_id) {
+        return Err(AppError::Validation(format!(
+            "pty session {session_id} is already being recorded"
+        )));
+    }
+    let (cols, rows) = state
+        .get_pty_size(session_id)
+        .unwrap_or((DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS));
+    let term = state
+        .get_pty_term(session_id)
+        .unwrap_or_else(|| DEFAULT_PTY_TERM.to_string());
+    let recorder = PtyRecorder::create(Path::new(local_path), cols, rows, &term, record_input)?;
+    let info = PtyRecordingInfo {
+        session_id: session_id.to_string(),
+        local_path: recorder.path().to_string_lossy().to_string(),
+        cols,
+        rows,
+        started_at: recorder.started_at().to_string(),
+        records_input: record_input,
+        warning: record_input.then(|| {
+            "keystrokes are recorded as typed, including passwords entered at prompts".to_string()
+        }),
+    };
+    state.put_pty_recording(session_id, recorder)?;
+    Ok(info)
+}
+
+/// Stops the active recording for one PTY session; returns whether one was active.
+pub fn pty_stop_recording(state: &AppState, session_id: &str) -> AppResult<bool> {
+    match state.take_pty_recording(session_id) {
+        Some(recorder) => {
+            recorder.finish()?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 /// Executes user command in context of a shell session while preserving tab-specific cwd.
+///
+/// `env` is exported after the `cd` and before the command, so it covers every part
+/// of a chained command line; values are literal (no `$VAR` expansion) and a bare
+/// `cd` ignores them. Each output stream is capped at `max_output_bytes` (10 MiB by
+/// default); a command that exceeds it is cut off and reported as `truncated`.
 pub fn execute_command(
     state: &AppState,
     session_id: &str,
     command: &str,
+    env: &BTreeMap<String, String>,
+    max_output_bytes: Option<usize>,
+) -> AppResult<CommandExecutionResult> {
+    run_session_command(state, session_id, command, env, max_output_bytes, false)
+}
+
+/// True when the command line starts with a `sudo` invocation.
+pub fn is_sudo_command(command: &str) -> bool {
+    command
+        .trim_start()
+        .strip_prefix("sudo")
+        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Runs a `sudo ...` command after validating the profile's sudo password.
+///
+/// The password is fed on stdin to `sudo -S -v` only; the command itself runs as
+/// `sudo -n` with stdin from `/dev/null`, so the password never reaches it, even
+/// under NOPASSWD rules, and it is never part of the command line or the output.
+/// Fails with a validation error when the session's SSH profile has no sudo password stored.
+pub fn execute_sudo_command(
+    state: &AppState,
+    session_id: &str,
+    command: &str,
+) -> AppResult<CommandExecutionResult> {
+    if !is_sudo_command(command) {
+        return Err(AppError::Validation(
+            "command does not start with sudo".to_string(),
+        ));
+    }
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    if config.sudo_password.is_empty() {
+        return Err(AppError::Validation(format!(
+            "sudo needs a password: add a sudo password to SSH profile \"{}\", or run the command in the terminal",
+            config.name
+        )));
+    }
+    run_session_command(state, session_id, command, &BTreeMap::new(), None, true)
+}
+
+/// Rewrites a leading `sudo` into a credential check followed by a non-interactive run.
+///
+/// `sudo -S -v` reads the password from stdin without printing a prompt; the command
+/// then runs as `sudo -n` on the refreshed credentials with stdin from `/dev/null`.
+/// The group ends on a new line so a trailing `&` or comment cannot swallow the brace.
+fn sudo_stdin_command(command: &str) -> String {
+    let rest = command.trim_start().strip_prefix("sudo").unwrap_or(command);
+    format!(
+        "sudo -S -v -p '' && {{ sudo -n {}\n}} </dev/null",
+        rest.trim_start()
+    )
+}
+
+fn run_session_command(
+    state: &AppState,
+    session_id: &str,
+    command: &str,
+    env: &BTreeMap<String, String>,
+    max_output_bytes: Option<usize>,
+    pipe_sudo_password: bool,
 ) -> AppResult<CommandExecutionResult> {
+    let max_output_bytes = max_output_bytes.unwrap_or(DEFAULT_COMMAND_OUTPUT_MAX_BYTES);
+    if max_output_bytes == 0 {
+        return Err(AppError::Validation(
+            "maxOutputBytes must be greater than 0".to_string(),
+        ));
+    }
+    let env_prefix = env_export_prefix(env)?;
     let session = state.get_session(session_id)?;
     let config = state.storage.find_ssh_config(&session.config_id)?;
     let started_at = now_rfc3339();
@@ -147,9 +1060,9 @@ pub fn execute_command(
     }
 
     let result = if let Some(target) = parse_cd_target(trimmed) {
-        let cd_target = target.unwrap_or_else(|| "~".to_string());
+        let cd_target = resolve_cd_target(target.as_deref(), session.previous_dir.as_deref())?;
         let cd_cmd = format!(
-            "cd {} && cd {} && pwd",
+            "cd {} && cd -- {} && pwd",
             shell_quote(&session.current_dir),
             cd_target
         );
@@ -157,10 +1070,13 @@ pub fn execute_command(
         if exit_code == 0 {
             let new_dir = sanitize_cwd(stdout.trim());
             state.mutate_session(session_id, |entry| {
-                entry.current_dir = new_dir.clone();
-                entry.last_output = stdout.trim().to_string();
+                if entry.current_dir != new_dir {
+                    entry.previous_dir =
+                        Some(std::mem::replace(&mut entry.current_dir, new_dir.clone()));
+                }
                 entry.updated_at = now_rfc3339();
             })?;
+            state.replace_session_output(session_id, stdout.trim());
         }
         CommandExecutionResult {
             session_id: session_id.to_string(),
@@ -172,15 +1088,52 @@ pub fn execute_command(
             started_at,
             finished_at: now_rfc3339(),
             duration_ms: started_clock.elapsed().as_millis(),
+            tty_fallback_used: false,
+            truncated: false,
         }
     } else {
-        let exec_cmd = format!("cd {} && {}", shell_quote(&session.current_dir), command);
-        let (stdout, stderr, exit_code) = run_channel_command(&ssh, &exec_cmd)?;
+        let exec_cmd = format!(
+            "cd {} && {}{}",
+            shell_quote(&session.current_dir),
+            env_prefix,
+            command
+        );
+        let (channel_cmd, stdin) = if pipe_sudo_password {
+            (
+                format!(
+                    "cd {} && {}{}",
+                    shell_quote(&session.current_dir),
+                    env_prefix,
+                    sudo_stdin_command(command)
+                ),
+                Some(format!("{}\n", config.sudo_password)),
+            )
+        } else {
+            (exec_cmd.clone(), None)
+        };
+        let ChannelCommandOutput {
+            mut stdout,
+            mut stderr,
+            mut exit_code,
+            mut truncated,
+        } = run_channel_command_capped(&ssh, &channel_cmd, max_output_bytes, stdin.as_deref())?;
+        let mut tty_fallback_used = false;
+        if config.sudo_tty_fallback && exit_code != 0 && is_sudo_tty_required(&stderr) {
+            // The PTY rerun keeps the command as written and answers only sudo's own
+            // (nonce) prompt. Only the dedicated sudo password is used, never the login one.
+            let sudo_password =
+                Some(config.sudo_password.as_str()).filter(|value| !value.is_empty());
+            let (output, code, output_truncated) =
+                run_channel_command_with_pty(&ssh, &exec_cmd, sudo_password, max_output_bytes)?;
+            stdout = output;
+            stderr = String::new();
+            exit_code = code;
+            truncated = output_truncated;
+            tty_fallback_used = true;
+        }
 
-        state.mutate_session(session_id, |entry| {
-            entry.last_output = format_stdout_stderr(&stdout, &stderr);
-            entry.updated_at = now_rfc3339();
-        })?;
+        state.mutate_session(session_id, |entry| entry.updated_at = now_rfc3339())?;
+        state.replace_session_output(session_id, &format_stdout_stderr(&stdout, &stderr));
 
         CommandExecutionResult {
             session_id: session_id.to_string(),
@@ -192,19 +1145,32 @@ pub fn execute_command(
             started_at,
             finished_at: now_rfc3339(),
             duration_ms: started_clock.elapsed().as_millis(),
+            tty_fallback_used,
+            truncated,
         }
     };
 
+    state.record_command_history(session_id, CommandHistoryEntry::from(&result));
     Ok(result)
 }
 
+/// Returns the most recent commands run through `execute_command` in one session.
+pub fn get_command_history(
+    state: &AppState,
+    session_id: &str,
+    limit: Option<usize>,
+) -> AppResult<Vec<CommandHistoryEntry>> {
+    state.get_session(session_id)?;
+    Ok(state.get_command_history(session_id, limit.unwrap_or(MAX_COMMAND_HISTORY_ENTRIES)))
+}
+
 /// Lists directory entries through SFTP.
 pub fn sftp_list_dir(state: &AppState, input: SftpListInput) -> AppResult<SftpListResponse> {
     let session = state.get_session(&input.session_id)?;
     let config = state.storage.find_ssh_config(&session.config_id)?;
     let ssh = connect(&state, &config)?;
     let sftp = ssh.sftp()?;
-    let requested_path = normalize_remote_path(&input.path);
+    let requested_path = resolve_remote_realpath(&sftp, &normalize_remote_path(&input.path))?;
     let raw_entries = sftp.readdir(Path::new(&requested_path))?;
 
     let mut entries = raw_entries
@@ -241,20 +1207,204 @@ pub fn sftp_list_dir(state: &AppState, input: SftpListInput) -> AppResult<SftpLi
     })
 }
 
-/// Reads remote file as UTF-8 text for in-app editing.
-pub fn sftp_read_file(state: &AppState, input: SftpReadInput) -> AppResult<SftpFileContent> {
+/// Finds files under `root` by name and optionally by content.
+///
+/// `find` runs under the remote `timeout` utility when present, so a huge tree
+/// stops after `REMOTE_FIND_TIMEOUT_SECS`; every match is then stat'd over one
+/// SFTP session to fill in type, size and mtime.
+pub fn remote_find(state: &AppState, input: RemoteFindInput) -> AppResult<RemoteFindResult> {
     let session = state.get_session(&input.session_id)?;
     let config = state.storage.find_ssh_config(&session.config_id)?;
-    let ssh = connect(&state, &config)?;
+    let ssh = connect(state, &config)?;
     let sftp = ssh.sftp()?;
-    let remote_path = normalize_remote_path(&input.path);
-    let mut file = sftp.open(Path::new(&remote_path))?;
-    let mut bytes = Vec::new();
-    file.read_to_end(&mut bytes)?;
+    let root = resolve_remote_realpath(&sftp, &normalize_remote_path(&input.root))?;
+    let max_results = input
+        .max_results
+        .unwrap_or(REMOTE_FIND_DEFAULT_RESULTS)
+        .clamp(1, REMOTE_FIND_MAX_RESULTS);
+
+    let command = build_remote_find_command(
+        &root,
+        input.name_glob.as_deref(),
+        input.content_grep.as_deref(),
+    );
+    let output = run_channel_command_capped(
+        &ssh,
+        &command,
+        (max_results + 1) * REMOTE_FIND_OUTPUT_BYTES_PER_RESULT,
+        None,
+    )?;
+    let mut paths = output
+        .stdout
+        .lines()
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    if output.truncated {
+        // The last line may have been cut mid-path.
+        paths.pop();
+    }
+    let truncated = output.truncated || paths.len() > max_results;
+    paths.truncate(max_results);
 
-    Ok(SftpFileContent {
-        path: remote_path,
-        content: String::from_utf8_lossy(&bytes).to_string(),
+    let entries = paths
+        .into_iter()
+        .filter_map(|path| {
+            let stat = sftp.lstat(Path::new(path)).ok()?;
+            Some(SftpEntry {
+                name: extract_entry_name(path)?,
+                path: path.to_string(),
+                entry_type: stat_to_entry_type(&stat),
+                size: stat.size.unwrap_or_default(),
+                modified_at: stat.mtime,
+            })
+        })
+        .collect();
+
+    Ok(RemoteFindResult {
+        root,
+        entries,
+        truncated,
+        timed_out: output.exit_code == 124,
+    })
+}
+
+/// Builds the `find` pipeline for [`remote_find`]; every user input is single-quoted.
+fn build_remote_find_command(
+    root: &str,
+    name_glob: Option<&str>,
+    content_grep: Option<&str>,
+) -> String {
+    let mut command = format!(
+        "if command -v timeout >/dev/null 2>&1; then t='timeout {}'; else t=''; fi; $t find {}",
+        REMOTE_FIND_TIMEOUT_SECS,
+        shell_quote(root)
+    );
+    if let Some(glob) = name_glob.map(str::trim).filter(|glob| !glob.is_empty()) {
+        command.push_str(&format!(" -name {}", shell_quote(glob)));
+    }
+    match content_grep.filter(|pattern| !pattern.is_empty()) {
+        Some(pattern) => command.push_str(&format!(
+            " -type f -exec grep -l -s -e {} {{}} +",
+            shell_quote(pattern)
+        )),
+        None => command.push_str(" -print"),
+    }
+    command.push_str(" 2>/dev/null");
+    command
+}
+
+/// Reports directory sizes under `path` with `du -h`, largest first.
+///
+/// Hosts whose `du` lacks `--max-depth` (busybox) get a plain `du -h`, and
+/// entries deeper than `depth` are dropped locally. Lines `du` writes to stderr,
+/// usually permission errors, are returned as warnings instead of failing.
+pub fn sftp_dir_usage(state: &AppState, input: DirUsageInput) -> AppResult<DirUsageResult> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let path = normalize_remote_path(&input.path);
+    let depth = input
+        .depth
+        .unwrap_or(DIR_USAGE_DEFAULT_DEPTH)
+        .min(DIR_USAGE_MAX_DEPTH);
+
+    let quoted = shell_quote(&path);
+    let mut output = run_channel_command_capped(
+        &ssh,
+        &format!("du -h --max-depth={} -- {}", depth, quoted),
+        DEFAULT_COMMAND_OUTPUT_MAX_BYTES,
+        None,
+    )?;
+    if output.stdout.trim().is_empty() && is_unsupported_option_error(&output.stderr) {
+        output = run_channel_command_capped(
+            &ssh,
+            &format!("du -h {}", quoted),
+            DEFAULT_COMMAND_OUTPUT_MAX_BYTES,
+            None,
+        )?;
+    }
+
+    let mut entries = parse_du_output(&output.stdout)
+        .into_iter()
+        .filter(|entry| {
+            remote_path_depth(&path, &entry.path).is_some_and(|level| level <= depth as usize)
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|left, right| {
+        right
+            .size_bytes
+            .cmp(&left.size_bytes)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    let warnings = output
+        .stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(DIR_USAGE_MAX_WARNINGS)
+        .map(ToString::to_string)
+        .collect();
+
+    Ok(DirUsageResult {
+        path,
+        entries,
+        warnings,
+    })
+}
+
+fn is_unsupported_option_error(stderr: &str) -> bool {
+    let lower = stderr.to_ascii_lowercase();
+    [
+        "unrecognized option",
+        "invalid option",
+        "illegal option",
+        "unknown option",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+}
+
+/// Levels `path` sits below `root` (0 for `root` itself); `None` when outside it.
+fn remote_path_depth(root: &str, path: &str) -> Option<usize> {
+    let root = normalize_remote_path(root);
+    let path = normalize_remote_path(path);
+    let rest = if root == "/" {
+        path.strip_prefix('/')?
+    } else if path == root {
+        ""
+    } else {
+        path.strip_prefix(&root)?.strip_prefix('/')?
+    };
+    if rest.is_empty() {
+        Some(0)
+    } else {
+        Some(rest.split('/').count())
+    }
+}
+
+/// Resolves a remote path through every symlink to its canonical absolute form.
+pub fn sftp_realpath(state: &AppState, input: SftpRealpathInput) -> AppResult<String> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    resolve_remote_realpath(&sftp, &normalize_remote_path(&input.path))
+}
+
+/// Reads remote file as UTF-8 text for in-app editing.
+pub fn sftp_read_file(state: &AppState, input: SftpReadInput) -> AppResult<SftpFileContent> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(&state, &config)?;
+    let sftp = ssh.sftp()?;
+    let remote_path = normalize_remote_path(&input.path);
+    let mut file = sftp.open(Path::new(&remote_path))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    Ok(SftpFileContent {
+        path: remote_path,
+        content: String::from_utf8_lossy(&bytes).to_string(),
     })
 }
 
@@ -301,11 +1451,66 @@ pub fn sftp_upload_file(state: &AppState, input: SftpUploadInput) -> AppResult<(
     let config = state.storage.find_ssh_config(&session.config_id)?;
     let ssh = connect(&state, &config)?;
     let sftp = ssh.sftp()?;
-    let remote_path = normalize_remote_path(&input.remote_path);
+    upload_base64_file(&sftp, &input.remote_path, &input.content_base64)?;
+    Ok(())
+}
+
+/// Writes a base64 payload to one remote file and returns the normalized path.
+fn upload_base64_file(
+    sftp: &ssh2::Sftp,
+    remote_path: &str,
+    content_base64: &str,
+) -> AppResult<String> {
+    let remote_path = normalize_remote_path(remote_path);
     let mut file = sftp.create(Path::new(&remote_path))?;
-    let bytes = BASE64_STANDARD.decode(input.content_base64.as_bytes())?;
+    let bytes = BASE64_STANDARD.decode(content_base64.as_bytes())?;
     file.write_all(&bytes)?;
-    Ok(())
+    Ok(remote_path)
+}
+
+/// Uploads a script file to the session's server and saves it as a script definition.
+pub fn upload_and_register_script(
+    state: &AppState,
+    input: UploadAndRegisterScriptInput,
+) -> AppResult<ScriptDefinition> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::Validation(
+            "script name cannot be empty".to_string(),
+        ));
+    }
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let remote_path = upload_base64_file(&sftp, &input.remote_path, &input.content_base64)?;
+
+    if input.make_executable {
+        let path = Path::new(&remote_path);
+        let perm = sftp.stat(path)?.perm.unwrap_or(0o644) | 0o111;
+        sftp.setstat(
+            path,
+            FileStat {
+                size: None,
+                uid: None,
+                gid: None,
+                perm: Some(perm),
+                atime: None,
+                mtime: None,
+            },
+        )?;
+    }
+
+    state.storage.upsert_script(ScriptInput {
+        id: None,
+        name: input.name,
+        path: Some(remote_path),
+        command: None,
+        description: None,
+        parameters: Vec::new(),
+        default_args: Vec::new(),
+        env: Default::default(),
+        tags: Vec::new(),
+    })
 }
 
 /// Deletes one remote file or symlink through SFTP.
@@ -475,6 +1680,124 @@ pub fn sftp_upload_file_with_progress(
     })
 }
 
+/// Streams a local file to the remote host, then confirms the remote digest matches.
+///
+/// The local digest is computed while uploading; the remote one comes from the
+/// coreutils checksum tool, or an SFTP read-back when that tool is unavailable.
+pub fn sftp_upload_verified(
+    state: &AppState,
+    input: SftpUploadVerifiedInput,
+) -> AppResult<SftpUploadVerifiedResult> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let remote_path = normalize_remote_path(&input.remote_path);
+
+    let mut local_file = File::open(&input.local_path)?;
+    let mut remote_file = sftp.create(Path::new(&remote_path))?;
+    let mut hasher = ChecksumHasher::new(input.algorithm);
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    let mut size = 0_u64;
+    loop {
+        let read = local_file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        remote_file.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+    remote_file.flush()?;
+    drop(remote_file);
+
+    let local_hash = hasher.finalize_hex();
+    let remote_hash = remote_checksum(&ssh, &sftp, &remote_path, input.algorithm)?;
+    if local_hash != remote_hash {
+        let deleted = input.delete_on_mismatch && sftp.unlink(Path::new(&remote_path)).is_ok();
+        return Err(AppError::Runtime(format!(
+            "checksum mismatch for {remote_path}: local {local_hash}, remote {remote_hash}{}",
+            if deleted {
+                " (remote file deleted)"
+            } else {
+                ""
+            }
+        )));
+    }
+
+    Ok(SftpUploadVerifiedResult {
+        remote_path,
+        size,
+        algorithm: input.algorithm,
+        local_hash,
+        remote_hash,
+    })
+}
+
+/// Hashes one remote file and optionally compares it with an expected digest.
+pub fn sftp_checksum(state: &AppState, input: SftpChecksumInput) -> AppResult<SftpChecksumResult> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let path = normalize_remote_path(&input.path);
+    match sftp.stat(Path::new(&path)) {
+        Ok(stat) if stat_to_entry_type(&stat) == SftpEntryType::File => {}
+        Ok(_) => {
+            return Err(AppError::Validation(format!(
+                "only regular files can be hashed: {path}"
+            )));
+        }
+        Err(error) if is_sftp_missing_path_error(&error) => {
+            return Err(AppError::NotFound(format!("remote path {path}")));
+        }
+        Err(error) => return Err(AppError::Ssh(error)),
+    }
+
+    let hash = remote_checksum(&ssh, &sftp, &path, input.algorithm)?;
+    let matches = input
+        .expected
+        .as_deref()
+        .map(|expected| checksum_matches(expected, &hash));
+    Ok(SftpChecksumResult {
+        path,
+        algorithm: input.algorithm,
+        hash,
+        matches,
+    })
+}
+
+/// Hashes a remote file with the coreutils tool, falling back to an SFTP read-back.
+fn remote_checksum(
+    ssh: &Session,
+    sftp: &ssh2::Sftp,
+    remote_path: &str,
+    algorithm: ChecksumAlgorithm,
+) -> AppResult<String> {
+    let command = format!(
+        "{} -- {}",
+        checksum_tool(algorithm),
+        shell_quote(remote_path)
+    );
+    if let Ok((stdout, _, 0)) = run_channel_command(ssh, &command) {
+        if let Some(hash) = parse_checksum_output(&stdout, algorithm) {
+            return Ok(hash);
+        }
+    }
+
+    let mut remote_file = sftp.open(Path::new(remote_path))?;
+    let mut hasher = ChecksumHasher::new(algorithm);
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    loop {
+        let read = remote_file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize_hex())
+}
+
 /// Downloads remote file and returns base64-encoded bytes for frontend save flow.
 pub fn sftp_download_file(
     state: &AppState,
@@ -706,6 +2029,287 @@ pub fn sftp_download_file_to_local(
     })
 }
 
+/// Downloads a whole remote directory, preferring one `tar -czf -` stream over per-file SFTP.
+///
+/// With `tar` available the compressed stream is written to `local_path` as-is;
+/// otherwise the tree is walked over SFTP into a local directory (see
+/// [`SftpDownloadDirInput::local_path`]). The result reports which mode ran.
+pub fn sftp_download_dir(
+    state: &AppState,
+    app: &AppHandle,
+    input: SftpDownloadDirInput,
+) -> AppResult<SftpDirDownloadResult> {
+    let _transfer_guard = SftpTransferGuard::new(state, &input.transfer_id);
+    let local_path = input.local_path.trim();
+    if local_path.is_empty() {
+        return Err(AppError::Validation(
+            "download path cannot be empty".to_string(),
+        ));
+    }
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let remote_dir = resolve_remote_realpath(&sftp, &normalize_remote_path(&input.remote_dir))?;
+    if remote_dir == "/" {
+        return Err(AppError::Validation(
+            "refusing to download the remote root directory".to_string(),
+        ));
+    }
+    if stat_to_entry_type(&sftp.stat(Path::new(&remote_dir))?) != SftpEntryType::Directory {
+        return Err(AppError::Validation(format!(
+            "{} is not a directory",
+            remote_dir
+        )));
+    }
+
+    let mode = if remote_has_tar(&ssh) {
+        SftpDirDownloadMode::Tar
+    } else {
+        SftpDirDownloadMode::Sftp
+    };
+    let target = match mode {
+        SftpDirDownloadMode::Tar => PathBuf::from(local_path),
+        SftpDirDownloadMode::Sftp => PathBuf::from(strip_archive_suffix(local_path)),
+    };
+    let target_existed = target.exists();
+    let reporter = DirTransferReporter {
+        app,
+        direction: "download",
+        transfer_id: &input.transfer_id,
+        session_id: &input.session_id,
+        remote_dir: &remote_dir,
+        local_path: target.to_string_lossy().to_string(),
+        file_name: extract_remote_file_name(&remote_dir),
+        transferred_bytes: Cell::new(0),
+        total_bytes: Cell::new(None),
+    };
+    reporter.emit("started", Some(format!("mode: {}", mode.as_str())));
+
+    let outcome = match mode {
+        SftpDirDownloadMode::Tar => stream_remote_tar(state, &ssh, &remote_dir, &target, &reporter)
+            .map(|(size, warning)| (size, None, warning)),
+        SftpDirDownloadMode::Sftp => {
+            download_dir_via_sftp(state, &sftp, &remote_dir, &target, &reporter)
+                .map(|(size, count)| (size, Some(count), None))
+        }
+    };
+    let (size, file_count, warning) = match outcome {
+        Ok(done) => done,
+        Err(error) => {
+            // A failed tar stream only ever touches its temp file.
+            if !target_existed && mode == SftpDirDownloadMode::Sftp {
+                let _ = std::fs::remove_dir_all(&target);
+            }
+            if state.is_sftp_transfer_cancelled(&input.transfer_id) {
+                reporter.emit("cancelled", Some("Transfer cancelled by user".to_string()));
+            } else {
+                reporter.emit("failed", Some(error.to_string()));
+            }
+            return Err(error);
+        }
+    };
+
+    reporter.transferred_bytes.set(size);
+    reporter.total_bytes.set(Some(size));
+    let message = match &warning {
+        Some(warning) => format!("mode: {}; {}", mode.as_str(), warning),
+        None => format!("mode: {}", mode.as_str()),
+    };
+    reporter.emit("completed", Some(message));
+    let local_path = reporter.local_path;
+
+    Ok(SftpDirDownloadResult {
+        transfer_id: input.transfer_id,
+        mode,
+        remote_dir,
+        local_path,
+        size,
+        file_count,
+        warning,
+    })
+}
+
+/// Uploads the contents of a local directory into `remote_dir`, keeping relative structure.
+///
+/// Remote directories that already exist are reused. Besides the aggregate
+/// `progress` events, every chunk emits a `file_progress` event and every
+/// finished file a `file_completed` event. Local symlinks are skipped.
+pub fn sftp_upload_dir(
+    state: &AppState,
+    app: &AppHandle,
+    input: SftpUploadDirInput,
+) -> AppResult<SftpUploadDirResult> {
+    let _transfer_guard = SftpTransferGuard::new(state, &input.transfer_id);
+    let local_root = normalize_local_dir(&input.local_dir)?;
+    if !local_root.is_dir() {
+        return Err(AppError::Validation(format!(
+            "{} is not a local directory",
+            local_root.display()
+        )));
+    }
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let remote_dir = normalize_remote_path(&input.remote_dir);
+
+    let plan = plan_local_upload(&local_root)?;
+    let reporter = DirTransferReporter {
+        app,
+        direction: "upload",
+        transfer_id: &input.transfer_id,
+        session_id: &input.session_id,
+        remote_dir: &remote_dir,
+        local_path: local_root.to_string_lossy().to_string(),
+        file_name: extract_remote_file_name(&remote_dir),
+        transferred_bytes: Cell::new(0),
+        total_bytes: Cell::new(Some(plan.files.iter().map(|file| file.size).sum())),
+    };
+    reporter.emit("started", None);
+
+    match upload_planned_dir(
+        state,
+        &sftp,
+        &remote_dir,
+        &plan,
+        input.preserve_executable,
+        &reporter,
+    ) {
+        Ok(directories_created) => {
+            let bytes = reporter.transferred_bytes.get();
+            reporter.total_bytes.set(Some(bytes));
+            reporter.emit(
+                "completed",
+                Some(format!("{} files, {} bytes", plan.files.len(), bytes)),
+            );
+            let local_dir = reporter.local_path;
+            Ok(SftpUploadDirResult {
+                transfer_id: input.transfer_id,
+                remote_dir,
+                local_dir,
+                files_transferred: plan.files.len(),
+                directories_created,
+                bytes,
+            })
+        }
+        Err(error) => {
+            if state.is_sftp_transfer_cancelled(&input.transfer_id) {
+                reporter.emit("cancelled", Some("Transfer cancelled by user".to_string()));
+            } else {
+                reporter.emit("failed", Some(error.to_string()));
+            }
+            Err(error)
+        }
+    }
+}
+
+/// Copies one remote file to a new path on the same host.
+///
+/// `cp -p` runs first so mode and timestamps are kept. If it fails or leaves a
+/// file of the wrong size, the partial copy is removed and the bytes are copied
+/// over SFTP instead, restoring mode and mtime with `setstat`.
+pub fn sftp_copy(state: &AppState, input: SftpCopyInput) -> AppResult<SftpCopyResult> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let sftp = ssh.sftp()?;
+    let src = normalize_remote_path(&input.src);
+    let dest = normalize_remote_path(&input.dest);
+    if src == dest {
+        return Err(AppError::Validation(
+            "copy source and destination are the same path".to_string(),
+        ));
+    }
+
+    let src_stat = match sftp.stat(Path::new(&src)) {
+        Ok(stat) => stat,
+        Err(error) if is_sftp_missing_path_error(&error) => {
+            return Err(AppError::NotFound(format!("source file {src}")));
+        }
+        Err(error) => return Err(AppError::Ssh(error)),
+    };
+    if stat_to_entry_type(&src_stat) != SftpEntryType::File {
+        return Err(AppError::Validation(format!(
+            "only regular files can be copied: {src}"
+        )));
+    }
+    let dest_parent = remote_parent_dir(&dest);
+    match sftp.stat(Path::new(&dest_parent)) {
+        Ok(stat) if stat_to_entry_type(&stat) == SftpEntryType::Directory => {}
+        Ok(_) => {
+            return Err(AppError::Validation(format!(
+                "destination parent is not a directory: {dest_parent}"
+            )));
+        }
+        Err(error) if is_sftp_missing_path_error(&error) => {
+            return Err(AppError::NotFound(format!(
+                "destination directory {dest_parent}"
+            )));
+        }
+        Err(error) => return Err(AppError::Ssh(error)),
+    }
+    ensure_creatable_remote_path(&sftp, &dest)?;
+
+    let size = src_stat.size.unwrap_or(0);
+    let command = format!("cp -p -- {} {}", shell_quote(&src), shell_quote(&dest));
+    let copied_by_cp = matches!(
+        run_channel_command_capped(&ssh, &command, 4096, None),
+        Ok(output) if output.exit_code == 0
+    ) && sftp
+        .stat(Path::new(&dest))
+        .map(|stat| stat.size.unwrap_or(0) == size)
+        .unwrap_or(false);
+
+    let method = if copied_by_cp {
+        SftpCopyMethod::Cp
+    } else {
+        let _ = sftp.unlink(Path::new(&dest));
+        copy_remote_file_via_sftp(&sftp, &src, &dest, &src_stat)?;
+        SftpCopyMethod::Sftp
+    };
+
+    Ok(SftpCopyResult {
+        src,
+        dest,
+        size,
+        method,
+    })
+}
+
+fn copy_remote_file_via_sftp(
+    sftp: &ssh2::Sftp,
+    src: &str,
+    dest: &str,
+    src_stat: &FileStat,
+) -> AppResult<()> {
+    let mut source = sftp.open(Path::new(src))?;
+    let mut target = sftp.create(Path::new(dest))?;
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        target.write_all(&buffer[..read])?;
+    }
+    target.flush()?;
+    drop(target);
+
+    sftp.setstat(
+        Path::new(dest),
+        FileStat {
+            size: None,
+            uid: None,
+            gid: None,
+            perm: src_stat.perm.map(|perm| perm & 0o7777),
+            atime: src_stat.atime,
+            mtime: src_stat.mtime,
+        },
+    )?;
+    Ok(())
+}
+
 /// Returns a sensible default local download directory for current OS.
 pub fn default_download_dir() -> String {
     resolve_default_download_dir().to_string_lossy().to_string()
@@ -721,21 +2325,48 @@ pub fn fetch_server_status(
     state: &AppState,
     input: FetchServerStatusInput,
 ) -> AppResult<crate::models::ServerStatus> {
+    if let Some(limit) = input.process_limit {
+        if !(1..=STATUS_PROCESS_MAX_LIMIT).contains(&limit) {
+            return Err(AppError::Validation(format!(
+                "processLimit must be between 1 and {STATUS_PROCESS_MAX_LIMIT}"
+            )));
+        }
+    }
     let session = state.get_session(&input.session_id)?;
     let config = state.storage.find_ssh_config(&session.config_id)?;
     let ssh = connect(&state, &config)?;
+    collect_server_status(state, &ssh, &input)
+}
 
-    let top_output = run_channel_command(&ssh, "LANG=C top -bn1 | head -n 10")?.0;
-    let cpu_percent = parse_cpu_percent(&top_output).unwrap_or(0.0);
-    let memory = parse_memory(&top_output).unwrap_or(MemoryStatus {
-        used_mb: 0.0,
-        total_mb: 0.0,
-        used_percent: 0.0,
-    });
-
-    let net_output = run_channel_command(&ssh, "cat /proc/net/dev")?.0;
-    let network_interfaces = parse_network_interfaces(&net_output);
-    let selected_interface = pick_selected_interface(&network_interfaces, input.selected_interface);
+/// Samples every status metric over an established connection and refreshes the cache.
+fn collect_server_status(
+    state: &AppState,
+    ssh: &Session,
+    input: &FetchServerStatusInput,
+) -> AppResult<crate::models::ServerStatus> {
+    let session_id = input.session_id.as_str();
+    let profile = state.storage.get_status_command_profile();
+    let mut warnings = Vec::new();
+
+    // A failing channel here means the connection itself is gone; callers reconnect on Err.
+    let (uname, _, _) = run_channel_command(ssh, "uname -s")?;
+    let os_family = parse_os_family(&uname);
+
+    let (cpu_percent, memory) = fetch_cpu_and_memory(ssh, os_family, &profile, &mut warnings)?;
+    let per_core = fetch_per_core_cpu(ssh, os_family);
+
+    let mut network_interfaces = fetch_network_interfaces(ssh, os_family, &profile, &mut warnings)?;
+    let fetched_at = now_rfc3339();
+    if let Some(previous) = state.get_cached_status(session_id) {
+        let elapsed_secs = elapsed_secs_between(&previous.fetched_at, &fetched_at);
+        apply_network_rates(
+            &mut network_interfaces,
+            &previous.network_interfaces,
+            elapsed_secs,
+        );
+    }
+    let selected_interface =
+        pick_selected_interface(&network_interfaces, input.selected_interface.clone());
     let selected_interface_traffic = selected_interface.as_ref().and_then(|name| {
         network_interfaces
             .iter()
@@ -743,58 +2374,1473 @@ pub fn fetch_server_status(
             .cloned()
     });
 
-    let process_output =
-        run_channel_command(&ssh, "ps -eo pid,pcpu,rss,comm --sort=-pcpu | head -n 5")?.0;
-    let top_processes = parse_top_processes(&process_output);
-
-    let disk_output = run_channel_command(&ssh, "df -hP")?.0;
-    let disks = parse_disks(&disk_output);
+    let default_process_command = top_processes_command(
+        os_family,
+        input.process_sort,
+        input.process_limit.unwrap_or(STATUS_PROCESS_DEFAULT_LIMIT),
+    );
+    let process_command = profile
+        .process_command
+        .as_deref()
+        .unwrap_or(&default_process_command);
+    let top_processes = run_status_metric(ssh, "processes", process_command, &mut warnings)?
+        .map(|output| parse_top_processes(&output))
+        .unwrap_or_default();
+
+    let disk_command = profile.disk_command.as_deref().unwrap_or("df -hP");
+    let mut disks = run_status_metric(ssh, "disks", disk_command, &mut warnings)?
+        .map(|output| parse_disks(&output))
+        .unwrap_or_default();
+    if !input.include_pseudo_fs {
+        disks.retain(|disk| !is_pseudo_filesystem(disk));
+    }
+    let gpus = fetch_gpus(ssh);
 
     let status = crate::models::ServerStatus {
         cpu_percent,
+        per_core,
         memory,
         network_interfaces,
         selected_interface,
         selected_interface_traffic,
         top_processes,
         disks,
-        fetched_at: now_rfc3339(),
+        gpus,
+        fetched_at,
+        warnings,
     };
 
-    state.put_cached_status(&input.session_id, status.clone());
+    state.put_cached_status(session_id, status.clone());
     Ok(status)
 }
 
-/// Reads previously cached server status for current shell session.
-pub fn get_cached_server_status(
-    state: &AppState,
-    session_id: &str,
-) -> Option<crate::models::ServerStatus> {
-    state.get_cached_status(session_id)
-}
-
-struct SftpTransferGuard<'a> {
-    state: &'a AppState,
-    transfer_id: String,
-}
-
-impl<'a> SftpTransferGuard<'a> {
-    fn new(state: &'a AppState, transfer_id: &str) -> Self {
-        state.begin_sftp_transfer(transfer_id);
-        Self {
-            state,
-            transfer_id: transfer_id.to_string(),
+fn top_processes_command(os_family: RemoteOsFamily, sort: ProcessSort, limit: u32) -> String {
+    // `head` also keeps the header line that `parse_top_processes` skips.
+    let lines = limit + 1;
+    match (os_family, sort) {
+        (RemoteOsFamily::Linux, ProcessSort::Cpu) => {
+            format!("ps -eo pid,user,pcpu,pmem,comm --sort=-pcpu | head -n {lines}")
+        }
+        (RemoteOsFamily::Linux, ProcessSort::Mem) => {
+            format!("ps -eo pid,user,pcpu,pmem,comm --sort=-pmem | head -n {lines}")
+        }
+        // BSD ps has no --sort; `-r` sorts by CPU usage and `-m` by memory.
+        (RemoteOsFamily::Darwin | RemoteOsFamily::Bsd, ProcessSort::Cpu) => {
+            format!("ps -Ao pid,user,pcpu,pmem,comm -r | head -n {lines}")
+        }
+        (RemoteOsFamily::Darwin | RemoteOsFamily::Bsd, ProcessSort::Mem) => {
+            format!("ps -Ao pid,user,pcpu,pmem,comm -m | head -n {lines}")
         }
     }
 }
 
-impl Drop for SftpTransferGuard<'_> {
-    fn drop(&mut self) {
-        self.state.clear_sftp_transfer(&self.transfer_id);
-    }
-}
-
-fn emit_sftp_transfer_event(app: &AppHandle, event: SftpTransferEvent) {
+/// Starts a background worker that refreshes one session's status on an interval.
+///
+/// The worker keeps its SSH connection across ticks and reconnects only after a
+/// failed sample. Starting again replaces the previous worker for the session.
+pub fn start_status_polling(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: StartStatusPollingInput,
+) -> AppResult<()> {
+    state.get_session(&input.session_id)?;
+    let interval = Duration::from_secs(input.interval_secs.clamp(
+        STATUS_POLLING_MIN_INTERVAL_SECS,
+        STATUS_POLLING_MAX_INTERVAL_SECS,
+    ));
+
+    state.stop_status_polling(&input.session_id);
+    let worker_id = format!(
+        "{STATUS_POLLING_WORKER_PREFIX}{}:{}",
+        input.session_id,
+        Uuid::new_v4()
+    );
+    let request = FetchServerStatusInput {
+        session_id: input.session_id,
+        selected_interface: input.selected_interface,
+        include_pseudo_fs: false,
+        process_limit: None,
+        process_sort: ProcessSort::default(),
+    };
+    state.begin_background_worker(&worker_id);
+    thread::spawn(move || {
+        let _guard = BackgroundWorkerGuard::new(Arc::clone(&state), &worker_id);
+        run_status_polling(&state, &app, &worker_id, &request, interval);
+    });
+    Ok(())
+}
+
+/// Stops the status polling worker of one session.
+pub fn stop_status_polling(state: &AppState, session_id: &str) -> bool {
+    state.stop_status_polling(session_id)
+}
+
+fn run_status_polling(
+    state: &AppState,
+    app: &AppHandle,
+    worker_id: &str,
+    request: &FetchServerStatusInput,
+    interval: Duration,
+) {
+    let session_id = request.session_id.as_str();
+    let mut ssh: Option<Session> = None;
+    loop {
+        if state.is_background_worker_cancelled(worker_id) {
+            return;
+        }
+        let Ok(session) = state.get_session(session_id) else {
+            return;
+        };
+
+        let result = match ssh.as_ref() {
+            Some(connection) => Ok(connection),
+            None => state
+                .storage
+                .find_ssh_config(&session.config_id)
+                .and_then(|config| connect(state, &config))
+                .map(|connection| &*ssh.insert(connection)),
+        }
+        .and_then(|connection| collect_server_status(state, connection, request));
+
+        let event = match result {
+            Ok(status) => ServerStatusEvent {
+                session_id: session_id.to_string(),
+                status: Some(status),
+                error: None,
+            },
+            Err(err) => {
+                // Drop the connection so the next tick re-handshakes.
+                ssh = None;
+                ServerStatusEvent {
+                    session_id: session_id.to_string(),
+                    status: None,
+                    error: Some(err.to_string()),
+                }
+            }
+        };
+        let _ = app.emit(SERVER_STATUS_EVENT, event);
+
+        if !sleep_unless_worker_cancelled(state, worker_id, interval) {
+            return;
+        }
+    }
+}
+
+/// Forwards a local TCP port to `remote_host:remote_port` as seen from the session's server.
+///
+/// The forward runs on its own SSH connection in a background worker. Binding
+/// the local port happens there, and the outcome is reported through a
+/// `port-forward` event.
+pub fn start_local_forward(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: StartLocalForwardInput,
+) -> AppResult<PortForward> {
+    state.get_session(&input.session_id)?;
+    let remote_host = input.remote_host.trim();
+    if remote_host.is_empty() {
+        return Err(AppError::Validation(
+            "remote host cannot be empty".to_string(),
+        ));
+    }
+    if input.remote_port == 0 {
+        return Err(AppError::Validation(
+            "remote port must be in 1-65535".to_string(),
+        ));
+    }
+
+    let forward = PortForward {
+        forward_id: Uuid::new_v4().to_string(),
+        session_id: input.session_id,
+        local_port: input.local_port,
+        remote_host: remote_host.to_string(),
+        remote_port: input.remote_port,
+        created_at: now_rfc3339(),
+    };
+    let worker_id = format!(
+        "{PORT_FORWARD_WORKER_PREFIX}{}:{}",
+        forward.session_id, forward.forward_id
+    );
+    state.begin_background_worker(&worker_id);
+    state.put_port_forward(forward.clone());
+
+    let worker_forward = forward.clone();
+    thread::spawn(move || {
+        let _guard = BackgroundWorkerGuard::new(Arc::clone(&state), &worker_id);
+        let emit = |status, local_port, error| {
+            let _ = app.emit(
+                PORT_FORWARD_EVENT,
+                PortForwardEvent {
+                    forward_id: worker_forward.forward_id.clone(),
+                    session_id: worker_forward.session_id.clone(),
+                    status,
+                    local_port,
+                    error,
+                },
+            );
+        };
+        let result = run_local_forward(&state, &worker_id, &worker_forward, |local_port| {
+            emit(PortForwardStatus::Bound, local_port, None)
+        });
+        state.remove_port_forward(&worker_forward.forward_id);
+        match result {
+            Ok(()) => emit(PortForwardStatus::Closed, worker_forward.local_port, None),
+            Err(err) => emit(
+                PortForwardStatus::Failed,
+                worker_forward.local_port,
+                Some(err.to_string()),
+            ),
+        }
+    });
+    Ok(forward)
+}
+
+/// Stops one port forward. Returns whether it was active.
+pub fn stop_forward(state: &AppState, forward_id: &str) -> bool {
+    state.stop_port_forward(forward_id)
+}
+
+fn run_local_forward(
+    state: &AppState,
+    worker_id: &str,
+    forward: &PortForward,
+    on_bound: impl FnOnce(u16),
+) -> AppResult<()> {
+    let session = state.get_session(&forward.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let listener = TcpListener::bind(("127.0.0.1", forward.local_port)).map_err(|err| {
+        AppError::Runtime(format!(
+            "failed to bind local port {}: {err}",
+            forward.local_port
+        ))
+    })?;
+    listener.set_nonblocking(true)?;
+    on_bound(listener.local_addr()?.port());
+
+    ssh.set_keepalive(true, 20);
+    ssh.set_blocking(false);
+    let mut connections = Vec::<TcpChannelRelay>::new();
+    let mut buffer = [0_u8; 16_384];
+    while !state.is_background_worker_cancelled(worker_id) {
+        let mut moved = false;
+        match ssh.keepalive_send() {
+            Ok(_) => {}
+            Err(err) if is_would_block_ssh_error(&err) => {}
+            Err(err) => return Err(forward_session_lost(err)),
+        }
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                // Opening a channel is a request/response round trip, so do it blocking.
+                ssh.set_blocking(true);
+                let channel =
+                    ssh.channel_direct_tcpip(&forward.remote_host, forward.remote_port, None);
+                ssh.set_blocking(false);
+                match channel {
+                    Ok(channel) if stream.set_nonblocking(true).is_ok() => {
+                        connections.push(TcpChannelRelay::new(stream, channel));
+                        moved = true;
+                    }
+                    Ok(_) => {}
+                    // Only a refused channel is the target's fault; anything else
+                    // means the SSH connection itself is gone.
+                    Err(err) if !is_channel_open_failure(&err) => {
+                        return Err(forward_session_lost(err));
+                    }
+                    Err(err) => append_server_ops_debug_log(
+                        state,
+                        "port_forward.channel_failed",
+                        &forward.session_id,
+                        format!(
+                            "peer={peer} target={}:{} error={err}",
+                            forward.remote_host, forward.remote_port
+                        ),
+                    ),
+                }
+            }
+            Err(err) if is_transient_pty_io_error(&err) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let mut index = 0;
+        while index < connections.len() {
+            match connections[index].step(&mut buffer) {
+                RelayStep::Moved => moved = true,
+                RelayStep::Idle => {}
+                RelayStep::Closed => {
+                    connections.swap_remove(index).close();
+                    continue;
+                }
+            }
+            index += 1;
+        }
+
+        if !moved {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+    for relay in connections {
+        relay.close();
+    }
+    Ok(())
+}
+
+/// libssh2 reports a refused `direct-tcpip` open as `LIBSSH2_ERROR_CHANNEL_FAILURE` (-21).
+fn is_channel_open_failure(err: &ssh2::Error) -> bool {
+    err.code() == ErrorCode::Session(-21)
+}
+
+fn forward_session_lost(err: ssh2::Error) -> AppError {
+    AppError::Runtime(format!(
+        "ssh connection of the port forward was lost: {err}"
+    ))
+}
+
+fn elapsed_secs_between(earlier: &str, later: &str) -> f64 {
+    match (
+        chrono::DateTime::parse_from_rfc3339(earlier),
+        chrono::DateTime::parse_from_rfc3339(later),
+    ) {
+        (Ok(earlier), Ok(later)) => (later - earlier).num_milliseconds() as f64 / 1000.0,
+        _ => 0.0,
+    }
+}
+
+fn fetch_cpu_and_memory(
+    ssh: &Session,
+    os_family: RemoteOsFamily,
+    profile: &StatusCommandProfile,
+    warnings: &mut Vec<String>,
+) -> AppResult<(f64, MemoryStatus)> {
+    let top_command = profile.top_command.as_deref().unwrap_or(match os_family {
+        RemoteOsFamily::Linux => "LANG=C top -bn1 | head -n 10",
+        RemoteOsFamily::Darwin => "LANG=C top -l 1 -n 0",
+        RemoteOsFamily::Bsd => "LANG=C top -b -d 1 | head -n 10",
+    });
+    let mut top_warnings = Vec::new();
+    let top_output = run_status_metric(ssh, "cpu", top_command, &mut top_warnings)?;
+    let mut cpu_percent = top_output.as_deref().and_then(parse_cpu_percent);
+    if cpu_percent.is_none() && os_family == RemoteOsFamily::Linux {
+        cpu_percent = sample_proc_stat_cpu(ssh);
+    }
+    if cpu_percent.is_none() {
+        warnings.append(&mut top_warnings);
+        if top_output.is_some() {
+            warnings.push(format!("cpu: unable to parse usage from `{top_command}`"));
+        }
+    }
+
+    let memory = match os_family {
+        RemoteOsFamily::Linux => top_output.as_deref().and_then(parse_memory),
+        RemoteOsFamily::Darwin => {
+            let total_bytes = run_status_metric(ssh, "memory", "sysctl -n hw.memsize", warnings)?
+                .and_then(|output| output.trim().parse::<u64>().ok())
+                .unwrap_or(0);
+            run_status_metric(ssh, "memory", "vm_stat", warnings)?
+                .and_then(|output| parse_vm_stat_memory(&output, total_bytes))
+        }
+        RemoteOsFamily::Bsd => run_status_metric(
+            ssh,
+            "memory",
+            "sysctl -n hw.physmem hw.pagesize vm.stats.vm.v_free_count vm.stats.vm.v_inactive_count",
+            warnings,
+        )?
+        .and_then(|output| parse_bsd_sysctl_memory(&output)),
+    };
+    if memory.is_none() {
+        warnings.push("memory: unable to determine memory usage".to_string());
+    }
+
+    Ok((
+        cpu_percent.unwrap_or(0.0),
+        memory.unwrap_or(MemoryStatus {
+            used_mb: 0.0,
+            total_mb: 0.0,
+            used_percent: 0.0,
+            available_mb: None,
+            swap_used_mb: None,
+            swap_total_mb: None,
+        }),
+    ))
+}
+
+/// Fallback for hosts without a usable `top`: samples `/proc/stat` one second apart.
+fn sample_proc_stat_cpu(ssh: &Session) -> Option<f64> {
+    let command = "head -n 1 /proc/stat; echo ---; sleep 1; head -n 1 /proc/stat";
+    let (stdout, _, _) = run_channel_command(ssh, command).ok()?;
+    let (first, second) = stdout.split_once("---")?;
+    parse_proc_stat_cpu(first, second)
+}
+
+/// GPU metrics are best effort: hosts without `nvidia-smi` report no GPUs.
+fn fetch_gpus(ssh: &Session) -> Vec<GpuStatus> {
+    let command = "command -v nvidia-smi >/dev/null 2>&1 && nvidia-smi \
+                   --query-gpu=index,name,utilization.gpu,memory.used,memory.total \
+                   --format=csv,noheader,nounits";
+    run_channel_command(ssh, command)
+        .map(|(stdout, _, _)| parse_nvidia_smi_gpus(&stdout))
+        .unwrap_or_default()
+}
+
+/// Per-core usage is best effort: prefers `mpstat`, falls back to procps `top -1`.
+fn fetch_per_core_cpu(ssh: &Session, os_family: RemoteOsFamily) -> Vec<f64> {
+    if os_family != RemoteOsFamily::Linux {
+        return Vec::new();
+    }
+    let command = "if command -v mpstat >/dev/null 2>&1; then LANG=C mpstat -P ALL 1 1; \
+                   else LANG=C top -1 -bn1 | grep '^%Cpu[0-9]'; fi";
+    run_channel_command(ssh, command)
+        .map(|(stdout, _, _)| parse_per_core_cpu(&stdout))
+        .unwrap_or_default()
+}
+
+fn fetch_network_interfaces(
+    ssh: &Session,
+    os_family: RemoteOsFamily,
+    profile: &StatusCommandProfile,
+    warnings: &mut Vec<String>,
+) -> AppResult<Vec<NetworkInterfaceStatus>> {
+    let default_command = match os_family {
+        RemoteOsFamily::Linux => "cat /proc/net/dev",
+        RemoteOsFamily::Darwin | RemoteOsFamily::Bsd => "netstat -ib",
+    };
+    let command = profile
+        .network_command
+        .as_deref()
+        .unwrap_or(default_command);
+    let Some(net_output) = run_status_metric(ssh, "network", command, warnings)? else {
+        return Ok(Vec::new());
+    };
+    Ok(match os_family {
+        RemoteOsFamily::Linux => parse_network_interfaces(&net_output),
+        RemoteOsFamily::Darwin | RemoteOsFamily::Bsd => parse_netstat_interfaces(&net_output),
+    })
+}
+
+/// Runs one status metric command; a failing or empty command becomes a warning.
+///
+/// Only a broken session or channel is an error, so status polling can reconnect.
+fn run_status_metric(
+    ssh: &Session,
+    metric: &str,
+    command: &str,
+    warnings: &mut Vec<String>,
+) -> AppResult<Option<String>> {
+    let (stdout, stderr, exit_code) = run_channel_command(ssh, command)?;
+    if !stdout.trim().is_empty() {
+        return Ok(Some(stdout));
+    }
+    let detail = stderr.trim();
+    warnings.push(if detail.is_empty() {
+        format!("{metric}: `{command}` produced no output (exit code {exit_code})")
+    } else {
+        format!("{metric}: `{command}` failed: {detail}")
+    });
+    Ok(None)
+}
+
+/// Reads previously cached server status for current shell session.
+pub fn get_cached_server_status(
+    state: &AppState,
+    session_id: &str,
+) -> Option<crate::models::ServerStatus> {
+    state.get_cached_status(session_id)
+}
+
+/// Lists zombie/defunct processes together with their parent pids.
+pub fn get_zombie_processes(state: &AppState, session_id: &str) -> AppResult<Vec<ZombieProcess>> {
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+
+    let (stdout, stderr, exit_code) = run_channel_command(&ssh, "ps -eo pid,ppid,stat,comm")?;
+    if exit_code != 0 && stdout.trim().is_empty() {
+        return Err(AppError::Runtime(format!(
+            "failed to list processes: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(parse_zombie_processes(&stdout))
+}
+
+/// Samples `vmstat 1 2` and returns the interval row (run queue, swap, io, cs, cpu split).
+pub fn get_vmstat(state: &AppState, session_id: &str) -> AppResult<VmStat> {
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+
+    let (stdout, stderr, _) = run_channel_command(&ssh, "LANG=C vmstat 1 2")?;
+    parse_vmstat(&stdout).ok_or_else(|| {
+        AppError::Runtime(format!("failed to read vmstat output: {}", stderr.trim()))
+    })
+}
+
+/// Lists systemd services (falling back to sysvinit `service --status-all`).
+///
+/// `filter` matches case-insensitively against the unit name and description.
+pub fn list_services(
+    state: &AppState,
+    session_id: &str,
+    filter: Option<&str>,
+) -> AppResult<Vec<ServiceStatus>> {
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+
+    let (stdout, stderr, _) = run_channel_command(
+        &ssh,
+        "systemctl list-units --type=service --all --no-pager --plain 2>/dev/null \
+         || service --status-all 2>&1",
+    )?;
+    let services = parse_service_statuses(&stdout);
+    if services.is_empty() && !stderr.trim().is_empty() {
+        return Err(AppError::Runtime(format!(
+            "failed to list services: {}",
+            stderr.trim()
+        )));
+    }
+
+    let needle = filter
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty());
+    Ok(match needle {
+        Some(needle) => services
+            .into_iter()
+            .filter(|service| {
+                service.name.to_ascii_lowercase().contains(&needle)
+                    || service.description.to_ascii_lowercase().contains(&needle)
+            })
+            .collect(),
+        None => services,
+    })
+}
+
+/// Queues a service start/stop/restart as an Ops Agent pending action.
+///
+/// Service control is never executed directly: the command goes through the
+/// same approval flow as agent-proposed shell writes and runs once approved.
+pub fn request_service_action(
+    state: &AppState,
+    input: ServiceActionInput,
+    action: ServiceAction,
+) -> AppResult<OpsAgentPendingAction> {
+    let service = input.service.trim();
+    if !is_valid_service_name(service) {
+        return Err(AppError::Validation(format!(
+            "invalid service name `{service}`"
+        )));
+    }
+
+    let verb = action.as_str();
+    let quoted = shell_quote(service);
+    let command = format!(
+        "if command -v systemctl >/dev/null 2>&1; then systemctl {verb} {quoted}; \
+         else service {quoted} {verb}; fi"
+    );
+    let risk_level = match action {
+        ServiceAction::Stop => OpsAgentRiskLevel::High,
+        ServiceAction::Start | ServiceAction::Restart => OpsAgentRiskLevel::Medium,
+    };
+    queue_user_shell_action(
+        state,
+        &input.session_id,
+        input.conversation_id,
+        risk_level,
+        &command,
+        &format!("User requested to {verb} service {service}."),
+    )
+}
+
+/// Unit-name characters only; a leading `-` would be parsed as a `systemctl` option.
+fn is_valid_service_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "@._:-".contains(ch))
+}
+
+/// Queues `kill -<signal> <pid>` for approval and returns the pending action.
+pub fn kill_process(state: &AppState, input: KillProcessInput) -> AppResult<OpsAgentPendingAction> {
+    let pid = input.pid;
+    if pid <= 0 || pid > i64::from(i32::MAX) {
+        return Err(AppError::Validation(format!(
+            "pid must be a positive integer, got {pid}"
+        )));
+    }
+    let signal = input.signal.as_str();
+    let risk_level = match input.signal {
+        KillSignal::Term => OpsAgentRiskLevel::Medium,
+        KillSignal::Kill => OpsAgentRiskLevel::High,
+    };
+    queue_user_shell_action(
+        state,
+        &input.session_id,
+        input.conversation_id,
+        risk_level,
+        &format!("kill -{signal} {pid}"),
+        &format!("User requested to send SIG{signal} to process {pid}."),
+    )
+}
+
+/// Lists Docker containers (running and stopped) on the remote host.
+pub fn docker_list_containers(
+    state: &AppState,
+    session_id: &str,
+) -> AppResult<Vec<DockerContainer>> {
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+
+    let (stdout, stderr, exit_code) =
+        run_channel_command(&ssh, "docker ps -a --no-trunc --format '{{json .}}'")?;
+    if exit_code != 0 {
+        return Err(docker_command_error(&stderr, exit_code));
+    }
+    Ok(parse_docker_containers(&stdout))
+}
+
+/// Outcome of `docker_container_action`: output of an immediate action, or the
+/// pending action queued for approval.
+///
+/// Lives here rather than in `models` because it carries an Ops Agent type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerContainerActionResult {
+    pub output: Option<String>,
+    pub pending_action: Option<OpsAgentPendingAction>,
+}
+
+/// Runs or queues one container action.
+///
+/// `start` and `logs` execute immediately; `stop` and `restart` interrupt running
+/// workloads, so they are queued as pending actions for approval instead.
+pub fn docker_container_action(
+    state: &AppState,
+    input: DockerContainerActionInput,
+) -> AppResult<DockerContainerActionResult> {
+    let container_id = input.container_id.trim();
+    if container_id.is_empty()
+        || !container_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "_.-".contains(ch))
+    {
+        return Err(AppError::Validation(format!(
+            "invalid container id `{container_id}`"
+        )));
+    }
+    let quoted = shell_quote(container_id);
+
+    let command = match input.action {
+        DockerContainerAction::Start => format!("docker start {quoted}"),
+        DockerContainerAction::Logs => format!(
+            "docker logs --tail {} {quoted}",
+            input.tail.unwrap_or(DOCKER_LOGS_DEFAULT_TAIL)
+        ),
+        DockerContainerAction::Stop | DockerContainerAction::Restart => {
+            let verb = input.action.as_str();
+            let pending_action = queue_user_shell_action(
+                state,
+                &input.session_id,
+                input.conversation_id,
+                OpsAgentRiskLevel::Medium,
+                &format!("docker {verb} {quoted}"),
+                &format!("User requested to {verb} container {container_id}."),
+            )?;
+            return Ok(DockerContainerActionResult {
+                output: None,
+                pending_action: Some(pending_action),
+            });
+        }
+    };
+
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(state, &config)?;
+    let (stdout, stderr, exit_code) = run_channel_command(&ssh, &command)?;
+    if exit_code != 0 {
+        return Err(docker_command_error(&stderr, exit_code));
+    }
+    Ok(DockerContainerActionResult {
+        // `docker logs` replays the container's stderr on our stderr.
+        output: Some(format_stdout_stderr(&stdout, &stderr)),
+        pending_action: None,
+    })
+}
+
+fn docker_command_error(stderr: &str, exit_code: i32) -> AppError {
+    let detail = stderr.trim();
+    let lower = detail.to_ascii_lowercase();
+    if exit_code == 127 || lower.contains("command not found") {
+        AppError::Runtime("docker is not installed on the remote host".to_string())
+    } else if lower.contains("permission denied") {
+        AppError::Runtime(format!(
+            "permission denied talking to the Docker daemon; add the SSH user to the \
+             `docker` group: {detail}"
+        ))
+    } else if lower.contains("cannot connect to the docker daemon") {
+        AppError::Runtime(format!("the Docker daemon is not running: {detail}"))
+    } else {
+        AppError::Runtime(format!(
+            "docker command failed (exit code {exit_code}): {detail}"
+        ))
+    }
+}
+
+/// Queues a user-initiated mutating shell command in the Ops Agent approval queue.
+///
+/// Uses the given conversation, else the active one, else opens a new one bound
+/// to the session so the action has somewhere to appear.
+fn queue_user_shell_action(
+    state: &AppState,
+    session_id: &str,
+    conversation_id: Option<String>,
+    risk_level: OpsAgentRiskLevel,
+    command: &str,
+    reason: &str,
+) -> AppResult<OpsAgentPendingAction> {
+    state.get_session(session_id)?;
+    let conversation_id = match conversation_id.or_else(|| state.ops_agent.active_conversation_id())
+    {
+        Some(id) => id,
+        None => {
+            state
+                .ops_agent
+                .create_conversation(Some("Server actions"), Some(session_id))?
+                .id
+        }
+    };
+    state.ops_agent.create_pending_action(
+        &conversation_id,
+        None,
+        Some(session_id),
+        OpsAgentToolKind::shell(),
+        risk_level,
+        command,
+        reason,
+    )
+}
+
+/// Starts sampling CPU/memory of one remote process and streams `process-sample` events.
+///
+/// The watch runs on its own SSH connection in a background worker until `samples`
+/// are collected, the process exits, or `stop_watch_process` is called.
+pub fn watch_process(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: WatchProcessInput,
+) -> AppResult<WatchProcessStarted> {
+    let target = input.pid_or_name.trim().to_string();
+    if target.is_empty() {
+        return Err(AppError::Validation(
+            "pidOrName cannot be empty".to_string(),
+        ));
+    }
+    if input.samples == 0 {
+        return Err(AppError::Validation(
+            "samples must be greater than 0".to_string(),
+        ));
+    }
+
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(&state, &config)?;
+    let pid = resolve_process_pid(&ssh, &target)?;
+
+    let watch_id = Uuid::new_v4().to_string();
+    let started = WatchProcessStarted {
+        watch_id: watch_id.clone(),
+        session_id: input.session_id.clone(),
+        pid,
+    };
+    let interval = Duration::from_secs(input.interval.max(1));
+    let samples = input.samples.min(PROCESS_WATCH_MAX_SAMPLES);
+
+    let worker_id = format!(
+        "{PROCESS_WATCH_WORKER_PREFIX}{}:{watch_id}",
+        input.session_id
+    );
+    state.begin_background_worker(&worker_id);
+    thread::spawn(move || {
+        let _guard = BackgroundWorkerGuard::new(Arc::clone(&state), &worker_id);
+        run_process_watch(
+            &state,
+            &app,
+            &worker_id,
+            &watch_id,
+            &input.session_id,
+            &ssh,
+            pid,
+            interval,
+            samples,
+        );
+    });
+
+    Ok(started)
+}
+
+/// Requests a running process watch to stop.
+pub fn stop_watch_process(state: &AppState, watch_id: &str) -> bool {
+    state.stop_process_watch(watch_id)
+}
+
+/// Polls a remote file's size and mtime, emitting `file-changed` when either moves.
+///
+/// When the file disappears a single `file-removed` event is sent and the watch
+/// ends. Any other `stat` failure sends one `file-watch-error` event and also ends it.
+pub fn watch_file_start(
+    state: Arc<AppState>,
+    app: AppHandle,
+    input: WatchFileInput,
+) -> AppResult<FileWatch> {
+    let session = state.get_session(&input.session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let ssh = connect(&state, &config)?;
+    let sftp = ssh.sftp()?;
+    let path = normalize_remote_path(&input.path);
+    let initial = match sftp.stat(Path::new(&path)) {
+        Ok(stat) => file_snapshot(&stat),
+        Err(error) if is_sftp_missing_path_error(&error) => {
+            return Err(AppError::NotFound(format!("remote path {path}")));
+        }
+        Err(error) => return Err(AppError::Ssh(error)),
+    };
+
+    let watch = FileWatch {
+        watch_id: Uuid::new_v4().to_string(),
+        session_id: input.session_id,
+        path,
+        interval_secs: input
+            .interval_secs
+            .clamp(FILE_WATCH_MIN_INTERVAL_SECS, FILE_WATCH_MAX_INTERVAL_SECS),
+        created_at: now_rfc3339(),
+    };
+    let worker_id = format!(
+        "{FILE_WATCH_WORKER_PREFIX}{}:{}",
+        watch.session_id, watch.watch_id
+    );
+    state.begin_background_worker(&worker_id);
+    state.put_file_watch(watch.clone());
+
+    let worker_watch = watch.clone();
+    thread::spawn(move || {
+        let _guard = BackgroundWorkerGuard::new(Arc::clone(&state), &worker_id);
+        // Keep the SSH session alive for as long as its SFTP channel is polled.
+        let _ssh = ssh;
+        run_file_watch(&state, &app, &worker_id, &worker_watch, &sftp, initial);
+    });
+    Ok(watch)
+}
+
+/// Stops one file watch. Returns whether it existed.
+pub fn watch_file_stop(state: &AppState, watch_id: &str) -> bool {
+    state.stop_file_watch(watch_id)
+}
+
+fn run_file_watch(
+    state: &AppState,
+    app: &AppHandle,
+    worker_id: &str,
+    watch: &FileWatch,
+    sftp: &ssh2::Sftp,
+    initial: FileSnapshot,
+) {
+    let interval = Duration::from_secs(watch.interval_secs);
+    let mut previous = initial;
+    while sleep_unless_worker_cancelled(state, worker_id, interval) {
+        match sftp.stat(Path::new(&watch.path)) {
+            Ok(stat) => {
+                let current = file_snapshot(&stat);
+                if current != previous {
+                    let _ = app.emit(
+                        FILE_CHANGED_EVENT,
+                        FileChangedEvent {
+                            watch_id: watch.watch_id.clone(),
+                            session_id: watch.session_id.clone(),
+                            path: watch.path.clone(),
+                            previous,
+                            current,
+                        },
+                    );
+                    previous = current;
+                }
+            }
+            Err(error) if is_sftp_missing_path_error(&error) => {
+                let _ = app.emit(
+                    FILE_REMOVED_EVENT,
+                    FileRemovedEvent {
+                        watch_id: watch.watch_id.clone(),
+                        session_id: watch.session_id.clone(),
+                        path: watch.path.clone(),
+                        last_seen: previous,
+                    },
+                );
+                state.stop_file_watch(&watch.watch_id);
+                return;
+            }
+            Err(error) => {
+                let _ = app.emit(
+                    FILE_WATCH_ERROR_EVENT,
+                    FileWatchErrorEvent {
+                        watch_id: watch.watch_id.clone(),
+                        session_id: watch.session_id.clone(),
+                        path: watch.path.clone(),
+                        message: error.to_string(),
+                    },
+                );
+                state.stop_file_watch(&watch.watch_id);
+                return;
+            }
+        }
+    }
+}
+
+fn file_snapshot(stat: &FileStat) -> FileSnapshot {
+    FileSnapshot {
+        size: stat.size.unwrap_or_default(),
+        modified_at: stat.mtime,
+    }
+}
+
+fn resolve_process_pid(ssh: &Session, target: &str) -> AppResult<i32> {
+    if let Ok(pid) = target.parse::<i32>() {
+        if pid > 0 {
+            return Ok(pid);
+        }
+    }
+
+    let quoted = shell_quote(target);
+    let lookup = format!("pgrep -o -x {quoted} || pgrep -o {quoted}");
+    let (stdout, _, _) = run_channel_command(ssh, &lookup)?;
+    parse_first_pid(&stdout).ok_or_else(|| AppError::NotFound(format!("remote process {target}")))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_process_watch(
+    state: &AppState,
+    app: &AppHandle,
+    worker_id: &str,
+    watch_id: &str,
+    session_id: &str,
+    ssh: &Session,
+    pid: i32,
+    interval: Duration,
+    samples: u32,
+) {
+    let command = format!("ps -o pid,pcpu,pmem,rss,vsz -p {pid}");
+    let mut series = Vec::<ProcessSample>::new();
+    let emit = |stage: &str, series: &[ProcessSample], message: Option<String>| {
+        let _ = app.emit(
+            PROCESS_SAMPLE_EVENT,
+            ProcessSampleEvent {
+                watch_id: watch_id.to_string(),
+                session_id: session_id.to_string(),
+                pid,
+                stage: stage.to_string(),
+                samples: series.to_vec(),
+                message,
+            },
+        );
+    };
+
+    for index in 0..samples {
+        if state.is_background_worker_cancelled(worker_id) {
+            emit("stopped", &series, None);
+            return;
+        }
+
+        let stdout = match run_channel_command(ssh, &command) {
+            Ok((stdout, _, _)) => stdout,
+            Err(error) => {
+                emit("failed", &series, Some(error.to_string()));
+                return;
+            }
+        };
+        let Some(sample) = parse_process_sample(&stdout, now_rfc3339()) else {
+            emit(
+                "exited",
+                &series,
+                Some(format!("process {pid} is no longer running")),
+            );
+            return;
+        };
+        series.push(sample);
+        emit("sample", &series, None);
+
+        if index + 1 < samples && !sleep_unless_worker_cancelled(state, worker_id, interval) {
+            emit("stopped", &series, None);
+            return;
+        }
+    }
+
+    emit("completed", &series, None);
+}
+
+/// Sleeps for `duration` in short slices; returns `false` if the worker was cancelled meanwhile.
+fn sleep_unless_worker_cancelled(state: &AppState, worker_id: &str, duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if state.is_background_worker_cancelled(worker_id) {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep(BACKGROUND_WORKER_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+struct BackgroundWorkerGuard {
+    state: Arc<AppState>,
+    worker_id: String,
+}
+
+impl BackgroundWorkerGuard {
+    fn new(state: Arc<AppState>, worker_id: &str) -> Self {
+        state.begin_background_worker(worker_id);
+        Self {
+            state,
+            worker_id: worker_id.to_string(),
+        }
+    }
+}
+
+impl Drop for BackgroundWorkerGuard {
+    fn drop(&mut self) {
+        self.state.clear_background_worker(&self.worker_id);
+    }
+}
+
+struct SftpTransferGuard<'a> {
+    state: &'a AppState,
+    transfer_id: String,
+}
+
+impl<'a> SftpTransferGuard<'a> {
+    fn new(state: &'a AppState, transfer_id: &str) -> Self {
+        state.begin_sftp_transfer(transfer_id);
+        Self {
+            state,
+            transfer_id: transfer_id.to_string(),
+        }
+    }
+}
+
+impl Drop for SftpTransferGuard<'_> {
+    fn drop(&mut self) {
+        self.state.clear_sftp_transfer(&self.transfer_id);
+    }
+}
+
+/// Emits `sftp-transfer` events for one directory transfer, tracking aggregate byte counts.
+struct DirTransferReporter<'a> {
+    app: &'a AppHandle,
+    direction: &'static str,
+    transfer_id: &'a str,
+    session_id: &'a str,
+    remote_dir: &'a str,
+    local_path: String,
+    file_name: String,
+    transferred_bytes: Cell<u64>,
+    total_bytes: Cell<Option<u64>>,
+}
+
+impl DirTransferReporter<'_> {
+    fn advance(&self, bytes: usize) {
+        self.transferred_bytes
+            .set(self.transferred_bytes.get() + bytes as u64);
+        self.emit("progress", None);
+    }
+
+    fn emit(&self, stage: &str, message: Option<String>) {
+        let transferred_bytes = self.transferred_bytes.get();
+        let total_bytes = self.total_bytes.get();
+        emit_sftp_transfer_event(
+            self.app,
+            SftpTransferEvent {
+                transfer_id: self.transfer_id.to_string(),
+                session_id: self.session_id.to_string(),
+                direction: self.direction.to_string(),
+                stage: stage.to_string(),
+                remote_path: self.remote_dir.to_string(),
+                local_path: Some(self.local_path.clone()),
+                file_name: self.file_name.clone(),
+                transferred_bytes,
+                total_bytes,
+                percent: compute_transfer_percent(transferred_bytes, total_bytes),
+                message,
+            },
+        );
+    }
+
+    /// Per-file event under the same transfer id; `file_name` is the path relative to the root.
+    fn emit_file(
+        &self,
+        stage: &str,
+        remote_path: &str,
+        local_path: &Path,
+        file_name: &str,
+        transferred_bytes: u64,
+        total_bytes: u64,
+    ) {
+        emit_sftp_transfer_event(
+            self.app,
+            SftpTransferEvent {
+                transfer_id: self.transfer_id.to_string(),
+                session_id: self.session_id.to_string(),
+                direction: self.direction.to_string(),
+                stage: stage.to_string(),
+                remote_path: remote_path.to_string(),
+                local_path: Some(local_path.to_string_lossy().to_string()),
+                file_name: file_name.to_string(),
+                transferred_bytes,
+                total_bytes: Some(total_bytes),
+                percent: compute_transfer_percent(transferred_bytes, Some(total_bytes)),
+                message: None,
+            },
+        );
+    }
+}
+
+fn ensure_transfer_not_cancelled(state: &AppState, transfer_id: &str) -> AppResult<()> {
+    if state.is_sftp_transfer_cancelled(transfer_id) {
+        return Err(AppError::Runtime("transfer cancelled by user".to_string()));
+    }
+    Ok(())
+}
+
+fn remote_has_tar(ssh: &Session) -> bool {
+    run_channel_command_capped(ssh, "command -v tar >/dev/null 2>&1", 1024, None)
+        .map(|output| output.exit_code == 0)
+        .unwrap_or(false)
+}
+
+/// Builds `tar -czf - -C <parent> <name>` for an absolute, non-root remote directory.
+fn build_tar_dir_command(remote_dir: &str) -> String {
+    let (parent, name) = match remote_dir.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => (".", remote_dir),
+    };
+    // A leading dash would be parsed by tar as an option.
+    let member = if name.starts_with('-') {
+        format!("./{}", name)
+    } else {
+        name.to_string()
+    };
+    format!(
+        "tar -czf - -C {} {}",
+        shell_quote(parent),
+        shell_quote(&member)
+    )
+}
+
+/// Streams a remote `tar -czf -` of `remote_dir` into `target`, returning the archive
+/// size and tar's warning, if any.
+///
+/// The archive is written to a temp file next to `target` and only renamed over it
+/// once tar succeeds, so a failed or cancelled transfer never clobbers an existing file.
+/// Exit code 1 (GNU tar: files changed while being read) still yields a complete
+/// archive and is reported as a warning.
+fn stream_remote_tar(
+    state: &AppState,
+    ssh: &Session,
+    remote_dir: &str,
+    target: &Path,
+    reporter: &DirTransferReporter<'_>,
+) -> AppResult<(u64, Option<String>)> {
+    if let Some(parent) = target
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file_name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = target.with_file_name(format!(".{}.part-{}", file_name, Uuid::new_v4()));
+    let result =
+        stream_remote_tar_to(state, ssh, remote_dir, &temp_path, reporter).and_then(|done| {
+            std::fs::rename(&temp_path, target)?;
+            Ok(done)
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn stream_remote_tar_to(
+    state: &AppState,
+    ssh: &Session,
+    remote_dir: &str,
+    target: &Path,
+    reporter: &DirTransferReporter<'_>,
+) -> AppResult<(u64, Option<String>)> {
+    let mut local_file = File::create(target)?;
+    let mut channel = ssh.channel_session()?;
+    channel.exec(&build_tar_dir_command(remote_dir))?;
+
+    let mut written = 0_u64;
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    loop {
+        if let Err(error) = ensure_transfer_not_cancelled(state, reporter.transfer_id) {
+            let _ = channel.close();
+            return Err(error);
+        }
+        let read = channel.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        local_file.write_all(&buffer[..read])?;
+        written += read as u64;
+        reporter.advance(read);
+    }
+    local_file.flush()?;
+
+    let (stderr, _) = read_capped(&mut channel.stderr(), 4096)?;
+    channel.wait_close()?;
+    let exit_code = channel.exit_status()?;
+    let stderr = String::from_utf8_lossy(&stderr).trim().to_string();
+    match exit_code {
+        0 => Ok((written, None)),
+        1 => Ok((written, Some(format!("tar reported warnings: {}", stderr)))),
+        _ => Err(AppError::Runtime(format!(
+            "tar exited with code {}: {}",
+            exit_code, stderr
+        ))),
+    }
+}
+
+/// Mirrors `remote_dir` into `target` file by file, returning total bytes and file count.
+///
+/// Symlinks and special files are skipped, as are entries whose name is not a single
+/// plain path component locally, so a hostile server cannot write outside `target`.
+fn download_dir_via_sftp(
+    state: &AppState,
+    sftp: &ssh2::Sftp,
+    remote_dir: &str,
+    target: &Path,
+    reporter: &DirTransferReporter<'_>,
+) -> AppResult<(u64, usize)> {
+    let mut files = Vec::new();
+    let mut pending = vec![(remote_dir.to_string(), target.to_path_buf())];
+    while let Some((remote, local)) = pending.pop() {
+        ensure_transfer_not_cancelled(state, reporter.transfer_id)?;
+        std::fs::create_dir_all(&local)?;
+        for (path, stat) in sftp.readdir(Path::new(&remote))? {
+            let Some(name) = extract_entry_name(&path.to_string_lossy()) else {
+                continue;
+            };
+            if !is_plain_local_file_name(&name) {
+                continue;
+            }
+            let child_remote = join_remote_path(&remote, &name);
+            match stat_to_entry_type(&stat) {
+                SftpEntryType::Directory => pending.push((child_remote, local.join(&name))),
+                SftpEntryType::File => {
+                    files.push((child_remote, local.join(&name), stat.size.unwrap_or(0)))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let total = files.iter().map(|(_, _, size)| size).sum::<u64>();
+    reporter.total_bytes.set(Some(total));
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    let mut transferred = 0_u64;
+    for (remote, local, _) in &files {
+        let mut remote_file = sftp.open(Path::new(remote))?;
+        let mut local_file = File::create(local)?;
+        loop {
+            ensure_transfer_not_cancelled(state, reporter.transfer_id)?;
+            let read = remote_file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            local_file.write_all(&buffer[..read])?;
+            transferred += read as u64;
+            reporter.advance(read);
+        }
+    }
+    Ok((transferred, files.len()))
+}
+
+/// True when `name` maps to exactly one normal path component on this machine:
+/// no separators (`/` or `\\`), no `.`/`..`, no drive or root prefix.
+fn is_plain_local_file_name(name: &str) -> bool {
+    if name.is_empty() || name.contains(['/', '\\']) {
+        return false;
+    }
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
+struct LocalUploadFile {
+    local_path: PathBuf,
+    /// `/`-separated path relative to the upload root.
+    relative_path: String,
+    size: u64,
+    executable: bool,
+}
+
+#[derive(Default)]
+struct LocalUploadPlan {
+    /// Relative directories, parents before children.
+    directories: Vec<String>,
+    files: Vec<LocalUploadFile>,
+}
+
+/// Walks `root` without following symlinks, in a stable (sorted) order.
+fn plan_local_upload(root: &Path) -> AppResult<LocalUploadPlan> {
+    let mut plan = LocalUploadPlan::default();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+    while let Some((dir, relative)) = pending.pop() {
+        let mut entries = std::fs::read_dir(&dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        let mut subdirs = Vec::new();
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child_relative = if relative.is_empty() {
+                name
+            } else {
+                format!("{}/{}", relative, name)
+            };
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                plan.directories.push(child_relative.clone());
+                subdirs.push((entry.path(), child_relative));
+            } else if file_type.is_file() {
+                let metadata = entry.metadata()?;
+                plan.files.push(LocalUploadFile {
+                    local_path: entry.path(),
+                    relative_path: child_relative,
+                    size: metadata.len(),
+                    executable: is_local_executable(&metadata),
+                });
+            }
+        }
+        pending.extend(subdirs.into_iter().rev());
+    }
+    Ok(plan)
+}
+
+#[cfg(unix)]
+fn is_local_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_local_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// Creates `remote_dir` if needed; returns whether it was newly created.
+fn ensure_remote_dir(sftp: &ssh2::Sftp, remote_dir: &str) -> AppResult<bool> {
+    match sftp.mkdir(Path::new(remote_dir), 0o755) {
+        Ok(()) => Ok(true),
+        Err(error) => match sftp.stat(Path::new(remote_dir)) {
+            Ok(stat) if stat_to_entry_type(&stat) == SftpEntryType::Directory => Ok(false),
+            Ok(_) => Err(AppError::Validation(format!(
+                "{} exists and is not a directory",
+                remote_dir
+            ))),
+            Err(_) => Err(AppError::Ssh(error)),
+        },
+    }
+}
+
+/// Creates the remote tree and uploads every planned file; returns how many directories were created.
+fn upload_planned_dir(
+    state: &AppState,
+    sftp: &ssh2::Sftp,
+    remote_dir: &str,
+    plan: &LocalUploadPlan,
+    preserve_executable: bool,
+    reporter: &DirTransferReporter<'_>,
+) -> AppResult<usize> {
+    let mut directories_created = usize::from(ensure_remote_dir(sftp, remote_dir)?);
+    for relative in &plan.directories {
+        ensure_transfer_not_cancelled(state, reporter.transfer_id)?;
+        if ensure_remote_dir(sftp, &join_remote_path(remote_dir, relative))? {
+            directories_created += 1;
+        }
+    }
+
+    let mut buffer = vec![0_u8; SFTP_TRANSFER_CHUNK_BYTES];
+    for file in &plan.files {
+        let remote_path = join_remote_path(remote_dir, &file.relative_path);
+        let mut local_file = File::open(&file.local_path)?;
+        let mut remote_file = sftp.create(Path::new(&remote_path))?;
+        let mut sent = 0_u64;
+        loop {
+            ensure_transfer_not_cancelled(state, reporter.transfer_id)?;
+            let read = local_file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            remote_file.write_all(&buffer[..read])?;
+            sent += read as u64;
+            reporter.emit_file(
+                "file_progress",
+                &remote_path,
+                &file.local_path,
+                &file.relative_path,
+                sent,
+                file.size,
+            );
+            reporter.advance(read);
+        }
+        remote_file.flush()?;
+        drop(remote_file);
+
+        if preserve_executable && file.executable {
+            let path = Path::new(&remote_path);
+            let perm = sftp.stat(path)?.perm.unwrap_or(0o644) | 0o111;
+            sftp.setstat(
+                path,
+                FileStat {
+                    size: None,
+                    uid: None,
+                    gid: None,
+                    perm: Some(perm),
+                    atime: None,
+                    mtime: None,
+                },
+            )?;
+        }
+        reporter.emit_file(
+            "file_completed",
+            &remote_path,
+            &file.local_path,
+            &file.relative_path,
+            sent,
+            sent,
+        );
+    }
+    Ok(directories_created)
+}
+
+/// Drops a `.tar.gz`/`.tgz` suffix so the SFTP fallback gets a directory path.
+fn strip_archive_suffix(local_path: &str) -> &str {
+    [".tar.gz", ".tgz"]
+        .iter()
+        .find_map(|suffix| {
+            local_path
+                .strip_suffix(suffix)
+                .filter(|stem| !stem.is_empty())
+        })
+        .unwrap_or(local_path)
+}
+
+fn emit_sftp_transfer_event(app: &AppHandle, event: SftpTransferEvent) {
     let _ = app.emit(SFTP_TRANSFER_EVENT, event);
 }
 
@@ -911,6 +3957,13 @@ fn join_remote_path(base: &str, name: &str) -> String {
     }
 }
 
+fn remote_parent_dir(path: &str) -> String {
+    match normalize_remote_path(path).rsplit_once('/') {
+        Some(("", _)) | None => "/".to_string(),
+        Some((parent, _)) => parent.to_string(),
+    }
+}
+
 fn extract_entry_name(raw_path: &str) -> Option<String> {
     let normalized = raw_path.replace('\\', "/");
     normalized
@@ -919,6 +3972,22 @@ fn extract_entry_name(raw_path: &str) -> Option<String> {
         .map(ToString::to_string)
 }
 
+/// Canonicalizes a remote path; a missing path maps to `NotFound` rather than an SSH error.
+fn resolve_remote_realpath(sftp: &ssh2::Sftp, path: &str) -> AppResult<String> {
+    match sftp.realpath(Path::new(path)) {
+        Ok(resolved) => Ok(sanitize_cwd(&resolved.to_string_lossy())),
+        Err(err) if is_sftp_missing_path_error(&err) => {
+            Err(AppError::NotFound(format!("remote path {path}")))
+        }
+        Err(err) => Err(AppError::Ssh(err)),
+    }
+}
+
+fn is_sftp_missing_path_error(err: &ssh2::Error) -> bool {
+    // LIBSSH2_FX_NO_SUCH_FILE (2) and LIBSSH2_FX_NO_SUCH_PATH (10).
+    matches!(err.code(), ErrorCode::SFTP(2) | ErrorCode::SFTP(10))
+}
+
 fn ensure_creatable_remote_path(sftp: &ssh2::Sftp, path: &str) -> AppResult<()> {
     let normalized_path = normalize_remote_path(path);
     if normalized_path == "/" {
@@ -953,20 +4022,153 @@ fn delete_remote_dir_recursive(sftp: &ssh2::Sftp, path: &str) -> AppResult<()> {
         }
     }
 
-    sftp.rmdir(Path::new(&normalized_path))?;
-    Ok(())
+    sftp.rmdir(Path::new(&normalized_path))?;
+    Ok(())
+}
+
+fn parse_cd_target(command: &str) -> Option<Option<String>> {
+    let trimmed = command.trim();
+    if trimmed == "cd" {
+        return Some(None);
+    }
+    trimmed
+        .strip_prefix("cd ")
+        .map(|target| Some(target.trim().to_string()))
+}
+
+/// Turns the argument of a typed `cd` into the shell word for `cd -- <word>`.
+///
+/// `None` (a bare `cd`) goes home and `-` goes to `previous_dir`, which the remote
+/// shell cannot know because every command runs in a fresh exec channel.
+fn resolve_cd_target(target: Option<&str>, previous_dir: Option<&str>) -> AppResult<String> {
+    match target {
+        None => Ok("~".to_string()),
+        Some("-") => previous_dir.map(shell_quote).ok_or_else(|| {
+            AppError::Validation("cd -: no previous directory in this session".to_string())
+        }),
+        Some(raw) => quote_cd_target(raw),
+    }
+}
+
+/// Turns a `cd` path into one safely quoted shell word.
+///
+/// Quotes and backslash escapes are resolved the way a shell would. A leading `~`
+/// or `~user` and `$NAME` / `${NAME}` references stay expandable; the literal
+/// parts are single-quoted. Command substitution, backticks, globs, operators and
+/// unquoted whitespace are rejected instead of being passed through.
+fn quote_cd_target(raw: &str) -> AppResult<String> {
+    let invalid = || {
+        AppError::Validation(format!(
+            "cd target {raw} is not a single path; quote it if it contains spaces or special characters"
+        ))
+    };
+    let (tilde_prefix, rest) = split_tilde_prefix(raw);
+    if rest.is_empty() && !tilde_prefix.is_empty() {
+        // Any quoting right after `~` would stop it from expanding.
+        return Ok(tilde_prefix.to_string());
+    }
+    let word = quote_shell_word(rest).ok_or_else(invalid)?;
+    Ok(format!("{tilde_prefix}{word}"))
+}
+
+/// Splits off a leading `~`, `~/`, `~user` or `~user/` so it can stay unquoted.
+fn split_tilde_prefix(raw: &str) -> (&str, &str) {
+    if !raw.starts_with('~') {
+        return ("", raw);
+    }
+    let end = raw.find('/').map_or(raw.len(), |index| index + 1);
+    let user = raw[1..end].trim_end_matches('/');
+    if user
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'))
+    {
+        raw.split_at(end)
+    } else {
+        ("", raw)
+    }
+}
+
+/// Re-quotes one shell word; `None` if it would run commands, glob or split.
+///
+/// Literal text ends up single-quoted and variable references double-quoted, so
+/// the result expands variables but nothing else.
+fn quote_shell_word(raw: &str) -> Option<String> {
+    fn push_variable(quoted: &mut String, literal: &mut String, name: &str) {
+        if !literal.is_empty() {
+            quoted.push_str(&shell_quote(literal));
+            literal.clear();
+        }
+        quoted.push_str(&format!("\"${{{name}}}\""));
+    }
+
+    let mut quoted = String::new();
+    let mut literal = String::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    inner => literal.push(inner),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        escaped @ ('$' | '`' | '"' | '\\') => literal.push(escaped),
+                        '\n' => {}
+                        other => {
+                            literal.push('\\');
+                            literal.push(other);
+                        }
+                    },
+                    '$' => {
+                        let name = read_variable_name(&mut chars)?;
+                        push_variable(&mut quoted, &mut literal, &name);
+                    }
+                    '`' => return None,
+                    inner => literal.push(inner),
+                }
+            },
+            '$' => {
+                let name = read_variable_name(&mut chars)?;
+                push_variable(&mut quoted, &mut literal, &name);
+            }
+            '\\' => literal.push(chars.next()?),
+            ch if ch.is_whitespace() => return None,
+            '`' | ';' | '&' | '|' | '<' | '>' | '(' | ')' | '*' | '?' | '[' | '{' => return None,
+            other => literal.push(other),
+        }
+    }
+    if !literal.is_empty() || quoted.is_empty() {
+        quoted.push_str(&shell_quote(&literal));
+    }
+    Some(quoted)
 }
 
-fn parse_cd_target(command: &str) -> Option<Option<String>> {
-    let trimmed = command.trim();
-    if trimmed == "cd" {
-        return Some(None);
+/// Reads `NAME` or `{NAME}` after a `$`; `None` for `$(`, `$?` and other specials.
+fn read_variable_name(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+    let mut name = String::new();
+    if chars.next_if_eq(&'{').is_some() {
+        loop {
+            match chars.next()? {
+                '}' => break,
+                ch => name.push(ch),
+            }
+        }
+    } else {
+        while let Some(ch) = chars.next_if(|ch| ch.is_ascii_alphanumeric() || *ch == '_') {
+            name.push(ch);
+        }
     }
-    trimmed
-        .strip_prefix("cd ")
-        .map(|target| Some(target.trim().to_string()))
+    is_valid_env_var_name(&name).then_some(name)
 }
 
+/// Wraps a value in single quotes for POSIX shells.
+///
+/// Everything inside single quotes is literal, newlines included; embedded single
+/// quotes are closed, emitted as `"'"`, and reopened.
 fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\"'\"'"))
 }
@@ -980,61 +4182,104 @@ fn format_stdout_stderr(stdout: &str, stderr: &str) -> String {
     }
 }
 
+/// Opens a PTY shell channel, retrying while a session shared with running
+/// PTY workers is in non-blocking mode.
+fn open_pty_channel(ssh: &Session, spec: &PtySpec) -> AppResult<ssh2::Channel> {
+    let deadline = Instant::now() + PTY_CHANNEL_OPEN_TIMEOUT;
+    let mut channel = retry_would_block(deadline, || ssh.channel_session())?;
+    retry_would_block(deadline, || {
+        channel.request_pty(
+            &spec.term,
+            None,
+            Some((u32::from(spec.cols), u32::from(spec.rows), 0, 0)),
+        )
+    })?;
+    retry_would_block(deadline, || channel.shell())?;
+    Ok(channel)
+}
+
+fn retry_would_block<T>(
+    deadline: Instant,
+    mut op: impl FnMut() -> Result<T, ssh2::Error>,
+) -> AppResult<T> {
+    loop {
+        match op() {
+            Err(err) if is_would_block_ssh_error(&err) && Instant::now() < deadline => {
+                thread::sleep(PTY_IDLE_MIN_WAIT);
+            }
+            result => return Ok(result?),
+        }
+    }
+}
+
+/// libssh2 reports `LIBSSH2_ERROR_EAGAIN` as session error -37.
+fn is_would_block_ssh_error(err: &ssh2::Error) -> bool {
+    err.code() == ErrorCode::Session(-37)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn start_pty_worker(
     state: Arc<AppState>,
     app: AppHandle,
     session_id: String,
     ssh: Session,
+    mut channel: ssh2::Channel,
+    decoder: PtyOutputDecoder,
+    spec: &PtySpec,
+    startup_input: Option<&str>,
+    reconnect: Option<PtyReconnectPlan>,
 ) -> AppResult<()> {
     ssh.set_keepalive(true, 20);
-    let mut channel = ssh.channel_session()?;
-    channel.request_pty(
-        "xterm-256color",
-        None,
-        Some((
-            u32::from(DEFAULT_PTY_COLS),
-            u32::from(DEFAULT_PTY_ROWS),
-            0,
-            0,
-        )),
-    )?;
-    channel.shell()?;
+    if let Some(startup_input) = startup_input {
+        write_pty_startup_input(&mut channel, startup_input.as_bytes())?;
+    }
     ssh.set_blocking(false);
 
     let (tx, rx) = mpsc::channel::<PtyCommand>();
-    state.put_pty_channel(session_id.clone(), tx);
+    if !state.install_pty_worker(&session_id, tx, ssh.clone()) {
+        let _ = channel.close();
+        return Err(AppError::NotFound(format!("shell session {session_id}")));
+    }
+    state.put_pty_size(&session_id, spec.cols, spec.rows);
+    state.put_pty_term(&session_id, &spec.term);
     append_server_ops_debug_log(
         state.as_ref(),
         "pty.worker.started",
         &session_id,
         format!(
-            "keepalive_sec=20 cols={} rows={}",
-            DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS
+            "keepalive_sec=20 term={} cols={} rows={}",
+            spec.term, spec.cols, spec.rows
         ),
     );
 
     thread::spawn(move || {
-        run_pty_worker(state, app, session_id, ssh, channel, rx);
+        run_pty_worker(state, app, session_id, ssh, channel, rx, decoder, reconnect);
     });
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_pty_worker(
     state: Arc<AppState>,
     app: AppHandle,
     session_id: String,
-    _ssh: Session,
+    ssh: Session,
     mut channel: ssh2::Channel,
     rx: mpsc::Receiver<PtyCommand>,
+    mut decoder: PtyOutputDecoder,
+    reconnect: Option<PtyReconnectPlan>,
 ) {
     let mut io_buffer = [0_u8; 16_384];
     let mut keep_running = true;
     let mut pending_input = Vec::<u8>::new();
     let mut pending_input_offset = 0usize;
+    let mut close_reason = (PtyCloseReason::Eof, None);
+    let mut idle_wait = PTY_IDLE_MIN_WAIT;
+    let mut woken_by = None;
 
     while keep_running {
-        let batch = drain_pty_command_batch(&rx, PTY_MAX_COMMANDS_PER_TICK);
+        let batch = drain_pty_command_batch(woken_by.take(), &rx, PTY_MAX_COMMANDS_PER_TICK);
         if batch.close_requested {
             append_server_ops_debug_log(
                 state.as_ref(),
@@ -1042,11 +4287,14 @@ fn run_pty_worker(
                 &session_id,
                 "reason=close_command_or_channel_dropped",
             );
+            close_reason = (PtyCloseReason::Closed, None);
             break;
         }
 
         if let Some((cols, rows)) = batch.latest_resize {
             let _ = channel.request_pty_size(u32::from(cols), u32::from(rows), None, None);
+            state.put_pty_size(&session_id, cols, rows);
+            state.with_pty_recording(&session_id, |recorder| recorder.record_resize(cols, rows));
         }
 
         if !batch.input.is_empty() {
@@ -1068,6 +4316,7 @@ fn run_pty_worker(
                     &session_id,
                     error.to_string(),
                 );
+                close_reason = (PtyCloseReason::Error, Some(error.to_string()));
                 break;
             }
         };
@@ -1079,9 +4328,16 @@ fn run_pty_worker(
                 Ok(size) if size > 0 => {
                     did_read = true;
                     read_chunks += 1;
-                    let chunk = String::from_utf8_lossy(&io_buffer[..size]).to_string();
+                    let chunk = decoder.decode(&io_buffer[..size]);
+                    if chunk.is_empty() {
+                        continue;
+                    }
+                    if let Some(enabled) = bracketed_paste_mode_change(&chunk) {
+                        state.set_pty_bracketed_paste(&session_id, enabled);
+                    }
                     append_session_output(&state, &session_id, &chunk);
                     emit_pty_output(&app, &session_id, &chunk);
+                    record_pty_output(&state, &session_id, &chunk);
                 }
                 Ok(_) => {
                     if channel.eof() {
@@ -1097,6 +4353,7 @@ fn run_pty_worker(
                         &session_id,
                         err.to_string(),
                     );
+                    close_reason = (PtyCloseReason::Error, Some(err.to_string()));
                     keep_running = false;
                     break;
                 }
@@ -1107,22 +4364,154 @@ fn run_pty_worker(
             keep_running = false;
         }
 
-        if !did_read && !wrote_any && batch.drained_messages == 0 {
-            thread::sleep(Duration::from_millis(PTY_IDLE_SLEEP_MS));
+        if did_read || wrote_any || batch.drained_messages > 0 {
+            idle_wait = PTY_IDLE_MIN_WAIT;
+        } else {
+            if idle_wait >= PTY_IDLE_MAX_WAIT {
+                // Quiet long enough that no split secret is still in flight.
+                state.with_pty_recording(&session_id, PtyRecorder::flush_output);
+            }
+            // A disconnected channel is picked up as a close request on the next drain.
+            woken_by = rx.recv_timeout(idle_wait).ok();
+            idle_wait = next_pty_idle_wait(idle_wait);
         }
     }
 
+    let (reason, mut detail) = close_reason;
     let _ = channel.close();
     let _ = channel.wait_close();
+    drop(channel);
+    drop(ssh);
+    drop(rx);
+    // Only I/O failures are retried; an exited shell or a closed tab stays closed.
+    if let Some(plan) = reconnect.filter(|_| reason == PtyCloseReason::Error) {
+        match recover_pty_session(&state, &app, &session_id, plan, detail.take()) {
+            Ok(()) => return,
+            Err(last_error) => detail = Some(last_error),
+        }
+    }
+    let _ = app.emit(
+        PTY_CLOSED_EVENT,
+        PtyClosedEvent {
+            session_id: session_id.clone(),
+            reason,
+            detail,
+        },
+    );
     append_server_ops_debug_log(
         state.as_ref(),
         "pty.worker.stopped",
         &session_id,
-        "session_removed=true",
+        format!("session_removed=true reason={reason:?}"),
     );
     let _ = state.remove_session(&session_id);
 }
 
+/// Reopens a dropped PTY for the same session id with exponential backoff.
+///
+/// The session keeps its scrollback and output while retrying, and the new
+/// shell starts in the last known working directory. Input sent during the
+/// backoff fails fast with a "reconnecting" error. Gives up, returning the
+/// last error, after `PTY_RECONNECT_MAX_ATTEMPTS` or once the tab is closed.
+fn recover_pty_session(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    session_id: &str,
+    plan: PtyReconnectPlan,
+    mut detail: Option<String>,
+) -> Result<(), String> {
+    // The shared connection most likely dropped too; keep it from new tabs.
+    state.discard_shared_ssh(session_id);
+    // Until a new worker is installed (or the session is removed), input fails fast.
+    state.begin_pty_reconnect(session_id);
+    let mut delay = PTY_RECONNECT_INITIAL_DELAY;
+    for attempt in 1..=PTY_RECONNECT_MAX_ATTEMPTS {
+        let event = PtyReconnectEvent {
+            session_id: session_id.to_string(),
+            attempt,
+            max_attempts: PTY_RECONNECT_MAX_ATTEMPTS,
+            detail: detail.clone(),
+        };
+        let _ = app.emit(PTY_RECONNECTING_EVENT, event.clone());
+        thread::sleep(delay);
+        delay = (delay * 2).min(PTY_RECONNECT_MAX_DELAY);
+        if state.is_shutting_down() || state.get_session(session_id).is_err() {
+            return Err("session closed while reconnecting".to_string());
+        }
+
+        match reopen_pty_worker(state, app, session_id, &plan) {
+            Ok(()) => {
+                append_server_ops_debug_log(
+                    state.as_ref(),
+                    "pty.worker.reconnected",
+                    session_id,
+                    format!("attempt={attempt}"),
+                );
+                let _ = app.emit(
+                    PTY_RECONNECTED_EVENT,
+                    PtyReconnectEvent {
+                        detail: None,
+                        ..event
+                    },
+                );
+                return Ok(());
+            }
+            Err(err) => {
+                append_server_ops_debug_log(
+                    state.as_ref(),
+                    "pty.worker.reconnect_failed",
+                    session_id,
+                    format!("attempt={attempt} error={err}"),
+                );
+                detail = Some(err.to_string());
+            }
+        }
+    }
+
+    let last_error = detail.unwrap_or_else(|| "reconnect failed".to_string());
+    let _ = app.emit(
+        PTY_RECONNECT_FAILED_EVENT,
+        PtyReconnectEvent {
+            session_id: session_id.to_string(),
+            attempt: PTY_RECONNECT_MAX_ATTEMPTS,
+            max_attempts: PTY_RECONNECT_MAX_ATTEMPTS,
+            detail: Some(last_error.clone()),
+        },
+    );
+    Err(last_error)
+}
+
+/// Connects again and starts a new PTY worker in the session's current directory.
+fn reopen_pty_worker(
+    state: &Arc<AppState>,
+    app: &AppHandle,
+    session_id: &str,
+    plan: &PtyReconnectPlan,
+) -> AppResult<()> {
+    let session = state.get_session(session_id)?;
+    let config = state.storage.find_ssh_config(&session.config_id)?;
+    let mut spec = plan.spec.clone();
+    if let Some((cols, rows)) = state.get_pty_size(session_id) {
+        spec.cols = cols;
+        spec.rows = rows;
+    }
+    let decoder = PtyOutputDecoder::for_label(plan.encoding.as_deref())?;
+    let ssh = connect(state, &config)?;
+    let channel = open_pty_channel(&ssh, &spec)?;
+    let startup_input = build_pty_startup_input(Some(&session.current_dir), None);
+    start_pty_worker(
+        Arc::clone(state),
+        app.clone(),
+        session_id.to_string(),
+        ssh,
+        channel,
+        decoder,
+        &spec,
+        startup_input.as_deref(),
+        Some(plan.clone()),
+    )
+}
+
 fn emit_pty_output(app: &AppHandle, session_id: &str, chunk: &str) {
     let _ = app.emit(
         "pty-output",
@@ -1134,13 +4523,28 @@ fn emit_pty_output(app: &AppHandle, session_id: &str, chunk: &str) {
 }
 
 fn append_session_output(state: &AppState, session_id: &str, chunk: &str) {
-    let _ = state.mutate_session(session_id, |session| {
-        session.last_output.push_str(chunk);
-        trim_to_last_chars(&mut session.last_output, MAX_SESSION_LAST_OUTPUT_CHARS);
-        session.updated_at = now_rfc3339();
+    state.with_session_output(session_id, |buffer| push_redacted(state, buffer, chunk));
+    state.touch_session(session_id);
+}
+
+/// Appends redacted PTY output to the scrollback and the active recording.
+fn record_pty_output(state: &AppState, session_id: &str, chunk: &str) {
+    state.with_pty_scrollback(session_id, |buffer| push_redacted(state, buffer, chunk));
+    state.with_pty_recording(session_id, |recorder| {
+        recorder.record_redacted_output(chunk, REDACTION_OVERLAP_BYTES, |text| {
+            state.storage.redact_output(text)
+        })
     });
 }
 
+fn push_redacted(state: &AppState, buffer: &mut ScrollbackBuffer, chunk: &str) {
+    // PTY chunks can split a secret, so redaction re-scans a short tail of
+    // the buffered output together with the new chunk.
+    let mut tail = buffer.take_tail(REDACTION_OVERLAP_BYTES);
+    tail.push_str(chunk);
+    buffer.push_str(&state.storage.redact_output(&tail));
+}
+
 fn append_server_ops_debug_log(
     state: &AppState,
     event: &str,
@@ -1153,7 +4557,7 @@ fn append_server_ops_debug_log(
         now_rfc3339(),
         event,
         session_id,
-        detail.as_ref()
+        redact_secrets(detail.as_ref())
     );
 
     if let Some(parent) = path.parent() {
@@ -1164,26 +4568,6 @@ fn append_server_ops_debug_log(
     }
 }
 
-fn trim_to_last_chars(value: &mut String, max_chars: usize) {
-    if max_chars == 0 {
-        value.clear();
-        return;
-    }
-
-    let total_chars = value.chars().count();
-    if total_chars <= max_chars {
-        return;
-    }
-
-    let drop_chars = total_chars - max_chars;
-    let drop_bytes = value
-        .char_indices()
-        .nth(drop_chars)
-        .map(|(index, _)| index)
-        .unwrap_or(0);
-    value.drain(..drop_bytes);
-}
-
 #[derive(Debug, Default, PartialEq, Eq)]
 struct PtyCommandBatch {
     input: Vec<u8>,
@@ -1192,41 +4576,52 @@ struct PtyCommandBatch {
     drained_messages: usize,
 }
 
+impl PtyCommandBatch {
+    fn push(&mut self, command: PtyCommand) {
+        self.drained_messages += 1;
+        match command {
+            PtyCommand::Input(data) => self.input.extend_from_slice(data.as_bytes()),
+            PtyCommand::Resize { cols, rows } => self.latest_resize = Some((cols, rows)),
+            PtyCommand::Signal(signal) => self.input.push(signal.control_byte()),
+            PtyCommand::Close => self.close_requested = true,
+        }
+    }
+
+    /// Drains queued commands until `max_messages` are batched or a close is seen.
+    fn drain_from(&mut self, rx: &mpsc::Receiver<PtyCommand>, max_messages: usize) {
+        let max_messages = max_messages.max(1);
+        while !self.close_requested && self.drained_messages < max_messages {
+            match rx.try_recv() {
+                Ok(command) => self.push(command),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.close_requested = true;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Batches `first` (a command that woke the idle worker) ahead of the queued ones.
 fn drain_pty_command_batch(
+    first: Option<PtyCommand>,
     rx: &mpsc::Receiver<PtyCommand>,
     max_messages: usize,
 ) -> PtyCommandBatch {
     let mut batch = PtyCommandBatch::default();
-    let max_messages = max_messages.max(1);
-
-    while batch.drained_messages < max_messages {
-        match rx.try_recv() {
-            Ok(PtyCommand::Input(data)) => {
-                batch.drained_messages += 1;
-                if !data.is_empty() {
-                    batch.input.extend_from_slice(data.as_bytes());
-                }
-            }
-            Ok(PtyCommand::Resize { cols, rows }) => {
-                batch.drained_messages += 1;
-                batch.latest_resize = Some((cols, rows));
-            }
-            Ok(PtyCommand::Close) => {
-                batch.drained_messages += 1;
-                batch.close_requested = true;
-                break;
-            }
-            Err(mpsc::TryRecvError::Empty) => break,
-            Err(mpsc::TryRecvError::Disconnected) => {
-                batch.close_requested = true;
-                break;
-            }
-        }
+    if let Some(command) = first {
+        batch.push(command);
     }
-
+    batch.drain_from(rx, max_messages);
     batch
 }
 
+/// Doubles the idle wait up to `PTY_IDLE_MAX_WAIT`.
+fn next_pty_idle_wait(current: Duration) -> Duration {
+    (current * 2).min(PTY_IDLE_MAX_WAIT)
+}
+
 fn compact_pending_input(pending_input: &mut Vec<u8>, pending_offset: &mut usize) {
     if *pending_offset == 0 {
         return;
@@ -1284,6 +4679,30 @@ fn pump_channel_input(
     Ok(written_total)
 }
 
+/// Writes the startup lines in full; a shared session may already be non-blocking.
+fn write_pty_startup_input(channel: &mut ssh2::Channel, bytes: &[u8]) -> AppResult<()> {
+    let deadline = Instant::now() + PTY_CHANNEL_OPEN_TIMEOUT;
+    let mut offset = 0;
+    while offset < bytes.len() {
+        match channel.write(&bytes[offset..]) {
+            Ok(written) => offset += written,
+            Err(err) if is_transient_pty_io_error(&err) && Instant::now() < deadline => {
+                thread::sleep(PTY_IDLE_MAX_WAIT);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    loop {
+        match channel.flush() {
+            Ok(()) => return Ok(()),
+            Err(err) if is_transient_pty_io_error(&err) && Instant::now() < deadline => {
+                thread::sleep(PTY_IDLE_MAX_WAIT);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
 fn is_transient_pty_io_error(err: &std::io::Error) -> bool {
     if matches!(
         err.kind(),
@@ -1309,7 +4728,10 @@ fn connect_with_cancellation(
     config: &SshConfig,
     cancellation: Option<(&AppState, &str)>,
 ) -> AppResult<Session> {
-    let tcp = connect_tcp_with_cancellation(config, cancellation)?;
+    let tcp = match config.jump_host.as_ref() {
+        Some(jump_host) => connect_through_jump_host(state, config, jump_host, cancellation)?,
+        None => connect_tcp_with_cancellation(config, cancellation)?,
+    };
     tcp.set_read_timeout(Some(std::time::Duration::from_secs(20)))?;
     tcp.set_write_timeout(Some(std::time::Duration::from_secs(20)))?;
 
@@ -1325,8 +4747,8 @@ fn connect_with_cancellation(
     authenticate_session(config, &mut session)?;
 
     if !session.authenticated() {
-        return Err(AppError::Runtime(format!(
-            "authentication failed for {}@{}:{}",
+        return Err(AppError::AuthFailed(format!(
+            "{}@{}:{}",
             config.username, config.host, config.port
         )));
     }
@@ -1334,11 +4756,406 @@ fn connect_with_cancellation(
     Ok(session)
 }
 
-fn verify_host_key_trust(
+/// Opens a TCP stream to `config` that is tunnelled through its jump host.
+///
+/// libssh2 can only run a session over a real socket, so the bastion's
+/// `direct-tcpip` channel is bridged to a loopback connection by a relay thread
+/// that lives as long as the target session keeps the stream open.
+fn connect_through_jump_host(
     state: &AppState,
     config: &SshConfig,
-    session: &Session,
-) -> AppResult<()> {
+    jump_host: &SshJumpHost,
+    cancellation: Option<(&AppState, &str)>,
+) -> AppResult<TcpStream> {
+    let bastion_config = SshConfig {
+        id: format!("{}:jump-host", config.id),
+        name: format!("{} (jump host)", config.name),
+        host: jump_host.host.clone(),
+        port: jump_host.port,
+        username: jump_host.username.clone(),
+        auth_type: jump_host.auth_type,
+        password: jump_host.password.clone(),
+        private_key_path: jump_host.private_key_path.clone(),
+        private_key_passphrase: jump_host.private_key_passphrase.clone(),
+        use_password_fallback: false,
+        sudo_tty_fallback: false,
+        sudo_password: String::new(),
+        jump_host: None,
+        description: String::new(),
+        created_at: config.created_at.clone(),
+        updated_at: config.updated_at.clone(),
+    };
+    let bastion = connect_with_cancellation(state, &bastion_config, cancellation)
+        .map_err(|err| map_jump_host_error(&bastion_config, err))?;
+    check_shell_connection_cancelled(cancellation)?;
+
+    let channel = bastion
+        .channel_direct_tcpip(&config.host, config.port, None)
+        .map_err(|err| {
+            AppError::Runtime(format!(
+                "jump host {}:{} could not open a tunnel to {}:{}: {err}",
+                jump_host.host, jump_host.port, config.host, config.port
+            ))
+        })?;
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let stream = TcpStream::connect(listener.local_addr()?)?;
+    let (relay_stream, peer) = listener.accept()?;
+    // Only bridge our own connection, never another local process racing for the port.
+    if peer != stream.local_addr()? {
+        return Err(AppError::Runtime(
+            "unexpected connection on jump host relay".to_string(),
+        ));
+    }
+    thread::spawn(move || run_jump_host_relay(bastion, channel, relay_stream));
+    Ok(stream)
+}
+
+/// Keeps host key trust challenges intact and labels everything else as a bastion failure.
+fn map_jump_host_error(bastion: &SshConfig, err: AppError) -> AppError {
+    let message = err.to_string();
+    if message.contains(SSH_HOST_KEY_TRUST_REQUIRED_PREFIX) {
+        return err;
+    }
+    let detail = format!(
+        "jump host {}@{}:{} failed: {message}",
+        bastion.username, bastion.host, bastion.port
+    );
+    // Keep the failure class so the UI can still branch on auth vs network errors.
+    match err {
+        AppError::AuthFailed(_) => AppError::AuthFailed(detail),
+        AppError::ConnectionRefused(_) => AppError::ConnectionRefused(detail),
+        AppError::HostUnreachable(_) => AppError::HostUnreachable(detail),
+        _ => AppError::Runtime(detail),
+    }
+}
+
+fn run_jump_host_relay(bastion: Session, channel: ssh2::Channel, local: TcpStream) {
+    if local.set_nonblocking(true).is_err() {
+        return;
+    }
+    bastion.set_blocking(false);
+    let mut relay = TcpChannelRelay::new(local, channel);
+    let mut buffer = [0_u8; 16_384];
+    let mut idle_wait = RELAY_IDLE_MIN_WAIT;
+    loop {
+        match relay.step(&mut buffer) {
+            RelayStep::Moved => idle_wait = RELAY_IDLE_MIN_WAIT,
+            RelayStep::Idle => {
+                thread::sleep(idle_wait);
+                idle_wait = (idle_wait * 2).min(RELAY_IDLE_MAX_WAIT);
+            }
+            RelayStep::Closed => break,
+        }
+    }
+    relay.close();
+    bastion.set_blocking(true);
+}
+
+enum RelayStep {
+    Moved,
+    Idle,
+    Closed,
+}
+
+/// Relays one TCP connection through an SSH channel, both ends non-blocking.
+///
+/// Bytes the other end cannot take yet stay queued (one buffer per direction at
+/// most) and are retried on the next step, so a slow peer never stalls the loop
+/// driving the relay. A local half-close is passed on as channel EOF while the
+/// reply keeps flowing back until the channel reaches EOF itself.
+struct TcpChannelRelay {
+    local: TcpStream,
+    channel: ssh2::Channel,
+    to_channel: Vec<u8>,
+    to_local: Vec<u8>,
+    local_eof: bool,
+    eof_sent: bool,
+}
+
+impl TcpChannelRelay {
+    fn new(local: TcpStream, channel: ssh2::Channel) -> Self {
+        Self {
+            local,
+            channel,
+            to_channel: Vec::new(),
+            to_local: Vec::new(),
+            local_eof: false,
+            eof_sent: false,
+        }
+    }
+
+    /// Moves at most one buffer in each direction.
+    fn step(&mut self, buffer: &mut [u8]) -> RelayStep {
+        let mut moved = false;
+        if !self.local_eof && self.to_channel.is_empty() {
+            match self.local.read(buffer) {
+                Ok(0) => self.local_eof = true,
+                Ok(size) => {
+                    self.to_channel.extend_from_slice(&buffer[..size]);
+                    moved = true;
+                }
+                Err(err) if is_transient_pty_io_error(&err) => {}
+                Err(_) => return RelayStep::Closed,
+            }
+        }
+        match write_pending(&mut self.channel, &mut self.to_channel) {
+            Ok(written) => moved |= written > 0,
+            Err(_) => return RelayStep::Closed,
+        }
+        if self.local_eof && self.to_channel.is_empty() && !self.eof_sent {
+            match self.channel.send_eof() {
+                Ok(()) => {
+                    self.eof_sent = true;
+                    moved = true;
+                }
+                Err(err) if is_would_block_ssh_error(&err) => {}
+                Err(_) => return RelayStep::Closed,
+            }
+        }
+
+        if self.to_local.is_empty() {
+            match self.channel.read(buffer) {
+                Ok(0) if self.channel.eof() => return RelayStep::Closed,
+                Ok(0) => {}
+                Ok(size) => {
+                    self.to_local.extend_from_slice(&buffer[..size]);
+                    moved = true;
+                }
+                Err(err) if is_transient_pty_io_error(&err) => {}
+                Err(_) => return RelayStep::Closed,
+            }
+        }
+        match write_pending(&mut self.local, &mut self.to_local) {
+            Ok(written) => moved |= written > 0,
+            Err(_) => return RelayStep::Closed,
+        }
+
+        if moved {
+            RelayStep::Moved
+        } else {
+            RelayStep::Idle
+        }
+    }
+
+    fn close(mut self) {
+        let _ = self.local.shutdown(std::net::Shutdown::Both);
+        let _ = self.channel.close();
+    }
+}
+
+/// Writes as much of `pending` as `writer` takes without blocking and drops it
+/// from the queue; returns how many bytes went out.
+fn write_pending(writer: &mut impl Write, pending: &mut Vec<u8>) -> std::io::Result<usize> {
+    let mut written = 0;
+    while written < pending.len() {
+        match writer.write(&pending[written..]) {
+            Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+            Ok(size) => written += size,
+            Err(err) if is_transient_pty_io_error(&err) => break,
+            Err(err) => return Err(err),
+        }
+    }
+    pending.drain(..written);
+    Ok(written)
+}
+
+/// Runs the connection steps one by one and reports each outcome.
+///
+/// Steps: DNS resolution, TCP connect, SSH banner, key exchange, host key trust
+/// and authentication. The report stops at the first failed step; nothing is
+/// executed on the remote host.
+pub fn diagnose_connection(
+    state: &AppState,
+    config_id: &str,
+) -> AppResult<ConnectionDiagnosticsReport> {
+    let config = state.storage.find_ssh_config(config_id)?;
+    let mut steps = Vec::new();
+    let success = run_connection_diagnostics(state, &config, &mut steps).is_some();
+    Ok(ConnectionDiagnosticsReport {
+        config_id: config.id,
+        host: config.host,
+        port: config.port,
+        success,
+        steps,
+        finished_at: now_rfc3339(),
+    })
+}
+
+/// Connects with an unsaved profile, runs `echo ok` and disconnects.
+///
+/// Nothing is persisted and no shell session is created. Handshake time covers
+/// TCP connect, key exchange, host key check and authentication.
+pub fn test_ssh_connection(
+    state: &AppState,
+    input: SshConfigInput,
+) -> AppResult<SshConnectionTestResult> {
+    let now = now_rfc3339();
+    let config = SshConfig {
+        id: input.id.unwrap_or_default(),
+        name: input.name.trim().to_string(),
+        host: input.host.trim().to_string(),
+        port: input.port,
+        username: input.username.trim().to_string(),
+        auth_type: input.auth_type,
+        password: input.password,
+        private_key_path: input.private_key_path.trim().to_string(),
+        private_key_passphrase: input.private_key_passphrase,
+        use_password_fallback: input.use_password_fallback,
+        sudo_tty_fallback: input.sudo_tty_fallback,
+        sudo_password: input.sudo_password,
+        jump_host: input.jump_host,
+        description: input.description.unwrap_or_default(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    if config.host.is_empty() || config.username.is_empty() || config.port == 0 {
+        return Err(AppError::Validation(
+            "host, port and username are required to test a connection".to_string(),
+        ));
+    }
+
+    let started = Instant::now();
+    let session = connect(state, &config)?;
+    let handshake_ms = started.elapsed().as_millis();
+
+    let started = Instant::now();
+    let (stdout, stderr, status) = run_channel_command(&session, "echo ok")?;
+    let command_ms = started.elapsed().as_millis();
+    let handshake = read_handshake_info(&session);
+    let _ = session.disconnect(None, "connection test finished", None);
+    if status != 0 || stdout.trim() != "ok" {
+        return Err(AppError::Runtime(format!(
+            "test command failed on {}:{} (exit {status}): {}",
+            config.host,
+            config.port,
+            stderr.trim()
+        )));
+    }
+
+    Ok(SshConnectionTestResult {
+        host: config.host,
+        port: config.port,
+        handshake_ms,
+        command_ms,
+        handshake,
+    })
+}
+
+/// Reads the server banner and the algorithms chosen during key exchange.
+fn read_handshake_info(session: &Session) -> SshHandshakeInfo {
+    let method = |kind| session.methods(kind).map(str::to_string);
+    SshHandshakeInfo {
+        server_banner: session.banner().map(|banner| banner.trim().to_string()),
+        host_key_type: method(MethodType::HostKey),
+        kex_algorithm: method(MethodType::Kex),
+        cipher: method(MethodType::CryptCs),
+    }
+}
+
+fn run_connection_diagnostics(
+    state: &AppState,
+    config: &SshConfig,
+    steps: &mut Vec<ConnectionDiagnosticStep>,
+) -> Option<()> {
+    run_diagnostic_step(steps, "dns", || {
+        let addresses = (config.host.as_str(), config.port)
+            .to_socket_addrs()?
+            .map(|address| address.ip().to_string())
+            .collect::<Vec<_>>();
+        if addresses.is_empty() {
+            return Err(AppError::Runtime(format!(
+                "no addresses resolved for {}",
+                config.host
+            )));
+        }
+        Ok(((), format!("resolved {}", addresses.join(", "))))
+    })?;
+
+    let tcp = run_diagnostic_step(steps, "tcp", || {
+        let tcp = connect_tcp_with_cancellation(config, None)?;
+        let peer = tcp.peer_addr()?;
+        Ok((tcp, format!("connected to {peer}")))
+    })?;
+
+    // The banner is read on a separate probe connection: consuming it from `tcp`
+    // would break the libssh2 handshake below.
+    run_diagnostic_step(steps, "banner", || {
+        let mut probe = connect_tcp_with_cancellation(config, None)?;
+        probe.set_read_timeout(Some(Duration::from_secs(10)))?;
+        let mut banner = Vec::new();
+        let mut byte = [0_u8; 1];
+        while banner.len() < 255 && probe.read(&mut byte)? == 1 && byte[0] != b'\n' {
+            banner.push(byte[0]);
+        }
+        let banner = String::from_utf8_lossy(&banner).trim().to_string();
+        if !banner.starts_with("SSH-") {
+            return Err(AppError::Runtime(format!(
+                "unexpected server banner `{banner}`; the port may not be an SSH server"
+            )));
+        }
+        Ok(((), banner))
+    })?;
+
+    let mut session = run_diagnostic_step(steps, "kex", || {
+        tcp.set_read_timeout(Some(Duration::from_secs(20)))?;
+        tcp.set_write_timeout(Some(Duration::from_secs(20)))?;
+        let mut session = Session::new()?;
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|err| map_handshake_error(config, err))?;
+        let method = |kind| session.methods(kind).unwrap_or("-").to_string();
+        let detail = format!(
+            "kex={} hostkey={} cipher={} mac={}",
+            method(MethodType::Kex),
+            method(MethodType::HostKey),
+            method(MethodType::CryptCs),
+            method(MethodType::MacCs)
+        );
+        Ok((session, detail))
+    })?;
+
+    run_diagnostic_step(steps, "host_key", || {
+        verify_host_key_trust(state, config, &session)?;
+        let fingerprint = extract_host_key_fingerprint(&session)
+            .map(|key| format!("{} {}", key.key_type, key.fingerprint))
+            .unwrap_or_default();
+        Ok(((), format!("trusted {fingerprint}")))
+    })?;
+
+    run_diagnostic_step(steps, "auth", || {
+        authenticate_session(config, &mut session)?;
+        if !session.authenticated() {
+            return Err(AppError::AuthFailed(format!(
+                "{}@{}:{}",
+                config.username, config.host, config.port
+            )));
+        }
+        Ok(((), format!("authenticated as {}", config.username)))
+    })
+}
+
+fn run_diagnostic_step<T>(
+    steps: &mut Vec<ConnectionDiagnosticStep>,
+    name: &str,
+    step: impl FnOnce() -> AppResult<(T, String)>,
+) -> Option<T> {
+    let started = Instant::now();
+    let (value, passed, detail) = match step() {
+        Ok((value, detail)) => (Some(value), true, detail),
+        Err(err) => (None, false, err.to_string()),
+    };
+    steps.push(ConnectionDiagnosticStep {
+        name: name.to_string(),
+        passed,
+        detail,
+        duration_ms: started.elapsed().as_millis(),
+    });
+    value
+}
+
+fn verify_host_key_trust(state: &AppState, config: &SshConfig, session: &Session) -> AppResult<()> {
     let host_key = extract_host_key_fingerprint(session).ok_or_else(|| {
         AppError::Runtime(format!(
             "SSH host key is unavailable for {}:{}",
@@ -1415,8 +5232,17 @@ fn authenticate_with_password(config: &SshConfig, session: &mut Session) -> AppR
             "password cannot be empty for password authentication".to_string(),
         ));
     }
-    session.userauth_password(&config.username, &config.password)?;
-    Ok(())
+    session
+        .userauth_password(&config.username, &config.password)
+        .map_err(|err| {
+            AppError::AuthFailed(format!(
+                "{}@{}:{}: {}",
+                config.username,
+                config.host,
+                config.port,
+                err.message()
+            ))
+        })
 }
 
 struct HostKeyFingerprint {
@@ -1452,8 +5278,8 @@ fn host_key_trust_required(challenge: SshHostKeyTrustChallenge) -> AppError {
 
 fn map_key_auth_error(config: &SshConfig, err: AppError) -> AppError {
     let detail = err.to_string();
-    AppError::Runtime(format!(
-        "private key authentication failed for {}@{}:{}: {detail}. Check the private key path and passphrase.",
+    AppError::AuthFailed(format!(
+        "private key rejected for {}@{}:{}: {detail}. Check the private key path and passphrase.",
         config.username, config.host, config.port
     ))
 }
@@ -1463,10 +5289,16 @@ fn connect_tcp_with_cancellation(
     cancellation: Option<(&AppState, &str)>,
 ) -> AppResult<TcpStream> {
     let addresses = (config.host.as_str(), config.port)
-        .to_socket_addrs()?
+        .to_socket_addrs()
+        .map_err(|err| {
+            AppError::HostUnreachable(format!(
+                "could not resolve {}:{}: {err}",
+                config.host, config.port
+            ))
+        })?
         .collect::<Vec<SocketAddr>>();
     if addresses.is_empty() {
-        return Err(AppError::Runtime(format!(
+        return Err(AppError::HostUnreachable(format!(
             "no socket addresses resolved for {}:{}",
             config.host, config.port
         )));
@@ -1489,22 +5321,37 @@ fn connect_tcp_with_cancellation(
                 Err(err) if is_retryable_connect_error(&err) => {
                     last_error = Some(err);
                 }
-                Err(err) => return Err(AppError::Io(err)),
+                Err(err) => return Err(map_tcp_connect_error(config, err)),
             }
         }
 
         thread::sleep(SSH_CONNECT_POLL_INTERVAL);
     }
 
-    Err(AppError::Io(last_error.unwrap_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::TimedOut,
-            format!(
-                "connection attempt timed out for {}:{}",
-                config.host, config.port
-            ),
-        )
-    })))
+    Err(map_tcp_connect_error(
+        config,
+        last_error.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!(
+                    "connection attempt timed out for {}:{}",
+                    config.host, config.port
+                ),
+            )
+        }),
+    ))
+}
+
+/// Classifies socket errors so the UI can tell a closed port from a dead host.
+fn map_tcp_connect_error(config: &SshConfig, err: std::io::Error) -> AppError {
+    let target = format!("{}:{} ({err})", config.host, config.port);
+    match err.kind() {
+        std::io::ErrorKind::ConnectionRefused => AppError::ConnectionRefused(target),
+        std::io::ErrorKind::HostUnreachable
+        | std::io::ErrorKind::NetworkUnreachable
+        | std::io::ErrorKind::TimedOut => AppError::HostUnreachable(target),
+        _ => AppError::Io(err),
+    }
 }
 
 fn check_shell_connection_cancelled(cancellation: Option<(&AppState, &str)>) -> AppResult<()> {
@@ -1550,28 +5397,249 @@ fn map_handshake_error(config: &SshConfig, err: ssh2::Error) -> AppError {
 }
 
 fn run_channel_command(session: &Session, command: &str) -> AppResult<(String, String, i32)> {
+    let output =
+        run_channel_command_capped(session, command, DEFAULT_COMMAND_OUTPUT_MAX_BYTES, None)?;
+    Ok((output.stdout, output.stderr, output.exit_code))
+}
+
+struct ChannelCommandOutput {
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+    truncated: bool,
+}
+
+/// Runs one exec command, keeping at most `max_bytes` of each output stream.
+///
+/// `stdin` is written and then closed before any output is read. Once a stream
+/// overflows the channel is closed so the remote command stops producing output;
+/// the exit code is then whatever the server reported, if anything.
+fn run_channel_command_capped(
+    session: &Session,
+    command: &str,
+    max_bytes: usize,
+    stdin: Option<&str>,
+) -> AppResult<ChannelCommandOutput> {
     let mut channel = session.channel_session()?;
     channel.exec(command)?;
+    if let Some(input) = stdin {
+        channel.write_all(input.as_bytes())?;
+        channel.send_eof()?;
+    }
 
-    let mut stdout = Vec::new();
-    channel.read_to_end(&mut stdout)?;
-
-    let mut stderr = Vec::new();
-    channel.stderr().read_to_end(&mut stderr)?;
+    let (stdout, stdout_truncated) = read_capped(&mut channel, max_bytes)?;
+    if stdout_truncated {
+        let _ = channel.close();
+    }
+    let (stderr, stderr_truncated) = read_capped(&mut channel.stderr(), max_bytes)?;
+    if stderr_truncated && !stdout_truncated {
+        let _ = channel.close();
+    }
 
     channel.wait_close()?;
     let exit_code = channel.exit_status()?;
 
-    Ok((
-        String::from_utf8_lossy(&stdout).to_string(),
-        String::from_utf8_lossy(&stderr).to_string(),
+    Ok(ChannelCommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
         exit_code,
-    ))
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+/// Reads to the end or until `max_bytes`; the flag tells whether more was available.
+fn read_capped<R: Read>(reader: &mut R, max_bytes: usize) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut bytes = Vec::new();
+    reader.take(max_bytes as u64 + 1).read_to_end(&mut bytes)?;
+    let truncated = bytes.len() > max_bytes;
+    bytes.truncate(max_bytes);
+    Ok((bytes, truncated))
+}
+
+/// Matches sudo's refusal to run without a terminal (`Defaults requiretty`).
+fn is_sudo_tty_required(stderr: &str) -> bool {
+    let lower = stderr.to_ascii_lowercase();
+    lower.contains("you must have a tty to run sudo") || lower.contains("a terminal is required")
+}
+
+/// Re-runs a command on a PTY-backed exec channel, answering one sudo password prompt.
+///
+/// sudo is given a one-off nonce prompt through `SUDO_PROMPT`, and only that exact
+/// prompt is answered, so a password prompt of the command itself (`mysql -p`,
+/// `passwd`, ...) never receives the sudo password. The nonce is removed from the output.
+///
+/// The PTY merges stderr into stdout and emits CRLF line endings, so only the
+/// combined (newline-normalized) output is returned alongside the exit code and
+/// whether it was cut off at `max_bytes`. A second prompt (wrong password), a
+/// prompt with no password configured, or a run past `SUDO_TTY_FALLBACK_TIMEOUT`
+/// is an error instead of a hang.
+fn run_channel_command_with_pty(
+    session: &Session,
+    command: &str,
+    sudo_password: Option<&str>,
+    max_bytes: usize,
+) -> AppResult<(String, i32, bool)> {
+    let prompt = sudo_tty_prompt();
+    let command = format!("export SUDO_PROMPT={}; {command}", shell_quote(&prompt));
+    let mut channel = session.channel_session()?;
+    channel.request_pty(
+        "xterm-256color",
+        None,
+        Some((
+            u32::from(DEFAULT_PTY_COLS),
+            u32::from(DEFAULT_PTY_ROWS),
+            0,
+            0,
+        )),
+    )?;
+    channel.exec(&command)?;
+
+    let deadline = Instant::now() + SUDO_TTY_FALLBACK_TIMEOUT;
+    let mut output = Vec::new();
+    // Output offset right after the password was sent; prompts are looked for past it.
+    let mut prompt_scan_from = 0usize;
+    let mut password_sent = false;
+    let mut truncated = false;
+    let mut buf = [0u8; 4096];
+    let result = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break Err(AppError::Runtime(format!(
+                "sudo tty fallback timed out after {}s",
+                SUDO_TTY_FALLBACK_TIMEOUT.as_secs()
+            )));
+        }
+        // Bounds each blocking read so a silent remote cannot stall past the deadline.
+        session.set_timeout(remaining.as_millis().clamp(1, u128::from(u32::MAX)) as u32);
+        let read = match channel.read(&mut buf) {
+            Ok(read) => read,
+            Err(_) if Instant::now() >= deadline => continue,
+            Err(err) => break Err(err.into()),
+        };
+        if read == 0 {
+            break Ok(());
+        }
+        output.extend_from_slice(&buf[..read]);
+        if output.len() > max_bytes {
+            output.truncate(max_bytes);
+            truncated = true;
+            let _ = channel.close();
+            break Ok(());
+        }
+        if ends_with_sudo_prompt(&output[prompt_scan_from..], &prompt) {
+            match sudo_password {
+                _ if password_sent => {
+                    break Err(AppError::AuthFailed(
+                        "sudo rejected the configured sudo password".to_string(),
+                    ))
+                }
+                None => {
+                    break Err(AppError::Validation(
+                        "sudo asked for a password but no sudo password is configured".to_string(),
+                    ))
+                }
+                Some(password) => {
+                    channel.write_all(format!("{password}\n").as_bytes())?;
+                    channel.flush()?;
+                    password_sent = true;
+                    prompt_scan_from = output.len();
+                }
+            }
+        }
+    };
+    session.set_timeout(0);
+    if let Err(err) = result {
+        let _ = channel.close();
+        return Err(err);
+    }
+
+    channel.wait_close()?;
+    let exit_code = channel.exit_status()?;
+    let text = String::from_utf8_lossy(&output)
+        .replace(&prompt, "")
+        .replace("\r\n", "\n");
+    Ok((text, exit_code, truncated))
+}
+
+/// A prompt no program other than this sudo run will print.
+fn sudo_tty_prompt() -> String {
+    format!("[eshell-sudo-{}]:", Uuid::new_v4().simple())
+}
+
+fn ends_with_sudo_prompt(output: &[u8], prompt: &str) -> bool {
+    String::from_utf8_lossy(output).trim_end().ends_with(prompt)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::tests::{session_fixture, test_state};
+    use crate::state::SESSION_LAST_OUTPUT_BYTES;
+
+    #[test]
+    fn is_valid_service_name_rejects_option_like_names() {
+        assert!(is_valid_service_name("nginx"));
+        assert!(is_valid_service_name("getty@tty1.service"));
+        assert!(!is_valid_service_name(""));
+        assert!(!is_valid_service_name("--now"));
+        assert!(!is_valid_service_name("-H"));
+        assert!(!is_valid_service_name("nginx; reboot"));
+    }
+
+    #[test]
+    fn wrap_bracketed_paste_only_wraps_pastes() {
+        assert_eq!(wrap_bracketed_paste("ls -la\r", true), "ls -la\r");
+        assert_eq!(
+            wrap_bracketed_paste("echo a\necho b\n", false),
+            "echo a\necho b\n"
+        );
+        assert_eq!(
+            wrap_bracketed_paste("echo a\necho b\n", true),
+            "\x1b[200~echo a\necho b\n\x1b[201~"
+        );
+        assert_eq!(
+            wrap_bracketed_paste("a\x1b[201~\nb", true),
+            "\x1b[200~a\nb\x1b[201~"
+        );
+        assert_eq!(
+            wrap_bracketed_paste("\x1b[200~a\nb\x1b[201~", true),
+            "\x1b[200~a\nb\x1b[201~"
+        );
+    }
+
+    #[test]
+    fn bracketed_paste_mode_change_uses_last_switch() {
+        assert_eq!(bracketed_paste_mode_change("plain output"), None);
+        assert_eq!(bracketed_paste_mode_change("\x1b[?2004h$ "), Some(true));
+        assert_eq!(
+            bracketed_paste_mode_change("\x1b[?2004h$ vim\r\n\x1b[?2004l"),
+            Some(false)
+        );
+        assert_eq!(
+            bracketed_paste_mode_change("\x1b[?2004l\r\n\x1b[?2004h$ "),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn top_processes_command_follows_sort_and_limit() {
+        assert_eq!(
+            top_processes_command(RemoteOsFamily::Linux, ProcessSort::Mem, 10),
+            "ps -eo pid,user,pcpu,pmem,comm --sort=-pmem | head -n 11"
+        );
+        assert_eq!(
+            top_processes_command(
+                RemoteOsFamily::Linux,
+                ProcessSort::Cpu,
+                STATUS_PROCESS_DEFAULT_LIMIT
+            ),
+            "ps -eo pid,user,pcpu,pmem,comm --sort=-pcpu | head -n 5"
+        );
+        assert_eq!(
+            top_processes_command(RemoteOsFamily::Darwin, ProcessSort::Mem, 3),
+            "ps -Ao pid,user,pcpu,pmem,comm -m | head -n 4"
+        );
+    }
 
     #[test]
     fn drain_pty_command_batch_respects_limit_and_keeps_order() {
@@ -1586,13 +5654,13 @@ mod tests {
         tx.send(PtyCommand::Input("bb".to_string()))
             .expect("send input");
 
-        let first = drain_pty_command_batch(&rx, 2);
+        let first = drain_pty_command_batch(None, &rx, 2);
         assert_eq!(first.drained_messages, 2);
         assert_eq!(first.input, b"aa");
         assert_eq!(first.latest_resize, Some((120, 40)));
         assert!(!first.close_requested);
 
-        let second = drain_pty_command_batch(&rx, 2);
+        let second = drain_pty_command_batch(None, &rx, 2);
         assert_eq!(second.drained_messages, 1);
         assert_eq!(second.input, b"bb");
         assert_eq!(second.latest_resize, None);
@@ -1608,12 +5676,49 @@ mod tests {
         tx.send(PtyCommand::Input("after".to_string()))
             .expect("send input");
 
-        let batch = drain_pty_command_batch(&rx, 10);
+        let batch = drain_pty_command_batch(None, &rx, 10);
         assert!(batch.close_requested);
         assert_eq!(batch.input, b"before");
         assert_eq!(batch.drained_messages, 2);
     }
 
+    #[test]
+    fn drain_pty_command_batch_writes_signals_in_order() {
+        let (tx, rx) = mpsc::channel::<PtyCommand>();
+        tx.send(PtyCommand::Input("sleep 60\n".to_string()))
+            .expect("send input");
+        tx.send(PtyCommand::Signal(PtySignal::Interrupt))
+            .expect("send signal");
+        tx.send(PtyCommand::Signal(PtySignal::Eof))
+            .expect("send signal");
+
+        let batch = drain_pty_command_batch(None, &rx, 10);
+        assert_eq!(batch.input, b"sleep 60\n\x03\x04");
+        assert_eq!(batch.drained_messages, 3);
+    }
+
+    #[test]
+    fn drain_pty_command_batch_puts_the_waking_command_first() {
+        let (tx, rx) = mpsc::channel::<PtyCommand>();
+        tx.send(PtyCommand::Input("b".to_string()))
+            .expect("send input");
+
+        let batch = drain_pty_command_batch(Some(PtyCommand::Input("a".to_string())), &rx, 10);
+        assert_eq!(batch.input, b"ab");
+        assert_eq!(batch.drained_messages, 2);
+    }
+
+    #[test]
+    fn pty_idle_wait_backs_off_to_the_cap() {
+        let mut wait = PTY_IDLE_MIN_WAIT;
+        let mut waits = Vec::new();
+        for _ in 0..8 {
+            waits.push(wait.as_millis());
+            wait = next_pty_idle_wait(wait);
+        }
+        assert_eq!(waits, vec![1, 2, 4, 8, 16, 32, 50, 50]);
+    }
+
     #[test]
     fn compact_pending_input_drops_consumed_prefix_when_large_enough() {
         let mut pending = vec![b'x'; 10_000];
@@ -1626,6 +5731,617 @@ mod tests {
         assert_eq!(offset, 0);
     }
 
+    #[test]
+    fn quote_cd_target_keeps_paths_literal() {
+        assert_eq!(quote_cd_target("/var/log").expect("plain"), "'/var/log'");
+        assert_eq!(quote_cd_target("\"my dir\"").expect("double"), "'my dir'");
+        assert_eq!(quote_cd_target("my\\ dir").expect("escaped"), "'my dir'");
+        assert_eq!(quote_cd_target("\"it's\"").expect("quote"), "'it'\"'\"'s'");
+        assert_eq!(quote_cd_target("'$(id)'").expect("literal"), "'$(id)'");
+        assert_eq!(quote_cd_target("dir#1").expect("hash"), "'dir#1'");
+
+        for injected in [
+            "$(rm -rf /)",
+            "`id`",
+            "/tmp; rm -rf /",
+            "/tmp && id",
+            "\"$(id)\"",
+            "$?",
+            "my dir",
+            "'unterminated",
+        ] {
+            assert!(
+                matches!(quote_cd_target(injected), Err(AppError::Validation(_))),
+                "{injected} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_cd_target_expands_home_variables_and_previous_dir() {
+        assert_eq!(resolve_cd_target(None, None).expect("cd"), "~");
+        assert_eq!(resolve_cd_target(Some("~"), None).expect("cd ~"), "~");
+        assert_eq!(
+            resolve_cd_target(Some("~/sub"), None).expect("cd ~/sub"),
+            "~/'sub'"
+        );
+        assert_eq!(
+            resolve_cd_target(Some("~/'logs dir'"), None).expect("quoted subdir"),
+            "~/'logs dir'"
+        );
+        assert_eq!(
+            resolve_cd_target(Some("~deploy"), None).expect("user"),
+            "~deploy"
+        );
+        assert_eq!(
+            resolve_cd_target(Some("$HOME/logs"), None).expect("variable"),
+            "\"${HOME}\"'/logs'"
+        );
+        assert_eq!(
+            resolve_cd_target(Some("\"${APP_DIR}/current\""), None).expect("braced"),
+            "\"${APP_DIR}\"'/current'"
+        );
+        assert_eq!(
+            resolve_cd_target(Some("-"), Some("/var/www")).expect("cd -"),
+            "'/var/www'"
+        );
+        assert!(matches!(
+            resolve_cd_target(Some("-"), None),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn build_tar_dir_command_archives_from_the_parent() {
+        assert_eq!(
+            build_tar_dir_command("/var/log/nginx"),
+            "tar -czf - -C '/var/log' 'nginx'"
+        );
+        assert_eq!(build_tar_dir_command("/srv"), "tar -czf - -C '/' 'srv'");
+        assert_eq!(
+            build_tar_dir_command("/tmp/-rf"),
+            "tar -czf - -C '/tmp' './-rf'"
+        );
+    }
+
+    #[test]
+    fn downloaded_entry_names_must_be_plain_file_names() {
+        assert!(is_plain_local_file_name("access.log"));
+        assert!(is_plain_local_file_name("..hidden"));
+        assert!(!is_plain_local_file_name(""));
+        assert!(!is_plain_local_file_name("."));
+        assert!(!is_plain_local_file_name(".."));
+        assert!(!is_plain_local_file_name("a/b"));
+        assert!(!is_plain_local_file_name("..\\..\\evil"));
+        assert!(!is_plain_local_file_name("/etc"));
+    }
+
+    #[test]
+    fn plan_local_upload_lists_parents_before_children() {
+        let root = std::env::temp_dir().join(format!("eshell-upload-plan-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("bin/tools")).expect("create dirs");
+        std::fs::write(root.join("index.html"), "<html>").expect("write index");
+        std::fs::write(root.join("bin/run"), "#!/bin/sh").expect("write run");
+        std::fs::write(root.join("bin/tools/x"), "x").expect("write x");
+
+        let plan = plan_local_upload(&root).expect("plan");
+        let files = plan
+            .files
+            .iter()
+            .map(|file| (file.relative_path.as_str(), file.size))
+            .collect::<Vec<_>>();
+        let _ = std::fs::remove_dir_all(&root);
+
+        assert_eq!(plan.directories, vec!["bin", "bin/tools"]);
+        assert_eq!(
+            files,
+            vec![("index.html", 6), ("bin/run", 9), ("bin/tools/x", 1)]
+        );
+    }
+
+    #[test]
+    fn build_remote_find_command_quotes_every_input() {
+        assert_eq!(
+            build_remote_find_command("/var/log", Some("*.log"), None),
+            "if command -v timeout >/dev/null 2>&1; then t='timeout 30'; else t=''; fi; \
+             $t find '/var/log' -name '*.log' -print 2>/dev/null"
+        );
+        assert_eq!(
+            build_remote_find_command("/etc", None, Some("it's; rm -rf /")),
+            "if command -v timeout >/dev/null 2>&1; then t='timeout 30'; else t=''; fi; \
+             $t find '/etc' -type f -exec grep -l -s -e 'it'\"'\"'s; rm -rf /' {} + 2>/dev/null"
+        );
+        assert!(build_remote_find_command("/", Some("  "), Some(""))
+            .ends_with("$t find '/' -print 2>/dev/null"));
+    }
+
+    #[test]
+    fn remote_path_depth_counts_levels_below_root() {
+        assert_eq!(remote_path_depth("/var", "/var"), Some(0));
+        assert_eq!(remote_path_depth("/var", "/var/log"), Some(1));
+        assert_eq!(remote_path_depth("/var", "/var/log/nginx"), Some(2));
+        assert_eq!(remote_path_depth("/var", "/variable"), None);
+        assert_eq!(remote_path_depth("/", "/"), Some(0));
+        assert_eq!(remote_path_depth("/", "/usr/lib"), Some(2));
+    }
+
+    #[test]
+    fn is_unsupported_option_error_matches_busybox_and_gnu() {
+        assert!(is_unsupported_option_error(
+            "du: unrecognized option '--max-depth=1'"
+        ));
+        assert!(is_unsupported_option_error("du: invalid option -- '-'"));
+        assert!(!is_unsupported_option_error(
+            "du: cannot read directory '/root': Permission denied"
+        ));
+    }
+
+    #[test]
+    fn remote_parent_dir_handles_root_children() {
+        assert_eq!(remote_parent_dir("/etc/nginx/nginx.conf"), "/etc/nginx");
+        assert_eq!(remote_parent_dir("/etc"), "/");
+        assert_eq!(remote_parent_dir("/"), "/");
+        assert_eq!(remote_parent_dir("notes.txt"), "/");
+    }
+
+    #[test]
+    fn strip_archive_suffix_yields_a_directory_path() {
+        assert_eq!(strip_archive_suffix("/dl/logs.tar.gz"), "/dl/logs");
+        assert_eq!(strip_archive_suffix("/dl/logs.tgz"), "/dl/logs");
+        assert_eq!(strip_archive_suffix("/dl/logs"), "/dl/logs");
+        assert_eq!(strip_archive_suffix(".tgz"), ".tgz");
+    }
+
+    #[test]
+    fn pty_spec_defaults_and_clamps_like_resize() {
+        let mut input = OpenShellInput {
+            config_id: "c1".to_string(),
+            request_id: None,
+            scrollback_bytes: None,
+            encoding: None,
+            initial_dir: None,
+            initial_command: None,
+            term: None,
+            cols: None,
+            rows: None,
+            auto_reconnect: false,
+        };
+        let spec = PtySpec::from_input(&input).expect("defaults");
+        assert_eq!(spec.term, "xterm-256color");
+        assert_eq!((spec.cols, spec.rows), (DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS));
+
+        input.term = Some("screen-256color".to_string());
+        input.cols = Some(5);
+        input.rows = Some(200);
+        let spec = PtySpec::from_input(&input).expect("custom");
+        assert_eq!(spec.term, "screen-256color");
+        assert_eq!((spec.cols, spec.rows), (MIN_PTY_COLS, 200));
+
+        input.term = Some("xterm; rm -rf /".to_string());
+        assert!(matches!(
+            PtySpec::from_input(&input),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn build_pty_startup_input_quotes_dir_and_keeps_command_verbatim() {
+        assert_eq!(build_pty_startup_input(None, None), None);
+        assert_eq!(build_pty_startup_input(None, Some("  \n")), None);
+        assert_eq!(
+            build_pty_startup_input(Some("/srv/it's here"), Some("tmux attach\r\n")).as_deref(),
+            Some("cd -- '/srv/it'\"'\"'s here'\ntmux attach\n")
+        );
+        assert_eq!(
+            build_pty_startup_input(None, Some("source .venv/bin/activate")).as_deref(),
+            Some("source .venv/bin/activate\n")
+        );
+    }
+
+    #[test]
+    fn shell_quote_keeps_newlines_and_quotes_literal() {
+        assert_eq!(shell_quote("a\nb"), "'a\nb'");
+        assert_eq!(shell_quote("it's"), "'it'\"'\"'s'");
+    }
+
+    #[test]
+    fn sudo_commands_are_rewritten_to_read_stdin() {
+        assert!(is_sudo_command("sudo systemctl restart nginx"));
+        assert!(is_sudo_command("  sudo\tapt-get update"));
+        assert!(!is_sudo_command("sudoedit /etc/hosts"));
+        assert!(!is_sudo_command("echo sudo"));
+        assert_eq!(
+            sudo_stdin_command("sudo -u postgres psql -c 'select 1'"),
+            "sudo -S -v -p '' && { sudo -n -u postgres psql -c 'select 1'\n} </dev/null"
+        );
+    }
+
+    #[test]
+    fn env_export_prefix_quotes_values_and_rejects_bad_names() {
+        let mut env = BTreeMap::new();
+        assert_eq!(env_export_prefix(&env).expect("empty"), "");
+
+        env.insert("DEBIAN_FRONTEND".to_string(), "noninteractive".to_string());
+        env.insert("PATH".to_string(), "/opt/tools/bin:/usr/bin".to_string());
+        assert_eq!(
+            env_export_prefix(&env).expect("prefix"),
+            "export DEBIAN_FRONTEND='noninteractive' PATH='/opt/tools/bin:/usr/bin' && "
+        );
+
+        env.insert("1BAD".to_string(), "x".to_string());
+        assert!(matches!(
+            env_export_prefix(&env),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn read_capped_stops_at_the_limit() {
+        let mut huge = std::io::repeat(b'x').take(64 * 1024 * 1024);
+        let (bytes, truncated) = read_capped(&mut huge, 1024 * 1024).expect("read");
+        assert!(truncated);
+        assert_eq!(bytes.len(), 1024 * 1024);
+
+        let mut exact = std::io::Cursor::new(vec![b'y'; 4096]);
+        let (bytes, truncated) = read_capped(&mut exact, 4096).expect("read");
+        assert!(!truncated);
+        assert_eq!(bytes.len(), 4096);
+    }
+
+    #[test]
+    fn sudo_tty_detection_matches_requiretty_errors() {
+        assert!(is_sudo_tty_required(
+            "sudo: sorry, you must have a tty to run sudo\n"
+        ));
+        assert!(is_sudo_tty_required(
+            "sudo: a terminal is required to read the password"
+        ));
+        assert!(!is_sudo_tty_required("sudo: 1 incorrect password attempt"));
+
+        let prompt = sudo_tty_prompt();
+        assert_ne!(prompt, sudo_tty_prompt());
+        assert!(ends_with_sudo_prompt(
+            format!("line\r\n{prompt}").as_bytes(),
+            &prompt
+        ));
+        // A prompt of the command itself is never answered with the sudo password.
+        assert!(!ends_with_sudo_prompt(b"Password:", &prompt));
+        assert!(!ends_with_sudo_prompt(b"Enter password: ", &prompt));
+        assert!(!ends_with_sudo_prompt(
+            b"[sudo] password for ops: ",
+            &prompt
+        ));
+        assert!(!ends_with_sudo_prompt(
+            format!("{prompt}\r\nEnter password:").as_bytes(),
+            &prompt
+        ));
+    }
+
+    #[test]
+    fn docker_command_error_explains_common_failures() {
+        let missing = docker_command_error("bash: docker: command not found", 127);
+        assert!(missing.to_string().contains("not installed"));
+
+        let denied = docker_command_error(
+            "permission denied while trying to connect to the Docker daemon socket at unix:///var/run/docker.sock",
+            1,
+        );
+        assert!(denied.to_string().contains("`docker` group"));
+
+        let other = docker_command_error("Error: No such container: web", 1);
+        assert!(other.to_string().contains("No such container"));
+    }
+
+    #[test]
+    fn kill_process_rejects_non_positive_pid_before_queueing() {
+        let state = test_state("kill-process");
+        let input = |pid| KillProcessInput {
+            session_id: "missing".to_string(),
+            pid,
+            signal: KillSignal::default(),
+            conversation_id: None,
+        };
+
+        let err = kill_process(&state, input(0)).expect_err("pid 0 must fail");
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = kill_process(&state, input(-9)).expect_err("negative pid must fail");
+        assert!(matches!(err, AppError::Validation(_)));
+        let err = kill_process(&state, input(42)).expect_err("unknown session must fail");
+        assert!(matches!(err, AppError::NotFound(_)));
+        assert!(state.ops_agent.list_pending_actions(None, true).is_empty());
+    }
+
+    #[test]
+    fn build_script_command_quotes_env_and_args() {
+        let mut script = ScriptDefinition {
+            id: "deploy".to_string(),
+            name: "deploy".to_string(),
+            path: "/opt/deploy it.sh".to_string(),
+            command: String::new(),
+            description: String::new(),
+            parameters: Vec::new(),
+            default_args: vec!["staging".to_string()],
+            env: [
+                ("REGION".to_string(), "eu west".to_string()),
+                ("APP_ENV".to_string(), "it's".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            tags: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        assert_eq!(
+            build_script_command(&script, None).expect("command"),
+            "APP_ENV='it'\"'\"'s' REGION='eu west' bash '/opt/deploy it.sh' 'staging'"
+        );
+        let args = ["prod".to_string(), "--force".to_string()];
+        assert_eq!(
+            build_script_command(&script, Some(&args)).expect("command"),
+            "APP_ENV='it'\"'\"'s' REGION='eu west' bash '/opt/deploy it.sh' 'prod' '--force'"
+        );
+
+        script.env.insert("BAD;NAME".to_string(), "x".to_string());
+        assert!(build_script_command(&script, None).is_err());
+    }
+
+    #[test]
+    fn preview_script_matches_run_script_command() {
+        let state = test_state("preview-script");
+        let script = state
+            .storage
+            .upsert_script(ScriptInput {
+                id: None,
+                name: "backup".to_string(),
+                path: Some("/opt/backup.sh".to_string()),
+                command: None,
+                description: None,
+                parameters: Vec::new(),
+                default_args: vec!["--full".to_string()],
+                env: Default::default(),
+                tags: Vec::new(),
+            })
+            .expect("create script");
+
+        let preview = preview_script(&state, &script.id, None).expect("preview");
+        assert_eq!(preview, "bash '/opt/backup.sh' '--full'");
+        assert_eq!(
+            preview,
+            build_script_command(&script, None).expect("command")
+        );
+        let err = preview_script(&state, "missing", None).expect_err("unknown script");
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+
+    #[test]
+    fn command_history_is_bounded_and_dropped_with_session() {
+        let state = test_state("history");
+        state.put_session(session_fixture("s1"));
+        for index in 0..MAX_COMMAND_HISTORY_ENTRIES + 5 {
+            state.record_command_history(
+                "s1",
+                CommandHistoryEntry {
+                    command: format!("echo {index}"),
+                    exit_code: 0,
+                    current_dir: "/root".to_string(),
+                    started_at: now_rfc3339(),
+                    finished_at: now_rfc3339(),
+                    duration_ms: 1,
+                },
+            );
+        }
+
+        let all = get_command_history(&state, "s1", None).expect("history");
+        assert_eq!(all.len(), MAX_COMMAND_HISTORY_ENTRIES);
+        assert_eq!(all[0].command, "echo 5");
+        let recent = get_command_history(&state, "s1", Some(2)).expect("history");
+        assert_eq!(recent.len(), 2);
+        assert_eq!(
+            recent[1].command,
+            format!("echo {}", MAX_COMMAND_HISTORY_ENTRIES + 4)
+        );
+
+        state.remove_session("s1").expect("remove session");
+        assert!(state.get_command_history("s1", 10).is_empty());
+        assert!(get_command_history(&state, "s1", None).is_err());
+    }
+
+    #[test]
+    fn shutdown_all_sessions_keeps_the_manifest_and_runs_once() {
+        let state = test_state("shutdown");
+        state.put_session(session_fixture("s1"));
+        let (tx, rx) = mpsc::channel();
+        state.put_pty_channel("s1".to_string(), tx);
+
+        assert_eq!(shutdown_all_sessions(&state), 1);
+        assert!(matches!(rx.try_recv(), Ok(PtyCommand::Close)));
+        assert!(state.list_sessions().is_empty());
+        let manifest = state.storage.load_session_manifest().expect("manifest");
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest[0].current_dir, "/root");
+
+        assert_eq!(shutdown_all_sessions(&state), 0);
+        assert_eq!(close_all_sessions(&state), 0);
+        assert!(state.is_shutting_down());
+    }
+
+    #[test]
+    fn pty_output_redacts_a_secret_split_across_chunks() {
+        let state = test_state("split-secret");
+        state.put_session(session_fixture("s1"));
+        state.init_pty_scrollback("s1", DEFAULT_PTY_SCROLLBACK_BYTES);
+        state.put_pty_term("s1", "screen-256color");
+        let (tx, _rx) = mpsc::channel();
+        state.put_pty_channel("s1".to_string(), tx);
+        let path =
+            std::env::temp_dir().join(format!("eshell-split-secret-{}.cast", Uuid::new_v4()));
+        let info = pty_start_recording(&state, "s1", &path.to_string_lossy(), false)
+            .expect("start recording");
+        assert!(info.warning.is_none());
+
+        record_pty_output(&state, "s1", "export API_TO");
+        record_pty_output(&state, "s1", "KEN=abc123secret\r\n$ ");
+        pty_write_input(&state, "s1", "hunter2\r", false).expect("input");
+
+        let scrollback = state
+            .get_pty_scrollback("s1", usize::MAX)
+            .expect("scrollback");
+        assert!(!scrollback.contains("abc123secret"));
+        assert!(scrollback.ends_with("$ "));
+        assert!(pty_stop_recording(&state, "s1").expect("stop recording"));
+        let cast = std::fs::read_to_string(&path).expect("read cast");
+        assert!(cast
+            .lines()
+            .next()
+            .is_some_and(|header| header.contains("\"TERM\":\"screen-256color\"")));
+        assert!(!cast.contains("abc123secret"));
+        assert!(!cast.contains("hunter2"));
+        assert!(cast.contains("$ "));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn close_all_sessions_releases_the_shutdown_latch() {
+        let state = test_state("close-all");
+        state.put_session(session_fixture("s1"));
+
+        assert_eq!(close_all_sessions(&state), 1);
+        assert!(!state.is_shutting_down());
+        assert_eq!(
+            state
+                .storage
+                .load_session_manifest()
+                .expect("manifest")
+                .len(),
+            1
+        );
+        assert_eq!(shutdown_all_sessions(&state), 0);
+        assert!(state.is_shutting_down());
+    }
+
+    #[test]
+    fn is_would_block_ssh_error_matches_eagain_only() {
+        assert!(is_would_block_ssh_error(&ssh2::Error::from_errno(
+            ErrorCode::Session(-37)
+        )));
+        assert!(!is_would_block_ssh_error(&ssh2::Error::from_errno(
+            ErrorCode::Session(-7)
+        )));
+    }
+
+    #[test]
+    fn session_output_keeps_a_bounded_tail() {
+        let state = test_state("output");
+        state.put_session(ShellSession {
+            last_output: "login banner\n".to_string(),
+            ..session_fixture("s1")
+        });
+        assert_eq!(
+            state.get_session("s1").expect("session").last_output,
+            "login banner\n"
+        );
+
+        let binary_spew = "\u{fffd}x".repeat(SESSION_LAST_OUTPUT_BYTES);
+        append_session_output(&state, "s1", &binary_spew);
+        append_session_output(&state, "s1", "$ done\n");
+
+        let output = state.get_session("s1").expect("session").last_output;
+        assert!(output.len() <= SESSION_LAST_OUTPUT_BYTES);
+        assert!(output.ends_with("x$ done\n"));
+        assert!(!output.contains("login banner"));
+
+        let mutated = state
+            .mutate_session("s1", |session| {
+                assert!(session.last_output.is_empty());
+                session.last_output = "ignored".to_string();
+            })
+            .expect("mutate session");
+        assert_eq!(mutated.last_output, output);
+    }
+
+    #[test]
+    fn test_ssh_connection_requires_host_and_persists_nothing() {
+        let state = test_state("conn-test");
+        let err = test_ssh_connection(
+            &state,
+            SshConfigInput {
+                id: None,
+                name: "draft".to_string(),
+                host: "  ".to_string(),
+                port: 22,
+                username: "root".to_string(),
+                auth_type: SshAuthType::Password,
+                password: "secret".to_string(),
+                private_key_path: String::new(),
+                private_key_passphrase: String::new(),
+                use_password_fallback: false,
+                sudo_tty_fallback: false,
+                sudo_password: String::new(),
+                jump_host: None,
+                description: None,
+            },
+        )
+        .expect_err("blank host is rejected");
+        assert!(matches!(err, AppError::Validation(_)));
+        assert!(state.storage.list_ssh_configs().is_empty());
+        assert!(state.list_sessions().is_empty());
+    }
+
+    #[test]
+    fn execute_command_multi_reports_each_host_separately() {
+        let state = test_state("multi-host");
+        let config_ids = (0..10).map(|i| format!("missing-{i}")).collect::<Vec<_>>();
+
+        let results = execute_command_multi(&state, &config_ids, "uptime").expect("batch runs");
+        assert_eq!(results.len(), 10);
+        for (config_id, result) in config_ids.iter().zip(&results) {
+            assert_eq!(&result.config_id, config_id);
+            assert!(result.result.is_none());
+            let error = result.error.as_deref().unwrap_or_default();
+            assert!(error.contains(config_id));
+        }
+
+        let err = execute_command_multi(&state, &config_ids, "  ").expect_err("empty command");
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    #[test]
+    fn write_pending_keeps_what_a_busy_peer_did_not_take() {
+        struct Throttled {
+            accepted: Vec<u8>,
+            budget: usize,
+        }
+        impl Write for Throttled {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                if self.budget == 0 {
+                    return Err(std::io::ErrorKind::WouldBlock.into());
+                }
+                let size = bytes.len().min(self.budget).min(3);
+                self.budget -= size;
+                self.accepted.extend_from_slice(&bytes[..size]);
+                Ok(size)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut peer = Throttled {
+            accepted: Vec::new(),
+            budget: 5,
+        };
+        let mut pending = b"hello world".to_vec();
+        assert_eq!(write_pending(&mut peer, &mut pending).expect("write"), 5);
+        assert_eq!(pending, b" world");
+        assert_eq!(write_pending(&mut peer, &mut pending).expect("write"), 0);
+
+        peer.budget = usize::MAX;
+        assert_eq!(write_pending(&mut peer, &mut pending).expect("write"), 6);
+        assert!(pending.is_empty());
+        assert_eq!(peer.accepted, b"hello world");
+    }
+
     #[test]
     fn is_transient_pty_io_error_detects_timeout_and_wouldblock() {
         let timeout = std::io::Error::new(std::io::ErrorKind::TimedOut, "operation timed out");
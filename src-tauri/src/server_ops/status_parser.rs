@@ -1,4 +1,11 @@
-use crate::models::{DiskStatus, MemoryStatus, NetworkInterfaceStatus, ProcessStatus};
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::models::{
+    DirUsageEntry, DiskStatus, DockerContainer, GpuStatus, MemoryStatus, NetworkInterfaceStatus,
+    ProcessSample, ProcessStatus, ServiceStatus, VmStat, ZombieProcess,
+};
 
 /// Parses `top -bn1` output and extracts CPU usage plus memory totals.
 #[allow(dead_code)]
@@ -33,10 +40,165 @@ pub fn parse_cpu_percent(top_output: &str) -> Option<f64> {
     None
 }
 
+/// Filesystem names `df` reports for in-memory and kernel mounts.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "tmpfs",
+    "devtmpfs",
+    "overlay",
+    "shm",
+    "udev",
+    "none",
+    "proc",
+    "sysfs",
+    "cgroup",
+    "cgroup2",
+    "devfs",
+    "fdescfs",
+    "linprocfs",
+    "ramfs",
+    "squashfs",
+    "efivarfs",
+];
+
+/// True for virtual mounts (tmpfs, overlay, snap loop devices) that are not real storage.
+pub fn is_pseudo_filesystem(disk: &DiskStatus) -> bool {
+    let filesystem = disk.filesystem.to_ascii_lowercase();
+    PSEUDO_FILESYSTEMS.contains(&filesystem.as_str())
+        || filesystem.starts_with("/dev/loop")
+        || disk.mount_point.starts_with("/snap/")
+}
+
+/// Computes CPU usage from two `/proc/stat` snapshots taken some time apart.
+///
+/// Only the aggregate `cpu` line is used; iowait counts as idle, guest time is
+/// already part of user time and is skipped.
+pub fn parse_proc_stat_cpu(first: &str, second: &str) -> Option<f64> {
+    let (first_idle, first_total) = proc_stat_cpu_jiffies(first)?;
+    let (second_idle, second_total) = proc_stat_cpu_jiffies(second)?;
+    let total = second_total.checked_sub(first_total)?;
+    if total == 0 {
+        return None;
+    }
+    let idle = second_idle.saturating_sub(first_idle).min(total);
+    Some(round2((total - idle) as f64 * 100.0 / total as f64))
+}
+
+// Returns (idle + iowait, user..steal) jiffies from the aggregate `cpu` line.
+fn proc_stat_cpu_jiffies(snapshot: &str) -> Option<(u64, u64)> {
+    let line = snapshot
+        .lines()
+        .find(|line| line.split_whitespace().next() == Some("cpu"))?;
+    let values = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|value| value.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() < 4 {
+        return None;
+    }
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+    Some((idle, values.iter().sum()))
+}
+
+/// Parses `nvidia-smi --query-gpu` CSV rows (`noheader,nounits`), one GPU per line.
+pub fn parse_nvidia_smi_gpus(output: &str) -> Vec<GpuStatus> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split(',').map(str::trim).collect();
+            if cols.len() != 5 {
+                return None;
+            }
+            // Unsupported fields are reported as "[N/A]" and parse to `None`.
+            let metric = |value: &str| value.parse::<f64>().ok().map(round2);
+            Some(GpuStatus {
+                index: cols[0].parse::<u32>().ok()?,
+                name: cols[1].to_string(),
+                utilization_percent: metric(cols[2]),
+                memory_used_mb: metric(cols[3]),
+                memory_total_mb: metric(cols[4]),
+            })
+        })
+        .collect()
+}
+
+/// Parses per-core CPU usage from procps `%CpuN` lines or `mpstat -P ALL` output.
+///
+/// Values are ordered by core index; an empty result means no per-core data was found.
+pub fn parse_per_core_cpu(output: &str) -> Vec<f64> {
+    let mut cores = BTreeMap::new();
+    for line in output.lines() {
+        let lower = line.trim().to_ascii_lowercase();
+        // procps top with per-CPU toggle: "%Cpu0  :  1.0 us,  0.5 sy, ... 98.0 id, ..."
+        let Some(rest) = lower.strip_prefix("%cpu") else {
+            continue;
+        };
+        let Some((index, metrics)) = rest.split_once(':') else {
+            continue;
+        };
+        let Ok(index) = index.trim().parse::<usize>() else {
+            continue;
+        };
+        if let Some(idle) = extract_metric_value(metrics, " id") {
+            cores.insert(index, round2((100.0 - idle).clamp(0.0, 100.0)));
+        }
+    }
+
+    if cores.is_empty() {
+        parse_mpstat_per_core(output, &mut cores);
+    }
+    cores.into_values().collect()
+}
+
+// mpstat rows share the header's leading timestamp tokens, so the CPU column
+// index from the header applies to each row; `%idle` is always the last column.
+fn parse_mpstat_per_core(output: &str, cores: &mut BTreeMap<usize, f64>) {
+    let mut cpu_column = None;
+    for line in output.lines() {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        if tokens.last() == Some(&"%idle") {
+            cpu_column = tokens.iter().position(|token| *token == "CPU");
+            continue;
+        }
+        let Some(column) = cpu_column else {
+            continue;
+        };
+        let index = tokens
+            .get(column)
+            .and_then(|token| token.parse::<usize>().ok());
+        let idle = tokens.last().and_then(|token| token.parse::<f64>().ok());
+        if let (Some(index), Some(idle)) = (index, idle) {
+            cores.insert(index, round2((100.0 - idle).clamp(0.0, 100.0)));
+        }
+    }
+}
+
 /// Parses memory usage from `top -bn1` output and converts values to MiB.
 pub fn parse_memory(top_output: &str) -> Option<MemoryStatus> {
+    let mut memory = None;
+    let mut swap = None;
+    let mut available = None;
     for line in top_output.lines() {
-        let lower = line.to_ascii_lowercase();
+        // procps ends the swap "used" column with a period instead of a comma.
+        let lower = line.to_ascii_lowercase().replace(" used.", " used,");
+
+        // procps top:
+        // "MiB Swap:  2048.0 total,  2048.0 free,     0.0 used.  11200.0 avail Mem"
+        if lower.contains("swap") && lower.contains("total") {
+            let scale_mb = extract_top_memory_scale_mb(&lower);
+            if let (Some(used), Some(total)) = (
+                extract_metric_value(&lower, " used"),
+                extract_metric_value(&lower, " total"),
+            ) {
+                swap = Some((used * scale_mb, total * scale_mb));
+            }
+            available = extract_metric_value(&lower, " avail mem").map(|value| value * scale_mb);
+            continue;
+        }
+        if memory.is_some() {
+            continue;
+        }
 
         // procps top:
         // "MiB Mem : 15935.1 total, 1200.2 free, 4300.0 used, ..."
@@ -44,7 +206,8 @@ pub fn parse_memory(top_output: &str) -> Option<MemoryStatus> {
             let scale_mb = extract_top_memory_scale_mb(&lower);
             let total = extract_metric_value(&lower, " total")? * scale_mb;
             let used = extract_metric_value(&lower, " used")? * scale_mb;
-            return Some(build_memory_status(used, total));
+            memory = Some((used, total));
+            continue;
         }
 
         // busybox top:
@@ -52,12 +215,23 @@ pub fn parse_memory(top_output: &str) -> Option<MemoryStatus> {
         if lower.contains("mem:") && lower.contains(" used") && lower.contains(" free") {
             let used = extract_metric_value_mb(&lower, " used")?;
             let free = extract_metric_value_mb(&lower, " free")?;
-            let total = used + free;
-            return Some(build_memory_status(used, total));
+            memory = Some((used, used + free));
         }
     }
 
-    None
+    let (used, total) = memory?;
+    let mut status = build_memory_status(used, total);
+    // "used" excludes reclaimable cache inconsistently across kernels; available
+    // is what the kernel considers usable without swapping.
+    if let Some(available) = available.filter(|value| total > 0.0 && *value <= total) {
+        status.available_mb = Some(round2(available));
+        status.used_percent = round2((total - available) / total * 100.0);
+    }
+    if let Some((swap_used, swap_total)) = swap {
+        status.swap_used_mb = Some(round2(swap_used));
+        status.swap_total_mb = Some(round2(swap_total));
+    }
+    Some(status)
 }
 
 /// Parses `/proc/net/dev` output to per-interface RX/TX traffic.
@@ -86,59 +260,480 @@ pub fn parse_network_interfaces(output: &str) -> Vec<NetworkInterfaceStatus> {
             interface: iface.trim().to_string(),
             rx_bytes,
             tx_bytes,
+            rx_rate_bps: 0.0,
+            tx_rate_bps: 0.0,
         });
     }
 
     rows
 }
 
-/// Parses top process rows from `ps -eo pid,pcpu,rss,comm --sort=-pcpu`.
+/// Fills `rx_rate_bps`/`tx_rate_bps` by diffing against a previous sample.
+///
+/// Interfaces missing from the previous sample (first load) keep a zero rate.
+pub fn apply_network_rates(
+    current: &mut [NetworkInterfaceStatus],
+    previous: &[NetworkInterfaceStatus],
+    elapsed_secs: f64,
+) {
+    for row in current.iter_mut() {
+        let Some(prior) = previous.iter().find(|item| item.interface == row.interface) else {
+            continue;
+        };
+        row.rx_rate_bps = compute_counter_rate(prior.rx_bytes, row.rx_bytes, elapsed_secs);
+        row.tx_rate_bps = compute_counter_rate(prior.tx_bytes, row.tx_bytes, elapsed_secs);
+    }
+}
+
+/// Computes bytes/sec between two cumulative counter readings.
+///
+/// A drop from the upper half of the 32-bit range is treated as a counter
+/// wraparound; any other drop (interface reset, 64-bit counter) reports zero.
+fn compute_counter_rate(previous: u64, current: u64, elapsed_secs: f64) -> f64 {
+    if elapsed_secs <= 0.0 {
+        return 0.0;
+    }
+    let delta = if current >= previous {
+        current - previous
+    } else if previous > u64::from(u32::MAX / 2) && previous <= u64::from(u32::MAX) {
+        u64::from(u32::MAX) - previous + current + 1
+    } else {
+        return 0.0;
+    };
+    round2(delta as f64 / elapsed_secs)
+}
+
+/// Remote operating system family, detected from `uname -s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteOsFamily {
+    Linux,
+    Darwin,
+    Bsd,
+}
+
+/// Maps `uname -s` output to a parser family. Unknown kernels use the Linux path.
+pub fn parse_os_family(uname_output: &str) -> RemoteOsFamily {
+    let kernel = uname_output.trim().to_ascii_lowercase();
+    if kernel.starts_with("darwin") {
+        RemoteOsFamily::Darwin
+    } else if kernel.ends_with("bsd") || kernel == "dragonfly" {
+        RemoteOsFamily::Bsd
+    } else {
+        RemoteOsFamily::Linux
+    }
+}
+
+/// Parses macOS `vm_stat` output into memory usage, given `hw.memsize` in bytes.
+///
+/// Used memory follows Activity Monitor: active + wired + compressed pages.
+pub fn parse_vm_stat_memory(vm_stat_output: &str, total_bytes: u64) -> Option<MemoryStatus> {
+    let mut page_size = 4096_u64;
+    let mut used_pages = 0_u64;
+    let mut matched = false;
+
+    for line in vm_stat_output.lines() {
+        let lower = line.trim().to_ascii_lowercase();
+        if let Some(rest) = lower.split("page size of ").nth(1) {
+            if let Some(size) = rest
+                .split_whitespace()
+                .next()
+                .and_then(|token| token.parse::<u64>().ok())
+            {
+                page_size = size;
+            }
+            continue;
+        }
+
+        let Some((label, value)) = lower.split_once(':') else {
+            continue;
+        };
+        let Ok(pages) = value.trim().trim_end_matches('.').parse::<u64>() else {
+            continue;
+        };
+        if matches!(
+            label.trim(),
+            "pages active" | "pages wired down" | "pages occupied by compressor"
+        ) {
+            used_pages += pages;
+            matched = true;
+        }
+    }
+
+    if !matched || total_bytes == 0 {
+        return None;
+    }
+    let bytes_per_mb = 1024.0 * 1024.0;
+    Some(build_memory_status(
+        (used_pages * page_size) as f64 / bytes_per_mb,
+        total_bytes as f64 / bytes_per_mb,
+    ))
+}
+
+/// Parses BSD `sysctl -n hw.physmem hw.pagesize vm.stats.vm.v_free_count vm.stats.vm.v_inactive_count`.
+pub fn parse_bsd_sysctl_memory(output: &str) -> Option<MemoryStatus> {
+    let values: Vec<u64> = output
+        .split_whitespace()
+        .filter_map(|token| token.parse::<u64>().ok())
+        .collect();
+    if values.len() < 4 {
+        return None;
+    }
+
+    let (total_bytes, page_size) = (values[0], values[1]);
+    let available_bytes = (values[2] + values[3]) * page_size;
+    let bytes_per_mb = 1024.0 * 1024.0;
+    Some(build_memory_status(
+        total_bytes.saturating_sub(available_bytes) as f64 / bytes_per_mb,
+        total_bytes as f64 / bytes_per_mb,
+    ))
+}
+
+/// Parses `netstat -ib` output (macOS and BSD) into per-interface RX/TX traffic.
+///
+/// Only the `<Link#N>` row of each interface is used because it carries the
+/// hardware counters; address rows repeat the same interface name.
+pub fn parse_netstat_interfaces(output: &str) -> Vec<NetworkInterfaceStatus> {
+    let mut lines = output.lines().filter(|line| !line.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let header_cols: Vec<&str> = header.split_whitespace().collect();
+    let position = |name: &str| header_cols.iter().position(|col| *col == name);
+    let (Some(ipkts), Some(ibytes), Some(obytes)) =
+        (position("Ipkts"), position("Ibytes"), position("Obytes"))
+    else {
+        return Vec::new();
+    };
+    // Counter columns are right-aligned, so index them from the end of the row;
+    // the Address column is blank for some link rows.
+    let tail_len = header_cols.len() - ipkts;
+    let ibytes_from_end = tail_len - (ibytes - ipkts);
+    let obytes_from_end = tail_len - (obytes - ipkts);
+
+    let mut rows: Vec<NetworkInterfaceStatus> = Vec::new();
+    for line in lines {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < tail_len + 2 || !cols[2].starts_with("<Link") {
+            continue;
+        }
+        let interface = cols[0].trim_end_matches('*');
+        if rows.iter().any(|row| row.interface == interface) {
+            continue;
+        }
+        let (Ok(rx_bytes), Ok(tx_bytes)) = (
+            cols[cols.len() - ibytes_from_end].parse::<u64>(),
+            cols[cols.len() - obytes_from_end].parse::<u64>(),
+        ) else {
+            continue;
+        };
+        rows.push(NetworkInterfaceStatus {
+            interface: interface.to_string(),
+            rx_bytes,
+            tx_bytes,
+            rx_rate_bps: 0.0,
+            tx_rate_bps: 0.0,
+        });
+    }
+
+    rows
+}
+
+/// Parses top process rows from `ps -eo pid,user,pcpu,pmem,comm --sort=-pcpu`.
 pub fn parse_top_processes(output: &str) -> Vec<ProcessStatus> {
-    output
-        .lines()
-        .skip(1)
+    let mut lines = output.lines().filter(|line| !line.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    // Custom process commands may still use the older `pid,pcpu,pmem,comm` layout.
+    let has_user = header
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|column| column.eq_ignore_ascii_case("user"));
+    let offset = usize::from(has_user);
+
+    lines
         .filter_map(|line| {
             let cols: Vec<&str> = line.split_whitespace().collect();
-            if cols.len() < 4 {
+            if cols.len() < 4 + offset {
                 return None;
             }
 
             Some(ProcessStatus {
                 pid: cols[0].parse::<i32>().ok()?,
-                cpu_percent: cols[1].parse::<f64>().ok().map(round2)?,
-                memory_mb: cols[2]
-                    .parse::<f64>()
-                    .ok()
-                    .map(|value_kb| round2(value_kb / 1024.0))?,
+                user: has_user.then(|| cols[1].to_string()),
+                cpu_percent: cols[1 + offset].parse::<f64>().ok().map(round2)?,
+                memory_percent: cols[2 + offset].parse::<f64>().ok().map(round2)?,
+                command: cols[3 + offset..].join(" "),
+            })
+        })
+        .collect()
+}
+
+/// Parses `ps -eo pid,ppid,stat,comm` and keeps zombie (`Z`) rows.
+///
+/// The STAT column may carry modifiers (`Z`, `Z+`, `Zs`, `Z<`, ...) and some
+/// `ps` builds print it in lower case, so any `Z`/`z` flag counts.
+pub fn parse_zombie_processes(output: &str) -> Vec<ZombieProcess> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() < 4 {
+                return None;
+            }
+            let pid = cols[0].parse::<i32>().ok()?;
+            let ppid = cols[1].parse::<i32>().ok()?;
+            let stat = cols[2];
+            if !stat.contains(['Z', 'z']) {
+                return None;
+            }
+            Some(ZombieProcess {
+                pid,
+                ppid,
+                stat: stat.to_string(),
                 command: cols[3..].join(" "),
             })
         })
         .collect()
 }
 
+/// Parses `systemctl list-units --plain` rows or sysvinit `service --status-all` rows.
+///
+/// systemd: `nginx.service loaded active running A high performance web server`.
+/// sysvinit: ` [ + ]  nginx` where `+`/`-`/`?` mean running, stopped and unknown.
+pub fn parse_service_statuses(output: &str) -> Vec<ServiceStatus> {
+    let mut services = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim().trim_start_matches('●').trim();
+        // systemctl prints a legend after the first blank line.
+        if trimmed.is_empty() {
+            if !services.is_empty() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix('[') {
+            let Some((flag, name)) = rest.split_once(']') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            let (active_state, sub_state) = match flag.trim() {
+                "+" => ("active", "running"),
+                "-" => ("inactive", "dead"),
+                _ => ("unknown", "unknown"),
+            };
+            services.push(ServiceStatus {
+                name: name.to_string(),
+                load_state: "unknown".to_string(),
+                active_state: active_state.to_string(),
+                sub_state: sub_state.to_string(),
+                description: String::new(),
+            });
+            continue;
+        }
+
+        let cols: Vec<&str> = trimmed.split_whitespace().collect();
+        if cols.len() < 4 || cols[0] == "UNIT" || !cols[0].ends_with(".service") {
+            continue;
+        }
+        services.push(ServiceStatus {
+            name: cols[0].to_string(),
+            load_state: cols[1].to_string(),
+            active_state: cols[2].to_string(),
+            sub_state: cols[3].to_string(),
+            description: cols[4..].join(" "),
+        });
+    }
+    services
+}
+
+/// Parses `vmstat 1 2` output, returning the last (interval delta) data row.
+///
+/// Columns are located by header name so `-w` output and optional columns such
+/// as `st`/`gu` do not shift the values; missing columns read as zero.
+pub fn parse_vmstat(output: &str) -> Option<VmStat> {
+    let mut header: Option<Vec<&str>> = None;
+    let mut last_row: Option<Vec<&str>> = None;
+    for line in output.lines() {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.first() == Some(&"r") && cols.get(1) == Some(&"b") {
+            header = Some(cols);
+        } else if header.is_some()
+            && !cols.is_empty()
+            && cols.iter().all(|col| col.parse::<u64>().is_ok())
+        {
+            last_row = Some(cols);
+        }
+    }
+
+    let header = header?;
+    let row = last_row?;
+    let value = |name: &str| -> u64 {
+        header
+            .iter()
+            .position(|col| *col == name)
+            .and_then(|idx| row.get(idx))
+            .and_then(|raw| raw.parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Some(VmStat {
+        runnable: value("r"),
+        blocked: value("b"),
+        swap_used_kb: value("swpd"),
+        free_kb: value("free"),
+        buffer_kb: value("buff"),
+        cache_kb: value("cache"),
+        swap_in: value("si"),
+        swap_out: value("so"),
+        blocks_in: value("bi"),
+        blocks_out: value("bo"),
+        interrupts: value("in"),
+        context_switches: value("cs"),
+        cpu_user: value("us"),
+        cpu_system: value("sy"),
+        cpu_idle: value("id"),
+        cpu_wait: value("wa"),
+        cpu_steal: value("st"),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerPsRow {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    names: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    ports: String,
+}
+
+/// Parses `docker ps --format '{{json .}}'` output (one JSON object per line).
+pub fn parse_docker_containers(output: &str) -> Vec<DockerContainer> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<DockerPsRow>(line.trim()).ok())
+        .map(|row| DockerContainer {
+            id: row.id,
+            image: row.image,
+            name: row.names,
+            state: row.state,
+            status: row.status,
+            ports: row.ports,
+        })
+        .collect()
+}
+
 /// Parses `df -hP` output into filesystem rows.
 pub fn parse_disks(output: &str) -> Vec<DiskStatus> {
-    output
+    let mut disks = Vec::new();
+    // Long device names (LVM, iSCSI) can sit alone on a line, with their
+    // stats wrapped onto the next one.
+    let mut wrapped_filesystem = None;
+    for line in output
         .lines()
         .filter(|line| !line.trim().is_empty())
         .skip(1)
+    {
+        let mut cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() == 1 {
+            wrapped_filesystem = Some(cols[0]);
+            continue;
+        }
+        if let Some(filesystem) = wrapped_filesystem.take() {
+            if cols.len() == 5 {
+                cols.insert(0, filesystem);
+            }
+        }
+        if cols.len() < 6 || cols[0].eq_ignore_ascii_case("filesystem") {
+            continue;
+        }
+        disks.push(DiskStatus {
+            filesystem: cols[0].to_string(),
+            total: cols[1].to_string(),
+            used: cols[2].to_string(),
+            used_percent: cols[4].to_string(),
+            mount_point: cols[5].to_string(),
+        });
+    }
+    disks
+}
+
+/// Parses `du -h` output (`<size>\t<path>` per line); paths may contain spaces.
+pub fn parse_du_output(output: &str) -> Vec<DirUsageEntry> {
+    output
+        .lines()
         .filter_map(|line| {
+            let (size, path) = line.split_once('\t')?;
+            let size = size.trim();
+            Some(DirUsageEntry {
+                path: path.to_string(),
+                size: size.to_string(),
+                size_bytes: parse_human_size(size)?,
+            })
+        })
+        .collect()
+}
+
+/// Converts a `du -h` size (`512`, `4.0K`, `1.5G`) to bytes using powers of 1024.
+pub fn parse_human_size(token: &str) -> Option<u64> {
+    let token = token.trim();
+    let split_at = token
+        .find(|ch: char| !ch.is_ascii_digit() && ch != '.' && ch != ',')
+        .unwrap_or(token.len());
+    let number = token[..split_at].replace(',', ".").parse::<f64>().ok()?;
+    let exponent = match token[split_at..].trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        "P" => 5,
+        "E" => 6,
+        _ => return None,
+    };
+    Some((number * 1024_f64.powi(exponent)).round() as u64)
+}
+
+/// Parses one row from `ps -o pid,pcpu,pmem,rss,vsz -p <pid>`.
+///
+/// Returns `None` when only the header is present, which means the process is gone.
+pub fn parse_process_sample(output: &str, sampled_at: String) -> Option<ProcessSample> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .skip(1)
+        .find_map(|line| {
             let cols: Vec<&str> = line.split_whitespace().collect();
-            if cols.len() < 6 {
-                return None;
-            }
-            if cols[0].eq_ignore_ascii_case("filesystem") {
+            if cols.len() < 5 {
                 return None;
             }
-            Some(DiskStatus {
-                filesystem: cols[0].to_string(),
-                total: cols[1].to_string(),
-                used: cols[2].to_string(),
-                used_percent: cols[4].to_string(),
-                mount_point: cols[5].to_string(),
+            Some(ProcessSample {
+                pid: cols[0].parse::<i32>().ok()?,
+                cpu_percent: cols[1].parse::<f64>().ok().map(round2)?,
+                memory_percent: cols[2].parse::<f64>().ok().map(round2)?,
+                rss_kb: cols[3].parse::<u64>().ok()?,
+                vsz_kb: cols[4].parse::<u64>().ok()?,
+                sampled_at: sampled_at.clone(),
             })
         })
-        .collect()
+}
+
+/// Parses the first pid printed by `pgrep`.
+pub fn parse_first_pid(output: &str) -> Option<i32> {
+    output
+        .lines()
+        .find_map(|line| line.trim().parse::<i32>().ok())
 }
 
 fn extract_metric_value(line: &str, suffix: &str) -> Option<f64> {
@@ -243,6 +838,9 @@ fn build_memory_status(used: f64, total: f64) -> MemoryStatus {
         used_mb: round2(used),
         total_mb: round2(total),
         used_percent: round2(used_percent),
+        available_mb: None,
+        swap_used_mb: None,
+        swap_total_mb: None,
     }
 }
 
@@ -267,6 +865,105 @@ MiB Mem :  8000.0 total,  1200.0 free,  3500.0 used,  3300.0 buff/cache
         assert_eq!(parsed.1.used_percent, 43.75);
     }
 
+    #[test]
+    fn parse_per_core_cpu_reads_procps_lines() {
+        let top = r#"
+%Cpu0  :  3.0 us,  1.0 sy,  0.0 ni, 96.0 id,  0.0 wa,  0.0 hi,  0.0 si,  0.0 st
+%Cpu1  : 99.0 us,  1.0 sy,  0.0 ni,  0.0 id,  0.0 wa,  0.0 hi,  0.0 si,  0.0 st
+%Cpu2  : 10.5 us,  2.0 sy,  0.0 ni, 87.5 id,  0.0 wa,  0.0 hi,  0.0 si,  0.0 st
+"#;
+        assert_eq!(parse_per_core_cpu(top), vec![4.0, 100.0, 12.5]);
+    }
+
+    #[test]
+    fn parse_per_core_cpu_reads_mpstat_output() {
+        let mpstat = r#"
+Linux 5.15.0 (host)  01/01/2026  _x86_64_  (2 CPU)
+
+12:00:01 AM  CPU    %usr   %nice    %sys %iowait    %irq   %soft  %steal  %guest  %gnice   %idle
+12:00:02 AM  all   25.00    0.00    1.00    0.00    0.00    0.00    0.00    0.00    0.00   74.00
+12:00:02 AM    0   50.00    0.00    2.00    0.00    0.00    0.00    0.00    0.00    0.00   48.00
+12:00:02 AM    1    0.00    0.00    0.00    0.00    0.00    0.00    0.00    0.00    0.00  100.00
+
+Average:     CPU    %usr   %nice    %sys %iowait    %irq   %soft  %steal  %guest  %gnice   %idle
+Average:     all   25.00    0.00    1.00    0.00    0.00    0.00    0.00    0.00    0.00   74.00
+Average:       0   50.00    0.00    2.00    0.00    0.00    0.00    0.00    0.00    0.00   48.00
+Average:       1    0.00    0.00    0.00    0.00    0.00    0.00    0.00    0.00    0.00  100.00
+"#;
+        assert_eq!(parse_per_core_cpu(mpstat), vec![52.0, 0.0]);
+        assert!(parse_per_core_cpu("%Cpu(s):  1.0 us, 99.0 id").is_empty());
+    }
+
+    #[test]
+    fn parse_service_statuses_reads_systemctl_rows() {
+        let raw = r#"
+UNIT                 LOAD      ACTIVE   SUB     DESCRIPTION
+nginx.service        loaded    active   running A high performance web server
+postgresql.service   loaded    inactive dead    PostgreSQL RDBMS
+ghost.service        not-found inactive dead    ghost.service
+
+LOAD   = Reflects whether the unit definition was properly loaded.
+3 loaded units listed.
+"#;
+        let services = parse_service_statuses(raw);
+        assert_eq!(services.len(), 3);
+        assert_eq!(services[0].name, "nginx.service");
+        assert_eq!(services[0].sub_state, "running");
+        assert_eq!(services[0].description, "A high performance web server");
+        assert_eq!(services[1].active_state, "inactive");
+        assert_eq!(services[2].load_state, "not-found");
+    }
+
+    #[test]
+    fn parse_service_statuses_reads_sysv_status_all() {
+        let raw = " [ + ]  cron\n [ - ]  nginx\n [ ? ]  hwclock.sh\n";
+        let services = parse_service_statuses(raw);
+        assert_eq!(services.len(), 3);
+        assert_eq!(services[0].name, "cron");
+        assert_eq!(services[0].active_state, "active");
+        assert_eq!(services[1].sub_state, "dead");
+        assert_eq!(services[2].active_state, "unknown");
+    }
+
+    #[test]
+    fn parse_vmstat_reads_delta_row() {
+        let raw = r#"
+procs -----------memory---------- ---swap-- -----io---- -system-- ------cpu-----
+ r  b   swpd   free   buff  cache   si   so    bi    bo   in   cs us sy id wa st
+ 1  0      0 812344  90412 2101232    0    0    12    30  110  220  3  1 95  1  0
+ 3  2   1024 800100  90412 2101300    5    7   400   800 1500 4200 20 10 60  8  2
+"#;
+        let stat = parse_vmstat(raw).expect("vmstat row");
+        assert_eq!(stat.runnable, 3);
+        assert_eq!(stat.blocked, 2);
+        assert_eq!(stat.swap_used_kb, 1024);
+        assert_eq!(stat.swap_in, 5);
+        assert_eq!(stat.swap_out, 7);
+        assert_eq!(stat.blocks_out, 800);
+        assert_eq!(stat.interrupts, 1500);
+        assert_eq!(stat.context_switches, 4200);
+        assert_eq!(stat.cpu_idle, 60);
+        assert_eq!(stat.cpu_steal, 2);
+        assert!(parse_vmstat("vmstat: command not found").is_none());
+    }
+
+    #[test]
+    fn parse_docker_containers_reads_json_lines() {
+        let raw = concat!(
+            r#"{"Command":"\"nginx -g\"","ID":"3f2a","Image":"nginx:1.25","Names":"web","Ports":"0.0.0.0:80->80/tcp","State":"running","Status":"Up 2 hours"}"#,
+            "\n",
+            r#"{"ID":"9b1c","Image":"redis:7","Names":"cache","Ports":"","State":"exited","Status":"Exited (0) 3 days ago"}"#,
+            "\nWARNING: not json\n",
+        );
+        let containers = parse_docker_containers(raw);
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].id, "3f2a");
+        assert_eq!(containers[0].name, "web");
+        assert_eq!(containers[0].ports, "0.0.0.0:80->80/tcp");
+        assert_eq!(containers[1].state, "exited");
+        assert_eq!(containers[1].status, "Exited (0) 3 days ago");
+    }
+
     #[test]
     fn parse_cpu_and_memory_busybox_works() {
         let top = r#"
@@ -278,6 +975,25 @@ CPU: 1.0% usr 2.0% sys 0.0% nic 96.0% idle 0.0% io 0.0% irq 0.0% sirq
         assert_eq!(parsed.1.used_mb, 15.56);
         assert_eq!(parsed.1.total_mb, 16.54);
         assert_eq!(parsed.1.used_percent, 94.1);
+        assert_eq!(parsed.1.available_mb, None);
+        assert_eq!(parsed.1.swap_total_mb, None);
+    }
+
+    #[test]
+    fn parse_memory_reads_swap_and_available() {
+        let top = r#"
+top - 15:30:10 up 1 day,  1 user
+%Cpu(s):  3.0 us,  1.0 sy,  0.0 ni, 96.0 id,  0.0 wa,  0.0 hi,  0.0 si,  0.0 st
+MiB Mem :  8000.0 total,   600.0 free,  3500.0 used,  3900.0 buff/cache
+MiB Swap:  2048.0 total,  1536.0 free,   512.0 used.  6000.0 avail Mem
+"#;
+        let memory = parse_memory(top).expect("parse memory");
+        assert_eq!(memory.used_mb, 3500.0);
+        assert_eq!(memory.total_mb, 8000.0);
+        assert_eq!(memory.available_mb, Some(6000.0));
+        assert_eq!(memory.used_percent, 25.0);
+        assert_eq!(memory.swap_used_mb, Some(512.0));
+        assert_eq!(memory.swap_total_mb, Some(2048.0));
     }
 
     #[test]
@@ -311,16 +1027,44 @@ eth0: 9876543 9999 0 0 0 0 0 0 1234567 8888 0 0 0 0 0 0
     #[test]
     fn parse_top_processes_works() {
         let raw = r#"
-PID %CPU RSS COMMAND
-123 12.5 41984 java
-234 5.0 2048 nginx
+  PID USER     %CPU %MEM COMMAND
+  123 app      12.5 41.3 java
+  234 www-data  5.0  0.2 nginx
 "#;
         let rows = parse_top_processes(raw);
         assert_eq!(rows.len(), 2);
         assert_eq!(rows[0].pid, 123);
+        assert_eq!(rows[0].user.as_deref(), Some("app"));
         assert_eq!(rows[0].cpu_percent, 12.5);
-        assert_eq!(rows[0].memory_mb, 41.0);
-        assert_eq!(rows[1].memory_mb, 2.0);
+        assert_eq!(rows[0].memory_percent, 41.3);
+        assert_eq!(rows[1].user.as_deref(), Some("www-data"));
+        assert_eq!(rows[1].memory_percent, 0.2);
+
+        let legacy = parse_top_processes("PID %CPU %MEM COMMAND\n123 12.5 41.3 java\n");
+        assert_eq!(legacy[0].user, None);
+        assert_eq!(legacy[0].command, "java");
+    }
+
+    #[test]
+    fn parse_nvidia_smi_gpus_works() {
+        let raw = "0, NVIDIA A100-SXM4-40GB, 87, 31245, 40960\n\
+1, NVIDIA A100-SXM4-40GB, 0, 4, 40960\n\
+2, NVIDIA GeForce GT 710, [N/A], 312, 2048\n";
+        let gpus = parse_nvidia_smi_gpus(raw);
+        assert_eq!(gpus.len(), 3);
+        assert_eq!(gpus[0].index, 0);
+        assert_eq!(gpus[0].name, "NVIDIA A100-SXM4-40GB");
+        assert_eq!(gpus[0].utilization_percent, Some(87.0));
+        assert_eq!(gpus[0].memory_used_mb, Some(31245.0));
+        assert_eq!(gpus[1].memory_total_mb, Some(40960.0));
+        assert_eq!(gpus[2].utilization_percent, None);
+        assert_eq!(gpus[2].memory_used_mb, Some(312.0));
+
+        assert!(parse_nvidia_smi_gpus("").is_empty());
+        assert!(parse_nvidia_smi_gpus(
+            "NVIDIA-SMI has failed because it couldn't communicate with the NVIDIA driver."
+        )
+        .is_empty());
     }
 
     #[test]
@@ -335,4 +1079,242 @@ tmpfs           1.9G  2.0M  1.9G   1% /run
         assert_eq!(rows[0].filesystem, "/dev/sda1");
         assert_eq!(rows[0].used_percent, "27%");
     }
+
+    #[test]
+    fn pseudo_filesystems_are_recognized() {
+        let raw = r#"
+Filesystem      Size  Used Avail Use% Mounted on
+/dev/sda1       100G   25G   70G  27% /
+tmpfs           1.9G  2.0M  1.9G   1% /run
+overlay         100G   25G   70G  27% /var/lib/docker/overlay2/abc/merged
+/dev/loop3       56M   56M     0 100% /snap/core18/2812
+"#;
+        let real = parse_disks(raw)
+            .into_iter()
+            .filter(|disk| !is_pseudo_filesystem(disk))
+            .collect::<Vec<_>>();
+        assert_eq!(real.len(), 1);
+        assert_eq!(real[0].filesystem, "/dev/sda1");
+    }
+
+    #[test]
+    fn parse_disks_joins_wrapped_device_names() {
+        let raw = r#"
+Filesystem      Size  Used Avail Use% Mounted on
+/dev/mapper/ip-10.0.0.5:3260-iscsi-iqn.2004-04.com.example:storage-lun-0-vg-data
+                 500G  312G  188G  63% /srv/data
+/dev/sda1       100G   25G   70G  27% /
+"#;
+        let rows = parse_disks(raw);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].filesystem,
+            "/dev/mapper/ip-10.0.0.5:3260-iscsi-iqn.2004-04.com.example:storage-lun-0-vg-data"
+        );
+        assert_eq!(rows[0].total, "500G");
+        assert_eq!(rows[0].used_percent, "63%");
+        assert_eq!(rows[0].mount_point, "/srv/data");
+        assert_eq!(rows[1].filesystem, "/dev/sda1");
+    }
+
+    #[test]
+    fn parse_du_output_keeps_spaces_in_paths() {
+        let raw = "4.0K\t/srv/empty dir\n1.5G\t/srv/data\n12\t/srv/tiny\ngarbage\n";
+        let rows = parse_du_output(raw);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].path, "/srv/empty dir");
+        assert_eq!(rows[0].size_bytes, 4096);
+        assert_eq!(rows[1].size, "1.5G");
+        assert_eq!(rows[1].size_bytes, 1_610_612_736);
+        assert_eq!(rows[2].size_bytes, 12);
+    }
+
+    #[test]
+    fn parse_human_size_accepts_comma_decimals() {
+        assert_eq!(parse_human_size("2,0M"), Some(2 * 1024 * 1024));
+        assert_eq!(parse_human_size("0"), Some(0));
+        assert_eq!(parse_human_size("3X"), None);
+    }
+
+    #[test]
+    fn parse_process_sample_works() {
+        let raw = r#"
+    PID %CPU %MEM   RSS    VSZ
+   4321  7.5  2.3 48212 912344
+"#;
+        let sample = parse_process_sample(raw, "now".to_string()).expect("parse sample");
+        assert_eq!(sample.pid, 4321);
+        assert_eq!(sample.cpu_percent, 7.5);
+        assert_eq!(sample.memory_percent, 2.3);
+        assert_eq!(sample.rss_kb, 48_212);
+        assert_eq!(sample.vsz_kb, 912_344);
+
+        let exited = "    PID %CPU %MEM   RSS    VSZ\n";
+        assert!(parse_process_sample(exited, "now".to_string()).is_none());
+    }
+
+    #[test]
+    fn parse_first_pid_works() {
+        assert_eq!(parse_first_pid("812\n913\n"), Some(812));
+        assert_eq!(parse_first_pid(""), None);
+    }
+
+    #[test]
+    fn parse_os_family_works() {
+        assert_eq!(parse_os_family("Linux\n"), RemoteOsFamily::Linux);
+        assert_eq!(parse_os_family("Darwin\n"), RemoteOsFamily::Darwin);
+        assert_eq!(parse_os_family("FreeBSD\n"), RemoteOsFamily::Bsd);
+        assert_eq!(parse_os_family("OpenBSD"), RemoteOsFamily::Bsd);
+        assert_eq!(parse_os_family(""), RemoteOsFamily::Linux);
+    }
+
+    #[test]
+    fn parse_proc_stat_cpu_uses_jiffy_deltas() {
+        let first = "cpu  4705 356 584 3699176 23060 0 277 0 0 0\n\
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0\n\
+intr 114930548 113199788 3 0 5 263 0 4 [...]\n";
+        let second = "cpu  4805 356 634 3699426 23160 0 277 0 0 0\n\
+cpu0 1393380 32966 572106 13343542 6230 0 17875 0 23933 0\n\
+intr 114931002 113200105 3 0 5 263 0 4 [...]\n";
+        // busy = 100 user + 50 system, idle = 250 idle + 100 iowait.
+        assert_eq!(parse_proc_stat_cpu(first, second), Some(30.0));
+        assert_eq!(parse_proc_stat_cpu(first, first), None);
+        assert_eq!(parse_proc_stat_cpu(second, first), None);
+        assert_eq!(parse_proc_stat_cpu("", second), None);
+    }
+
+    #[test]
+    fn parse_cpu_percent_darwin_top_works() {
+        let top = r#"
+Processes: 512 total, 3 running, 509 sleeping, 2400 threads
+Load Avg: 1.92, 2.10, 2.23
+CPU usage: 5.12% user, 7.68% sys, 87.20% idle
+PhysMem: 15G used (2048M wired), 1024M unused.
+"#;
+        assert_eq!(parse_cpu_percent(top), Some(12.8));
+    }
+
+    #[test]
+    fn parse_cpu_percent_freebsd_top_works() {
+        let top = r#"
+last pid: 12345;  load averages:  0.10,  0.12,  0.09  up 10+01:02:03
+27 processes:  1 running, 26 sleeping
+CPU:  1.5% user,  0.0% nice,  0.8% system,  0.1% interrupt, 97.6% idle
+Mem: 20M Active, 300M Inact, 800M Wired, 2000M Free
+"#;
+        assert_eq!(parse_cpu_percent(top), Some(2.4));
+    }
+
+    #[test]
+    fn parse_vm_stat_memory_works() {
+        let vm_stat = r#"Mach Virtual Memory Statistics: (page size of 16384 bytes)
+Pages free:                               12000.
+Pages active:                            262144.
+Pages inactive:                          250000.
+Pages speculative:                         3000.
+Pages throttled:                              0.
+Pages wired down:                        131072.
+Pages purgeable:                           1000.
+"Translation faults":                 123456789.
+Pages occupied by compressor:             65536.
+"#;
+        let memory = parse_vm_stat_memory(vm_stat, 16 * 1024 * 1024 * 1024).expect("parse vm_stat");
+        assert_eq!(memory.total_mb, 16384.0);
+        assert_eq!(memory.used_mb, 7168.0);
+        assert_eq!(memory.used_percent, 43.75);
+    }
+
+    #[test]
+    fn parse_bsd_sysctl_memory_works() {
+        let raw = "8589934592\n4096\n262144\n262144\n";
+        let memory = parse_bsd_sysctl_memory(raw).expect("parse sysctl");
+        assert_eq!(memory.total_mb, 8192.0);
+        assert_eq!(memory.used_mb, 6144.0);
+        assert_eq!(memory.used_percent, 75.0);
+        assert!(parse_bsd_sysctl_memory("8589934592\n").is_none());
+    }
+
+    #[test]
+    fn parse_netstat_interfaces_darwin_works() {
+        let raw = r#"
+Name       Mtu   Network       Address            Ipkts Ierrs     Ibytes    Opkts Oerrs     Obytes  Coll
+lo0        16384 <Link#1>                         40231     0    9321412    40231     0    9321412     0
+lo0        16384 127           localhost          40231     -    9321412    40231     -    9321412     -
+en0        1500  <Link#6>    a4:83:e7:11:22:33  1848210     0 2204411992   901223     0  141233120     0
+en0        1500  192.168.1     192.168.1.20     1848000     - 2204400000   901000     -  141200000     -
+"#;
+        let rows = parse_netstat_interfaces(raw);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].interface, "lo0");
+        assert_eq!(rows[0].rx_bytes, 9_321_412);
+        assert_eq!(rows[1].interface, "en0");
+        assert_eq!(rows[1].rx_bytes, 2_204_411_992);
+        assert_eq!(rows[1].tx_bytes, 141_233_120);
+    }
+
+    #[test]
+    fn parse_netstat_interfaces_freebsd_works() {
+        let raw = r#"
+Name    Mtu Network       Address              Ipkts Ierrs Idrop     Ibytes    Opkts Oerrs     Obytes  Coll
+vtnet0 1500 <Link#1>      52:54:00:12:34:56   512345     0     0  734512345   312345     0   42512345     0
+vtnet0    - 10.0.0.0/24   10.0.0.15           512000     -     -  734500000   312000     -   42500000     -
+lo0   16384 <Link#2>      lo0                   1200     0     0     102400     1200     0     102400     0
+"#;
+        let rows = parse_netstat_interfaces(raw);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].interface, "vtnet0");
+        assert_eq!(rows[0].rx_bytes, 734_512_345);
+        assert_eq!(rows[0].tx_bytes, 42_512_345);
+        assert_eq!(rows[1].interface, "lo0");
+    }
+
+    #[test]
+    fn parse_zombie_processes_works() {
+        let raw = r#"
+    PID    PPID STAT COMMAND
+      1       0 Ss   systemd
+   4100    4000 Z    worker <defunct>
+   4101    4000 Z+   worker
+   4200       1 Zs   cron
+   4300    4300 S<l  java
+"#;
+        let rows = parse_zombie_processes(raw);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].pid, 4100);
+        assert_eq!(rows[0].ppid, 4000);
+        assert_eq!(rows[0].command, "worker <defunct>");
+        assert_eq!(rows[1].stat, "Z+");
+        assert_eq!(rows[2].ppid, 1);
+
+        assert!(parse_zombie_processes("PID PPID STAT COMMAND\n1 0 Ss init\n").is_empty());
+    }
+
+    #[test]
+    fn apply_network_rates_works() {
+        let interface = |name: &str, rx_bytes: u64, tx_bytes: u64| NetworkInterfaceStatus {
+            interface: name.to_string(),
+            rx_bytes,
+            tx_bytes,
+            rx_rate_bps: 0.0,
+            tx_rate_bps: 0.0,
+        };
+        let previous = vec![
+            interface("eth0", 1_000, 4_000),
+            interface("eth1", u64::from(u32::MAX) - 99, 9_000),
+        ];
+        let mut current = vec![
+            interface("eth0", 3_000, 5_000),
+            interface("eth1", 100, 1_000),
+            interface("docker0", 500, 500),
+        ];
+        apply_network_rates(&mut current, &previous, 2.0);
+
+        assert_eq!(current[0].rx_rate_bps, 1_000.0);
+        assert_eq!(current[0].tx_rate_bps, 500.0);
+        // rx wrapped around the 32-bit boundary; tx dropped without wrapping.
+        assert_eq!(current[1].rx_rate_bps, 100.0);
+        assert_eq!(current[1].tx_rate_bps, 0.0);
+        // First sample for an interface reports zero.
+        assert_eq!(current[2].rx_rate_bps, 0.0);
+    }
 }
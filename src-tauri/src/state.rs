@@ -1,24 +1,68 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::RwLock;
 
+use ssh2::Session;
+
 use crate::error::{AppError, AppResult};
-use crate::models::{ServerStatus, ShellSession};
+use crate::models::{
+    now_rfc3339, CommandHistoryEntry, FileWatch, PortForward, PtySignal, ScriptSchedule,
+    ServerStatus, SessionManifestEntry, ShellSession, SshHandshakeInfo,
+};
 use crate::ops_agent::infrastructure::agent_trace_store::OpsAgentTraceStore;
 use crate::ops_agent::infrastructure::attachments::OpsAgentAttachmentStore;
 use crate::ops_agent::infrastructure::run_registry::OpsAgentRunRegistry;
 use crate::ops_agent::infrastructure::store::OpsAgentStore;
 use crate::ops_agent::tools::{default_ops_agent_tool_registry, OpsAgentToolRegistry};
+use crate::server_ops::{PtyRecorder, ScrollbackBuffer};
 use crate::storage::Storage;
 
+/// Bytes of recent output kept per session as `ShellSession::last_output` for AI context.
+pub const SESSION_LAST_OUTPUT_BYTES: usize = 16_000;
+
+/// Number of `execute_command` runs kept per session.
+pub const MAX_COMMAND_HISTORY_ENTRIES: usize = 500;
+
+/// Background worker ids for status polling are `status-poll:<session_id>:<uuid>`.
+pub const STATUS_POLLING_WORKER_PREFIX: &str = "status-poll:";
+
+/// Background worker ids for port forwards are `port-forward:<session_id>:<forward_id>`.
+pub const PORT_FORWARD_WORKER_PREFIX: &str = "port-forward:";
+
+/// Background worker ids for script schedules are `script-schedule:<session_id>:<schedule_id>`.
+pub const SCRIPT_SCHEDULE_WORKER_PREFIX: &str = "script-schedule:";
+
+/// Background worker ids for file watches are `file-watch:<session_id>:<watch_id>`.
+pub const FILE_WATCH_WORKER_PREFIX: &str = "file-watch:";
+
+/// Background worker ids for process watches are `process-watch:<session_id>:<watch_id>`.
+pub const PROCESS_WATCH_WORKER_PREFIX: &str = "process-watch:";
+
 #[derive(Debug, Clone)]
 pub enum PtyCommand {
     Input(String),
     Resize { cols: u16, rows: u16 },
+    Signal(PtySignal),
     Close,
 }
 
+/// One SSH connection whose PTY channels back several shell tabs of the same config.
+///
+/// The host details are captured when the connection is opened, because a
+/// session already driving PTY workers is non-blocking and cannot run setup
+/// commands like `pwd` again.
+#[derive(Clone)]
+pub struct SharedSshSession {
+    /// Tells a replacement connection apart from the one a lease was taken on.
+    pub pool_id: String,
+    pub session: Session,
+    pub login_dir: String,
+    pub remote_os: Option<String>,
+    pub handshake: SshHandshakeInfo,
+}
+
 /// Shared application state managed by Tauri.
 ///
 /// Design goals:
@@ -33,10 +77,33 @@ pub struct AppState {
     pub ops_agent_tools: OpsAgentToolRegistry,
     pub ops_agent_runs: OpsAgentRunRegistry,
     sessions: RwLock<HashMap<String, ShellSession>>,
+    session_output: RwLock<HashMap<String, ScrollbackBuffer>>,
+    restorable_sessions: RwLock<Vec<SessionManifestEntry>>,
     status_cache: RwLock<HashMap<String, ServerStatus>>,
+    command_history: RwLock<HashMap<String, VecDeque<CommandHistoryEntry>>>,
     pty_channels: RwLock<HashMap<String, Sender<PtyCommand>>>,
+    pty_sizes: RwLock<HashMap<String, (u16, u16)>>,
+    pty_terms: RwLock<HashMap<String, String>>,
+    /// Shell session id -> the SSH connection its PTY worker currently runs on.
+    pty_connections: RwLock<HashMap<String, Session>>,
+    /// Sessions whose PTY worker dropped and is waiting to reconnect.
+    pty_reconnecting: RwLock<HashSet<String>>,
+    pty_recordings: RwLock<HashMap<String, PtyRecorder>>,
+    pty_scrollback: RwLock<HashMap<String, ScrollbackBuffer>>,
+    pty_bracketed_paste: RwLock<HashMap<String, bool>>,
     shell_connection_cancellations: RwLock<HashMap<String, bool>>,
     sftp_transfer_cancellations: RwLock<HashMap<String, bool>>,
+    ai_ask_cancellations: RwLock<HashMap<String, bool>>,
+    background_workers: RwLock<HashMap<String, bool>>,
+    port_forwards: RwLock<HashMap<String, PortForward>>,
+    script_schedules: RwLock<HashMap<String, ScriptSchedule>>,
+    file_watches: RwLock<HashMap<String, FileWatch>>,
+    /// Config id -> shared connection and how many shell sessions use it.
+    shared_ssh: RwLock<HashMap<String, (SharedSshSession, usize)>>,
+    /// Shell session id -> (config id, pool id) of the shared connection it uses.
+    shared_ssh_leases: RwLock<HashMap<String, (String, String)>>,
+    /// Set by `begin_shutdown`; freezes `sessions.json` for the next launch.
+    shutting_down: AtomicBool,
 }
 
 impl AppState {
@@ -50,50 +117,86 @@ impl AppState {
         storage_root: PathBuf,
         ops_agent_tools: OpsAgentToolRegistry,
     ) -> AppResult<Self> {
+        let storage = Storage::new(storage_root.clone())?;
+        // An unreadable manifest only costs the restore offer, never startup.
+        let restorable_sessions = storage.load_session_manifest().unwrap_or_default();
         Ok(Self {
-            storage: Storage::new(storage_root.clone())?,
+            storage,
             ops_agent: OpsAgentStore::new(storage_root.clone())?,
             ops_agent_attachments: OpsAgentAttachmentStore::new(storage_root.clone())?,
             ops_agent_traces: OpsAgentTraceStore::new(storage_root)?,
             ops_agent_tools,
             ops_agent_runs: OpsAgentRunRegistry::new(),
             sessions: RwLock::new(HashMap::new()),
+            session_output: RwLock::new(HashMap::new()),
+            restorable_sessions: RwLock::new(restorable_sessions),
             status_cache: RwLock::new(HashMap::new()),
+            command_history: RwLock::new(HashMap::new()),
             pty_channels: RwLock::new(HashMap::new()),
+            pty_sizes: RwLock::new(HashMap::new()),
+            pty_terms: RwLock::new(HashMap::new()),
+            pty_connections: RwLock::new(HashMap::new()),
+            pty_reconnecting: RwLock::new(HashSet::new()),
+            pty_recordings: RwLock::new(HashMap::new()),
+            pty_scrollback: RwLock::new(HashMap::new()),
+            pty_bracketed_paste: RwLock::new(HashMap::new()),
             shell_connection_cancellations: RwLock::new(HashMap::new()),
             sftp_transfer_cancellations: RwLock::new(HashMap::new()),
+            ai_ask_cancellations: RwLock::new(HashMap::new()),
+            background_workers: RwLock::new(HashMap::new()),
+            port_forwards: RwLock::new(HashMap::new()),
+            script_schedules: RwLock::new(HashMap::new()),
+            file_watches: RwLock::new(HashMap::new()),
+            shared_ssh: RwLock::new(HashMap::new()),
+            shared_ssh_leases: RwLock::new(HashMap::new()),
+            shutting_down: AtomicBool::new(false),
         })
     }
 
     /// Returns all active shell sessions.
     pub fn list_sessions(&self) -> Vec<ShellSession> {
-        self.sessions
+        let sessions = self
+            .sessions
             .read()
             .expect("session lock poisoned")
             .values()
             .cloned()
+            .collect::<Vec<_>>();
+        sessions
+            .into_iter()
+            .map(|session| self.with_last_output(session))
             .collect()
     }
 
     /// Stores or updates a shell session in the runtime registry.
-    pub fn put_session(&self, session: ShellSession) {
+    ///
+    /// `last_output` seeds the session's output buffer; the registry copy keeps it empty.
+    pub fn put_session(&self, mut session: ShellSession) {
+        self.replace_session_output(&session.id, &std::mem::take(&mut session.last_output));
         self.sessions
             .write()
             .expect("session lock poisoned")
             .insert(session.id.clone(), session);
+        self.persist_session_manifest();
     }
 
     /// Retrieves a shell session by id.
     pub fn get_session(&self, session_id: &str) -> AppResult<ShellSession> {
-        self.sessions
+        let session = self
+            .sessions
             .read()
             .expect("session lock poisoned")
             .get(session_id)
             .cloned()
-            .ok_or_else(|| AppError::NotFound(format!("shell session {session_id}")))
+            .ok_or_else(|| AppError::NotFound(format!("shell session {session_id}")))?;
+        Ok(self.with_last_output(session))
     }
 
     /// Applies an update closure to a session atomically.
+    ///
+    /// The closure sees the registry copy, whose `last_output` is always empty;
+    /// anything written to it is dropped. Buffered output only changes through
+    /// `put_session` and `with_session_output`.
     pub fn mutate_session<F>(&self, session_id: &str, mutator: F) -> AppResult<ShellSession>
     where
         F: FnOnce(&mut ShellSession),
@@ -102,29 +205,206 @@ impl AppState {
         let session = guard
             .get_mut(session_id)
             .ok_or_else(|| AppError::NotFound(format!("shell session {session_id}")))?;
+        let previous_dir = session.current_dir.clone();
         mutator(session);
-        Ok(session.clone())
+        session.last_output.clear();
+        let session = session.clone();
+        drop(guard);
+        // Output updates are frequent; only a directory change affects the manifest.
+        if session.current_dir != previous_dir {
+            self.persist_session_manifest();
+        }
+        Ok(self.with_last_output(session))
+    }
+
+    /// Bumps `updated_at` without copying the session out of the registry.
+    pub fn touch_session(&self, session_id: &str) {
+        if let Some(session) = self
+            .sessions
+            .write()
+            .expect("session lock poisoned")
+            .get_mut(session_id)
+        {
+            session.updated_at = now_rfc3339();
+        }
+    }
+
+    /// Replaces the buffered recent output of one session.
+    pub fn replace_session_output(&self, session_id: &str, output: &str) {
+        let mut buffer = ScrollbackBuffer::new(SESSION_LAST_OUTPUT_BYTES);
+        buffer.push_str(output);
+        self.session_output
+            .write()
+            .expect("session output lock poisoned")
+            .insert(session_id.to_string(), buffer);
+    }
+
+    /// Gives mutable access to the buffered recent output of one session, if any.
+    pub fn with_session_output<F>(&self, session_id: &str, update: F)
+    where
+        F: FnOnce(&mut ScrollbackBuffer),
+    {
+        if let Some(buffer) = self
+            .session_output
+            .write()
+            .expect("session output lock poisoned")
+            .get_mut(session_id)
+        {
+            update(buffer);
+        }
+    }
+
+    fn with_last_output(&self, mut session: ShellSession) -> ShellSession {
+        if let Some(buffer) = self
+            .session_output
+            .read()
+            .expect("session output lock poisoned")
+            .get(&session.id)
+        {
+            session.last_output = buffer.tail(usize::MAX);
+        }
+        session
+    }
+
+    /// Hands out the sessions left over from the previous run, once.
+    pub fn take_restorable_sessions(&self) -> Vec<SessionManifestEntry> {
+        let entries = std::mem::take(
+            &mut *self
+                .restorable_sessions
+                .write()
+                .expect("restorable session lock poisoned"),
+        );
+        self.persist_session_manifest();
+        entries
+    }
+
+    /// Writes open sessions, plus any not yet restored, to `sessions.json`.
+    ///
+    /// Skipped during shutdown, so closing the sessions keeps them restorable.
+    fn persist_session_manifest(&self) {
+        if self.is_shutting_down() {
+            return;
+        }
+        let mut entries = self
+            .restorable_sessions
+            .read()
+            .expect("restorable session lock poisoned")
+            .clone();
+        let mut sessions = self.list_sessions();
+        sessions.sort_by(|left, right| left.created_at.cmp(&right.created_at));
+        for session in sessions {
+            if entries.iter().all(|entry| entry.id != session.id) {
+                entries.push(SessionManifestEntry {
+                    id: session.id,
+                    config_id: session.config_id,
+                    current_dir: session.current_dir,
+                    previous_dir: session.previous_dir,
+                });
+            }
+        }
+        let _ = self.storage.save_session_manifest(&entries);
+    }
+
+    /// Marks the app as shutting down; true only for the first caller.
+    pub fn begin_shutdown(&self) -> bool {
+        !self.shutting_down.swap(true, Ordering::SeqCst)
+    }
+
+    /// Clears the latch again after a shutdown that was not an app exit.
+    pub fn end_shutdown(&self) {
+        self.shutting_down.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
     }
 
     /// Removes a shell session and any stale cache bound to that session.
     pub fn remove_session(&self, session_id: &str) -> AppResult<()> {
-        self.remove_pty_channel(session_id);
-
+        // Unregister first: `install_pty_worker` re-checks the registry, so a
+        // reconnecting worker cannot slip in after its channel was removed.
         let removed = self
             .sessions
             .write()
             .expect("session lock poisoned")
             .remove(session_id);
+        self.remove_pty_channel(session_id);
+        self.release_shared_ssh(session_id);
+        self.stop_status_polling(session_id);
+        self.stop_session_port_forwards(session_id);
+        self.stop_session_script_schedules(session_id);
+        self.stop_session_file_watches(session_id);
+        self.stop_session_process_watches(session_id);
+        if let Some(recorder) = self.take_pty_recording(session_id) {
+            let _ = recorder.finish();
+        }
+        self.pty_sizes
+            .write()
+            .expect("pty size lock poisoned")
+            .remove(session_id);
+        self.pty_terms
+            .write()
+            .expect("pty term lock poisoned")
+            .remove(session_id);
+        self.pty_connections
+            .write()
+            .expect("pty connection lock poisoned")
+            .remove(session_id);
+        self.end_pty_reconnect(session_id);
+        self.pty_scrollback
+            .write()
+            .expect("pty scrollback lock poisoned")
+            .remove(session_id);
+        self.session_output
+            .write()
+            .expect("session output lock poisoned")
+            .remove(session_id);
+        self.pty_bracketed_paste
+            .write()
+            .expect("pty bracketed paste lock poisoned")
+            .remove(session_id);
+
         if removed.is_none() {
             return Err(AppError::NotFound(format!("shell session {session_id}")));
         }
+        self.persist_session_manifest();
         self.status_cache
             .write()
             .expect("status cache lock poisoned")
             .remove(session_id);
+        self.command_history
+            .write()
+            .expect("command history lock poisoned")
+            .remove(session_id);
         Ok(())
     }
 
+    /// Appends one command to the session history, dropping the oldest beyond the cap.
+    pub fn record_command_history(&self, session_id: &str, entry: CommandHistoryEntry) {
+        let mut guard = self
+            .command_history
+            .write()
+            .expect("command history lock poisoned");
+        let history = guard.entry(session_id.to_string()).or_default();
+        if history.len() == MAX_COMMAND_HISTORY_ENTRIES {
+            history.pop_front();
+        }
+        history.push_back(entry);
+    }
+
+    /// Returns up to `limit` most recent commands of one session, oldest first.
+    pub fn get_command_history(&self, session_id: &str, limit: usize) -> Vec<CommandHistoryEntry> {
+        self.command_history
+            .read()
+            .expect("command history lock poisoned")
+            .get(session_id)
+            .map(|history| {
+                let skip = history.len().saturating_sub(limit);
+                history.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Registers or replaces PTY control channel for one shell session.
     pub fn put_pty_channel(&self, session_id: String, sender: Sender<PtyCommand>) {
         if let Some(previous) = self
@@ -137,8 +417,58 @@ impl AppState {
         }
     }
 
+    /// Registers a new PTY worker's channel and connection, unless the session is gone.
+    ///
+    /// The session registry stays locked while both are installed, so a session
+    /// removed concurrently never ends up with a worker nobody will close. Also
+    /// ends a pending reconnect.
+    pub fn install_pty_worker(
+        &self,
+        session_id: &str,
+        sender: Sender<PtyCommand>,
+        ssh: Session,
+    ) -> bool {
+        let sessions = self.sessions.read().expect("session lock poisoned");
+        if !sessions.contains_key(session_id) {
+            return false;
+        }
+        self.put_pty_channel(session_id.to_string(), sender);
+        self.put_pty_connection(session_id, ssh);
+        self.end_pty_reconnect(session_id);
+        true
+    }
+
+    /// Marks a session's PTY as reconnecting, so input gets a clear error meanwhile.
+    ///
+    /// Cleared by `install_pty_worker` or when the session is removed.
+    pub fn begin_pty_reconnect(&self, session_id: &str) {
+        self.pty_reconnecting
+            .write()
+            .expect("pty reconnect lock poisoned")
+            .insert(session_id.to_string());
+    }
+
+    fn end_pty_reconnect(&self, session_id: &str) {
+        self.pty_reconnecting
+            .write()
+            .expect("pty reconnect lock poisoned")
+            .remove(session_id);
+    }
+
     /// Sends PTY control message to one shell session worker.
+    ///
+    /// Fails with a "reconnecting" error while the worker is being reopened.
     pub fn send_pty_command(&self, session_id: &str, command: PtyCommand) -> AppResult<()> {
+        if self
+            .pty_reconnecting
+            .read()
+            .expect("pty reconnect lock poisoned")
+            .contains(session_id)
+        {
+            return Err(AppError::Runtime(format!(
+                "pty session {session_id} is reconnecting, try again shortly"
+            )));
+        }
         let sender = self
             .pty_channels
             .read()
@@ -151,6 +481,14 @@ impl AppState {
         })
     }
 
+    /// True while a PTY worker is registered for the session.
+    pub fn has_pty_channel(&self, session_id: &str) -> bool {
+        self.pty_channels
+            .read()
+            .expect("pty channel lock poisoned")
+            .contains_key(session_id)
+    }
+
     /// Unregisters PTY channel and asks worker to stop.
     pub fn remove_pty_channel(&self, session_id: &str) {
         if let Some(sender) = self
@@ -163,6 +501,259 @@ impl AppState {
         }
     }
 
+    /// Remembers the SSH connection a session's PTY worker runs on.
+    fn put_pty_connection(&self, session_id: &str, ssh: Session) {
+        self.pty_connections
+            .write()
+            .expect("pty connection lock poisoned")
+            .insert(session_id.to_string(), ssh);
+    }
+
+    /// Returns the SSH connection a session's PTY worker runs on, if it has one.
+    pub fn get_pty_connection(&self, session_id: &str) -> Option<Session> {
+        self.pty_connections
+            .read()
+            .expect("pty connection lock poisoned")
+            .get(session_id)
+            .cloned()
+    }
+
+    /// Remembers the last PTY size applied to one shell session.
+    pub fn put_pty_size(&self, session_id: &str, cols: u16, rows: u16) {
+        self.pty_sizes
+            .write()
+            .expect("pty size lock poisoned")
+            .insert(session_id.to_string(), (cols, rows));
+    }
+
+    /// Remembers the terminal type a shell session's PTY was opened with.
+    pub fn put_pty_term(&self, session_id: &str, term: &str) {
+        self.pty_terms
+            .write()
+            .expect("pty term lock poisoned")
+            .insert(session_id.to_string(), term.to_string());
+    }
+
+    /// Returns the terminal type of one shell session's PTY, if it has one.
+    pub fn get_pty_term(&self, session_id: &str) -> Option<String> {
+        self.pty_terms
+            .read()
+            .expect("pty term lock poisoned")
+            .get(session_id)
+            .cloned()
+    }
+
+    /// Leases the shared connection for `config_id` to one shell session, if there is one.
+    pub fn acquire_shared_ssh(
+        &self,
+        config_id: &str,
+        session_id: &str,
+    ) -> Option<SharedSshSession> {
+        let shared = {
+            let mut guard = self.shared_ssh.write().expect("shared ssh lock poisoned");
+            let (shared, users) = guard.get_mut(config_id)?;
+            *users += 1;
+            shared.clone()
+        };
+        self.shared_ssh_leases
+            .write()
+            .expect("shared ssh lease lock poisoned")
+            .insert(
+                session_id.to_string(),
+                (config_id.to_string(), shared.pool_id.clone()),
+            );
+        Some(shared)
+    }
+
+    /// Offers a fresh connection for sharing and leases it to its first shell session.
+    ///
+    /// When another connection for the config is already shared, this one stays
+    /// private to `session_id` and closes with it.
+    pub fn register_shared_ssh(&self, config_id: &str, session_id: &str, shared: SharedSshSession) {
+        let pool_id = shared.pool_id.clone();
+        {
+            let mut guard = self.shared_ssh.write().expect("shared ssh lock poisoned");
+            if guard.contains_key(config_id) {
+                return;
+            }
+            guard.insert(config_id.to_string(), (shared, 1));
+        }
+        self.shared_ssh_leases
+            .write()
+            .expect("shared ssh lease lock poisoned")
+            .insert(session_id.to_string(), (config_id.to_string(), pool_id));
+    }
+
+    /// Drops one shell session's lease; the last lease unregisters the connection.
+    pub fn release_shared_ssh(&self, session_id: &str) {
+        let Some((config_id, pool_id)) = self
+            .shared_ssh_leases
+            .write()
+            .expect("shared ssh lease lock poisoned")
+            .remove(session_id)
+        else {
+            return;
+        };
+        let mut guard = self.shared_ssh.write().expect("shared ssh lock poisoned");
+        if let Some((shared, users)) = guard.get_mut(&config_id) {
+            if shared.pool_id == pool_id {
+                *users = users.saturating_sub(1);
+                if *users == 0 {
+                    guard.remove(&config_id);
+                }
+            }
+        }
+    }
+
+    /// Stops sharing the connection a session leased, e.g. after it failed to open a channel.
+    ///
+    /// Sessions already running on it keep their own handle until they close.
+    pub fn discard_shared_ssh(&self, session_id: &str) {
+        let Some((config_id, pool_id)) = self
+            .shared_ssh_leases
+            .write()
+            .expect("shared ssh lease lock poisoned")
+            .remove(session_id)
+        else {
+            return;
+        };
+        self.discard_shared_ssh_pool(&config_id, &pool_id);
+    }
+
+    /// Stops sharing one connection of `config_id`, unless it was already replaced.
+    pub fn discard_shared_ssh_pool(&self, config_id: &str, pool_id: &str) {
+        let mut guard = self.shared_ssh.write().expect("shared ssh lock poisoned");
+        if guard
+            .get(config_id)
+            .is_some_and(|(shared, _)| shared.pool_id == pool_id)
+        {
+            guard.remove(config_id);
+        }
+    }
+
+    /// Unregisters every shared connection and drops all leases, returning the connections.
+    pub fn take_shared_ssh_sessions(&self) -> Vec<Session> {
+        self.shared_ssh_leases
+            .write()
+            .expect("shared ssh lease lock poisoned")
+            .clear();
+        self.shared_ssh
+            .write()
+            .expect("shared ssh lock poisoned")
+            .drain()
+            .map(|(_, (shared, _))| shared.session)
+            .collect()
+    }
+
+    /// Returns the last PTY size applied to one shell session, if any.
+    pub fn get_pty_size(&self, session_id: &str) -> Option<(u16, u16)> {
+        self.pty_sizes
+            .read()
+            .expect("pty size lock poisoned")
+            .get(session_id)
+            .copied()
+    }
+
+    /// Allocates the scrollback ring buffer for one shell session.
+    pub fn init_pty_scrollback(&self, session_id: &str, capacity: usize) {
+        self.pty_scrollback
+            .write()
+            .expect("pty scrollback lock poisoned")
+            .insert(session_id.to_string(), ScrollbackBuffer::new(capacity));
+    }
+
+    /// Runs `update` against the session scrollback, if one is allocated.
+    pub fn with_pty_scrollback<F>(&self, session_id: &str, update: F)
+    where
+        F: FnOnce(&mut ScrollbackBuffer),
+    {
+        if let Some(buffer) = self
+            .pty_scrollback
+            .write()
+            .expect("pty scrollback lock poisoned")
+            .get_mut(session_id)
+        {
+            update(buffer);
+        }
+    }
+
+    /// Returns up to `max_bytes` of the most recent scrollback for one session.
+    pub fn get_pty_scrollback(&self, session_id: &str, max_bytes: usize) -> AppResult<String> {
+        self.pty_scrollback
+            .read()
+            .expect("pty scrollback lock poisoned")
+            .get(session_id)
+            .map(|buffer| buffer.tail(max_bytes))
+            .ok_or_else(|| AppError::NotFound(format!("pty session {session_id}")))
+    }
+
+    /// Records whether the remote shell has bracketed paste mode switched on.
+    pub fn set_pty_bracketed_paste(&self, session_id: &str, enabled: bool) {
+        self.pty_bracketed_paste
+            .write()
+            .expect("pty bracketed paste lock poisoned")
+            .insert(session_id.to_string(), enabled);
+    }
+
+    /// True once the remote shell has requested bracketed paste and not turned it off.
+    pub fn is_pty_bracketed_paste(&self, session_id: &str) -> bool {
+        self.pty_bracketed_paste
+            .read()
+            .expect("pty bracketed paste lock poisoned")
+            .get(session_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    pub fn has_pty_recording(&self, session_id: &str) -> bool {
+        self.pty_recordings
+            .read()
+            .expect("pty recording lock poisoned")
+            .contains_key(session_id)
+    }
+
+    /// Registers an active recording; a session can only have one at a time.
+    pub fn put_pty_recording(&self, session_id: &str, recorder: PtyRecorder) -> AppResult<()> {
+        let mut guard = self
+            .pty_recordings
+            .write()
+            .expect("pty recording lock poisoned");
+        if guard.contains_key(session_id) {
+            return Err(AppError::Validation(format!(
+                "pty session {session_id} is already being recorded"
+            )));
+        }
+        guard.insert(session_id.to_string(), recorder);
+        Ok(())
+    }
+
+    /// Runs `write` against the session recording when one is active.
+    ///
+    /// A failed write stops the recording so a full disk does not stall the PTY.
+    pub fn with_pty_recording<F>(&self, session_id: &str, write: F)
+    where
+        F: FnOnce(&mut PtyRecorder) -> AppResult<()>,
+    {
+        let mut guard = self
+            .pty_recordings
+            .write()
+            .expect("pty recording lock poisoned");
+        let Some(recorder) = guard.get_mut(session_id) else {
+            return;
+        };
+        if write(recorder).is_err() {
+            guard.remove(session_id);
+        }
+    }
+
+    /// Unregisters and returns the active recording for one session.
+    pub fn take_pty_recording(&self, session_id: &str) -> Option<PtyRecorder> {
+        self.pty_recordings
+            .write()
+            .expect("pty recording lock poisoned")
+            .remove(session_id)
+    }
+
     /// Marks one shell connection attempt as active unless it was already pre-cancelled.
     pub fn begin_shell_connection(&self, request_id: &str) {
         self.shell_connection_cancellations
@@ -201,6 +792,50 @@ impl AppState {
             .remove(request_id);
     }
 
+    /// Marks one `ai_ask` request as in flight; `clear_ai_ask` drops it again.
+    pub fn begin_ai_ask(&self, request_id: &str) {
+        self.ai_ask_cancellations
+            .write()
+            .expect("ai ask cancellation lock poisoned")
+            .insert(request_id.to_string(), false);
+    }
+
+    /// Requests cancellation for an in-flight `ai_ask` request.
+    ///
+    /// Unknown or finished ids are ignored so stray cancels never pile up.
+    pub fn cancel_ai_ask(&self, request_id: &str) -> bool {
+        match self
+            .ai_ask_cancellations
+            .write()
+            .expect("ai ask cancellation lock poisoned")
+            .get_mut(request_id)
+        {
+            Some(cancelled) => {
+                *cancelled = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether an `ai_ask` request is cancelled.
+    pub fn is_ai_ask_cancelled(&self, request_id: &str) -> bool {
+        self.ai_ask_cancellations
+            .read()
+            .expect("ai ask cancellation lock poisoned")
+            .get(request_id)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Clears one `ai_ask` cancellation marker.
+    pub fn clear_ai_ask(&self, request_id: &str) {
+        self.ai_ask_cancellations
+            .write()
+            .expect("ai ask cancellation lock poisoned")
+            .remove(request_id);
+    }
+
     /// Returns cached status for a session when available.
     pub fn get_cached_status(&self, session_id: &str) -> Option<ServerStatus> {
         self.status_cache
@@ -255,4 +890,391 @@ impl AppState {
             .expect("sftp cancellation lock poisoned")
             .remove(transfer_id);
     }
+
+    /// Registers a long-running background worker (process watch, polling, ...).
+    pub fn begin_background_worker(&self, worker_id: &str) {
+        self.background_workers
+            .write()
+            .expect("background worker lock poisoned")
+            .entry(worker_id.to_string())
+            .or_insert(false);
+    }
+
+    /// Requests a background worker to stop. Returns whether the worker was running.
+    pub fn cancel_background_worker(&self, worker_id: &str) -> bool {
+        let mut guard = self
+            .background_workers
+            .write()
+            .expect("background worker lock poisoned");
+        match guard.get_mut(worker_id) {
+            Some(cancelled) => {
+                *cancelled = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Checks whether a background worker was asked to stop.
+    pub fn is_background_worker_cancelled(&self, worker_id: &str) -> bool {
+        self.background_workers
+            .read()
+            .expect("background worker lock poisoned")
+            .get(worker_id)
+            .copied()
+            .unwrap_or(true)
+    }
+
+    /// Unregisters one background worker.
+    pub fn clear_background_worker(&self, worker_id: &str) {
+        self.background_workers
+            .write()
+            .expect("background worker lock poisoned")
+            .remove(worker_id);
+    }
+
+    /// Requests every background worker whose id starts with `prefix` to stop.
+    pub fn cancel_background_workers_with_prefix(&self, prefix: &str) -> usize {
+        let mut guard = self
+            .background_workers
+            .write()
+            .expect("background worker lock poisoned");
+        let mut cancelled = 0;
+        for (worker_id, flag) in guard.iter_mut() {
+            if worker_id.starts_with(prefix) && !*flag {
+                *flag = true;
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    /// Stops the status polling worker of one session. Returns whether one was running.
+    pub fn stop_status_polling(&self, session_id: &str) -> bool {
+        let prefix = format!("{STATUS_POLLING_WORKER_PREFIX}{session_id}:");
+        self.cancel_background_workers_with_prefix(&prefix) > 0
+    }
+
+    /// Registers an active port forward.
+    pub fn put_port_forward(&self, forward: PortForward) {
+        self.port_forwards
+            .write()
+            .expect("port forward lock poisoned")
+            .insert(forward.forward_id.clone(), forward);
+    }
+
+    /// Unregisters one port forward without stopping its worker.
+    pub fn remove_port_forward(&self, forward_id: &str) -> Option<PortForward> {
+        self.port_forwards
+            .write()
+            .expect("port forward lock poisoned")
+            .remove(forward_id)
+    }
+
+    /// Stops one port forward. Returns whether it was active.
+    pub fn stop_port_forward(&self, forward_id: &str) -> bool {
+        match self.remove_port_forward(forward_id) {
+            Some(forward) => {
+                self.cancel_background_worker(&format!(
+                    "{PORT_FORWARD_WORKER_PREFIX}{}:{forward_id}",
+                    forward.session_id
+                ));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops every port forward bound to one session.
+    pub fn stop_session_port_forwards(&self, session_id: &str) {
+        self.port_forwards
+            .write()
+            .expect("port forward lock poisoned")
+            .retain(|_, forward| forward.session_id != session_id);
+        let prefix = format!("{PORT_FORWARD_WORKER_PREFIX}{session_id}:");
+        self.cancel_background_workers_with_prefix(&prefix);
+    }
+
+    /// Registers a script schedule.
+    pub fn put_script_schedule(&self, schedule: ScriptSchedule) {
+        self.script_schedules
+            .write()
+            .expect("script schedule lock poisoned")
+            .insert(schedule.schedule_id.clone(), schedule);
+    }
+
+    /// Returns script schedules, optionally limited to one session, oldest first.
+    pub fn list_script_schedules(&self, session_id: Option<&str>) -> Vec<ScriptSchedule> {
+        let mut schedules = self
+            .script_schedules
+            .read()
+            .expect("script schedule lock poisoned")
+            .values()
+            .filter(|schedule| session_id.is_none_or(|id| schedule.session_id == id))
+            .cloned()
+            .collect::<Vec<_>>();
+        schedules.sort_by(|left, right| left.created_at.cmp(&right.created_at));
+        schedules
+    }
+
+    /// Applies an update to one schedule; returns the updated copy when it still exists.
+    pub fn mutate_script_schedule<F>(&self, schedule_id: &str, mutator: F) -> Option<ScriptSchedule>
+    where
+        F: FnOnce(&mut ScriptSchedule),
+    {
+        let mut guard = self
+            .script_schedules
+            .write()
+            .expect("script schedule lock poisoned");
+        let schedule = guard.get_mut(schedule_id)?;
+        mutator(schedule);
+        Some(schedule.clone())
+    }
+
+    /// Stops and unregisters one script schedule. Returns whether it existed.
+    pub fn stop_script_schedule(&self, schedule_id: &str) -> bool {
+        let removed = self
+            .script_schedules
+            .write()
+            .expect("script schedule lock poisoned")
+            .remove(schedule_id);
+        match removed {
+            Some(schedule) => {
+                self.cancel_background_worker(&format!(
+                    "{SCRIPT_SCHEDULE_WORKER_PREFIX}{}:{schedule_id}",
+                    schedule.session_id
+                ));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops every script schedule bound to one session.
+    pub fn stop_session_script_schedules(&self, session_id: &str) {
+        self.script_schedules
+            .write()
+            .expect("script schedule lock poisoned")
+            .retain(|_, schedule| schedule.session_id != session_id);
+        let prefix = format!("{SCRIPT_SCHEDULE_WORKER_PREFIX}{session_id}:");
+        self.cancel_background_workers_with_prefix(&prefix);
+    }
+
+    /// Registers a file watch.
+    pub fn put_file_watch(&self, watch: FileWatch) {
+        self.file_watches
+            .write()
+            .expect("file watch lock poisoned")
+            .insert(watch.watch_id.clone(), watch);
+    }
+
+    /// Stops and unregisters one file watch. Returns whether it existed.
+    pub fn stop_file_watch(&self, watch_id: &str) -> bool {
+        let removed = self
+            .file_watches
+            .write()
+            .expect("file watch lock poisoned")
+            .remove(watch_id);
+        match removed {
+            Some(watch) => {
+                self.cancel_background_worker(&format!(
+                    "{FILE_WATCH_WORKER_PREFIX}{}:{watch_id}",
+                    watch.session_id
+                ));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stops every file watch bound to one session.
+    pub fn stop_session_file_watches(&self, session_id: &str) {
+        self.file_watches
+            .write()
+            .expect("file watch lock poisoned")
+            .retain(|_, watch| watch.session_id != session_id);
+        let prefix = format!("{FILE_WATCH_WORKER_PREFIX}{session_id}:");
+        self.cancel_background_workers_with_prefix(&prefix);
+    }
+
+    /// Stops one process watch, whichever session it samples. Returns whether it was running.
+    pub fn stop_process_watch(&self, watch_id: &str) -> bool {
+        let suffix = format!(":{watch_id}");
+        let mut guard = self
+            .background_workers
+            .write()
+            .expect("background worker lock poisoned");
+        let mut stopped = false;
+        for (worker_id, cancelled) in guard.iter_mut() {
+            if worker_id.starts_with(PROCESS_WATCH_WORKER_PREFIX)
+                && worker_id.ends_with(&suffix)
+                && !*cancelled
+            {
+                *cancelled = true;
+                stopped = true;
+            }
+        }
+        stopped
+    }
+
+    /// Stops every process watch bound to one session.
+    pub fn stop_session_process_watches(&self, session_id: &str) {
+        let prefix = format!("{PROCESS_WATCH_WORKER_PREFIX}{session_id}:");
+        self.cancel_background_workers_with_prefix(&prefix);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use uuid::Uuid;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("eshell-{name}-{}", Uuid::new_v4()))
+    }
+
+    /// Builds an `AppState` rooted in a fresh temp dir.
+    pub(crate) fn test_state(name: &str) -> AppState {
+        AppState::new(temp_dir(name)).expect("create state")
+    }
+
+    /// A connected-looking session of config `c1` sitting in `/root`.
+    pub(crate) fn session_fixture(id: &str) -> ShellSession {
+        ShellSession {
+            id: id.to_string(),
+            config_id: "c1".to_string(),
+            config_name: "prod".to_string(),
+            current_dir: "/root".to_string(),
+            previous_dir: None,
+            last_output: String::new(),
+            remote_os: None,
+            created_at: now_rfc3339(),
+            updated_at: now_rfc3339(),
+            handshake: SshHandshakeInfo::default(),
+        }
+    }
+
+    #[test]
+    fn remove_session_stops_its_file_and_process_watches() {
+        let state = test_state("file-watch");
+        state.put_session(session_fixture("s1"));
+        let worker_id = format!("{FILE_WATCH_WORKER_PREFIX}s1:w1");
+        state.begin_background_worker(&worker_id);
+        state.put_file_watch(FileWatch {
+            watch_id: "w1".to_string(),
+            session_id: "s1".to_string(),
+            path: "/etc/nginx/nginx.conf".to_string(),
+            interval_secs: 5,
+            created_at: now_rfc3339(),
+        });
+        let process_worker_id = format!("{PROCESS_WATCH_WORKER_PREFIX}s1:p1");
+        state.begin_background_worker(&process_worker_id);
+
+        state.remove_session("s1").expect("remove session");
+        assert!(state.is_background_worker_cancelled(&worker_id));
+        assert!(!state.stop_file_watch("w1"));
+        assert!(state.is_background_worker_cancelled(&process_worker_id));
+        assert!(!state.stop_process_watch("p1"));
+    }
+
+    #[test]
+    fn shared_ssh_session_lives_until_its_last_shell_closes() {
+        let state = test_state("shared-ssh");
+        let shared = |pool_id: &str| SharedSshSession {
+            pool_id: pool_id.to_string(),
+            session: Session::new().expect("ssh session"),
+            login_dir: "/root".to_string(),
+            remote_os: Some("Linux".to_string()),
+            handshake: SshHandshakeInfo::default(),
+        };
+        assert!(state.acquire_shared_ssh("c1", "s1").is_none());
+
+        state.register_shared_ssh("c1", "s1", shared("p1"));
+        // A second fresh connection for the same config stays private.
+        state.register_shared_ssh("c1", "s9", shared("p2"));
+        let leased = state
+            .acquire_shared_ssh("c1", "s2")
+            .expect("shared session");
+        assert_eq!(leased.pool_id, "p1");
+        assert_eq!(leased.login_dir, "/root");
+
+        state.release_shared_ssh("s9");
+        state.release_shared_ssh("s1");
+        assert!(state.acquire_shared_ssh("c1", "s3").is_some());
+        state.release_shared_ssh("s2");
+        state.release_shared_ssh("s3");
+        assert!(state.acquire_shared_ssh("c1", "s4").is_none());
+
+        state.register_shared_ssh("c1", "s5", shared("p3"));
+        // Discarding a connection that was already replaced leaves the new one alone.
+        state.discard_shared_ssh_pool("c1", "p1");
+        assert_eq!(
+            state
+                .acquire_shared_ssh("c1", "s7")
+                .map(|shared| shared.pool_id),
+            Some("p3".to_string())
+        );
+        state.release_shared_ssh("s7");
+        state.discard_shared_ssh("s5");
+        assert!(state.acquire_shared_ssh("c1", "s6").is_none());
+    }
+
+    #[test]
+    fn reconnecting_pty_rejects_input_and_closed_sessions_get_no_worker() {
+        let state = test_state("reconnect");
+        state.put_session(session_fixture("s1"));
+
+        state.begin_pty_reconnect("s1");
+        let error = state
+            .send_pty_command("s1", PtyCommand::Input("ls\n".to_string()))
+            .expect_err("input while reconnecting");
+        assert!(error.to_string().contains("reconnecting"));
+
+        let (tx, rx) = mpsc::channel();
+        let ssh = Session::new().expect("ssh session");
+        assert!(state.install_pty_worker("s1", tx, ssh.clone()));
+        state
+            .send_pty_command("s1", PtyCommand::Input("ls\n".to_string()))
+            .expect("input after reconnect");
+        assert!(matches!(rx.try_recv(), Ok(PtyCommand::Input(_))));
+
+        state.remove_session("s1").expect("remove session");
+        let (tx, _rx) = mpsc::channel();
+        assert!(!state.install_pty_worker("s1", tx, ssh));
+        assert!(!state.has_pty_channel("s1"));
+        assert!(state.get_pty_connection("s1").is_none());
+    }
+
+    #[test]
+    fn session_manifest_survives_restart_until_restored() {
+        let root = temp_dir("manifest");
+        let state = AppState::new(root.clone()).expect("create state");
+        state.put_session(session_fixture("s1"));
+        state
+            .mutate_session("s1", |session| {
+                session.previous_dir = Some(session.current_dir.clone());
+                session.current_dir = "/var/log".to_string();
+            })
+            .expect("update cwd");
+        drop(state);
+
+        let restarted = AppState::new(root).expect("reopen state");
+        let entries = restarted.take_restorable_sessions();
+        assert_eq!(
+            entries,
+            vec![SessionManifestEntry {
+                id: "s1".to_string(),
+                config_id: "c1".to_string(),
+                current_dir: "/var/log".to_string(),
+                previous_dir: Some("/root".to_string()),
+            }]
+        );
+        assert!(restarted.take_restorable_sessions().is_empty());
+        assert!(restarted
+            .storage
+            .load_session_manifest()
+            .expect("manifest")
+            .is_empty());
+    }
 }
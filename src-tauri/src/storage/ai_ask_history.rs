@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+
+use crate::error::AppResult;
+use crate::models::AiAskHistoryEntry;
+
+use super::io::{lock_and_reload, read_json_or_default, write_json_pretty};
+use super::Storage;
+
+/// Oldest answers are dropped once the history holds this many entries.
+pub(super) const MAX_AI_ASK_HISTORY_ENTRIES: usize = 200;
+
+impl Storage {
+    /// Returns up to `limit` most recent `ai_ask` answers, oldest first.
+    pub fn list_ai_ask_history(&self, limit: Option<usize>) -> AppResult<Vec<AiAskHistoryEntry>> {
+        let history =
+            read_json_or_default::<VecDeque<AiAskHistoryEntry>>(&self.ai_ask_history_path)?;
+        let skip = history
+            .len()
+            .saturating_sub(limit.unwrap_or(MAX_AI_ASK_HISTORY_ENTRIES));
+        Ok(history.into_iter().skip(skip).collect())
+    }
+
+    /// Appends one answer, dropping the oldest beyond [`MAX_AI_ASK_HISTORY_ENTRIES`].
+    pub fn append_ai_ask_history(&self, entry: AiAskHistoryEntry) -> AppResult<()> {
+        let mut history = VecDeque::<AiAskHistoryEntry>::new();
+        let _file_lock = lock_and_reload(&self.ai_ask_history_path, &mut history)?;
+        while history.len() >= MAX_AI_ASK_HISTORY_ENTRIES {
+            history.pop_front();
+        }
+        history.push_back(entry);
+        write_json_pretty(&self.ai_ask_history_path, &history)
+    }
+
+    /// Removes every stored answer and returns how many were dropped.
+    pub fn clear_ai_ask_history(&self) -> AppResult<usize> {
+        let mut history = VecDeque::<AiAskHistoryEntry>::new();
+        let _file_lock = lock_and_reload(&self.ai_ask_history_path, &mut history)?;
+        write_json_pretty(
+            &self.ai_ask_history_path,
+            &VecDeque::<AiAskHistoryEntry>::new(),
+        )?;
+        Ok(history.len())
+    }
+}
@@ -2,17 +2,20 @@ use std::fs;
 use std::path::Path;
 
 use serde_json::Value;
+use url::Url;
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
+use crate::error::{remember_secret, AppError, AppResult};
 use crate::models::{
     now_rfc3339, AiAgentMode, AiApiType, AiApprovalMode, AiConfig, AiConfigInput, AiProfile,
     AiProfileInput, AiProfilesState,
 };
 
-use super::io::write_json_pretty;
+use super::io::{lock_and_reload, write_json_pretty};
 use super::Storage;
 
+const MAX_TOOL_STEPS_LIMIT: u32 = 50;
+
 impl Storage {
     /// Returns AI profile collection and active profile id.
     pub fn list_ai_profiles(&self) -> AiProfilesState {
@@ -41,8 +44,15 @@ impl Storage {
                 "maxContextTokens must be greater than 0".to_string(),
             ));
         }
+        if input.tool_output_max_bytes == 0 {
+            return Err(AppError::Validation(
+                "toolOutputMaxBytes must be greater than 0".to_string(),
+            ));
+        }
+        validate_max_tool_steps(input.max_tool_steps)?;
 
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         ensure_ai_profiles_state(&mut guard, &AiConfig::default());
         let now = now_rfc3339();
 
@@ -65,6 +75,9 @@ impl Storage {
                     temperature: input.temperature,
                     max_tokens: input.max_tokens,
                     max_context_tokens: input.max_context_tokens,
+                    tool_output_max_bytes: input.tool_output_max_bytes,
+                    max_tool_steps: input.max_tool_steps,
+                    supports_json_mode: input.supports_json_mode,
                     created_at: existing.created_at.clone(),
                     updated_at: now,
                 };
@@ -83,6 +96,9 @@ impl Storage {
                     temperature: input.temperature,
                     max_tokens: input.max_tokens,
                     max_context_tokens: input.max_context_tokens,
+                    tool_output_max_bytes: input.tool_output_max_bytes,
+                    max_tool_steps: input.max_tool_steps,
+                    supports_json_mode: input.supports_json_mode,
                     created_at: now.clone(),
                     updated_at: now,
                 };
@@ -91,6 +107,7 @@ impl Storage {
             }
         };
 
+        remember_secret(&profile.api_key);
         if guard.active_profile_id.is_none() {
             guard.active_profile_id = Some(profile.id);
         }
@@ -101,6 +118,7 @@ impl Storage {
     /// Deletes an AI profile by id. Keeps at least one profile available.
     pub fn delete_ai_profile(&self, id: &str) -> AppResult<AiProfilesState> {
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         let before = guard.profiles.len();
         guard.profiles.retain(|item| item.id != id);
         if guard.profiles.len() == before {
@@ -118,6 +136,7 @@ impl Storage {
         approval_mode: AiApprovalMode,
     ) -> AppResult<AiProfilesState> {
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         ensure_ai_profiles_state(&mut guard, &AiConfig::default());
         guard.approval_mode = approval_mode;
         write_json_pretty(&self.ai_profiles_path, &*guard)?;
@@ -127,6 +146,7 @@ impl Storage {
     /// Persists the global agent runtime mode used by every AI profile.
     pub fn save_ai_agent_mode(&self, agent_mode: AiAgentMode) -> AppResult<AiProfilesState> {
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         ensure_ai_profiles_state(&mut guard, &AiConfig::default());
         guard.agent_mode = agent_mode;
         write_json_pretty(&self.ai_profiles_path, &*guard)?;
@@ -136,6 +156,7 @@ impl Storage {
     /// Sets one profile as active for AI chat calls.
     pub fn set_active_ai_profile(&self, id: &str) -> AppResult<AiProfilesState> {
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         if !guard.profiles.iter().any(|item| item.id == id) {
             return Err(AppError::NotFound(format!("ai profile {id}")));
         }
@@ -171,6 +192,23 @@ impl Storage {
             })
     }
 
+    /// Resolves one stored profile into a request config, active or not.
+    pub fn get_ai_config_for_profile(&self, id: &str) -> AppResult<AiConfig> {
+        let guard = self.ai_profiles.read().expect("ai profiles lock poisoned");
+        guard
+            .profiles
+            .iter()
+            .find(|item| item.id == id)
+            .map(|profile| {
+                config_from_profile(
+                    profile,
+                    guard.approval_mode.clone(),
+                    guard.agent_mode.clone(),
+                )
+            })
+            .ok_or_else(|| AppError::NotFound(format!("ai profile {id}")))
+    }
+
     /// Updates active profile using old single-config API for compatibility.
     pub fn save_ai_config(&self, input: AiConfigInput) -> AppResult<AiConfig> {
         validate_ai_payload(
@@ -190,8 +228,15 @@ impl Storage {
                 "maxContextTokens must be greater than 0".to_string(),
             ));
         }
+        if input.tool_output_max_bytes == 0 {
+            return Err(AppError::Validation(
+                "toolOutputMaxBytes must be greater than 0".to_string(),
+            ));
+        }
+        validate_max_tool_steps(input.max_tool_steps)?;
 
         let mut guard = self.ai_profiles.write().expect("ai profiles lock poisoned");
+        let _file_lock = lock_and_reload(&self.ai_profiles_path, &mut *guard)?;
         ensure_ai_profiles_state(&mut guard, &AiConfig::default());
 
         let active_id = guard
@@ -217,6 +262,9 @@ impl Storage {
             temperature: input.temperature,
             max_tokens: input.max_tokens,
             max_context_tokens: input.max_context_tokens,
+            tool_output_max_bytes: input.tool_output_max_bytes,
+            max_tool_steps: input.max_tool_steps,
+            supports_json_mode: input.supports_json_mode,
             created_at: existing.created_at.clone(),
             updated_at: now,
         };
@@ -225,6 +273,7 @@ impl Storage {
         guard.agent_mode = input.agent_mode.clone();
 
         write_json_pretty(&self.ai_profiles_path, &*guard)?;
+        remember_secret(&updated.api_key);
         Ok(config_from_profile(
             &updated,
             guard.approval_mode.clone(),
@@ -311,9 +360,7 @@ fn validate_ai_payload(
             return Err(AppError::Validation("name cannot be empty".to_string()));
         }
     }
-    if base_url.trim().is_empty() {
-        return Err(AppError::Validation("baseUrl cannot be empty".to_string()));
-    }
+    validate_base_url(base_url)?;
     if model.trim().is_empty() {
         return Err(AppError::Validation("model cannot be empty".to_string()));
     }
@@ -325,8 +372,46 @@ fn validate_ai_payload(
     Ok(())
 }
 
+fn validate_max_tool_steps(max_tool_steps: u32) -> AppResult<()> {
+    if !(1..=MAX_TOOL_STEPS_LIMIT).contains(&max_tool_steps) {
+        return Err(AppError::Validation(format!(
+            "maxToolSteps must be between 1 and {MAX_TOOL_STEPS_LIMIT}"
+        )));
+    }
+    Ok(())
+}
+
+/// Requires an absolute http(s) URL so requests do not fail later with an opaque error.
+fn validate_base_url(base_url: &str) -> AppResult<()> {
+    let base_url = base_url.trim();
+    if base_url.is_empty() {
+        return Err(AppError::Validation("baseUrl cannot be empty".to_string()));
+    }
+    let lower = base_url.to_ascii_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        return Err(AppError::Validation(format!(
+            "baseUrl must start with http:// or https:// (got `{base_url}`)"
+        )));
+    }
+    let parsed = Url::parse(base_url)
+        .map_err(|err| AppError::Validation(format!("baseUrl is not a valid URL: {err}")))?;
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(AppError::Validation(
+            "baseUrl must include a host".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Trims trailing slashes and a pasted `/chat/completions` endpoint suffix.
 fn normalize_base_url(value: &str) -> String {
-    value.trim().trim_end_matches('/').to_string()
+    let trimmed = value.trim().trim_end_matches('/');
+    let lower = trimmed.to_ascii_lowercase();
+    let trimmed = match lower.strip_suffix("/chat/completions") {
+        Some(rest) => &trimmed[..rest.len()],
+        None => trimmed,
+    };
+    trimmed.trim_end_matches('/').to_string()
 }
 
 fn normalize_profile(profile: &mut AiProfile) {
@@ -367,6 +452,12 @@ fn normalize_profile(profile: &mut AiProfile) {
     if profile.max_context_tokens == 0 {
         profile.max_context_tokens = defaults.max_context_tokens;
     }
+    if profile.tool_output_max_bytes == 0 {
+        profile.tool_output_max_bytes = defaults.tool_output_max_bytes;
+    }
+    if validate_max_tool_steps(profile.max_tool_steps).is_err() {
+        profile.max_tool_steps = defaults.max_tool_steps;
+    }
     if profile.created_at.trim().is_empty() {
         profile.created_at = now_rfc3339();
     }
@@ -392,6 +483,9 @@ fn profile_from_config(config: &AiConfig, name: &str) -> AiProfile {
         temperature: config.temperature,
         max_tokens: config.max_tokens,
         max_context_tokens: config.max_context_tokens,
+        tool_output_max_bytes: config.tool_output_max_bytes,
+        max_tool_steps: config.max_tool_steps,
+        supports_json_mode: config.supports_json_mode,
         created_at: now.clone(),
         updated_at: now,
     }
@@ -411,6 +505,9 @@ fn config_from_profile(
         temperature: profile.temperature,
         max_tokens: profile.max_tokens,
         max_context_tokens: profile.max_context_tokens,
+        tool_output_max_bytes: profile.tool_output_max_bytes,
+        max_tool_steps: profile.max_tool_steps,
+        supports_json_mode: profile.supports_json_mode,
         approval_mode,
         agent_mode,
         updated_at: profile.updated_at.clone(),
@@ -1,9 +1,20 @@
-use std::fs;
-use std::path::Path;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
 
 use crate::error::AppResult;
+use crate::ops_agent::infrastructure::logging::{
+    append_debug_log_at_path, resolve_ops_agent_log_path,
+};
 
-pub(super) fn read_json_or_default<T>(path: &Path) -> AppResult<T>
+/// Reads a JSON file, treating a missing or blank file as `T::default()`.
+///
+/// A file that fails to parse is recovered from its `.bak` sibling when that
+/// one is still readable; otherwise the original parse error is returned.
+pub(crate) fn read_json_or_default<T>(path: &Path) -> AppResult<T>
 where
     T: serde::de::DeserializeOwned + Default,
 {
@@ -12,16 +23,128 @@ where
     }
     let content = fs::read_to_string(path)?;
     if content.trim().is_empty() {
-        return Ok(T::default());
+        return read_backup(path).or_else(|_| Ok(T::default()));
+    }
+    match serde_json::from_str(&content) {
+        Ok(value) => Ok(value),
+        Err(err) => read_backup(path).map_err(|_| err.into()),
+    }
+}
+
+/// Reads a JSON file that must exist, falling back to its `.bak` on parse errors.
+pub(crate) fn read_json<T>(path: &Path) -> AppResult<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let content = fs::read_to_string(path)?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Ok(value),
+        Err(err) => read_backup(path).map_err(|_| err.into()),
     }
-    Ok(serde_json::from_str(&content)?)
 }
 
-pub(super) fn write_json_pretty<T>(path: &Path, value: &T) -> AppResult<()>
+/// Writes JSON atomically: the text goes to a temp file in the same directory
+/// which is then renamed over `path`, so readers see either the old or the new
+/// content, never a truncated mix. The previous file is kept as `<name>.bak`.
+pub(crate) fn write_json_pretty<T>(path: &Path, value: &T) -> AppResult<()>
 where
     T: serde::Serialize,
 {
     let text = serde_json::to_string_pretty(value)?;
-    fs::write(path, text)?;
+    let temp_path = sibling_path(path, &format!(".tmp-{}", Uuid::new_v4()), true);
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(text.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
+
+    if is_valid_json(path) {
+        fs::copy(path, backup_path(path))?;
+    }
+    if let Err(err) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err.into());
+    }
     Ok(())
 }
+
+/// Exclusive advisory lock on `<name>.lock`, released when dropped.
+pub(crate) struct FileLock {
+    _file: File,
+}
+
+/// Takes the cross-process lock for `path` and refreshes `value` from disk, so
+/// a read-modify-write applies on top of whatever another process last saved.
+///
+/// Callers hold their in-memory write guard first and keep the returned lock
+/// alive until the updated value has been written back.
+pub(crate) fn lock_and_reload<T>(path: &Path, value: &mut T) -> AppResult<FileLock>
+where
+    T: serde::de::DeserializeOwned + Default,
+{
+    let lock = lock_file(path)?;
+    if path.exists() {
+        *value = read_json_or_default(path)?;
+    }
+    Ok(lock)
+}
+
+/// Blocks until the cross-process lock for `path` is held.
+pub(crate) fn lock_file(path: &Path) -> AppResult<FileLock> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling_path(path, ".lock", false))?;
+    file.lock()?;
+    Ok(FileLock { _file: file })
+}
+
+/// Location of the last-good copy kept next to `path`.
+pub(crate) fn backup_path(path: &Path) -> PathBuf {
+    sibling_path(path, ".bak", false)
+}
+
+fn read_backup<T>(path: &Path) -> AppResult<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let backup = backup_path(path);
+    let value = serde_json::from_str(&fs::read_to_string(&backup)?)?;
+    if let Some(data_dir) = path.parent() {
+        append_debug_log_at_path(
+            &resolve_ops_agent_log_path(data_dir),
+            "warn",
+            None,
+            None,
+            format!(
+                "[storage] {} is unreadable, recovered from {}",
+                path.display(),
+                backup.display()
+            ),
+        );
+    }
+    Ok(value)
+}
+
+/// Only a parseable file is worth keeping as backup; a corrupt one would
+/// overwrite the last good copy.
+fn is_valid_json(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .filter(|content| !content.trim().is_empty())
+        .is_some_and(|content| serde_json::from_str::<serde_json::Value>(&content).is_ok())
+}
+
+fn sibling_path(path: &Path, suffix: &str, hidden: bool) -> PathBuf {
+    let mut name = OsString::new();
+    if hidden {
+        name.push(".");
+    }
+    name.push(path.file_name().unwrap_or_default());
+    name.push(suffix);
+    path.with_file_name(name)
+}
@@ -1,7 +1,7 @@
 use crate::error::{AppError, AppResult};
 use crate::models::{now_rfc3339, SshKnownHost, TrustSshHostKeyInput};
 
-use super::io::write_json_pretty;
+use super::io::{lock_and_reload, write_json_pretty};
 use super::Storage;
 
 impl Storage {
@@ -39,6 +39,7 @@ impl Storage {
 
         let now = now_rfc3339();
         let mut guard = self.known_hosts.write().expect("known hosts lock poisoned");
+        let _file_lock = lock_and_reload(&self.known_hosts_path, &mut *guard)?;
         let record = match guard
             .iter()
             .position(|item| item.host == host && item.port == input.port)
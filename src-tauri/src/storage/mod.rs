@@ -1,24 +1,44 @@
-mod ai_profiles;
 mod agent_context;
-mod io;
+mod ai_ask_history;
+mod ai_profiles;
+pub(crate) mod io;
 mod known_hosts;
+mod openssh_config;
+mod redaction;
 mod scripts;
+mod sessions;
 mod ssh;
+mod ssh_presets;
+mod status_commands;
+mod trash;
 
 use std::fs;
 use std::path::PathBuf;
 use std::sync::RwLock;
 
-use crate::error::AppResult;
-use crate::models::{AiConfig, AiProfilesState, ScriptDefinition, SshConfig, SshKnownHost};
+use regex::Regex;
+
+use crate::error::{remember_secret, AppResult};
+use crate::models::{
+    AiConfig, AiProfilesState, RedactionSettings, ScriptDefinition, SshConfig, SshKnownHost,
+    StatusCommandProfile,
+};
 
 use ai_profiles::{ensure_ai_profiles_state, load_ai_profiles_state};
-use io::{read_json_or_default, write_json_pretty};
+use io::{lock_file, read_json_or_default, write_json_pretty};
+use redaction::compile_redaction_rules;
+use ssh::remember_ssh_secrets;
 
 const SSH_CONFIGS_FILE: &str = "ssh_configs.json";
 const KNOWN_HOSTS_FILE: &str = "known_hosts.json";
 const SCRIPTS_FILE: &str = "scripts.json";
 const AI_PROFILES_FILE: &str = "ai_profiles.json";
+const AI_ASK_HISTORY_FILE: &str = "ai_ask_history.json";
+const REDACTION_SETTINGS_FILE: &str = "redaction.json";
+const STATUS_COMMANDS_FILE: &str = "status_commands.json";
+const SESSIONS_MANIFEST_FILE: &str = "sessions.json";
+const TRASHED_SSH_CONFIGS_FILE: &str = "trashed_ssh_configs.json";
+const TRASHED_SCRIPTS_FILE: &str = "trashed_scripts.json";
 const LEGACY_AI_CONFIG_FILE: &str = "ai_config.json";
 const GLOBAL_AGENTS_FILE: &str = "AGENTS.md";
 const SERVER_AGENTS_DIR: &str = "server_agents";
@@ -27,17 +47,35 @@ const SERVER_AGENTS_DIR: &str = "server_agents";
 ///
 /// AI configuration is persisted in a single source of truth: `ai_profiles.json`.
 /// The legacy `ai_config.json` is read once for migration when profiles are missing.
+///
+/// Several eshell processes may share one root. Mutations of SSH configs, known
+/// hosts, scripts and AI profiles hold an advisory lock on the file and re-read
+/// it before applying the change, so concurrent writers never lose each other's
+/// updates. Plain reads are served from memory and may lag behind another
+/// process until this one next mutates the same file or restarts.
+///
+/// Deleted SSH configs and scripts go to `trashed_ssh_configs.json` and
+/// `trashed_scripts.json` unless a permanent delete is requested.
 pub struct Storage {
     ssh_configs_path: PathBuf,
     known_hosts_path: PathBuf,
     scripts_path: PathBuf,
     ai_profiles_path: PathBuf,
+    ai_ask_history_path: PathBuf,
     global_agents_path: PathBuf,
     server_agents_dir: PathBuf,
+    redaction_settings_path: PathBuf,
+    status_command_profile_path: PathBuf,
+    sessions_manifest_path: PathBuf,
+    trashed_ssh_configs_path: PathBuf,
+    trashed_scripts_path: PathBuf,
     ssh_configs: RwLock<Vec<SshConfig>>,
     known_hosts: RwLock<Vec<SshKnownHost>>,
     scripts: RwLock<Vec<ScriptDefinition>>,
     ai_profiles: RwLock<AiProfilesState>,
+    redaction_settings: RwLock<RedactionSettings>,
+    redaction_rules: RwLock<Vec<Regex>>,
+    status_command_profile: RwLock<StatusCommandProfile>,
 }
 
 impl Storage {
@@ -49,15 +87,39 @@ impl Storage {
         let known_hosts_path = root.join(KNOWN_HOSTS_FILE);
         let scripts_path = root.join(SCRIPTS_FILE);
         let ai_profiles_path = root.join(AI_PROFILES_FILE);
+        let ai_ask_history_path = root.join(AI_ASK_HISTORY_FILE);
         let global_agents_path = root.join(GLOBAL_AGENTS_FILE);
         let server_agents_dir = root.join(SERVER_AGENTS_DIR);
         let legacy_ai_config_path = root.join(LEGACY_AI_CONFIG_FILE);
+        let redaction_settings_path = root.join(REDACTION_SETTINGS_FILE);
+        let status_command_profile_path = root.join(STATUS_COMMANDS_FILE);
+        let sessions_manifest_path = root.join(SESSIONS_MANIFEST_FILE);
+        let trashed_ssh_configs_path = root.join(TRASHED_SSH_CONFIGS_FILE);
+        let trashed_scripts_path = root.join(TRASHED_SCRIPTS_FILE);
         fs::create_dir_all(&server_agents_dir)?;
 
+        // Bootstrap rewrites these files, so keep another process from saving in between.
+        let _bootstrap_locks = [
+            &ssh_configs_path,
+            &known_hosts_path,
+            &scripts_path,
+            &ai_profiles_path,
+        ]
+        .into_iter()
+        .map(|path| lock_file(path))
+        .collect::<AppResult<Vec<_>>>()?;
+
         let ssh_configs = read_json_or_default::<Vec<SshConfig>>(&ssh_configs_path)?;
         let known_hosts = read_json_or_default::<Vec<SshKnownHost>>(&known_hosts_path)?;
         let scripts = read_json_or_default::<Vec<ScriptDefinition>>(&scripts_path)?;
         let mut ai_profiles = load_ai_profiles_state(&ai_profiles_path)?;
+        let redaction_settings =
+            read_json_or_default::<RedactionSettings>(&redaction_settings_path)?;
+        // A hand-edited invalid pattern must not prevent startup; fall back to built-ins.
+        let redaction_rules = compile_redaction_rules(&redaction_settings)
+            .or_else(|_| compile_redaction_rules(&RedactionSettings::default()))?;
+        let status_command_profile =
+            read_json_or_default::<StatusCommandProfile>(&status_command_profile_path)?;
 
         // Migration fallback for older versions that only stored one ai_config.json.
         let legacy_ai_config = read_json_or_default::<AiConfig>(&legacy_ai_config_path)?;
@@ -68,10 +130,17 @@ impl Storage {
         write_json_pretty(&known_hosts_path, &known_hosts)?;
         write_json_pretty(&scripts_path, &scripts)?;
         write_json_pretty(&ai_profiles_path, &ai_profiles)?;
+        write_json_pretty(&redaction_settings_path, &redaction_settings)?;
+        write_json_pretty(&status_command_profile_path, &status_command_profile)?;
         if !global_agents_path.exists() {
             fs::write(&global_agents_path, "")?;
         }
 
+        ssh_configs.iter().for_each(remember_ssh_secrets);
+        for profile in &ai_profiles.profiles {
+            remember_secret(&profile.api_key);
+        }
+
         // Remove legacy file after successful migration to avoid dual-source confusion.
         if legacy_ai_config_path.exists() {
             let _ = fs::remove_file(&legacy_ai_config_path);
@@ -82,12 +151,21 @@ impl Storage {
             known_hosts_path,
             scripts_path,
             ai_profiles_path,
+            ai_ask_history_path,
             global_agents_path,
             server_agents_dir,
+            redaction_settings_path,
+            status_command_profile_path,
+            sessions_manifest_path,
+            trashed_ssh_configs_path,
+            trashed_scripts_path,
             ssh_configs: RwLock::new(ssh_configs),
             known_hosts: RwLock::new(known_hosts),
             scripts: RwLock::new(scripts),
             ai_profiles: RwLock::new(ai_profiles),
+            redaction_settings: RwLock::new(redaction_settings),
+            redaction_rules: RwLock::new(redaction_rules),
+            status_command_profile: RwLock::new(status_command_profile),
         })
     }
 
@@ -0,0 +1,172 @@
+use crate::models::{SshAuthType, SshConfigInput};
+
+use super::Storage;
+
+/// Settings collected from one `Host` block (or the global / `Host *` defaults).
+#[derive(Default, Clone)]
+struct HostBlock {
+    aliases: Vec<String>,
+    host_name: Option<String>,
+    user: Option<String>,
+    port: Option<u16>,
+    identity_file: Option<String>,
+}
+
+impl HostBlock {
+    /// OpenSSH keeps the first value it sees, so only unset fields take the default.
+    fn or_defaults(mut self, defaults: &HostBlock) -> Self {
+        self.host_name = self.host_name.or_else(|| defaults.host_name.clone());
+        self.user = self.user.or_else(|| defaults.user.clone());
+        self.port = self.port.or(defaults.port);
+        self.identity_file = self
+            .identity_file
+            .or_else(|| defaults.identity_file.clone());
+        self
+    }
+}
+
+impl Storage {
+    /// Parses `Host` blocks of an OpenSSH client config into editor inputs.
+    ///
+    /// Only `HostName`, `User`, `Port` and the first `IdentityFile` are read.
+    /// Wildcard and negated host patterns are not turned into profiles; a
+    /// `Host *` block (or settings before the first `Host`) fills in missing
+    /// values of the others. `Match` blocks are skipped. Nothing is persisted.
+    pub fn parse_openssh_config(&self, text: &str) -> Vec<SshConfigInput> {
+        let mut defaults = HostBlock::default();
+        let mut blocks = Vec::<HostBlock>::new();
+        // None while inside a `Match` block, whose settings are ignored.
+        let mut current: Option<HostBlock> = Some(HostBlock::default());
+        let mut current_is_default = true;
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, value) = split_directive(line);
+            match keyword.to_ascii_lowercase().as_str() {
+                "host" => {
+                    finish_block(
+                        current.take(),
+                        current_is_default,
+                        &mut defaults,
+                        &mut blocks,
+                    );
+                    let patterns = value.split_whitespace().collect::<Vec<_>>();
+                    current_is_default = patterns == ["*"];
+                    current = Some(HostBlock {
+                        aliases: patterns
+                            .into_iter()
+                            .filter(|pattern| !pattern.contains(['*', '?', '!']))
+                            .map(str::to_string)
+                            .collect(),
+                        ..HostBlock::default()
+                    });
+                }
+                "match" => {
+                    finish_block(
+                        current.take(),
+                        current_is_default,
+                        &mut defaults,
+                        &mut blocks,
+                    );
+                    current_is_default = false;
+                }
+                directive => {
+                    let Some(block) = current.as_mut() else {
+                        continue;
+                    };
+                    let value = unquote(value);
+                    match directive {
+                        "hostname" if block.host_name.is_none() => {
+                            block.host_name = Some(value.to_string());
+                        }
+                        "user" if block.user.is_none() => block.user = Some(value.to_string()),
+                        "port" if block.port.is_none() => block.port = value.parse().ok(),
+                        "identityfile" if block.identity_file.is_none() => {
+                            block.identity_file = Some(value.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        finish_block(
+            current.take(),
+            current_is_default,
+            &mut defaults,
+            &mut blocks,
+        );
+
+        blocks
+            .into_iter()
+            .map(|block| block.or_defaults(&defaults))
+            .flat_map(|block| {
+                block
+                    .aliases
+                    .iter()
+                    .map(|alias| to_config_input(alias, &block))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+fn finish_block(
+    block: Option<HostBlock>,
+    is_default: bool,
+    defaults: &mut HostBlock,
+    blocks: &mut Vec<HostBlock>,
+) {
+    let Some(block) = block else {
+        return;
+    };
+    if is_default {
+        *defaults = std::mem::take(defaults).or_defaults(&block);
+    } else if !block.aliases.is_empty() {
+        blocks.push(block);
+    }
+}
+
+fn to_config_input(alias: &str, block: &HostBlock) -> SshConfigInput {
+    let private_key_path = block.identity_file.clone().unwrap_or_default();
+    SshConfigInput {
+        id: None,
+        name: alias.to_string(),
+        host: block.host_name.clone().unwrap_or_else(|| alias.to_string()),
+        port: block.port.unwrap_or(22),
+        username: block.user.clone().unwrap_or_default(),
+        auth_type: if private_key_path.is_empty() {
+            SshAuthType::Password
+        } else {
+            SshAuthType::PrivateKey
+        },
+        password: String::new(),
+        private_key_path,
+        private_key_passphrase: String::new(),
+        use_password_fallback: false,
+        sudo_tty_fallback: false,
+        sudo_password: String::new(),
+        jump_host: None,
+        description: Some("Imported from OpenSSH config".to_string()),
+    }
+}
+
+/// Splits `Keyword value`, `Keyword=value` and `Keyword = value` forms.
+fn split_directive(line: &str) -> (&str, &str) {
+    let end = line
+        .find(|ch: char| ch.is_whitespace() || ch == '=')
+        .unwrap_or(line.len());
+    let (keyword, rest) = line.split_at(end);
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+    (keyword, rest.trim())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(value)
+}
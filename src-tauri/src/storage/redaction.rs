@@ -0,0 +1,78 @@
+use regex::Regex;
+
+use crate::error::{redact_with_rules, AppError, AppResult};
+use crate::models::{
+    default_redaction_patterns, now_rfc3339, RedactionSettings, RedactionSettingsInput,
+};
+
+use super::io::write_json_pretty;
+use super::Storage;
+
+impl Storage {
+    /// Returns the output redaction settings (built-in patterns unless overridden).
+    pub fn get_redaction_settings(&self) -> RedactionSettings {
+        self.redaction_settings
+            .read()
+            .expect("redaction settings lock poisoned")
+            .clone()
+    }
+
+    /// Validates and persists redaction settings, then swaps the compiled rules.
+    pub fn save_redaction_settings(
+        &self,
+        input: RedactionSettingsInput,
+    ) -> AppResult<RedactionSettings> {
+        let patterns = input
+            .patterns
+            .unwrap_or_else(default_redaction_patterns)
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect::<Vec<_>>();
+        let settings = RedactionSettings {
+            enabled: input.enabled,
+            patterns,
+            updated_at: now_rfc3339(),
+        };
+        let rules = compile_redaction_rules(&settings)?;
+
+        let mut guard = self
+            .redaction_settings
+            .write()
+            .expect("redaction settings lock poisoned");
+        write_json_pretty(&self.redaction_settings_path, &settings)?;
+        *guard = settings.clone();
+        *self
+            .redaction_rules
+            .write()
+            .expect("redaction rules lock poisoned") = rules;
+        Ok(settings)
+    }
+
+    /// Masks secrets in terminal output before it is persisted.
+    ///
+    /// Stored passwords and keys are masked too, even with the patterns disabled.
+    pub fn redact_output(&self, text: &str) -> String {
+        let rules = self
+            .redaction_rules
+            .read()
+            .expect("redaction rules lock poisoned");
+        redact_with_rules(&rules, text)
+    }
+}
+
+/// Compiles enabled redaction patterns; an invalid pattern is a validation error.
+pub(super) fn compile_redaction_rules(settings: &RedactionSettings) -> AppResult<Vec<Regex>> {
+    if !settings.enabled {
+        return Ok(Vec::new());
+    }
+    settings
+        .patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|error| {
+                AppError::Validation(format!("invalid redaction pattern `{pattern}`: {error}"))
+            })
+        })
+        .collect()
+}
@@ -1,9 +1,13 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::models::{now_rfc3339, ScriptDefinition, ScriptInput, ScriptParameter};
+use crate::models::{
+    is_valid_env_var_name, now_rfc3339, ScriptDefinition, ScriptInput, ScriptParameter,
+};
 
-use super::io::write_json_pretty;
+use super::io::{lock_and_reload, write_json_pretty};
 use super::Storage;
 
 impl Storage {
@@ -12,76 +16,104 @@ impl Storage {
         self.scripts.read().expect("script lock poisoned").clone()
     }
 
+    /// Returns scripts carrying `tag` (case-insensitive), in persistent order.
+    pub fn list_scripts_by_tag(&self, tag: &str) -> Vec<ScriptDefinition> {
+        let tag = tag.trim().to_lowercase();
+        self.scripts
+            .read()
+            .expect("script lock poisoned")
+            .iter()
+            .filter(|script| script.tags.contains(&tag))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the distinct tags used by any script, sorted.
+    pub fn list_script_tags(&self) -> Vec<String> {
+        self.scripts
+            .read()
+            .expect("script lock poisoned")
+            .iter()
+            .flat_map(|script| script.tags.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
     /// Creates or updates a script definition and persists the collection.
     pub fn upsert_script(&self, input: ScriptInput) -> AppResult<ScriptDefinition> {
-        if input.name.trim().is_empty() {
-            return Err(AppError::Validation(
-                "script name cannot be empty".to_string(),
-            ));
-        }
-
-        let path = input.path.unwrap_or_default().trim().to_string();
-        let command = input.command.unwrap_or_default().trim().to_string();
-        let parameters = normalize_script_parameters(input.parameters)?;
-        if path.is_empty() && command.is_empty() {
-            return Err(AppError::Validation(
-                "script path and command cannot both be empty".to_string(),
-            ));
-        }
+        let draft = validate_script_input(input)?;
 
         let mut guard = self.scripts.write().expect("script lock poisoned");
-        let now = now_rfc3339();
-
-        let script = match input.id.as_deref() {
-            Some(id) => {
-                let index = guard
-                    .iter()
-                    .position(|item| item.id == id)
-                    .ok_or_else(|| AppError::NotFound(format!("script {id}")))?;
-                let existing = &guard[index];
-                let updated = ScriptDefinition {
-                    id: existing.id.clone(),
-                    name: input.name.trim().to_string(),
-                    path,
-                    command,
-                    description: input.description.unwrap_or_default().trim().to_string(),
-                    parameters,
-                    created_at: existing.created_at.clone(),
-                    updated_at: now,
-                };
-                guard[index] = updated.clone();
-                updated
-            }
-            None => {
-                let created = ScriptDefinition {
-                    id: Uuid::new_v4().to_string(),
-                    name: input.name.trim().to_string(),
-                    path,
-                    command,
-                    description: input.description.unwrap_or_default().trim().to_string(),
-                    parameters,
-                    created_at: now.clone(),
-                    updated_at: now,
-                };
-                guard.push(created.clone());
-                created
-            }
-        };
+        let _file_lock = lock_and_reload(&self.scripts_path, &mut *guard)?;
+        let script = apply_script_draft(&mut guard, draft, &now_rfc3339())?;
 
         write_json_pretty(&self.scripts_path, &*guard)?;
         Ok(script)
     }
 
-    /// Deletes a script definition by id and persists changes.
-    pub fn delete_script(&self, id: &str) -> AppResult<()> {
+    /// Creates or updates several script definitions with a single write.
+    ///
+    /// All inputs are validated first; if any is invalid or names an unknown
+    /// id, nothing is changed. Returns the whole updated collection.
+    pub fn save_scripts_batch(&self, inputs: Vec<ScriptInput>) -> AppResult<Vec<ScriptDefinition>> {
+        let drafts = inputs
+            .into_iter()
+            .map(|input| {
+                let name = input.name.trim().to_string();
+                validate_script_input(input)
+                    .map_err(|err| AppError::Validation(format!("script {name}: {err}")))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let now = now_rfc3339();
         let mut guard = self.scripts.write().expect("script lock poisoned");
-        let before = guard.len();
-        guard.retain(|script| script.id != id);
-        if guard.len() == before {
-            return Err(AppError::NotFound(format!("script {id}")));
+        let _file_lock = lock_and_reload(&self.scripts_path, &mut *guard)?;
+        let mut next = guard.clone();
+        for draft in drafts {
+            apply_script_draft(&mut next, draft, &now)?;
         }
-        write_json_pretty(&self.scripts_path, &*guard)?;
-        Ok(())
+
+        write_json_pretty(&self.scripts_path, &next)?;
+        *guard = next.clone();
+        Ok(next)
+    }
+
+    /// Moves a script definition to the trash, or removes it for good when
+    /// `permanent` is set.
+    pub fn delete_script(&self, id: &str, permanent: bool) -> AppResult<()> {
+        self.delete_scripts_batch(&[id.to_string()], permanent)
+            .map(|_| ())
+    }
+
+    /// Deletes several script definitions with a single write, trashing them
+    /// unless `permanent` is set.
+    ///
+    /// Fails without changing anything when any id is unknown. Returns the
+    /// remaining collection.
+    pub fn delete_scripts_batch(
+        &self,
+        ids: &[String],
+        permanent: bool,
+    ) -> AppResult<Vec<ScriptDefinition>> {
+        let mut guard = self.scripts.write().expect("script lock poisoned");
+        let _file_lock = lock_and_reload(&self.scripts_path, &mut *guard)?;
+        if let Some(missing) = ids
+            .iter()
+            .find(|id| !guard.iter().any(|script| &script.id == *id))
+        {
+            return Err(AppError::NotFound(format!("script {missing}")));
+        }
+        let (removed, remaining): (Vec<_>, Vec<_>) = guard
+            .iter()
+            .cloned()
+            .partition(|script| ids.contains(&script.id));
+        if !permanent {
+            self.trash_scripts(removed)?;
+        }
+        write_json_pretty(&self.scripts_path, &remaining)?;
+        *guard = remaining;
+        Ok(guard.clone())
     }
 
     /// Returns a script by id.
@@ -96,6 +128,89 @@ impl Storage {
     }
 }
 
+/// Normalized fields of a `ScriptInput` that passed validation.
+struct ScriptDraft {
+    id: Option<String>,
+    name: String,
+    path: String,
+    command: String,
+    description: String,
+    parameters: Vec<ScriptParameter>,
+    default_args: Vec<String>,
+    env: BTreeMap<String, String>,
+    tags: Vec<String>,
+}
+
+fn validate_script_input(input: ScriptInput) -> AppResult<ScriptDraft> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::Validation(
+            "script name cannot be empty".to_string(),
+        ));
+    }
+
+    let path = input.path.unwrap_or_default().trim().to_string();
+    let command = input.command.unwrap_or_default().trim().to_string();
+    if path.is_empty() && command.is_empty() {
+        return Err(AppError::Validation(
+            "script path and command cannot both be empty".to_string(),
+        ));
+    }
+
+    Ok(ScriptDraft {
+        id: input.id,
+        name: input.name.trim().to_string(),
+        path,
+        command,
+        description: input.description.unwrap_or_default().trim().to_string(),
+        parameters: normalize_script_parameters(input.parameters)?,
+        default_args: input.default_args,
+        env: normalize_script_env(input.env)?,
+        tags: normalize_script_tags(input.tags),
+    })
+}
+
+/// Applies a validated draft to `scripts`, updating the entry with the same id
+/// or appending a new one.
+fn apply_script_draft(
+    scripts: &mut Vec<ScriptDefinition>,
+    draft: ScriptDraft,
+    now: &str,
+) -> AppResult<ScriptDefinition> {
+    let (id, created_at, index) = match draft.id.as_deref() {
+        Some(id) => {
+            let index = scripts
+                .iter()
+                .position(|item| item.id == id)
+                .ok_or_else(|| AppError::NotFound(format!("script {id}")))?;
+            let existing = &scripts[index];
+            (
+                existing.id.clone(),
+                existing.created_at.clone(),
+                Some(index),
+            )
+        }
+        None => (Uuid::new_v4().to_string(), now.to_string(), None),
+    };
+    let script = ScriptDefinition {
+        id,
+        name: draft.name,
+        path: draft.path,
+        command: draft.command,
+        description: draft.description,
+        parameters: draft.parameters,
+        default_args: draft.default_args,
+        env: draft.env,
+        tags: draft.tags,
+        created_at,
+        updated_at: now.to_string(),
+    };
+    match index {
+        Some(index) => scripts[index] = script.clone(),
+        None => scripts.push(script.clone()),
+    }
+    Ok(script)
+}
+
 fn normalize_script_parameters(
     parameters: Vec<ScriptParameter>,
 ) -> AppResult<Vec<ScriptParameter>> {
@@ -111,7 +226,10 @@ fn normalize_script_parameters(
                 "script parameter {name} uses unsupported characters"
             )));
         }
-        if normalized.iter().any(|item: &ScriptParameter| item.name == name) {
+        if normalized
+            .iter()
+            .any(|item: &ScriptParameter| item.name == name)
+        {
             return Err(AppError::Validation(format!(
                 "script parameter {name} is duplicated"
             )));
@@ -128,6 +246,36 @@ fn normalize_script_parameters(
     Ok(normalized)
 }
 
+fn normalize_script_env(env: BTreeMap<String, String>) -> AppResult<BTreeMap<String, String>> {
+    let mut normalized = BTreeMap::new();
+    for (name, value) in env {
+        let name = name.trim().to_string();
+        if !is_valid_env_var_name(&name) {
+            return Err(AppError::Validation(format!(
+                "script environment variable {name} must match [A-Za-z_][A-Za-z0-9_]*"
+            )));
+        }
+        if normalized.insert(name.clone(), value).is_some() {
+            return Err(AppError::Validation(format!(
+                "script environment variable {name} is duplicated"
+            )));
+        }
+    }
+    Ok(normalized)
+}
+
+/// Lowercases and trims tags, dropping empty and repeated ones while keeping order.
+fn normalize_script_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
 fn is_valid_script_parameter_name(value: &str) -> bool {
     value
         .chars()
@@ -0,0 +1,17 @@
+use crate::error::AppResult;
+use crate::models::SessionManifestEntry;
+
+use super::io::{read_json_or_default, write_json_pretty};
+use super::Storage;
+
+impl Storage {
+    /// Reads the shell sessions that were open when the app last ran.
+    pub fn load_session_manifest(&self) -> AppResult<Vec<SessionManifestEntry>> {
+        read_json_or_default(&self.sessions_manifest_path)
+    }
+
+    /// Replaces the persisted shell session manifest.
+    pub fn save_session_manifest(&self, entries: &[SessionManifestEntry]) -> AppResult<()> {
+        write_json_pretty(&self.sessions_manifest_path, &entries)
+    }
+}
@@ -1,9 +1,11 @@
 use uuid::Uuid;
 
-use crate::error::{AppError, AppResult};
-use crate::models::{now_rfc3339, SshAuthType, SshConfig, SshConfigInput};
+use crate::error::{remember_secret, AppError, AppResult};
+use crate::models::{
+    now_rfc3339, SshAuthType, SshConfig, SshConfigInput, SshImportMergeStrategy, SshJumpHost,
+};
 
-use super::io::write_json_pretty;
+use super::io::{lock_and_reload, write_json_pretty};
 use super::Storage;
 
 impl Storage {
@@ -17,84 +19,226 @@ impl Storage {
 
     /// Creates or updates an SSH configuration and persists the updated collection.
     pub fn upsert_ssh_config(&self, input: SshConfigInput) -> AppResult<SshConfig> {
-        if input.name.trim().is_empty() {
-            return Err(AppError::Validation("name cannot be empty".to_string()));
+        let jump_host = validate_ssh_config_input(&input)?;
+
+        let now = now_rfc3339();
+        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
+        let config = apply_ssh_config_input(&mut guard, input, jump_host, &now)?;
+
+        write_json_pretty(&self.ssh_configs_path, &*guard)?;
+        remember_ssh_secrets(&config);
+        self.get_agent_context(Some(&config.id))?;
+        Ok(config)
+    }
+
+    /// Creates or updates several SSH configurations with a single write.
+    ///
+    /// All inputs are validated first; if any is invalid or names an unknown
+    /// id, nothing is changed. Returns the whole updated collection.
+    pub fn save_ssh_configs_batch(&self, inputs: Vec<SshConfigInput>) -> AppResult<Vec<SshConfig>> {
+        let validated = inputs
+            .into_iter()
+            .map(|input| {
+                let jump_host = validate_ssh_config_input(&input).map_err(|err| {
+                    AppError::Validation(format!("ssh config {}: {err}", input.name.trim()))
+                })?;
+                Ok((input, jump_host))
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let now = now_rfc3339();
+        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
+        let mut next = guard.clone();
+        let mut saved = Vec::with_capacity(validated.len());
+        for (input, jump_host) in validated {
+            saved.push(apply_ssh_config_input(&mut next, input, jump_host, &now)?);
         }
-        if input.host.trim().is_empty() {
-            return Err(AppError::Validation("host cannot be empty".to_string()));
+
+        write_json_pretty(&self.ssh_configs_path, &next)?;
+        *guard = next.clone();
+        drop(guard);
+        for config in &saved {
+            remember_ssh_secrets(config);
+            self.get_agent_context(Some(&config.id))?;
+        }
+        Ok(next)
+    }
+
+    /// Copies an SSH configuration under a fresh id with " (copy)" appended to its name.
+    pub fn duplicate_ssh_config(&self, id: &str) -> AppResult<SshConfig> {
+        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
+        let source = guard
+            .iter()
+            .find(|item| item.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("ssh config {id}")))?;
+        let now = now_rfc3339();
+        let copy = SshConfig {
+            id: Uuid::new_v4().to_string(),
+            name: format!("{} (copy)", source.name),
+            created_at: now.clone(),
+            updated_at: now,
+            ..source.clone()
+        };
+        guard.push(copy.clone());
+
+        write_json_pretty(&self.ssh_configs_path, &*guard)?;
+        drop(guard);
+        self.get_agent_context(Some(&copy.id))?;
+        Ok(copy)
+    }
+
+    /// Moves an SSH configuration to the trash, or removes it for good when
+    /// `permanent` is set.
+    pub fn delete_ssh_config(&self, id: &str, permanent: bool) -> AppResult<()> {
+        self.delete_ssh_configs_batch(&[id.to_string()], permanent)
+            .map(|_| ())
+    }
+
+    /// Removes several SSH configurations with a single write, trashing them
+    /// unless `permanent` is set.
+    ///
+    /// Fails without changing anything when any id is unknown. Returns the
+    /// remaining collection.
+    pub fn delete_ssh_configs_batch(
+        &self,
+        ids: &[String],
+        permanent: bool,
+    ) -> AppResult<Vec<SshConfig>> {
+        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
+        if let Some(missing) = ids
+            .iter()
+            .find(|id| !guard.iter().any(|config| &config.id == *id))
+        {
+            return Err(AppError::NotFound(format!("ssh config {missing}")));
         }
-        if input.username.trim().is_empty() {
-            return Err(AppError::Validation("username cannot be empty".to_string()));
+        let (removed, remaining): (Vec<_>, Vec<_>) = guard
+            .iter()
+            .cloned()
+            .partition(|config| ids.contains(&config.id));
+        if !permanent {
+            self.trash_ssh_configs(removed)?;
         }
-        if input.port == 0 {
-            return Err(AppError::Validation("port must be in 1-65535".to_string()));
+        write_json_pretty(&self.ssh_configs_path, &remaining)?;
+        *guard = remaining;
+        Ok(guard.clone())
+    }
+
+    /// Serializes all SSH configurations as pretty JSON, optionally blanking secrets.
+    pub fn export_ssh_configs(&self, redact_secrets: bool) -> AppResult<String> {
+        let mut configs = self.list_ssh_configs();
+        if redact_secrets {
+            for config in &mut configs {
+                config.password.clear();
+                config.private_key_passphrase.clear();
+                config.sudo_password.clear();
+                if let Some(jump_host) = config.jump_host.as_mut() {
+                    jump_host.password.clear();
+                    jump_host.private_key_passphrase.clear();
+                }
+            }
         }
-        validate_ssh_credentials(&input)?;
+        Ok(serde_json::to_string_pretty(&configs)?)
+    }
 
+    /// Imports configurations produced by `export_ssh_configs`.
+    ///
+    /// Every entry is validated like `upsert_ssh_config` before anything is
+    /// written. Blank secrets (from a redacted export) are filled from the stored
+    /// profile with the same id. `Merge` updates matching ids and appends the
+    /// rest; `Replace` swaps the whole collection.
+    pub fn import_ssh_configs(
+        &self,
+        payload: &str,
+        strategy: SshImportMergeStrategy,
+    ) -> AppResult<Vec<SshConfig>> {
+        let entries = serde_json::from_str::<Vec<SshConfig>>(payload)
+            .map_err(|err| AppError::Validation(format!("invalid ssh config payload: {err}")))?;
         let now = now_rfc3339();
         let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
 
-        let config = match input.id.as_deref() {
-            Some(id) => {
-                let index = guard
-                    .iter()
-                    .position(|item| item.id == id)
-                    .ok_or_else(|| AppError::NotFound(format!("ssh config {id}")))?;
-                let existing = &guard[index];
-                let updated = SshConfig {
-                    id: existing.id.clone(),
-                    name: input.name.trim().to_string(),
-                    host: input.host.trim().to_string(),
-                    port: input.port,
-                    username: input.username.trim().to_string(),
-                    auth_type: input.auth_type,
-                    password: input.password,
-                    private_key_path: input.private_key_path.trim().to_string(),
-                    private_key_passphrase: input.private_key_passphrase,
-                    use_password_fallback: input.use_password_fallback,
-                    description: input.description.unwrap_or_default().trim().to_string(),
-                    created_at: existing.created_at.clone(),
-                    updated_at: now,
-                };
-                guard[index] = updated.clone();
-                updated
+        let mut imported = Vec::<SshConfig>::with_capacity(entries.len());
+        for mut entry in entries {
+            let id = entry.id.trim().to_string();
+            if !id.is_empty() && imported.iter().any(|config| config.id == id) {
+                return Err(AppError::Validation(format!(
+                    "duplicate ssh config id in payload: {id}"
+                )));
             }
-            None => {
-                let created = SshConfig {
-                    id: Uuid::new_v4().to_string(),
-                    name: input.name.trim().to_string(),
-                    host: input.host.trim().to_string(),
-                    port: input.port,
-                    username: input.username.trim().to_string(),
-                    auth_type: input.auth_type,
-                    password: input.password,
-                    private_key_path: input.private_key_path.trim().to_string(),
-                    private_key_passphrase: input.private_key_passphrase,
-                    use_password_fallback: input.use_password_fallback,
-                    description: input.description.unwrap_or_default().trim().to_string(),
-                    created_at: now.clone(),
-                    updated_at: now,
-                };
-                guard.push(created.clone());
-                created
+            if let Some(existing) = guard.iter().find(|config| config.id == id) {
+                fill_redacted_secrets(&mut entry, existing);
             }
-        };
-
-        write_json_pretty(&self.ssh_configs_path, &*guard)?;
-        self.get_agent_context(Some(&config.id))?;
-        Ok(config)
-    }
+            let input = SshConfigInput {
+                id: None,
+                name: entry.name,
+                host: entry.host,
+                port: entry.port,
+                username: entry.username,
+                auth_type: entry.auth_type,
+                password: entry.password,
+                private_key_path: entry.private_key_path,
+                private_key_passphrase: entry.private_key_passphrase,
+                use_password_fallback: entry.use_password_fallback,
+                sudo_tty_fallback: entry.sudo_tty_fallback,
+                sudo_password: entry.sudo_password,
+                jump_host: entry.jump_host,
+                description: Some(entry.description),
+            };
+            let jump_host = validate_ssh_config_input(&input).map_err(|err| {
+                AppError::Validation(format!("ssh config {}: {err}", input.name.trim()))
+            })?;
+            let created_at = if entry.created_at.trim().is_empty() {
+                now.clone()
+            } else {
+                entry.created_at
+            };
+            imported.push(SshConfig {
+                id: if id.is_empty() {
+                    Uuid::new_v4().to_string()
+                } else {
+                    id
+                },
+                name: input.name.trim().to_string(),
+                host: input.host.trim().to_string(),
+                port: input.port,
+                username: input.username.trim().to_string(),
+                auth_type: input.auth_type,
+                password: input.password,
+                private_key_path: input.private_key_path.trim().to_string(),
+                private_key_passphrase: input.private_key_passphrase,
+                use_password_fallback: input.use_password_fallback,
+                sudo_tty_fallback: input.sudo_tty_fallback,
+                sudo_password: input.sudo_password,
+                jump_host,
+                description: input.description.unwrap_or_default().trim().to_string(),
+                created_at,
+                updated_at: now.clone(),
+            });
+        }
 
-    /// Removes an SSH configuration by id and persists the collection.
-    pub fn delete_ssh_config(&self, id: &str) -> AppResult<()> {
-        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
-        let before = guard.len();
-        guard.retain(|config| config.id != id);
-        if guard.len() == before {
-            return Err(AppError::NotFound(format!("ssh config {id}")));
+        match strategy {
+            SshImportMergeStrategy::Merge => {
+                for config in imported.iter().cloned() {
+                    match guard.iter_mut().find(|item| item.id == config.id) {
+                        Some(existing) => *existing = config,
+                        None => guard.push(config),
+                    }
+                }
+            }
+            SshImportMergeStrategy::Replace => *guard = imported.clone(),
         }
+
         write_json_pretty(&self.ssh_configs_path, &*guard)?;
-        Ok(())
+        drop(guard);
+        for config in &imported {
+            remember_ssh_secrets(config);
+            self.get_agent_context(Some(&config.id))?;
+        }
+        Ok(imported)
     }
 
     /// Reads a single SSH configuration by id.
@@ -109,6 +253,129 @@ impl Storage {
     }
 }
 
+/// Applies the profile editor's rules and returns the normalized jump host.
+fn validate_ssh_config_input(input: &SshConfigInput) -> AppResult<Option<SshJumpHost>> {
+    if input.name.trim().is_empty() {
+        return Err(AppError::Validation("name cannot be empty".to_string()));
+    }
+    validate_host(input.host.trim(), "host")?;
+    if input.username.trim().is_empty() {
+        return Err(AppError::Validation("username cannot be empty".to_string()));
+    }
+    if input.port == 0 {
+        return Err(AppError::Validation("port must be in 1-65535".to_string()));
+    }
+    validate_ssh_credentials(input)?;
+    input.jump_host.clone().map(normalize_jump_host).transpose()
+}
+
+/// Applies a validated input to `configs`, updating the entry with the same id
+/// or appending a new one.
+fn apply_ssh_config_input(
+    configs: &mut Vec<SshConfig>,
+    input: SshConfigInput,
+    jump_host: Option<SshJumpHost>,
+    now: &str,
+) -> AppResult<SshConfig> {
+    let (id, created_at, index) = match input.id.as_deref() {
+        Some(id) => {
+            let index = configs
+                .iter()
+                .position(|item| item.id == id)
+                .ok_or_else(|| AppError::NotFound(format!("ssh config {id}")))?;
+            let existing = &configs[index];
+            (
+                existing.id.clone(),
+                existing.created_at.clone(),
+                Some(index),
+            )
+        }
+        None => (Uuid::new_v4().to_string(), now.to_string(), None),
+    };
+    let config = SshConfig {
+        id,
+        name: input.name.trim().to_string(),
+        host: input.host.trim().to_string(),
+        port: input.port,
+        username: input.username.trim().to_string(),
+        auth_type: input.auth_type,
+        password: input.password,
+        private_key_path: input.private_key_path.trim().to_string(),
+        private_key_passphrase: input.private_key_passphrase,
+        use_password_fallback: input.use_password_fallback,
+        sudo_tty_fallback: input.sudo_tty_fallback,
+        sudo_password: input.sudo_password,
+        jump_host,
+        description: input.description.unwrap_or_default().trim().to_string(),
+        created_at,
+        updated_at: now.to_string(),
+    };
+    match index {
+        Some(index) => configs[index] = config.clone(),
+        None => configs.push(config.clone()),
+    }
+    Ok(config)
+}
+
+/// Lets error and log redaction mask this profile's stored credentials.
+pub(super) fn remember_ssh_secrets(config: &SshConfig) {
+    remember_secret(&config.password);
+    remember_secret(&config.private_key_passphrase);
+    remember_secret(&config.sudo_password);
+    if let Some(jump_host) = config.jump_host.as_ref() {
+        remember_secret(&jump_host.password);
+        remember_secret(&jump_host.private_key_passphrase);
+    }
+}
+
+fn fill_redacted_secrets(entry: &mut SshConfig, existing: &SshConfig) {
+    if entry.password.is_empty() {
+        entry.password = existing.password.clone();
+    }
+    if entry.private_key_passphrase.is_empty() {
+        entry.private_key_passphrase = existing.private_key_passphrase.clone();
+    }
+    if entry.sudo_password.is_empty() {
+        entry.sudo_password = existing.sudo_password.clone();
+    }
+    if let (Some(jump_host), Some(existing_jump)) =
+        (entry.jump_host.as_mut(), existing.jump_host.as_ref())
+    {
+        if jump_host.password.is_empty() {
+            jump_host.password = existing_jump.password.clone();
+        }
+        if jump_host.private_key_passphrase.is_empty() {
+            jump_host.private_key_passphrase = existing_jump.private_key_passphrase.clone();
+        }
+    }
+}
+
+/// Accepts host names and IPv4 / IPv6 literals (with an optional zone id).
+fn validate_host(host: &str, label: &str) -> AppResult<()> {
+    if host.is_empty() {
+        return Err(AppError::Validation(format!("{label} cannot be empty")));
+    }
+    if host.chars().any(char::is_whitespace) {
+        return Err(AppError::Validation(format!(
+            "{label} cannot contain whitespace"
+        )));
+    }
+    if let Some(invalid) = host
+        .chars()
+        .find(|ch| !(ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_' | ':' | '%')))
+    {
+        return Err(AppError::Validation(format!(
+            "{label} contains invalid character `{invalid}`"
+        )));
+    }
+    if host.starts_with('-') || host.starts_with('.') || host.ends_with('-') {
+        return Err(AppError::Validation(format!(
+            "{label} `{host}` is not a valid host name"
+        )));
+    }
+    Ok(())
+}
+
 fn validate_ssh_credentials(input: &SshConfigInput) -> AppResult<()> {
     match input.auth_type {
         SshAuthType::Password => {
@@ -133,3 +400,36 @@ fn validate_ssh_credentials(input: &SshConfigInput) -> AppResult<()> {
     }
     Ok(())
 }
+
+fn normalize_jump_host(jump_host: SshJumpHost) -> AppResult<SshJumpHost> {
+    validate_host(jump_host.host.trim(), "jump host address")?;
+    if jump_host.username.trim().is_empty() {
+        return Err(AppError::Validation(
+            "jump host username cannot be empty".to_string(),
+        ));
+    }
+    if jump_host.port == 0 {
+        return Err(AppError::Validation(
+            "jump host port must be in 1-65535".to_string(),
+        ));
+    }
+    match jump_host.auth_type {
+        SshAuthType::Password if jump_host.password.is_empty() => {
+            return Err(AppError::Validation(
+                "jump host password cannot be empty for password authentication".to_string(),
+            ));
+        }
+        SshAuthType::PrivateKey if jump_host.private_key_path.trim().is_empty() => {
+            return Err(AppError::Validation(
+                "jump host private key path cannot be empty".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    Ok(SshJumpHost {
+        host: jump_host.host.trim().to_string(),
+        username: jump_host.username.trim().to_string(),
+        private_key_path: jump_host.private_key_path.trim().to_string(),
+        ..jump_host
+    })
+}
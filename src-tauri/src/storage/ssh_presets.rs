@@ -0,0 +1,101 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{SshAuthType, SshConfigInput, SshPreset};
+
+use super::Storage;
+
+/// (id, name, provider, default username, description)
+const BUILTIN_SSH_PRESETS: &[(&str, &str, &str, &str, &str)] = &[
+    (
+        "aws-ubuntu",
+        "AWS EC2 (Ubuntu)",
+        "aws",
+        "ubuntu",
+        "Ubuntu AMIs on EC2 log in as `ubuntu` with the instance key pair.",
+    ),
+    (
+        "aws-amazon-linux",
+        "AWS EC2 (Amazon Linux / RHEL)",
+        "aws",
+        "ec2-user",
+        "Amazon Linux, RHEL and SUSE AMIs log in as `ec2-user` with the instance key pair.",
+    ),
+    (
+        "aws-debian",
+        "AWS EC2 (Debian)",
+        "aws",
+        "admin",
+        "Debian AMIs on EC2 log in as `admin` with the instance key pair.",
+    ),
+    (
+        "gcp",
+        "Google Cloud Compute Engine",
+        "gcp",
+        "",
+        "Compute Engine uses the username attached to your OS Login or metadata SSH key.",
+    ),
+    (
+        "azure",
+        "Azure Virtual Machine",
+        "azure",
+        "azureuser",
+        "Azure Linux VMs default to `azureuser` with the key supplied at creation.",
+    ),
+    (
+        "digitalocean",
+        "DigitalOcean Droplet",
+        "digitalocean",
+        "root",
+        "Droplets created with an SSH key log in as `root`.",
+    ),
+    (
+        "oracle-cloud",
+        "Oracle Cloud Infrastructure",
+        "oracle",
+        "opc",
+        "Oracle Linux images on OCI log in as `opc` with the instance key pair.",
+    ),
+];
+
+impl Storage {
+    /// Returns the read-only connection presets for common cloud providers.
+    pub fn list_ssh_presets(&self) -> Vec<SshPreset> {
+        BUILTIN_SSH_PRESETS
+            .iter()
+            .map(|(id, name, provider, username, description)| SshPreset {
+                id: (*id).to_string(),
+                name: (*name).to_string(),
+                provider: (*provider).to_string(),
+                description: (*description).to_string(),
+                defaults: preset_input(name, username),
+            })
+            .collect()
+    }
+
+    /// Returns a pre-filled config input for one preset; nothing is persisted.
+    pub fn apply_ssh_preset(&self, preset_id: &str) -> AppResult<SshConfigInput> {
+        self.list_ssh_presets()
+            .into_iter()
+            .find(|preset| preset.id == preset_id)
+            .map(|preset| preset.defaults)
+            .ok_or_else(|| AppError::NotFound(format!("ssh preset {preset_id}")))
+    }
+}
+
+fn preset_input(name: &str, username: &str) -> SshConfigInput {
+    SshConfigInput {
+        id: None,
+        name: name.to_string(),
+        host: String::new(),
+        port: 22,
+        username: username.to_string(),
+        auth_type: SshAuthType::PrivateKey,
+        password: String::new(),
+        private_key_path: String::new(),
+        private_key_passphrase: String::new(),
+        use_password_fallback: false,
+        sudo_tty_fallback: false,
+        sudo_password: String::new(),
+        jump_host: None,
+        description: None,
+    }
+}
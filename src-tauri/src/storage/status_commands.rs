@@ -0,0 +1,43 @@
+use crate::error::AppResult;
+use crate::models::{now_rfc3339, StatusCommandProfile, StatusCommandProfileInput};
+
+use super::io::write_json_pretty;
+use super::Storage;
+
+impl Storage {
+    /// Returns the stored server-status command overrides.
+    pub fn get_status_command_profile(&self) -> StatusCommandProfile {
+        self.status_command_profile
+            .read()
+            .expect("status command profile lock poisoned")
+            .clone()
+    }
+
+    /// Persists server-status command overrides; blank commands restore the built-in default.
+    pub fn save_status_command_profile(
+        &self,
+        input: StatusCommandProfileInput,
+    ) -> AppResult<StatusCommandProfile> {
+        let profile = StatusCommandProfile {
+            top_command: normalize_command(input.top_command),
+            process_command: normalize_command(input.process_command),
+            disk_command: normalize_command(input.disk_command),
+            network_command: normalize_command(input.network_command),
+            updated_at: now_rfc3339(),
+        };
+
+        let mut guard = self
+            .status_command_profile
+            .write()
+            .expect("status command profile lock poisoned");
+        write_json_pretty(&self.status_command_profile_path, &profile)?;
+        *guard = profile.clone();
+        Ok(profile)
+    }
+}
+
+fn normalize_command(value: Option<String>) -> Option<String> {
+    value
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty())
+}
@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    now_rfc3339, PurgeTrashResult, ScriptDefinition, SshConfig, TrashedScript, TrashedSshConfig,
+};
+
+use super::io::{lock_and_reload, read_json_or_default, write_json_pretty};
+use super::ssh::remember_ssh_secrets;
+use super::Storage;
+
+impl Storage {
+    /// Returns SSH configurations in the trash, oldest deletion first.
+    pub fn list_trashed_ssh_configs(&self) -> AppResult<Vec<TrashedSshConfig>> {
+        read_json_or_default(&self.trashed_ssh_configs_path)
+    }
+
+    /// Moves a trashed SSH configuration back into the active collection.
+    pub fn restore_ssh_config(&self, id: &str) -> AppResult<SshConfig> {
+        let mut guard = self.ssh_configs.write().expect("ssh config lock poisoned");
+        let _file_lock = lock_and_reload(&self.ssh_configs_path, &mut *guard)?;
+        let mut trash = Vec::<TrashedSshConfig>::new();
+        let _trash_lock = lock_and_reload(&self.trashed_ssh_configs_path, &mut trash)?;
+        let index = trash
+            .iter()
+            .position(|entry| entry.config.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("trashed ssh config {id}")))?;
+        if guard.iter().any(|config| config.id == id) {
+            return Err(AppError::Validation(format!(
+                "ssh config {id} already exists"
+            )));
+        }
+        let config = trash.remove(index).config;
+        guard.push(config.clone());
+
+        // Active file first: a failure in between leaves a duplicate, never a loss.
+        write_json_pretty(&self.ssh_configs_path, &*guard)?;
+        write_json_pretty(&self.trashed_ssh_configs_path, &trash)?;
+        drop(guard);
+        remember_ssh_secrets(&config);
+        self.get_agent_context(Some(&config.id))?;
+        Ok(config)
+    }
+
+    /// Returns scripts in the trash, oldest deletion first.
+    pub fn list_trashed_scripts(&self) -> AppResult<Vec<TrashedScript>> {
+        read_json_or_default(&self.trashed_scripts_path)
+    }
+
+    /// Moves a trashed script back into the active collection.
+    pub fn restore_script(&self, id: &str) -> AppResult<ScriptDefinition> {
+        let mut guard = self.scripts.write().expect("script lock poisoned");
+        let _file_lock = lock_and_reload(&self.scripts_path, &mut *guard)?;
+        let mut trash = Vec::<TrashedScript>::new();
+        let _trash_lock = lock_and_reload(&self.trashed_scripts_path, &mut trash)?;
+        let index = trash
+            .iter()
+            .position(|entry| entry.script.id == id)
+            .ok_or_else(|| AppError::NotFound(format!("trashed script {id}")))?;
+        if guard.iter().any(|script| script.id == id) {
+            return Err(AppError::Validation(format!("script {id} already exists")));
+        }
+        let script = trash.remove(index).script;
+        guard.push(script.clone());
+
+        write_json_pretty(&self.scripts_path, &*guard)?;
+        write_json_pretty(&self.trashed_scripts_path, &trash)?;
+        Ok(script)
+    }
+
+    /// Permanently removes trash entries deleted at least `older_than_days` ago.
+    ///
+    /// `0` empties the trash. Entries whose timestamp cannot be parsed are kept.
+    pub fn purge_trash(&self, older_than_days: u32) -> AppResult<PurgeTrashResult> {
+        let cutoff = Utc::now() - Duration::days(i64::from(older_than_days));
+        Ok(PurgeTrashResult {
+            ssh_configs: purge_entries(
+                &self.trashed_ssh_configs_path,
+                cutoff,
+                |entry: &TrashedSshConfig| entry.deleted_at.as_str(),
+            )?,
+            scripts: purge_entries(
+                &self.trashed_scripts_path,
+                cutoff,
+                |entry: &TrashedScript| entry.deleted_at.as_str(),
+            )?,
+        })
+    }
+
+    /// Appends deleted SSH configurations to the trash. Callers hold the
+    /// SSH config locks so a concurrent restore cannot interleave.
+    pub(super) fn trash_ssh_configs(&self, configs: Vec<SshConfig>) -> AppResult<()> {
+        let deleted_at = now_rfc3339();
+        append_entries(
+            &self.trashed_ssh_configs_path,
+            configs.into_iter().map(|config| TrashedSshConfig {
+                config,
+                deleted_at: deleted_at.clone(),
+            }),
+        )
+    }
+
+    /// Appends deleted scripts to the trash. Callers hold the script locks.
+    pub(super) fn trash_scripts(&self, scripts: Vec<ScriptDefinition>) -> AppResult<()> {
+        let deleted_at = now_rfc3339();
+        append_entries(
+            &self.trashed_scripts_path,
+            scripts.into_iter().map(|script| TrashedScript {
+                script,
+                deleted_at: deleted_at.clone(),
+            }),
+        )
+    }
+}
+
+fn append_entries<T>(path: &Path, entries: impl IntoIterator<Item = T>) -> AppResult<()>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut trash = Vec::<T>::new();
+    let _file_lock = lock_and_reload(path, &mut trash)?;
+    trash.extend(entries);
+    write_json_pretty(path, &trash)
+}
+
+fn purge_entries<T>(
+    path: &Path,
+    cutoff: DateTime<Utc>,
+    deleted_at: impl Fn(&T) -> &str,
+) -> AppResult<usize>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut trash = Vec::<T>::new();
+    let _file_lock = lock_and_reload(path, &mut trash)?;
+    let before = trash.len();
+    trash.retain(|entry| {
+        !DateTime::parse_from_rfc3339(deleted_at(entry)).is_ok_and(|at| at <= cutoff)
+    });
+    let purged = before - trash.len();
+    if purged > 0 {
+        write_json_pretty(path, &trash)?;
+    }
+    Ok(purged)
+}
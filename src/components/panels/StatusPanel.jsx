@@ -52,10 +52,11 @@ function ProcessesView({ rows = [] }) {
 
   return (
     <div className="min-h-0 flex-1 overflow-auto">
-      <div className="sticky top-0 z-10 grid grid-cols-[72px_64px_96px_minmax(0,1fr)] gap-2 border-b border-border bg-panel px-3 py-2 text-[10px] font-semibold tracking-[0.14em] text-muted uppercase">
+      <div className="sticky top-0 z-10 grid grid-cols-[72px_88px_64px_96px_minmax(0,1fr)] gap-2 border-b border-border bg-panel px-3 py-2 text-[10px] font-semibold tracking-[0.14em] text-muted uppercase">
         <span>PID</span>
+        <span>{t("User")}</span>
         <span>{t("CPU")}</span>
-        <span>{t("Memory (MB)")}</span>
+        <span>{t("Memory (%)")}</span>
         <span>{t("Command")}</span>
       </div>
 
@@ -63,12 +64,15 @@ function ProcessesView({ rows = [] }) {
         {rows.map((proc) => (
           <div
             key={`${proc.pid}-${proc.command}`}
-            className="grid grid-cols-[72px_64px_96px_minmax(0,1fr)] gap-2 rounded-md border-b border-border/45 px-1 py-2 text-sm"
+            className="grid grid-cols-[72px_88px_64px_96px_minmax(0,1fr)] gap-2 rounded-md border-b border-border/45 px-1 py-2 text-sm"
           >
             <span className="tabular-nums text-text">{proc.pid}</span>
+            <span className="truncate text-muted" title={proc.user || ""}>
+              {proc.user || "-"}
+            </span>
             <span className="tabular-nums text-muted">{proc.cpuPercent}%</span>
             <span className="tabular-nums text-muted">
-              {Number.isFinite(Number(proc.memoryMb)) ? `${Number(proc.memoryMb).toFixed(1)} MB` : "-"}
+              {Number.isFinite(Number(proc.memoryPercent)) ? `${Number(proc.memoryPercent).toFixed(1)}%` : "-"}
             </span>
             <span className="truncate font-medium text-text" title={proc.command}>
               {proc.command}
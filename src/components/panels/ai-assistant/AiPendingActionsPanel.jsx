@@ -48,6 +48,15 @@ export default function AiPendingActionsPanel({
                   {t(riskLevel)}
                 </span>
               </div>
+              {action.commandSteps?.length > 1 && (
+                <ol className="mb-1 list-decimal space-y-0.5 pl-5 font-mono text-[11px] text-text">
+                  {action.commandSteps.map((step, index) => (
+                    <li key={`${action.id}-step-${index}`} className="break-all">
+                      {step}
+                    </li>
+                  ))}
+                </ol>
+              )}
               <div className="mb-2 truncate text-muted">{t(action.reason || "no reason")}</div>
               <textarea
                 value={comment}
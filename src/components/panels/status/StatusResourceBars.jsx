@@ -35,6 +35,34 @@ export default function StatusResourceBars({ currentStatus, formatMemoryGb }) {
           style={{ width: `${Math.min(currentStatus.memory.usedPercent, 100)}%` }}
         />
       </div>
+      {currentStatus.memory.swapTotalMb > 0 && (
+        <div className="mt-1 flex justify-between text-muted">
+          <span>{t("Swap (GB)")}</span>
+          <span>
+            {formatMemoryGb(currentStatus.memory.swapUsedMb ?? 0)} /{" "}
+            {formatMemoryGb(currentStatus.memory.swapTotalMb)} GB
+          </span>
+        </div>
+      )}
+
+      {(currentStatus.gpus || []).map((gpu) => (
+        <div key={`gpu-${gpu.index}`}>
+          <div className="mt-2 mb-1 flex justify-between gap-2">
+            <span className="truncate" title={gpu.name}>
+              GPU {gpu.index} · {gpu.name}
+            </span>
+            <span className="shrink-0">
+              {gpu.utilizationPercent == null ? "-" : `${gpu.utilizationPercent}%`}
+            </span>
+          </div>
+          <div className="h-2 bg-warm">
+            <div
+              className="h-full bg-warning"
+              style={{ width: `${Math.min(gpu.utilizationPercent ?? 0, 100)}%` }}
+            />
+          </div>
+        </div>
+      ))}
     </div>
   );
 }
@@ -24,6 +24,7 @@ import {
 } from "../../lib/aiProviderTypes";
 import { useI18n } from "../../lib/i18n";
 import { api } from "../../lib/tauri-api";
+import { toAiProfileInput } from "../../hooks/workbench/aiProfiles";
 
 const EMPTY_AI_FORM = {
   id: null,
@@ -37,6 +38,7 @@ const EMPTY_AI_FORM = {
   temperature: 0.2,
   maxTokens: 800,
   maxContextTokens: 100000,
+  supportsJsonMode: false,
 };
 
 export default function AiConfigModal({
@@ -58,6 +60,9 @@ export default function AiConfigModal({
   const [agentContextServer, setAgentContextServer] = useState("");
   const [agentContextBusy, setAgentContextBusy] = useState("");
   const [agentContextError, setAgentContextError] = useState("");
+  const [modelOptions, setModelOptions] = useState([]);
+  const [modelListBusy, setModelListBusy] = useState(false);
+  const [modelListNote, setModelListNote] = useState("");
 
   useEffect(() => {
     if (open) {
@@ -66,6 +71,11 @@ export default function AiConfigModal({
     }
   }, [open]);
 
+  useEffect(() => {
+    setModelOptions([]);
+    setModelListNote("");
+  }, [aiProfileForm.apiType, aiProfileForm.baseUrl, aiProfileForm.apiKey]);
+
   useEffect(() => {
     if (!open || mode !== "context") {
       return;
@@ -82,7 +92,7 @@ export default function AiConfigModal({
       })
       .catch((error) => {
         if (!cancelled) {
-          setAgentContextError(String(error || ""));
+          setAgentContextError(error?.message || String(error || ""));
         }
       })
       .finally(() => {
@@ -124,7 +134,7 @@ export default function AiConfigModal({
       })
       .catch((error) => {
         if (!cancelled) {
-          setAgentContextError(String(error || ""));
+          setAgentContextError(error?.message || String(error || ""));
         }
       })
       .finally(() => {
@@ -174,6 +184,25 @@ export default function AiConfigModal({
     });
   };
 
+  const loadModelOptions = async () => {
+    setModelListBusy(true);
+    setModelListNote("");
+    try {
+      const result = await api.listAiModels(toAiProfileInput(aiProfileForm));
+      setModelOptions(result?.models || []);
+      if (!result?.supported) {
+        setModelListNote(t("This provider does not list models; enter the model name manually."));
+      } else if (!result.models?.length) {
+        setModelListNote(t("The provider returned no models."));
+      }
+    } catch (error) {
+      setModelOptions([]);
+      setModelListNote(error?.message || String(error || ""));
+    } finally {
+      setModelListBusy(false);
+    }
+  };
+
   const submitProfile = async (event) => {
     await onSaveAiProfile(event);
     setMode("models");
@@ -186,7 +215,7 @@ export default function AiConfigModal({
       const saved = await api.saveAgentContext(null, agentContextGlobal);
       setAgentContextGlobal(saved?.content || "");
     } catch (error) {
-      setAgentContextError(String(error || ""));
+      setAgentContextError(error?.message || String(error || ""));
     } finally {
       setAgentContextBusy("");
     }
@@ -202,7 +231,7 @@ export default function AiConfigModal({
       const saved = await api.saveAgentContext(agentContextServerId, agentContextServer);
       setAgentContextServer(saved?.content || "");
     } catch (error) {
-      setAgentContextError(String(error || ""));
+      setAgentContextError(error?.message || String(error || ""));
     } finally {
       setAgentContextBusy("");
     }
@@ -549,12 +578,28 @@ export default function AiConfigModal({
               </div>
 
               <div className="grid grid-cols-2 gap-2">
-                <input
-                  className="rounded border border-border bg-surface px-2 py-1.5 text-sm"
-                  placeholder={t("Model")}
-                  value={aiProfileForm.model}
-                  onChange={(event) => setAiProfileForm((prev) => ({ ...prev, model: event.target.value }))}
-                />
+                <div className="flex gap-1">
+                  <input
+                    className="min-w-0 flex-1 rounded border border-border bg-surface px-2 py-1.5 text-sm"
+                    placeholder={t("Model")}
+                    list="ai-profile-model-options"
+                    value={aiProfileForm.model}
+                    onChange={(event) => setAiProfileForm((prev) => ({ ...prev, model: event.target.value }))}
+                  />
+                  <button
+                    type="button"
+                    disabled={modelListBusy || !aiProfileForm.baseUrl || !aiProfileForm.apiKey}
+                    className="shrink-0 rounded border border-border px-2 py-1.5 text-xs text-muted disabled:opacity-40"
+                    onClick={loadModelOptions}
+                  >
+                    {modelListBusy ? t("Loading...") : t("Load models")}
+                  </button>
+                  <datalist id="ai-profile-model-options">
+                    {modelOptions.map((model) => (
+                      <option key={model} value={model} />
+                    ))}
+                  </datalist>
+                </div>
                 <input
                   className="rounded border border-border bg-surface px-2 py-1.5 text-sm"
                   placeholder={t("Temperature")}
@@ -562,6 +607,7 @@ export default function AiConfigModal({
                   onChange={(event) => setAiProfileForm((prev) => ({ ...prev, temperature: event.target.value }))}
                 />
               </div>
+              {modelListNote ? <div className="text-xs text-muted">{modelListNote}</div> : null}
 
               <input
                 className="w-full rounded border border-border bg-surface px-2 py-1.5 text-sm"
@@ -579,6 +625,17 @@ export default function AiConfigModal({
                 }
               />
 
+              <label className="flex items-center gap-2 text-xs text-muted">
+                <input
+                  type="checkbox"
+                  checked={Boolean(aiProfileForm.supportsJsonMode)}
+                  onChange={(event) =>
+                    setAiProfileForm((prev) => ({ ...prev, supportsJsonMode: event.target.checked }))
+                  }
+                />
+                {t("Provider supports JSON mode")}
+              </label>
+
               <textarea
                 className="h-24 w-full rounded border border-border bg-surface px-2 py-1.5 text-sm"
                 placeholder={t("System prompt")}
@@ -13,6 +13,7 @@ const EMPTY_SSH_FORM = {
   privateKeyPath: "",
   privateKeyPassphrase: "",
   usePasswordFallback: false,
+  sudoPassword: "",
   description: "",
 };
 
@@ -340,6 +341,13 @@ export default function SshConfigModal({
                   ) : null}
                 </div>
               )}
+              <input
+                type="password"
+                className="w-full rounded border border-border bg-surface px-2 py-1.5 text-sm"
+                placeholder={t("Sudo password (optional)")}
+                value={sshForm.sudoPassword || ""}
+                onChange={(event) => setSshForm((prev) => ({ ...prev, sudoPassword: event.target.value }))}
+              />
               <div className="flex justify-end">
                 <button type="submit" className="inline-flex items-center gap-1.5 rounded bg-accent px-3 py-1.5 text-xs text-white">
                   <Save className="h-3.5 w-3.5" aria-hidden="true" />
@@ -78,6 +78,7 @@ export const EMPTY_SSH = {
   privateKeyPath: "",
   privateKeyPassphrase: "",
   usePasswordFallback: false,
+  sudoPassword: "",
   description: "",
 };
 
@@ -51,6 +51,7 @@ const normalizeAiProfile = (profile) => ({
     1,
     Math.round(parseNumber(profile?.maxContextTokens, DEFAULT_AI.maxContextTokens)),
   ),
+  supportsJsonMode: Boolean(profile?.supportsJsonMode),
 });
 
 export const normalizeAiProfilesState = (state) => {
@@ -83,6 +84,7 @@ export const toAiProfileInput = (profile) => ({
   temperature: Number(profile.temperature),
   maxTokens: Number(profile.maxTokens),
   maxContextTokens: Number(profile.maxContextTokens),
+  supportsJsonMode: Boolean(profile.supportsJsonMode),
 });
 
 export const DEFAULT_AI_PROFILE_FORM = {
@@ -96,4 +98,5 @@ export const DEFAULT_AI_PROFILE_FORM = {
   temperature: DEFAULT_AI.temperature,
   maxTokens: DEFAULT_AI.maxTokens,
   maxContextTokens: DEFAULT_AI.maxContextTokens,
+  supportsJsonMode: false,
 };
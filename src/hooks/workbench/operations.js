@@ -622,6 +622,7 @@ export function useWorkbenchOperations({
             privateKeyPath: sshForm.privateKeyPath || "",
             privateKeyPassphrase: sshForm.privateKeyPassphrase || "",
             usePasswordFallback: Boolean(sshForm.usePasswordFallback),
+            sudoPassword: sshForm.sudoPassword || "",
             description: sshForm.description,
           }),
         );
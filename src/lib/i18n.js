@@ -159,6 +159,8 @@ const zhMessages = {
   "Language": "语言",
   "Language: {language}": "语言：{language}",
   "Live diagnostics and guided actions": "实时诊断与引导式操作",
+  "Load models": "加载模型列表",
+  "Loading...": "加载中...",
   "Local: {path}": "本地：{path}",
   "Manage model profiles and pick one for conversation.":
     "管理模型配置，并为当前会话选择使用的配置。",
@@ -177,7 +179,7 @@ const zhMessages = {
   "Max tokens": "最大 Token",
   "Maximize": "最大化",
   "Memory (GB)": "内存（GB）",
-  "Memory (MB)": "内存（MB）",
+  "Memory (%)": "内存（%）",
   "Minimize": "最小化",
   "Model": "模型",
   "Model Configs": "模型配置",
@@ -227,6 +229,7 @@ const zhMessages = {
   "Private key": "私钥",
   "Private key path": "私钥路径",
   "Private key passphrase (optional)": "私钥密码短语（可选）",
+  "Sudo password (optional)": "sudo 密码（可选）",
   "Password": "密码",
   "Parameter label": "参数标签",
   "Parameter name": "参数名",
@@ -247,6 +250,7 @@ const zhMessages = {
   "Presets": "预设",
   "Preview": "预览",
   "Processes": "进程",
+  "Provider supports JSON mode": "服务商支持 JSON 模式",
   "PTY connected. Type directly in terminal.": "PTY 已连接，可以直接在终端中输入。",
   "Queued": "排队中",
   "Quick": "快捷",
@@ -312,6 +316,7 @@ const zhMessages = {
     "开始一段关于运维排障、诊断分析或安全命令规划的对话。",
   "Stop": "停止",
   "Sunset Ridge": "落日山脊",
+  "Swap (GB)": "交换分区（GB）",
   "Switch AI profile": "切换 AI 配置",
   "Active model: {model}": "当前模型：{model}",
   "Switch to {language}": "切换到 {language}",
@@ -321,6 +326,9 @@ const zhMessages = {
   "The agent will try to execute commands directly, including commands outside the read-only allowlist.":
     "agent 会尽量直接执行命令，包括只读白名单之外的命令。",
   "Text Editor Check": "文本编辑器检查",
+  "The provider returned no models.": "服务商未返回任何模型。",
+  "This provider does not list models; enter the model name manually.":
+    "该服务商不支持列出模型，请手动输入模型名称。",
   "This file is larger than 50 MB and may be slow to load in the text editor.":
     "该文件大于 50 MB，在文本编辑器中加载可能较慢。",
   "This file looks like a common binary format, so the content may be unreadable as text.":
@@ -350,6 +358,7 @@ const zhMessages = {
     "可在命令中使用 {{name}} 占位符；未使用占位符时会按顺序追加参数。",
   "Use an image smaller than 1.5MB.": "请使用小于 1.5MB 的图片。",
   "used": "已用",
+  "User": "用户",
   "Username": "用户名",
   "Vertical": "垂直",
   "Wallpaper": "壁纸",
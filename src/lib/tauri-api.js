@@ -7,21 +7,60 @@ export const api = {
   trustSshHostKey: (input) => invoke("trust_ssh_host_key", { input }),
 
   listShellSessions: () => invoke("list_shell_sessions"),
-  openShellSession: (configId, requestId = null) =>
-    invoke("open_shell_session", { input: { configId, requestId } }),
+  openShellSession: (
+    configId,
+    requestId = null,
+    {
+      initialDir = null,
+      initialCommand = null,
+      term = null,
+      cols = null,
+      rows = null,
+      autoReconnect = false,
+    } = {},
+  ) =>
+    invoke("open_shell_session", {
+      input: {
+        configId,
+        requestId,
+        initialDir,
+        initialCommand,
+        term,
+        cols,
+        rows,
+        autoReconnect,
+      },
+    }),
   cancelOpenShellSession: (requestId) =>
     invoke("cancel_open_shell_session", { input: { requestId } }),
   closeShellSession: (sessionId) =>
     invoke("close_shell_session", { input: { sessionId } }),
-  ptyWriteInput: (sessionId, data) =>
-    invoke("pty_write_input", { input: { sessionId, data } }),
+  shutdownAllSessions: () => invoke("shutdown_all_sessions"),
+  ptyWriteInput: (sessionId, data, bracketed = false) =>
+    invoke("pty_write_input", { input: { sessionId, data, bracketed } }),
   ptyResize: (sessionId, cols, rows) =>
     invoke("pty_resize", { input: { sessionId, cols, rows } }),
-  executeShellCommand: (sessionId, command) =>
-    invoke("execute_shell_command", { input: { sessionId, command } }),
+  ptySendSignal: (sessionId, signal) =>
+    invoke("pty_send_signal", { input: { sessionId, signal } }),
+  sessionIsAlive: (sessionId) => invoke("session_is_alive", { sessionId }),
+  pingSession: (sessionId) => invoke("ping_session", { sessionId }),
+  executeShellCommand: (sessionId, command, { env = {}, maxOutputBytes = null } = {}) =>
+    invoke("execute_shell_command", {
+      input: { sessionId, command, env, maxOutputBytes },
+    }),
 
   sftpListDir: (sessionId, path) =>
     invoke("sftp_list_dir", { input: { sessionId, path } }),
+  remoteFind: (
+    sessionId,
+    root,
+    { nameGlob = null, contentGrep = null, maxResults = null } = {},
+  ) =>
+    invoke("remote_find", {
+      input: { sessionId, root, nameGlob, contentGrep, maxResults },
+    }),
+  sftpDirUsage: (sessionId, path, depth = null) =>
+    invoke("sftp_dir_usage", { input: { sessionId, path, depth } }),
   sftpReadFile: (sessionId, path) =>
     invoke("sftp_read_file", { input: { sessionId, path } }),
   sftpWriteFile: (sessionId, path, content) =>
@@ -52,13 +91,35 @@ export const api = {
     invoke("sftp_download_file_to_local", {
       input: { sessionId, remotePath, localDir, transferId },
     }),
+  sftpDownloadDir: (sessionId, remoteDir, localPath, transferId) =>
+    invoke("sftp_download_dir", {
+      input: { sessionId, remoteDir, localPath, transferId },
+    }),
+  sftpUploadDir: (
+    sessionId,
+    localDir,
+    remoteDir,
+    transferId,
+    { preserveExecutable = false } = {},
+  ) =>
+    invoke("sftp_upload_dir", {
+      input: { sessionId, localDir, remoteDir, transferId, preserveExecutable },
+    }),
+  sftpChecksum: (sessionId, path, algorithm = "sha256", expected = null) =>
+    invoke("sftp_checksum", { input: { sessionId, path, algorithm, expected } }),
+  sftpCopy: (sessionId, src, dest) =>
+    invoke("sftp_copy", { input: { sessionId, src, dest } }),
   sftpDefaultDownloadDir: () => invoke("sftp_default_download_dir"),
   sftpCancelTransfer: (transferId) =>
     invoke("sftp_cancel_transfer", { input: { transferId } }),
 
-  fetchServerStatus: (sessionId, selectedInterface) =>
+  fetchServerStatus: (
+    sessionId,
+    selectedInterface,
+    { includePseudoFs = false, processLimit = null, processSort = "cpu" } = {},
+  ) =>
     invoke("fetch_server_status", {
-      input: { sessionId, selectedInterface },
+      input: { sessionId, selectedInterface, includePseudoFs, processLimit, processSort },
     }),
   getCachedServerStatus: (sessionId) =>
     invoke("get_cached_server_status", { sessionId }),
@@ -66,14 +127,15 @@ export const api = {
   listScripts: () => invoke("list_scripts"),
   saveScript: (input) => invoke("save_script", { input }),
   deleteScript: (id) => invoke("delete_script", { id }),
-  runScript: (sessionId, scriptId) =>
-    invoke("run_script", { input: { sessionId, scriptId } }),
+  runScript: (sessionId, scriptId, env = {}) =>
+    invoke("run_script", { input: { sessionId, scriptId, env } }),
 
   getAiConfig: () => invoke("get_ai_config"),
   saveAiConfig: (input) => invoke("save_ai_config", { input }),
   listAiProfiles: () => invoke("list_ai_profiles"),
   saveAiProfile: (input) => invoke("save_ai_profile", { input }),
   deleteAiProfile: (id) => invoke("delete_ai_profile", { id }),
+  listAiModels: (input) => invoke("list_ai_models", { input }),
   saveAiApprovalMode: (approvalMode) =>
     invoke("save_ai_approval_mode", { input: { approvalMode } }),
   saveAiAgentMode: (agentMode) =>
@@ -85,10 +147,23 @@ export const api = {
   setActiveAiProfile: (id) =>
     invoke("set_active_ai_profile", { input: { id } }),
   askAi: (input) => invoke("ai_ask", { input }),
+  cancelAiAsk: (requestId) => invoke("cancel_ai_ask", { input: { requestId } }),
+  listAiAskHistory: (limit) => invoke("list_ai_ask_history", { limit }),
+  clearAiAskHistory: () => invoke("clear_ai_ask_history"),
 
-  opsAgentListConversations: () => invoke("ops_agent_list_conversations"),
-  opsAgentCreateConversation: (title, sessionId = null) =>
-    invoke("ops_agent_create_conversation", { input: { title, sessionId } }),
+  opsAgentListConversations: (includeArchived = false) =>
+    invoke("ops_agent_list_conversations", { input: { includeArchived } }),
+  opsAgentGetSettings: () => invoke("ops_agent_get_settings"),
+  opsAgentSaveSettings: (autoTitleMaxChars, aiTitles) =>
+    invoke("ops_agent_save_settings", { input: { autoTitleMaxChars, aiTitles } }),
+  opsAgentRenameConversation: (conversationId, title) =>
+    invoke("ops_agent_rename_conversation", { input: { conversationId, title } }),
+  opsAgentSetPinned: (conversationId, pinned) =>
+    invoke("ops_agent_set_pinned", { input: { conversationId, pinned } }),
+  opsAgentSetArchived: (conversationId, archived) =>
+    invoke("ops_agent_set_archived", { input: { conversationId, archived } }),
+  opsAgentCreateConversation: (title, sessionId = null, aiProfileId = null) =>
+    invoke("ops_agent_create_conversation", { input: { title, sessionId, aiProfileId } }),
   opsAgentGetConversation: (conversationId) =>
     invoke("ops_agent_get_conversation", { input: { conversationId } }),
   opsAgentGetAttachmentContent: (attachmentId) =>